@@ -1,7 +1,7 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
-use curl::easy::Easy;
-use nyquest_interface::{client::BuildClientResult, r#async::AsyncResponse};
+use nyquest_interface::{client::BuildClientResult, r#async::AsyncResponse, Bytes};
 
 use crate::url::concat_url;
 
@@ -9,6 +9,8 @@ mod r#loop;
 
 pub struct CurlMultiClientInner {
     options: nyquest_interface::client::ClientOptions,
+    default_headers: crate::header_list::DefaultHeaders,
+    dns_cache: crate::dns_cache::DnsCache,
     loop_manager: r#loop::LoopManager,
 }
 #[derive(Clone)]
@@ -20,6 +22,7 @@ pub struct CurlAsyncResponse {
     status: u16,
     content_length: Option<u64>,
     headers: Vec<(String, String)>,
+    tls_info: Option<nyquest_interface::TlsInfo>,
     handle: r#loop::RequestHandle,
     max_response_buffer_size: Option<u64>,
 }
@@ -42,6 +45,14 @@ impl AsyncResponse for CurlAsyncResponse {
             .collect())
     }
 
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers.clone())
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.tls_info.clone()
+    }
+
     async fn text(&mut self) -> nyquest_interface::Result<String> {
         let buf = self.bytes().await?;
         #[cfg(feature = "charset")]
@@ -61,7 +72,7 @@ impl AsyncResponse for CurlAsyncResponse {
         Ok(String::from_utf8_lossy(&buf).into_owned())
     }
 
-    async fn bytes(&mut self) -> nyquest_interface::Result<Vec<u8>> {
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
         let mut buf = vec![];
         while let Some(()) = self
             .handle
@@ -71,12 +82,17 @@ impl AsyncResponse for CurlAsyncResponse {
                         return Err(nyquest_interface::Error::ResponseTooLarge);
                     }
                 }
-                buf.extend_from_slice(data);
+                if buf.is_empty() {
+                    // Takes over the buffer of the transfer instead of copying it
+                    std::mem::swap(&mut buf, data);
+                } else {
+                    buf.extend_from_slice(data);
+                }
                 Ok(())
             })
             .await?
         {}
-        Ok(buf)
+        Ok(buf.into())
     }
 }
 
@@ -87,21 +103,55 @@ impl nyquest_interface::r#async::AsyncClient for CurlMultiClient {
         &self,
         req: nyquest_interface::r#async::Request,
     ) -> nyquest_interface::Result<Self::Response> {
-        let req = loop {
-            // TODO: CURLOPT_SHARE
-            let mut easy = Easy::new();
+        let mut res = self.start_request(req).await?.wait_for_response().await?;
+        res.max_response_buffer_size = self.inner.options.max_response_buffer_size;
+        Ok(res)
+    }
+
+    async fn preconnect(&self, uri: Cow<'static, str>) -> nyquest_interface::Result<()> {
+        let req = nyquest_interface::r#async::Request::head(uri);
+        // A CONNECT_ONLY connection would never be reused, so a real transfer is completed
+        let mut res = self.request(req).await?;
+        res.bytes().await.map(drop)
+    }
+
+    fn flush_dns_cache(&self) -> nyquest_interface::Result<()> {
+        self.inner.dns_cache.flush();
+        Ok(())
+    }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
+}
+
+impl CurlMultiClient {
+    async fn start_request(
+        &self,
+        req: nyquest_interface::r#async::Request,
+    ) -> nyquest_interface::Result<r#loop::RequestHandle> {
+        loop {
+            let mut easy = self.inner.loop_manager.get_or_create_easy();
             // FIXME: properly concat base_url and url
             let url = concat_url(self.inner.options.base_url.as_deref(), &req.relative_uri);
-            crate::request::populate_request(&url, &req, &self.inner.options, &mut easy)?;
-            let req = self.inner.loop_manager.start_request(easy).await?;
+            let resources = crate::request::populate_request(
+                &url,
+                &req,
+                &self.inner.options,
+                &self.inner.default_headers,
+                &self.inner.dns_cache,
+                &mut easy,
+            )?;
+            let req = self
+                .inner
+                .loop_manager
+                .start_request(easy, resources)
+                .await?;
             match req {
                 r#loop::MaybeStartedRequest::Gone => {}
-                r#loop::MaybeStartedRequest::Started(req) => break req,
+                r#loop::MaybeStartedRequest::Started(req) => return Ok(req),
             }
-        };
-        let mut res = req.wait_for_response().await?;
-        res.max_response_buffer_size = self.inner.options.max_response_buffer_size;
-        Ok(res)
+        }
     }
 }
 
@@ -112,8 +162,11 @@ impl nyquest_interface::r#async::AsyncBackend for crate::CurlBackend {
         &self,
         options: nyquest_interface::client::ClientOptions,
     ) -> BuildClientResult<Self::AsyncClient> {
+        crate::request::check_options(&options)?;
         Ok(CurlMultiClient {
             inner: Arc::new(CurlMultiClientInner {
+                default_headers: crate::header_list::DefaultHeaders::new(&options),
+                dns_cache: crate::dns_cache::DnsCache::new(&options),
                 options,
                 loop_manager: r#loop::LoopManager::new(),
             }),
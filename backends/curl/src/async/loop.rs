@@ -1,24 +1,31 @@
 use std::collections::VecDeque;
 use std::future::poll_fn;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, LazyLock, Mutex};
 use std::task::Poll;
 use std::time::Duration;
 use std::{io, thread};
 
-use curl::easy::Easy;
+use curl::easy::{Easy, WriteError};
 use curl::multi::{EasyHandle, Multi, MultiWaker};
 use curl_sys::{CURLPAUSE_RECV, CURLPAUSE_RECV_CONT, CURLPAUSE_SEND, CURLPAUSE_SEND_CONT};
 use futures_channel::oneshot;
 use futures_util::lock::Mutex as FuturesMutex;
 use futures_util::task::AtomicWaker;
+use nyquest_interface::chunk_queue::FlowControl;
 use nyquest_interface::Result as NyquestResult;
 use slab::Slab;
 
 use crate::error::IntoNyquestResult;
+use crate::push::PushHook;
+use crate::request::{HeaderCounter, RequestResources};
 use crate::share::{Share, ShareHandle};
+use crate::tls_info::TlsProbe;
+use crate::upkeep::Upkeep;
 
 pub const CURLPAUSE_CONT: i32 = CURLPAUSE_RECV_CONT | CURLPAUSE_SEND_CONT;
 pub const CURLPAUSE_ALL: i32 = CURLPAUSE_RECV | CURLPAUSE_SEND;
+const MAX_POLL_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub(super) struct RequestHandle {
     shared_context: Arc<SharedRequestContext>,
@@ -33,6 +40,10 @@ struct SharedRequestContextState {
     header_finished: bool,
     response_headers_buffer: Vec<Vec<u8>>,
     response_buffer: Vec<u8>,
+    tls_info: Option<nyquest_interface::TlsInfo>,
+    /// Suspended initially, as the transfer is paused once the response headers arrive.
+    flow: FlowControl,
+    header_counter: HeaderCounter,
 }
 struct SharedRequestContext {
     id: usize,
@@ -45,7 +56,10 @@ impl SharedRequestContext {
         Self {
             id,
             waker: AtomicWaker::new(),
-            state: Default::default(),
+            state: Mutex::new(SharedRequestContextState {
+                flow: FlowControl::default().starting_suspended(),
+                ..Default::default()
+            }),
         }
     }
 }
@@ -53,6 +67,7 @@ impl SharedRequestContext {
 enum LoopTask {
     ConstructHandle(
         Easy,
+        EasyOwner,
         oneshot::Sender<NyquestResult<Arc<SharedRequestContext>>>,
     ),
     QueryHandleResponse(
@@ -62,7 +77,6 @@ enum LoopTask {
     ),
     UnpauseHandle(usize),
     DropHandle(usize),
-    Shutdown,
 }
 
 impl RequestHandle {
@@ -104,24 +118,32 @@ impl RequestHandle {
         &mut self,
         cb: impl FnOnce(&mut Vec<u8>) -> nyquest_interface::Result<T>,
     ) -> nyquest_interface::Result<Option<T>> {
-        self.manager
-            .dispatch_task(LoopTask::UnpauseHandle(self.shared_context.id));
         let mut cb = Some(cb);
         poll_fn(|cx| {
             let mut state = self.shared_context.state.lock().unwrap();
-            if !state.response_buffer.is_empty() {
+            let (poll, resume) = if !state.response_buffer.is_empty() {
                 let cb = cb
                     .take()
                     .expect("poll_bytes callback is called more than once");
+                let len = state.response_buffer.len();
                 let res = cb(&mut state.response_buffer);
                 state.response_buffer.clear();
-                return Poll::Ready(res.map(Some));
-            }
-            if let Some(res) = state.result.take() {
-                return Poll::Ready(res.map(|()| None));
+                (Poll::Ready(res.map(Some)), state.flow.consume(len))
+            } else if let Some(res) = state.result.take() {
+                (Poll::Ready(res.map(|()| None)), false)
+            } else {
+                self.shared_context.waker.register(cx.waker());
+                // The header function pauses the transfer after each header block, which is
+                // unpaused unless suspended for backpressure
+                state.flow.consume(0);
+                (Poll::Pending, !state.flow.is_suspended())
             };
-            self.shared_context.waker.register(cx.waker());
-            Poll::Pending
+            drop(state);
+            if resume {
+                self.manager
+                    .dispatch_task(LoopTask::UnpauseHandle(self.shared_context.id));
+            }
+            poll
         })
         .await
     }
@@ -137,6 +159,7 @@ impl Drop for RequestHandle {
 struct LoopManagerInner {
     tasks: VecDeque<LoopTask>,
     multi_waker: MultiWaker,
+    closed: bool,
 }
 
 #[derive(Clone)]
@@ -144,43 +167,77 @@ struct LoopManagerShared {
     inner: Arc<Mutex<LoopManagerInner>>,
 }
 
+/// The multi loop shared by all async clients. It is started on demand and exits when idle.
+static SHARED_LOOP: LazyLock<FuturesMutex<Option<LoopManagerShared>>> =
+    LazyLock::new(|| FuturesMutex::new(None));
+
+/// Per-client handle to the shared multi loop, carrying the client's own share and idle handles.
 pub(super) struct LoopManager {
-    inner: FuturesMutex<Option<LoopManagerShared>>,
     share: Share,
+    pool: EasyPool,
+}
+
+type EasyPool = Arc<Mutex<Vec<Easy>>>;
+
+/// Keeps the client's share and the request's resources alive for an easy handle in the loop,
+/// and takes the handle back into the client's pool once the request is done.
+struct EasyOwner {
+    pool: EasyPool,
+    upkeep: Option<Upkeep>, // Drop earlier than share
+    _share_handle: ShareHandle,
+    resources: RequestResources,
+}
+
+impl EasyOwner {
+    fn recycle(self, mut easy: Easy) {
+        let mut pool = self.pool.lock().unwrap();
+        if pool.len() < crate::MAX_IDLE_EASY_HANDLES {
+            easy.reset();
+            pool.push(easy);
+        } else {
+            // Release the handle before the resources it refers to
+            drop(easy);
+        }
+    }
 }
 
 impl LoopManagerShared {
-    async fn start_loop(share_handle: ShareHandle) -> Self {
+    async fn start_loop() -> Self {
         let (multi_waker_tx, multi_waker_rx) = oneshot::channel();
         thread::Builder::new()
             .name("nyquest-curl-multi-loop".into())
-            .spawn(move || {
-                let _share_handle = share_handle; // Ensure the handle outlives all easy handles in the loop
-                run_loop(multi_waker_tx);
-            })
+            .spawn(move || run_loop(multi_waker_tx))
             .expect("failed to spawn curl multi loop");
         multi_waker_rx.await.expect("not receiving request manager")
     }
     fn dispatch_task(&self, task: LoopTask) {
         let mut inner = self.inner.lock().unwrap();
+        if inner.closed {
+            return;
+        }
         inner.tasks.push_back(task);
         inner.multi_waker.wakeup().ok();
     }
     async fn start_request(
         self,
         easy: Easy,
-    ) -> NyquestResult<Result<RequestHandle, (Option<Easy>, Self)>> {
+        owner: EasyOwner,
+    ) -> NyquestResult<Result<RequestHandle, (Option<(Easy, EasyOwner)>, Self)>> {
         let (tx, rx) = oneshot::channel();
         {
             let mut inner = self.inner.lock().unwrap();
-            if inner.multi_waker.wakeup().is_err() {
+            if inner.closed || inner.multi_waker.wakeup().is_err() {
                 drop(inner);
-                return Ok(Err((Some(easy), self)));
+                return Ok(Err((Some((easy, owner)), self)));
             }
-            let request = LoopTask::ConstructHandle(easy, tx);
+            let request = LoopTask::ConstructHandle(easy, owner, tx);
             inner.tasks.push_back(request);
         }
-        let shared_context = match rx.await {
+        let res = {
+            let mut pending = PendingHandle { rx, manager: &self };
+            (&mut pending.rx).await
+        };
+        let shared_context = match res {
             Ok(Ok(ctx)) => ctx,
             Ok(Err(e)) => return Err(e),
             Err(_) => return Ok(Err((None, self))),
@@ -192,6 +249,23 @@ impl LoopManagerShared {
     }
 }
 
+/// Receives the context of a handle added by the loop, and removes the handle if the request is
+/// dropped before receiving it.
+struct PendingHandle<'a> {
+    rx: oneshot::Receiver<NyquestResult<Arc<SharedRequestContext>>>,
+    manager: &'a LoopManagerShared,
+}
+
+impl Drop for PendingHandle<'_> {
+    fn drop(&mut self) {
+        // Contexts sent after closing are handed back to the loop, which drops their handles
+        self.rx.close();
+        if let Ok(Some(Ok(ctx))) = self.rx.try_recv() {
+            self.manager.dispatch_task(LoopTask::DropHandle(ctx.id));
+        }
+    }
+}
+
 impl PartialEq for LoopManagerShared {
     fn eq(&self, other: &Self) -> bool {
         Arc::ptr_eq(&self.inner, &other.inner)
@@ -208,39 +282,52 @@ pub(super) enum MaybeStartedRequest {
 impl LoopManager {
     pub(super) fn new() -> Self {
         Self {
-            inner: FuturesMutex::new(None),
             share: Share::new(),
+            pool: Default::default(),
         }
     }
+    pub(super) fn get_or_create_easy(&self) -> Easy {
+        let idle = self.pool.lock().unwrap().pop();
+        idle.unwrap_or_else(Easy::new)
+    }
     pub(super) async fn start_request(
         &self,
         mut easy: Easy,
+        resources: RequestResources,
     ) -> nyquest_interface::Result<MaybeStartedRequest> {
         unsafe {
             self.share
                 .bind_easy(&mut easy)
                 .expect("failed to bind easy handle to share");
         }
+        let mut owner = EasyOwner {
+            pool: self.pool.clone(),
+            // Safety: the share handle is dropped later
+            upkeep: resources
+                .upkeep_interval
+                .and_then(|interval| unsafe { Upkeep::new(&self.share, interval) }),
+            _share_handle: self.share.get_handle(),
+            resources,
+        };
         loop {
-            let inner = match &mut *self.inner.lock().await {
+            let inner = match &mut *SHARED_LOOP.lock().await {
                 Some(inner) => inner.clone(),
                 manager @ None => manager
-                    .insert(LoopManagerShared::start_loop(self.share.get_handle()).await)
+                    .insert(LoopManagerShared::start_loop().await)
                     .clone(),
             };
-            let (backup_easy, inner) = match inner.start_request(easy).await? {
+            let (backup_easy, inner) = match inner.start_request(easy, owner).await? {
                 Ok(res) => return Ok(MaybeStartedRequest::Started(res)),
                 Err(res) => res,
             };
             {
-                let mut new_manager = self.inner.lock().await;
+                let mut new_manager = SHARED_LOOP.lock().await;
                 if *new_manager == Some(inner) {
-                    *new_manager =
-                        Some(LoopManagerShared::start_loop(self.share.get_handle()).await);
+                    *new_manager = Some(LoopManagerShared::start_loop().await);
                 }
             }
             match backup_easy {
-                Some(e) => easy = e,
+                Some(backup) => (easy, owner) = backup,
                 None => {
                     return Ok(MaybeStartedRequest::Gone);
                 }
@@ -249,14 +336,6 @@ impl LoopManager {
     }
 }
 
-impl Drop for LoopManager {
-    fn drop(&mut self) {
-        if let Some(inner) = self.inner.get_mut() {
-            inner.dispatch_task(LoopTask::Shutdown);
-        }
-    }
-}
-
 #[derive(Clone, Copy)]
 struct EasyPause(*mut curl_sys::CURL);
 
@@ -278,33 +357,39 @@ impl EasyPause {
 unsafe impl Send for EasyPause {}
 
 fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
+    // Dropped later than multi
+    let push_hook = PushHook::new();
     let multi = Multi::new();
     let request_manager = LoopManagerShared {
         inner: Arc::new(Mutex::new(LoopManagerInner {
             tasks: Default::default(),
             multi_waker: multi.waker(),
+            closed: false,
         })),
     };
     if multl_waker_tx.send(request_manager.clone()).is_err() {
         return;
     }
     // TODO: store ctx in Easy2Handle
-    let mut slab = Slab::<(EasyHandle, Arc<SharedRequestContext>)>::new();
+    let mut slab = Slab::<(EasyHandle, Arc<SharedRequestContext>, EasyOwner)>::new();
     let mut tasks = Default::default();
     let mut last_call = false;
+    let mut poll_timeout = MAX_POLL_TIMEOUT;
     loop {
-        let poll_res = multi.poll(&mut [], Duration::from_secs(120));
+        let poll_res = multi.poll(&mut [], poll_timeout);
         std::mem::swap(&mut request_manager.inner.lock().unwrap().tasks, &mut tasks);
         for mut task in tasks.drain(..) {
             loop {
                 match task {
-                    LoopTask::ConstructHandle(mut easy, tx) => {
+                    LoopTask::ConstructHandle(mut easy, owner, tx) => {
                         let slab_entry = slab.vacant_entry();
                         let id = slab_entry.key();
                         let ctx = Arc::new(SharedRequestContext::new(id));
                         let pause = EasyPause::new(easy.raw());
+                        let tls_probe = TlsProbe::new(easy.raw());
                         easy.header_function({
                             let ctx = ctx.clone();
+                            let header_limits = owner.resources.header_limits;
                             move |h| {
                                 let mut state = ctx.state.lock().unwrap();
                                 if h == b"\r\n" {
@@ -313,15 +398,25 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                                     // TODO: handle direct
                                     if !is_redirect && !state.is_established {
                                         state.header_finished = true;
+                                        state.tls_info = unsafe { tls_probe.tls_info() };
                                         unsafe {
                                             pause.pause();
                                         }
                                     }
                                 } else if h.contains(&b':') {
-                                    state
-                                        .response_headers_buffer
-                                        .push(h.strip_suffix(b"\r\n").unwrap_or(h).into());
+                                    let line = h.strip_suffix(b"\r\n").unwrap_or(h);
+                                    if let Err(err) =
+                                        state.header_counter.add(header_limits.as_ref(), line)
+                                    {
+                                        // Kept over the error of the aborted transfer
+                                        state.result = Some(Err(err));
+                                        drop(state);
+                                        ctx.waker.wake();
+                                        return false;
+                                    }
+                                    state.response_headers_buffer.push(line.into());
                                 } else {
+                                    state.header_counter.reset();
                                     let mut status_components =
                                         h.splitn(3, u8::is_ascii_whitespace).skip(1);
 
@@ -347,27 +442,39 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                         .expect("set curl header function");
                         easy.write_function({
                             let ctx = ctx.clone();
+                            let decompressed = owner.resources.decompressed.clone();
                             move |f| {
                                 let mut state = ctx.state.lock().unwrap();
                                 state.header_finished = true;
+                                if state.flow.is_suspended() {
+                                    // libcurl passes the data again once unpaused
+                                    return Err(WriteError::Pause);
+                                }
+                                if let Some(decompressed) = &decompressed {
+                                    decompressed.fetch_add(f.len() as u64, Ordering::Relaxed);
+                                }
                                 // TODO: handle max response buffer size
                                 state.response_buffer.extend_from_slice(f);
+                                state.flow.produce(f.len());
                                 drop(state);
                                 ctx.waker.wake();
                                 Ok(f.len())
                             }
                         })
                         .expect("set curl write function");
+                        let raw = easy.raw();
+                        push_hook.set(&multi, raw, owner.resources.push.clone());
                         let handle = multi.add(easy).into_nyquest_result("curl_multi_add_handle");
                         let send_res = match handle {
                             Ok(mut handle) => {
                                 handle
                                     .set_token(id)
                                     .expect("failed to set token on easy handle");
-                                slab_entry.insert((handle, ctx.clone()));
+                                slab_entry.insert((handle, ctx.clone(), owner));
                                 tx.send(Ok(ctx))
                             }
                             Err(e) => {
+                                push_hook.set(&multi, raw, None);
                                 tx.send(Err(e)).ok();
                                 break;
                             }
@@ -380,7 +487,7 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                         break;
                     }
                     LoopTask::QueryHandleResponse(id, req_handle, tx) => {
-                        let Some((handle, ctx)) = slab.get_mut(id) else {
+                        let Some((handle, ctx, _)) = slab.get_mut(id) else {
                             break;
                         };
                         let mut state = ctx.state.lock().unwrap();
@@ -399,6 +506,7 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                                     .filter_map(|line| line.split_once(':'))
                                     .map(|(k, v)| (k.into(), v.trim_start().into()))
                                     .collect(),
+                                tls_info: state.tls_info.take(),
                                 handle: req_handle,
                                 max_response_buffer_size: None, // To be filled in client.request()
                             });
@@ -407,7 +515,7 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                         break;
                     }
                     LoopTask::UnpauseHandle(id) => {
-                        if let Some((handle, _)) = slab.get(id) {
+                        if let Some((handle, _, _)) = slab.get(id) {
                             unsafe {
                                 let _res = curl_sys::curl_easy_pause(handle.raw(), CURLPAUSE_CONT);
                                 // Ignore the error. Also see
@@ -416,12 +524,11 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                         }
                     }
                     LoopTask::DropHandle(id) => {
-                        let (handle, _) = slab.remove(id);
-                        let _ = multi.remove(handle);
-                    }
-                    LoopTask::Shutdown => {
-                        // TODO: handle shutdown
-                        last_call = true;
+                        let (handle, _, owner) = slab.remove(id);
+                        push_hook.set(&multi, handle.raw(), None);
+                        if let Ok(easy) = multi.remove(handle) {
+                            owner.recycle(easy);
+                        }
                     }
                 }
                 break;
@@ -436,7 +543,7 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
         let (_poll_res, _perform_res) = match loop_res {
             Ok(res) => res,
             Err((err, err_ctx)) => {
-                for (_, (handle, ctx)) in slab {
+                for (_, (handle, ctx, _owner)) in slab {
                     if let Ok(mut state) = ctx.state.lock() {
                         if state.result.is_none() {
                             state.result = Some(Err(err.clone()).into_nyquest_result(err_ctx));
@@ -449,7 +556,7 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
         };
         // TODO: terminate the loop if the multi is empty after timeout
         multi.messages(|msg| {
-            let Some((handle, ctx)) = msg.token().ok().and_then(|t| slab.get_mut(t)) else {
+            let Some((handle, ctx, owner)) = msg.token().ok().and_then(|t| slab.get_mut(t)) else {
                 return;
             };
             // TODO: handle message
@@ -457,7 +564,18 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
                 return;
             };
             if let Some(res) = msg.result_for(handle) {
-                shared_state.result = Some(res.into_nyquest_result("curl_multi_info_read cb"));
+                if !shared_state.result.as_ref().is_some_and(Result::is_err) {
+                    let res = match res {
+                        Err(e) if e.is_aborted_by_callback() => {
+                            match owner.resources.abort_reason.take() {
+                                Some(err) => Err(err),
+                                None => Err(e).into_nyquest_result("curl_multi_info_read cb"),
+                            }
+                        }
+                        res => res.into_nyquest_result("curl_multi_info_read cb"),
+                    };
+                    shared_state.result = Some(res);
+                }
             }
             drop(shared_state);
             ctx.waker.wake();
@@ -470,5 +588,17 @@ fn run_loop(multl_waker_tx: oneshot::Sender<LoopManagerShared>) {
         }
 
         slab.shrink_to_fit();
+        poll_timeout = slab
+            .iter_mut()
+            .filter_map(|(_, (_, _, owner))| owner.upkeep.as_mut())
+            .map(Upkeep::perform)
+            .fold(MAX_POLL_TIMEOUT, Duration::min);
     }
+    // Drop pending tasks so that their requests are retried on a new loop
+    let pending_tasks = {
+        let mut inner = request_manager.inner.lock().unwrap();
+        inner.closed = true;
+        std::mem::take(&mut inner.tasks)
+    };
+    drop(pending_tasks);
 }
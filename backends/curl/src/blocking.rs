@@ -1,13 +1,16 @@
+use std::borrow::Cow;
 use std::io;
 use std::mem::ManuallyDrop;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use nyquest_interface::blocking::Request;
-use nyquest_interface::Error as NyquestError;
+use nyquest_interface::{Bytes, Error as NyquestError};
 
 mod multi_easy;
 
+use crate::dns_cache::DnsCache;
+use crate::header_list::DefaultHeaders;
 use crate::share::Share;
 use crate::url::concat_url;
 use multi_easy::MultiEasy;
@@ -15,32 +18,35 @@ use multi_easy::MultiEasy;
 #[derive(Clone)]
 pub struct CurlEasyClient {
     options: Arc<nyquest_interface::client::ClientOptions>,
-    slot: Arc<Mutex<Option<MultiEasy>>>,
+    default_headers: Arc<DefaultHeaders>,
+    dns_cache: Arc<DnsCache>,
+    pool: Arc<Mutex<Vec<MultiEasy>>>,
     share: Share,
 }
 
-struct EasyHandleGuard<S: AsRef<Mutex<Option<MultiEasy>>>> {
+struct EasyHandleGuard<S: AsRef<Mutex<Vec<MultiEasy>>>> {
     slot: S,
     handle: ManuallyDrop<Mutex<MultiEasy>>, // TODO: use std::sync::Exclusive when stabilized
 }
 
-type OwnedEasyHandleGuard = EasyHandleGuard<Arc<Mutex<Option<MultiEasy>>>>;
+type OwnedEasyHandleGuard = EasyHandleGuard<Arc<Mutex<Vec<MultiEasy>>>>;
 
 pub struct CurlResponse {
     status: u16,
     content_length: Option<u64>,
     headers: Vec<(String, String)>,
+    tls_info: Option<nyquest_interface::TlsInfo>,
     handle: OwnedEasyHandleGuard,
     max_response_buffer_size: Option<u64>,
 }
 
-impl<S: AsRef<Mutex<Option<MultiEasy>>>> EasyHandleGuard<S> {
+impl<S: AsRef<Mutex<Vec<MultiEasy>>>> EasyHandleGuard<S> {
     fn with_handle<T>(&mut self, cb: impl FnOnce(&mut MultiEasy) -> T) -> T {
         cb(self.handle.get_mut().unwrap())
     }
 }
 
-impl EasyHandleGuard<&'_ Arc<Mutex<Option<MultiEasy>>>> {
+impl EasyHandleGuard<&'_ Arc<Mutex<Vec<MultiEasy>>>> {
     fn into_owned(self) -> OwnedEasyHandleGuard {
         let mut this = ManuallyDrop::new(self);
         // Safety: self inside ManuallyDrop will not be dropped, hence the handle will not be taken out from Drop
@@ -52,15 +58,15 @@ impl EasyHandleGuard<&'_ Arc<Mutex<Option<MultiEasy>>>> {
     }
 }
 
-impl<S: AsRef<Mutex<Option<MultiEasy>>>> Drop for EasyHandleGuard<S> {
+impl<S: AsRef<Mutex<Vec<MultiEasy>>>> Drop for EasyHandleGuard<S> {
     fn drop(&mut self) {
         // Safety: the handle is only taken out once which is here, except in `into_owned` where a `ManuallyDrop` is
         // used to suppress our Drop
         let mut handle = unsafe { ManuallyDrop::take(&mut self.handle) };
-        let mut slot = self.slot.as_ref().lock().unwrap();
-        if slot.is_none() {
+        let mut pool = self.slot.as_ref().lock().unwrap();
+        if pool.len() < crate::MAX_IDLE_EASY_HANDLES {
             handle.get_mut().unwrap().reset_state();
-            *slot = Some(handle.into_inner().unwrap());
+            pool.push(handle.into_inner().unwrap());
         }
     }
 }
@@ -68,23 +74,25 @@ impl<S: AsRef<Mutex<Option<MultiEasy>>>> Drop for EasyHandleGuard<S> {
 impl CurlEasyClient {
     pub fn new(options: nyquest_interface::client::ClientOptions) -> Self {
         Self {
+            default_headers: Arc::new(DefaultHeaders::new(&options)),
+            dns_cache: Arc::new(DnsCache::new(&options)),
             options: Arc::new(options),
-            slot: Arc::new(Mutex::new(None)),
+            pool: Arc::new(Mutex::new(Vec::new())),
             share: Share::new(),
         }
     }
 
-    fn get_or_create_handle(&self) -> EasyHandleGuard<&Arc<Mutex<Option<MultiEasy>>>> {
-        let slot = {
-            let mut slot = self.slot.lock().unwrap();
-            slot.take()
+    fn get_or_create_handle(&self) -> EasyHandleGuard<&Arc<Mutex<Vec<MultiEasy>>>> {
+        let idle = {
+            let mut pool = self.pool.lock().unwrap();
+            pool.pop()
         };
-        let handle = match slot {
+        let handle = match idle {
             Some(handle) => handle,
             None => MultiEasy::new(&self.share),
         };
         EasyHandleGuard {
-            slot: &self.slot,
+            slot: &self.pool,
             handle: ManuallyDrop::new(Mutex::new(handle)),
         }
     }
@@ -114,6 +122,14 @@ impl nyquest_interface::blocking::BlockingResponse for CurlResponse {
             .collect())
     }
 
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers.clone())
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.tls_info.clone()
+    }
+
     fn text(&mut self) -> nyquest_interface::Result<String> {
         let buf = self.bytes()?;
         #[cfg(feature = "charset")]
@@ -133,7 +149,7 @@ impl nyquest_interface::blocking::BlockingResponse for CurlResponse {
         Ok(String::from_utf8_lossy(&buf).into_owned())
     }
 
-    fn bytes(&mut self) -> nyquest_interface::Result<Vec<u8>> {
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
         // TODO: proper timeouts
         self.handle.with_handle(|handle| {
             handle.poll_until_whole_response(Duration::from_secs(30), self.max_response_buffer_size)
@@ -148,7 +164,7 @@ impl nyquest_interface::blocking::BlockingResponse for CurlResponse {
         {
             return Err(NyquestError::ResponseTooLarge);
         }
-        Ok(buf)
+        Ok(buf.into())
     }
 }
 
@@ -159,7 +175,15 @@ impl nyquest_interface::blocking::BlockingClient for CurlEasyClient {
         let mut handle = self.get_or_create_handle();
         // FIXME: properly concat base_url and url
         let url = concat_url(self.options.base_url.as_deref(), &req.relative_uri);
-        handle.with_handle(|handle| handle.populate_request(&url, req, &self.options))?;
+        handle.with_handle(|handle| {
+            handle.populate_request(
+                &url,
+                req,
+                &self.options,
+                &self.default_headers,
+                &self.dns_cache,
+            )
+        })?;
         // TODO: proper timeouts
         handle.with_handle(|handle| handle.poll_until_response_headers(Duration::from_secs(30)))?;
         let (status, content_length) = handle.with_handle(|handle| {
@@ -172,14 +196,31 @@ impl nyquest_interface::blocking::BlockingClient for CurlEasyClient {
             .filter_map(|line| line.split_once(':'))
             .map(|(k, v)| (k.into(), v.trim_start().into()))
             .collect();
+        let tls_info = handle.with_handle(|handle| handle.take_tls_info());
         Ok(CurlResponse {
             status,
             content_length,
             headers,
+            tls_info,
             handle: handle.into_owned(),
             max_response_buffer_size: self.options.max_response_buffer_size,
         })
     }
+
+    fn preconnect(&self, uri: Cow<'static, str>) -> nyquest_interface::Result<()> {
+        // A CONNECT_ONLY connection would never be reused, so a real transfer is completed
+        let mut res = self.request(Request::head(uri))?;
+        nyquest_interface::blocking::BlockingResponse::bytes(&mut res).map(drop)
+    }
+
+    fn flush_dns_cache(&self) -> nyquest_interface::Result<()> {
+        self.dns_cache.flush();
+        Ok(())
+    }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
 }
 
 impl nyquest_interface::blocking::BlockingBackend for crate::CurlBackend {
@@ -189,6 +230,7 @@ impl nyquest_interface::blocking::BlockingBackend for crate::CurlBackend {
         &self,
         options: nyquest_interface::client::ClientOptions,
     ) -> nyquest_interface::client::BuildClientResult<Self::BlockingClient> {
+        crate::request::check_options(&options)?;
         Ok(CurlEasyClient::new(options))
     }
 }
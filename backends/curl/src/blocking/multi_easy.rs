@@ -1,4 +1,5 @@
 use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -7,10 +8,15 @@ use curl::{
     multi::{EasyHandle, Multi},
 };
 use nyquest_interface::blocking::Request;
-use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
+use nyquest_interface::client::ResponseHeaderLimits;
+use nyquest_interface::{Error as NyquestError, Result as NyquestResult, TlsInfo};
 
 use crate::error::IntoNyquestResult;
-use crate::share::{Share, ShareHandle};
+use crate::push::PushHook;
+use crate::request::{HeaderCounter, RequestResources};
+use crate::share::Share;
+use crate::tls_info::TlsProbe;
+use crate::upkeep::Upkeep;
 
 enum MaybeAttachedEasy {
     Attached(EasyHandle),
@@ -21,8 +27,11 @@ enum MaybeAttachedEasy {
 pub(crate) struct MultiEasy {
     state: Arc<Mutex<MultiEasyState>>,
     easy: MaybeAttachedEasy,
+    resources: RequestResources, // Drop later than easy
     multi: Multi,
-    _share_handle: ShareHandle,
+    push_hook: Box<PushHook>, // Drop later than multi
+    upkeep: Option<Upkeep>,   // Drop earlier than share
+    share: Share,             // Drop later than easy
 }
 
 #[derive(Default)]
@@ -31,6 +40,12 @@ struct MultiEasyState {
     header_finished: bool,
     response_headers_buffer: Vec<Vec<u8>>,
     response_buffer: Vec<u8>,
+    tls_info: Option<TlsInfo>,
+    decompressed: Option<Arc<AtomicU64>>,
+    header_limits: Option<ResponseHeaderLimits>,
+    header_counter: HeaderCounter,
+    /// The error that made a callback abort the transfer.
+    error: Option<NyquestError>,
 }
 
 impl MaybeAttachedEasy {
@@ -93,22 +108,28 @@ impl MaybeAttachedEasy {
 impl MultiEasy {
     pub fn new(share: &Share) -> Self {
         let state = Arc::new(Mutex::new(MultiEasyState::default()));
-        let share_handle = share.get_handle(); // Drop later than easy
         let mut easy = Easy::new();
         unsafe { share.bind_easy(&mut easy) }.expect("bind easy to share");
         easy.header_function({
             let state = state.clone();
+            let tls_probe = TlsProbe::new(easy.raw());
             move |h| {
                 let mut state = state.lock().unwrap();
+                let state = &mut *state;
                 if h == b"\r\n" {
                     let is_redirect = [301, 302, 303, 307, 308].contains(&state.temp_status_code);
                     if !is_redirect {
                         state.header_finished = true;
+                        // The connection may be detached from the transfer once it completes
+                        state.tls_info = unsafe { tls_probe.tls_info() };
                     }
                 } else if h.contains(&b':') {
-                    state
-                        .response_headers_buffer
-                        .push(h.strip_suffix(b"\r\n").unwrap_or(h).into());
+                    let line = h.strip_suffix(b"\r\n").unwrap_or(h);
+                    if let Err(err) = state.header_counter.add(state.header_limits.as_ref(), line) {
+                        state.error = Some(err);
+                        return false;
+                    }
+                    state.response_headers_buffer.push(line.into());
                 } else if let Some(status) = h
                     .split(u8::is_ascii_whitespace)
                     .nth(1)
@@ -116,6 +137,7 @@ impl MultiEasy {
                     .and_then(|s| s.parse().ok())
                 {
                     state.temp_status_code = status;
+                    state.header_counter.reset();
                 }
 
                 true
@@ -126,6 +148,9 @@ impl MultiEasy {
             let state = state.clone();
             move |f| {
                 let mut state = state.lock().unwrap();
+                if let Some(decompressed) = &state.decompressed {
+                    decompressed.fetch_add(f.len() as u64, Ordering::Relaxed);
+                }
                 state.header_finished = true;
                 // TODO: handle max response buffer size
                 state.response_buffer.extend_from_slice(f);
@@ -138,8 +163,11 @@ impl MultiEasy {
         MultiEasy {
             state,
             multi,
+            push_hook: PushHook::new(),
+            upkeep: None,
             easy: MaybeAttachedEasy::Detached(easy),
-            _share_handle: share_handle,
+            resources: Default::default(),
+            share: share.clone(),
         }
     }
 
@@ -161,6 +189,10 @@ impl MultiEasy {
                 .get_timeout()
                 .into_nyquest_result("multi_easy curl_multi_timeout")?
                 .unwrap_or(Duration::from_secs(1));
+            let suggested_timeout = match &mut self.upkeep {
+                Some(upkeep) => suggested_timeout.min(upkeep.perform()),
+                None => suggested_timeout,
+            };
             let multi_res = self
                 .multi
                 .wait(&mut [], suggested_timeout)
@@ -174,9 +206,12 @@ impl MultiEasy {
             self.multi.messages(|msg| match msg.result_for(easy) {
                 Some(Ok(())) => res = ControlFlow::Break(Ok(())),
                 Some(Err(err)) => {
-                    res = ControlFlow::Break(
-                        Err(err).into_nyquest_result("multi_easy curl_multi_info_read cb"),
-                    )
+                    let abort_reason = self.state.lock().unwrap().error.take();
+                    let abort_reason = abort_reason.or_else(|| self.resources.abort_reason.take());
+                    res = ControlFlow::Break(match abort_reason {
+                        Some(err) => Err(err),
+                        None => Err(err).into_nyquest_result("multi_easy curl_multi_info_read cb"),
+                    })
                 }
                 None => {}
             });
@@ -208,12 +243,31 @@ impl MultiEasy {
         url: &str,
         req: Request,
         options: &nyquest_interface::client::ClientOptions,
+        default_headers: &crate::header_list::DefaultHeaders,
+        dns_cache: &crate::dns_cache::DnsCache,
     ) -> NyquestResult<()> {
         self.reset_state();
         let easy = self.easy.detach(&mut self.multi)?;
         easy.reset();
+        // curl_easy_reset also clears CURLOPT_SHARE
+        unsafe { self.share.bind_easy(easy) }?;
         *self.state.lock().unwrap() = Default::default();
-        crate::request::populate_request(url, &req, options, easy)
+        // The previous resources are only released after the handle is reset
+        self.resources =
+            crate::request::populate_request(url, &req, options, default_headers, dns_cache, easy)?;
+        self.push_hook
+            .set(&self.multi, easy.raw(), self.resources.push.clone());
+        if self.upkeep.is_none() {
+            // Safety: the share is dropped later
+            self.upkeep = self
+                .resources
+                .upkeep_interval
+                .and_then(|interval| unsafe { Upkeep::new(&self.share, interval) });
+        }
+        let mut state = self.state.lock().unwrap();
+        state.decompressed = self.resources.decompressed.clone();
+        state.header_limits = self.resources.header_limits;
+        Ok(())
     }
 
     pub fn status(&mut self) -> NyquestResult<u16> {
@@ -250,6 +304,10 @@ impl MultiEasy {
         std::mem::take(&mut self.state.lock().unwrap().response_buffer)
     }
 
+    pub fn take_tls_info(&mut self) -> Option<TlsInfo> {
+        self.state.lock().unwrap().tls_info.take()
+    }
+
     pub fn take_response_headers_buffer(&mut self) -> Vec<Vec<u8>> {
         std::mem::take(&mut self.state.lock().unwrap().response_headers_buffer)
     }
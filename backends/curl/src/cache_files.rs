@@ -0,0 +1,77 @@
+use std::ffi::CString;
+use std::os::raw::c_long;
+use std::path::Path;
+
+use curl::easy::Easy;
+use curl_sys::{curl_easy_setopt, CURLoption, CURLE_OK, CURLOPTTYPE_LONG, CURLOPTTYPE_OBJECTPOINT};
+
+use crate::error::IntoNyquestResult;
+use crate::features::FEATURES;
+
+const CURLOPT_ALTSVC_CTRL: CURLoption = CURLOPTTYPE_LONG + 286;
+const CURLOPT_ALTSVC: CURLoption = CURLOPTTYPE_OBJECTPOINT + 287;
+const CURLOPT_HSTS_CTRL: CURLoption = CURLOPTTYPE_LONG + 299;
+const CURLOPT_HSTS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 300;
+const CURLALTSVC_H1: c_long = 1 << 3;
+const CURLALTSVC_H2: c_long = 1 << 4;
+const CURLALTSVC_H3: c_long = 1 << 5;
+const CURLHSTS_ENABLE: c_long = 1 << 0;
+
+/// Enables `Alt-Svc` handling on the easy handle, optionally backed by a cache file.
+///
+/// Does nothing if libcurl is built without `Alt-Svc` support.
+pub(crate) fn set_alt_svc(
+    easy: &mut Easy,
+    cache: Option<&Path>,
+    allow_h3: bool,
+) -> nyquest_interface::Result<()> {
+    if !FEATURES.altsvc {
+        return Ok(());
+    }
+    let mut ctrl = CURLALTSVC_H1 | CURLALTSVC_H2;
+    if allow_h3 {
+        ctrl |= CURLALTSVC_H3;
+    }
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_ALTSVC_CTRL, ctrl) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_ALTSVC_CTRL");
+    }
+    if let Some(cache) = cache {
+        set_cache_file(easy, CURLOPT_ALTSVC, cache, "set CURLOPT_ALTSVC")?;
+    }
+    Ok(())
+}
+
+/// Enables HSTS handling on the easy handle, backed by the given cache file.
+///
+/// Does nothing if libcurl is built without HSTS support.
+pub(crate) fn set_hsts(easy: &mut Easy, cache: &Path) -> nyquest_interface::Result<()> {
+    if !FEATURES.hsts {
+        return Ok(());
+    }
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_HSTS_CTRL, CURLHSTS_ENABLE) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_HSTS_CTRL");
+    }
+    set_cache_file(easy, CURLOPT_HSTS, cache, "set CURLOPT_HSTS")
+}
+
+fn set_cache_file(
+    easy: &mut Easy,
+    option: CURLoption,
+    path: &Path,
+    ctx: &str,
+) -> nyquest_interface::Result<()> {
+    let path = CString::new(path.as_os_str().as_encoded_bytes()).map_err(|_| {
+        nyquest_interface::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "cache file path contains a nul byte",
+        ))
+    })?;
+    // libcurl copies the string
+    let res = unsafe { curl_easy_setopt(easy.raw(), option, path.as_ptr()) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result(ctx);
+    }
+    Ok(())
+}
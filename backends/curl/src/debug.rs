@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+use curl::easy::InfoType;
+use nyquest_interface::client::RedactionPolicy;
+
+/// Emits a line of verbose output as a `tracing` event with the `tracing` feature, or as a `log`
+/// record otherwise.
+macro_rules! emit {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        log::$level!($($arg)+);
+    }};
+}
+
+/// Forwards libcurl verbose output to `log` or `tracing`, redacted according to the policy.
+pub(crate) fn log_debug(policy: &RedactionPolicy, kind: InfoType, data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    match kind {
+        InfoType::Text => emit!(debug, "* {}", text.trim_end()),
+        InfoType::HeaderIn => {
+            for line in text.lines().filter(|l| !l.is_empty()) {
+                emit!(debug, "< {}", redact_header_line(policy, line));
+            }
+        }
+        InfoType::HeaderOut => {
+            for line in text.lines().filter(|l| !l.is_empty()) {
+                emit!(debug, "> {}", redact_header_line(policy, line));
+            }
+        }
+        InfoType::DataIn if policy.log_bodies => emit!(trace, "< {text}"),
+        InfoType::DataOut if policy.log_bodies => emit!(trace, "> {text}"),
+        InfoType::DataIn => emit!(trace, "< [{} bytes data]", data.len()),
+        InfoType::DataOut => emit!(trace, "> [{} bytes data]", data.len()),
+        _ => {}
+    }
+}
+
+/// Redacts the value of a header line, or the query of a request or status line.
+fn redact_header_line<'a>(policy: &RedactionPolicy, line: &'a str) -> Cow<'a, str> {
+    if let Some((method, rest)) = line.split_once(' ') {
+        if let Some((target, version)) = rest.rsplit_once(' ') {
+            if version.starts_with("HTTP/") && !method.ends_with(':') {
+                return match policy.redact_url(target) {
+                    Cow::Borrowed(_) => Cow::Borrowed(line),
+                    Cow::Owned(target) => Cow::Owned(format!("{method} {target} {version}")),
+                };
+            }
+        }
+    }
+    match line.split_once(':') {
+        Some((name, _)) if policy.redacts_header(name.trim()) => {
+            Cow::Owned(format!("{}: {}", name, RedactionPolicy::REDACTED))
+        }
+        _ => Cow::Borrowed(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_header_line() {
+        let policy = RedactionPolicy {
+            denied_query_params: vec!["token".into()],
+            ..Default::default()
+        };
+        let testcases = [
+            ("GET / HTTP/1.1", "GET / HTTP/1.1"),
+            ("HTTP/1.1 200 OK", "HTTP/1.1 200 OK"),
+            (
+                "GET /a?token=abc&b=1 HTTP/1.1",
+                "GET /a?token=[REDACTED]&b=1 HTTP/1.1",
+            ),
+            ("Host: example.com", "Host: example.com"),
+            ("Authorization: Bearer abc", "Authorization: [REDACTED]"),
+            ("cookie: a=1; b=2", "cookie: [REDACTED]"),
+            ("Set-Cookie: a=1", "Set-Cookie: [REDACTED]"),
+            (
+                "Proxy-Authorization: Basic abc",
+                "Proxy-Authorization: [REDACTED]",
+            ),
+            ("X-Cookie-Note: a=1", "X-Cookie-Note: a=1"),
+        ];
+        for (line, expected) in testcases {
+            assert_eq!(redact_header_line(&policy, line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_redact_header_line_allowlist() {
+        let policy = RedactionPolicy {
+            allowed_headers: Some(vec!["content-type".into(), "authorization".into()]),
+            ..Default::default()
+        };
+        let testcases = [
+            ("Content-Type: text/plain", "Content-Type: text/plain"),
+            ("Authorization: Bearer abc", "Authorization: [REDACTED]"),
+            ("X-Request-Id: 42", "X-Request-Id: [REDACTED]"),
+        ];
+        for (line, expected) in testcases {
+            assert_eq!(redact_header_line(&policy, line), expected, "{line}");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use nyquest_interface::client::ClientOptions;
+
+/// The default of `CURLOPT_DNS_CACHE_TIMEOUT`.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Controls how long transfers of a client reuse the addresses in the shared DNS cache.
+///
+/// libcurl is not able to flush the DNS cache of a share. Instead, transfers after a flush are
+/// given a timeout shorter than the time since the flush, so that they treat older addresses as
+/// expired and look the host names up again.
+pub(crate) struct DnsCache {
+    timeout: Option<Duration>,
+    flushed_at: Mutex<Option<Instant>>,
+}
+
+impl DnsCache {
+    pub(crate) fn new(options: &ClientOptions) -> Self {
+        Self {
+            timeout: options.dns_cache_timeout,
+            flushed_at: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn flush(&self) {
+        *self.flushed_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns the timeout for the next transfer, or `None` to keep the default.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        match *self.flushed_at.lock().unwrap() {
+            // Rounded down, as libcurl only takes whole seconds
+            Some(flushed_at) if flushed_at.elapsed() < timeout => {
+                Some(Duration::from_secs(flushed_at.elapsed().as_secs()))
+            }
+            _ => self.timeout,
+        }
+    }
+}
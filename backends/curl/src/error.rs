@@ -1,5 +1,3 @@
-use std::io::ErrorKind;
-
 use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
 
 pub(crate) trait IntoNyquestResult<T> {
@@ -9,19 +7,11 @@ pub(crate) trait IntoNyquestResult<T> {
 impl<T> IntoNyquestResult<T> for Result<T, curl::Error> {
     fn into_nyquest_result(self, ctx: &str) -> NyquestResult<T> {
         // TODO: proper error mapping
-        if self
-            .as_ref()
-            .err()
-            .map(|e| e.is_operation_timedout())
-            .is_some()
-        {
+        if self.as_ref().is_err_and(|e| e.is_operation_timedout()) {
             return Err(NyquestError::RequestTimeout);
         }
         Ok(self.map_err(|e| {
-            std::io::Error::new(
-                ErrorKind::Other,
-                format!("curl error:{}:{}", ctx, e.description()),
-            )
+            std::io::Error::other(format!("curl error:{}:{}", ctx, e.description()))
         })?)
     }
 }
@@ -30,10 +20,7 @@ impl<T> IntoNyquestResult<T> for Result<T, curl::MultiError> {
     fn into_nyquest_result(self, ctx: &str) -> NyquestResult<T> {
         // TODO: proper error mapping
         Ok(self.map_err(|e| {
-            std::io::Error::new(
-                ErrorKind::Other,
-                format!("curl multi error:{}:{}", ctx, e.description()),
-            )
+            std::io::Error::other(format!("curl multi error:{}:{}", ctx, e.description()))
         })?)
     }
 }
@@ -42,10 +29,7 @@ impl<T> IntoNyquestResult<T> for Result<T, curl::ShareError> {
     fn into_nyquest_result(self, ctx: &str) -> NyquestResult<T> {
         // TODO: proper error mapping
         Ok(self.map_err(|e| {
-            std::io::Error::new(
-                ErrorKind::Other,
-                format!("curl share error:{}:{}", ctx, e.description()),
-            )
+            std::io::Error::other(format!("curl share error:{}:{}", ctx, e.description()))
         })?)
     }
 }
@@ -0,0 +1,88 @@
+//! Extensions specific to the libcurl backend, beyond the interface of nyquest.
+
+use std::io;
+use std::time::Duration;
+
+use curl::easy::Easy;
+use curl::multi::{Multi, Socket, WaitFd};
+use curl_sys::{curl_easy_getinfo, CURLE_OK, CURLINFO};
+
+const CURLINFO_ACTIVESOCKET: CURLINFO = 0x500000 + 44;
+
+/// How long to wait for the socket at once, so that a stuck wait is retried.
+const WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A raw connection to a host, established by libcurl, to speak a protocol of one's own over it.
+///
+/// Reads and writes block until the socket is ready. With `https` URLs, the data is encrypted
+/// with TLS.
+pub struct RawTunnel {
+    easy: Easy,
+    socket: Socket,
+    multi: Multi,
+}
+
+/// Connects to the host and port of the URL, performing the TLS handshake for `https` URLs, and
+/// returns the connection without sending anything.
+///
+/// This is an escape hatch for protocols that start as HTTP and then switch to something else,
+/// such as `Upgrade` requests or `CONNECT` tunnels, where the request is written by hand and the
+/// rest of the exchange is up to the caller. The path of the URL is ignored.
+pub fn raw_tunnel(url: &str, connect_timeout: Option<Duration>) -> io::Result<RawTunnel> {
+    let mut easy = Easy::new();
+    easy.url(url)?;
+    easy.connect_only(true)?;
+    if let Some(timeout) = connect_timeout {
+        easy.connect_timeout(timeout)?;
+    }
+    easy.perform()?;
+    let mut socket: Socket = 0 as _;
+    let res = unsafe { curl_easy_getinfo(easy.raw(), CURLINFO_ACTIVESOCKET, &mut socket) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res).into());
+    }
+    Ok(RawTunnel {
+        easy,
+        socket,
+        multi: Multi::new(),
+    })
+}
+
+impl RawTunnel {
+    /// Blocks until the socket is readable, or writable if `write` is set.
+    fn wait(&self, write: bool) -> io::Result<()> {
+        let mut fd = WaitFd::new();
+        fd.set_fd(self.socket);
+        fd.poll_on_read(!write).poll_on_write(write);
+        self.multi
+            .wait(&mut [fd], WAIT_INTERVAL)
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+impl io::Read for RawTunnel {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.easy.recv(buf) {
+                Err(e) if e.is_again() => self.wait(false)?,
+                res => return Ok(res?),
+            }
+        }
+    }
+}
+
+impl io::Write for RawTunnel {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        loop {
+            match self.easy.send(buf) {
+                Err(e) if e.is_again() => self.wait(true)?,
+                res => return Ok(res?),
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
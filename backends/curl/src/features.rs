@@ -0,0 +1,48 @@
+use std::sync::LazyLock;
+
+/// Features of the libcurl linked at runtime, which may be older or built with fewer features
+/// than the headers `curl-sys` was compiled against.
+pub(crate) struct Features {
+    pub http2: bool,
+    pub http3: bool,
+    pub brotli: bool,
+    pub zstd: bool,
+    pub unix_sockets: bool,
+    pub https_proxy: bool,
+    pub altsvc: bool,
+    pub hsts: bool,
+    pub spnego: bool,
+    /// Whether `CURLSSLOPT_EARLYDATA` is known, since libcurl 8.11.0.
+    pub early_data: bool,
+}
+
+pub(crate) static FEATURES: LazyLock<Features> = LazyLock::new(|| {
+    let version = curl::Version::get();
+    let features = Features {
+        http2: version.feature_http2(),
+        http3: version.feature_http3(),
+        brotli: version.feature_brotli(),
+        zstd: version.feature_zstd(),
+        unix_sockets: version.feature_unix_domain_socket(),
+        https_proxy: version.feature_https_proxy(),
+        altsvc: version.feature_altsvc(),
+        hsts: version.feature_hsts(),
+        spnego: version.feature_spnego(),
+        early_data: version.version_num() >= 0x080b00,
+    };
+    log::debug!(
+        "libcurl {}: http2={} http3={} brotli={} zstd={} unix_sockets={} https_proxy={} altsvc={} hsts={} spnego={} early_data={}",
+        version.version(),
+        features.http2,
+        features.http3,
+        features.brotli,
+        features.zstd,
+        features.unix_sockets,
+        features.https_proxy,
+        features.altsvc,
+        features.hsts,
+        features.spnego,
+        features.early_data,
+    );
+    features
+});
@@ -0,0 +1,134 @@
+use std::ffi::CString;
+use std::io;
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::Arc;
+
+use curl::easy::Easy;
+use curl_sys::{curl_easy_setopt, curl_slist, CURLE_OK, CURLOPT_HTTPHEADER};
+
+use nyquest_interface::HeaderMap;
+
+use crate::error::IntoNyquestResult;
+
+/// A header list for `CURLOPT_HTTPHEADER`, whose nodes are allocated here instead of by
+/// `curl_slist_append`, so that lines can be shared between lists.
+///
+/// libcurl never frees the list, and only reads it while a transfer is performed.
+pub(crate) struct HeaderList {
+    /// Lines of the client's default headers, shared by the lists of all its requests, along
+    /// with the lengths of their names.
+    defaults: Arc<[(usize, CString)]>,
+    /// Lines specific to the request.
+    _lines: Vec<CString>,
+    nodes: Box<[curl_slist]>,
+}
+
+// The nodes only point to the lines owned by the list, which are never modified
+unsafe impl Send for HeaderList {}
+unsafe impl Sync for HeaderList {}
+
+impl HeaderList {
+    fn new(defaults: Arc<[(usize, CString)]>, lines: Vec<CString>, headers: &HeaderMap) -> Self {
+        let mut nodes = defaults
+            .iter()
+            .filter(|(name_len, line)| {
+                let name = &line.to_bytes()[..*name_len];
+                // Headers set on the request replace the defaults
+                !std::str::from_utf8(name).is_ok_and(|name| headers.contains(name))
+            })
+            .map(|(_, line)| line)
+            .chain(&lines)
+            .map(|line| curl_slist {
+                data: line.as_ptr() as *mut c_char,
+                next: ptr::null_mut(),
+            })
+            .collect::<Box<[_]>>();
+        let base = nodes.as_mut_ptr();
+        for i in 1..nodes.len() {
+            unsafe { (*base.add(i - 1)).next = base.add(i) };
+        }
+        Self {
+            defaults,
+            _lines: lines,
+            nodes,
+        }
+    }
+
+    /// Sets the list as `CURLOPT_HTTPHEADER`, which must be kept alive until the handle is reset
+    /// or dropped.
+    pub(crate) fn apply(&self, easy: &mut Easy) -> nyquest_interface::Result<()> {
+        let head = self
+            .nodes
+            .first()
+            .map_or(ptr::null(), |node| node as *const curl_slist);
+        let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_HTTPHEADER, head) };
+        if res != CURLE_OK {
+            return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_HTTPHEADER");
+        }
+        Ok(())
+    }
+}
+
+/// Default header lines of a client, formatted once and shared by the header lists of all its
+/// requests.
+pub struct DefaultHeaders {
+    /// The list for requests without headers of their own.
+    list: Arc<HeaderList>,
+}
+
+impl DefaultHeaders {
+    /// Formats the default headers, which must have passed [`check_default_headers`].
+    pub fn new(options: &nyquest_interface::client::ClientOptions) -> Self {
+        let lines = options
+            .default_headers
+            .iter()
+            .map(|(name, value)| {
+                let line = CString::new(format!("{}: {}", name, value))
+                    .expect("default headers are checked when the client is built");
+                (name.len(), line)
+            })
+            .collect();
+        Self {
+            list: Arc::new(HeaderList::new(lines, vec![], &HeaderMap::new())),
+        }
+    }
+
+    /// Returns the list of the default headers followed by the lines of the request, leaving out
+    /// the default headers that the request sets itself.
+    pub(crate) fn with_lines(
+        &self,
+        lines: Vec<CString>,
+        headers: &HeaderMap,
+    ) -> nyquest_interface::Result<Arc<HeaderList>> {
+        if lines.is_empty() {
+            return Ok(self.list.clone());
+        }
+        Ok(Arc::new(HeaderList::new(
+            self.list.defaults.clone(),
+            lines,
+            headers,
+        )))
+    }
+}
+
+/// Rejects default headers that cannot be passed to libcurl, which would fail every request.
+pub(crate) fn check_default_headers(headers: &HeaderMap) -> nyquest_interface::Result<()> {
+    match headers
+        .iter()
+        .find(|(name, value)| name.contains('\0') || value.contains('\0'))
+    {
+        Some((name, _)) => Err(nyquest_interface::Error::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("default header {name} contains a nul byte"),
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Formats a header line for [`DefaultHeaders::with_lines`].
+pub(crate) fn header_line(line: String, ctx: &str) -> nyquest_interface::Result<CString> {
+    CString::new(line)
+        .map_err(curl::Error::from)
+        .into_nyquest_result(ctx)
+}
@@ -11,12 +11,28 @@ mod r#async;
 #[cfg(feature = "blocking")]
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 mod blocking;
+mod cache_files;
+mod debug;
+mod dns_cache;
 mod error;
+pub mod ext;
+mod features;
+mod header_list;
+#[cfg(feature = "multipart")]
+mod mime;
+mod network;
+mod progress;
+mod push;
 mod request;
 mod share;
+mod tls_info;
+mod upkeep;
 mod url;
 mod urlencoded;
 
+/// Maximum number of idle easy handles kept by each client for reuse.
+const MAX_IDLE_EASY_HANDLES: usize = 8;
+
 /// The backend implementation using libcurl.
 pub struct CurlBackend;
 
@@ -0,0 +1,170 @@
+use std::ffi::{c_char, c_int, CString};
+use std::io;
+use std::ptr::{self, NonNull};
+
+use curl::easy::Easy;
+use curl_sys::{
+    curl_easy_setopt, curl_slist, curl_slist_append, curl_slist_free_all, CURLcode, CURLoption,
+    CURL, CURLE_OK, CURLOPTTYPE_OBJECTPOINT,
+};
+use nyquest_interface::{Part, PartBody};
+
+use crate::error::IntoNyquestResult;
+
+const CURLOPT_MIMEPOST: CURLoption = CURLOPTTYPE_OBJECTPOINT + 269;
+
+#[repr(C)]
+struct curl_mime {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct curl_mimepart {
+    _private: [u8; 0],
+}
+
+// Not bound by curl-sys
+extern "C" {
+    fn curl_mime_init(easy: *mut CURL) -> *mut curl_mime;
+    fn curl_mime_free(mime: *mut curl_mime);
+    fn curl_mime_addpart(mime: *mut curl_mime) -> *mut curl_mimepart;
+    fn curl_mime_name(part: *mut curl_mimepart, name: *const c_char) -> CURLcode;
+    fn curl_mime_filename(part: *mut curl_mimepart, filename: *const c_char) -> CURLcode;
+    fn curl_mime_type(part: *mut curl_mimepart, mimetype: *const c_char) -> CURLcode;
+    fn curl_mime_data(part: *mut curl_mimepart, data: *const c_char, datasize: usize) -> CURLcode;
+    fn curl_mime_filedata(part: *mut curl_mimepart, filename: *const c_char) -> CURLcode;
+    fn curl_mime_headers(
+        part: *mut curl_mimepart,
+        headers: *mut curl_slist,
+        take_ownership: c_int,
+    ) -> CURLcode;
+}
+
+/// A multipart body built with the mime API of libcurl.
+///
+/// It must outlive the transfer of the easy handle it is attached to, until the handle is reset
+/// or dropped.
+pub(crate) struct Mime(NonNull<curl_mime>);
+
+// Safety: the mime structure is only accessed through the easy handle it is attached to
+unsafe impl Send for Mime {}
+
+impl Drop for Mime {
+    fn drop(&mut self) {
+        unsafe { curl_mime_free(self.0.as_ptr()) }
+    }
+}
+
+fn check(res: CURLcode, ctx: &str) -> nyquest_interface::Result<()> {
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result(ctx);
+    }
+    Ok(())
+}
+
+fn to_cstring(s: impl Into<Vec<u8>>) -> nyquest_interface::Result<CString> {
+    CString::new(s).map_err(|_| {
+        nyquest_interface::Error::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "multipart field contains a nul byte",
+        ))
+    })
+}
+
+impl Mime {
+    /// Builds the parts into a mime structure and attaches it to the easy handle.
+    pub fn attach<S>(easy: &mut Easy, parts: &[Part<S>]) -> nyquest_interface::Result<Self> {
+        let mime = NonNull::new(unsafe { curl_mime_init(easy.raw()) })
+            .map(Mime)
+            .ok_or_else(|| {
+                nyquest_interface::Error::Io(io::Error::other("curl_mime_init failed"))
+            })?;
+        for part in parts {
+            mime.add_part(part)?;
+        }
+        check(
+            unsafe { curl_easy_setopt(easy.raw(), CURLOPT_MIMEPOST, mime.0.as_ptr()) },
+            "set CURLOPT_MIMEPOST",
+        )?;
+        Ok(mime)
+    }
+
+    fn add_part<S>(&self, part: &Part<S>) -> nyquest_interface::Result<()> {
+        let mimepart = unsafe { curl_mime_addpart(self.0.as_ptr()) };
+        if mimepart.is_null() {
+            return Err(nyquest_interface::Error::Io(io::Error::other(
+                "curl_mime_addpart failed",
+            )));
+        }
+        // All the setters below copy the strings
+        let name = to_cstring(part.name.as_bytes())?;
+        check(
+            unsafe { curl_mime_name(mimepart, name.as_ptr()) },
+            "curl_mime_name",
+        )?;
+        match &part.body {
+            PartBody::Bytes { content } => check(
+                unsafe { curl_mime_data(mimepart, content.as_ptr() as _, content.len()) },
+                "curl_mime_data",
+            )?,
+            PartBody::File { path } => {
+                // Fail early instead of sending a truncated body
+                std::fs::metadata(path)?;
+                let path = to_cstring(path.as_os_str().as_encoded_bytes())?;
+                // libcurl reads the file while sending, and takes its size as the part length
+                check(
+                    unsafe { curl_mime_filedata(mimepart, path.as_ptr()) },
+                    "curl_mime_filedata",
+                )?;
+            }
+            PartBody::Stream(_) => {
+                return Err(nyquest_interface::Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "unsupported body type",
+                )));
+            }
+        }
+        // Overrides the file name implied by curl_mime_filedata
+        let filename = part
+            .filename
+            .as_deref()
+            .map(|filename| to_cstring(filename.as_bytes()))
+            .transpose()?;
+        if filename.is_some() || matches!(part.body, PartBody::File { .. }) {
+            check(
+                unsafe {
+                    curl_mime_filename(
+                        mimepart,
+                        filename.as_ref().map_or(ptr::null(), |f| f.as_ptr()),
+                    )
+                },
+                "curl_mime_filename",
+            )?;
+        }
+        let content_type = to_cstring(part.content_type.as_bytes())?;
+        check(
+            unsafe { curl_mime_type(mimepart, content_type.as_ptr()) },
+            "curl_mime_type",
+        )?;
+        if !part.headers.is_empty() {
+            let mut list: *mut curl_slist = ptr::null_mut();
+            for (name, value) in &part.headers {
+                let line = to_cstring(format!("{}: {}", name, value))?;
+                let new_list = unsafe { curl_slist_append(list, line.as_ptr()) };
+                if new_list.is_null() {
+                    unsafe { curl_slist_free_all(list) };
+                    return Err(nyquest_interface::Error::Io(io::Error::other(
+                        "multipart header curl_slist_append failed",
+                    )));
+                }
+                list = new_list;
+            }
+            // The part takes ownership of the list
+            check(
+                unsafe { curl_mime_headers(mimepart, list, 1) },
+                "curl_mime_headers",
+            )?;
+        }
+        Ok(())
+    }
+}
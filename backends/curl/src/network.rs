@@ -0,0 +1,65 @@
+use nyquest_interface::NetworkStatus;
+
+/// Route flags of the kernel.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const RTF_UP: u32 = 0x0001;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const RTF_REJECT: u32 = 0x0200;
+
+/// Looks for a default route over an interface that is up, which is what the system has as a path
+/// to the internet. libcurl has no notion of the network, so other platforms are not supported.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn network_status() -> NetworkStatus {
+    use std::fs;
+
+    let Ok(ipv4_routes) = fs::read_to_string("/proc/net/route") else {
+        return NetworkStatus::Unknown;
+    };
+    // Iface Destination Gateway Flags ... Mask ...
+    let ipv4_defaults = ipv4_routes.lines().skip(1).filter_map(|line| {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (&iface, &dest, &flags, &mask) = (
+            fields.first()?,
+            fields.get(1)?,
+            fields.get(3)?,
+            fields.get(7)?,
+        );
+        (dest == "00000000" && mask == "00000000").then_some((iface, flags))
+    });
+    // Destination PrefixLength Source PrefixLength NextHop Metric RefCount Use Flags Iface
+    let ipv6_routes = fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+    let ipv6_defaults = ipv6_routes.lines().filter_map(|line| {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (&dest, &prefix_len, &flags, &iface) = (
+            fields.first()?,
+            fields.get(1)?,
+            fields.get(8)?,
+            fields.get(9)?,
+        );
+        (dest.bytes().all(|b| b == b'0') && prefix_len == "00").then_some((iface, flags))
+    });
+    let online = ipv4_defaults.chain(ipv6_defaults).any(|(iface, flags)| {
+        let flags = u32::from_str_radix(flags, 16).unwrap_or_default();
+        flags & RTF_UP != 0 && flags & RTF_REJECT == 0 && is_interface_up(iface)
+    });
+    if online {
+        NetworkStatus::Online
+    } else {
+        NetworkStatus::Offline
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub(crate) fn network_status() -> NetworkStatus {
+    NetworkStatus::Unknown
+}
+
+/// Returns whether the interface has a carrier. Tunnels report an unknown state when they are up.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn is_interface_up(iface: &str) -> bool {
+    if iface == "lo" {
+        return false;
+    }
+    std::fs::read_to_string(format!("/sys/class/net/{iface}/operstate"))
+        .is_ok_and(|state| matches!(state.trim(), "up" | "unknown"))
+}
@@ -0,0 +1,211 @@
+use std::ffi::{c_int, c_void};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use curl::easy::Easy;
+use curl_sys::{
+    curl_easy_setopt, curl_off_t, CURLoption, CURLE_OK, CURLOPTTYPE_FUNCTIONPOINT,
+    CURLOPT_PROGRESSDATA,
+};
+use nyquest_interface::client::DecompressionLimits;
+use nyquest_interface::{Error as NyquestError, Progress, ProgressCallback};
+
+use crate::error::IntoNyquestResult;
+
+const CURLOPT_XFERINFOFUNCTION: CURLoption = CURLOPTTYPE_FUNCTIONPOINT + 219;
+const CURLOPT_XFERINFODATA: CURLoption = CURLOPT_PROGRESSDATA;
+
+/// Decides which progress updates are reported, so that the callback is not invoked for every
+/// chunk transferred.
+struct Throttle {
+    min_interval: Duration,
+    last: Option<(Instant, Progress)>,
+}
+
+impl Throttle {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: None,
+        }
+    }
+
+    fn should_report(&mut self, progress: Progress, now: Instant) -> bool {
+        let report = match &self.last {
+            None => true,
+            Some((_, last)) if *last == progress => false,
+            // Completing either direction is always reported
+            Some((_, last)) if completed(&progress) != completed(last) => true,
+            Some((at, _)) => now.duration_since(*at) >= self.min_interval,
+        };
+        if report {
+            self.last = Some((now, progress));
+        }
+        report
+    }
+}
+
+fn completed(progress: &Progress) -> (bool, bool) {
+    (
+        progress.upload_total == Some(progress.uploaded),
+        progress.download_total == Some(progress.downloaded),
+    )
+}
+
+fn to_progress(
+    dltotal: curl_off_t,
+    dlnow: curl_off_t,
+    ultotal: curl_off_t,
+    ulnow: curl_off_t,
+) -> Progress {
+    // libcurl reports 0 for unknown totals
+    let total = |t: curl_off_t| (t > 0).then_some(t as u64);
+    Progress {
+        uploaded: ulnow as u64,
+        upload_total: total(ultotal),
+        downloaded: dlnow as u64,
+        download_total: total(dltotal),
+    }
+}
+
+/// Why the progress function aborted the transfer, which libcurl only reports as
+/// `CURLE_ABORTED_BY_CALLBACK`.
+#[derive(Clone, Default)]
+pub(crate) struct AbortReason(Arc<Mutex<Option<NyquestError>>>);
+
+impl AbortReason {
+    fn set(&self, err: NyquestError) {
+        *self.0.lock().unwrap() = Some(err);
+    }
+
+    /// Takes the error to fail the transfer with instead of the one reported by libcurl.
+    pub(crate) fn take(&self) -> Option<NyquestError> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// State of the transfer info function of an easy handle, which must be kept alive until the
+/// handle is reset or dropped.
+pub(crate) struct ProgressFunction {
+    reporter: Option<(ProgressCallback, Throttle)>,
+    limits: Option<(DecompressionLimits, Arc<AtomicU64>)>,
+    abort: AbortReason,
+}
+
+impl ProgressFunction {
+    /// Returns the counter of decompressed bytes that the write function must add to when there
+    /// are limits. The downloaded size reported by libcurl is the one before decompression.
+    pub(crate) fn decompressed(&self) -> Option<Arc<AtomicU64>> {
+        let (_, decompressed) = self.limits.as_ref()?;
+        Some(decompressed.clone())
+    }
+
+    /// Returns whether the transfer is to continue.
+    fn call(
+        &mut self,
+        dltotal: curl_off_t,
+        dlnow: curl_off_t,
+        ultotal: curl_off_t,
+        ulnow: curl_off_t,
+    ) -> bool {
+        if let Some((limits, decompressed)) = &self.limits {
+            if let Err(err) = limits.check(dlnow as u64, decompressed.load(Ordering::Relaxed)) {
+                self.abort.set(err);
+                return false;
+            }
+        }
+        if let Some((progress_callback, throttle)) = &mut self.reporter {
+            let progress = to_progress(dltotal, dlnow, ultotal, ulnow);
+            if throttle.should_report(progress, Instant::now()) {
+                (progress_callback.callback)(progress);
+            }
+        }
+        true
+    }
+}
+
+extern "C" fn xferinfo_function(
+    clientp: *mut c_void,
+    dltotal: curl_off_t,
+    dlnow: curl_off_t,
+    ultotal: curl_off_t,
+    ulnow: curl_off_t,
+) -> c_int {
+    let function = unsafe { &mut *(clientp as *mut ProgressFunction) };
+    // Unwinding into libcurl is not allowed, so a panicking callback aborts the transfer
+    let proceed = panic::catch_unwind(AssertUnwindSafe(|| {
+        function.call(dltotal, dlnow, ultotal, ulnow)
+    }));
+    match proceed {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(_) => {
+            let err = io::Error::other("progress callback panicked");
+            function.abort.set(err.into());
+            1
+        }
+    }
+}
+
+/// Reports the transfer progress of the easy handle to the callback, if any, and aborts the
+/// transfer once the decompression limits are exceeded, recording the reason in `abort`.
+///
+/// `CURLOPT_XFERINFOFUNCTION` is set instead of the deprecated `CURLOPT_PROGRESSFUNCTION` that
+/// [`Easy::progress_function`] uses, so that sizes are reported as integers.
+pub(crate) fn set_progress(
+    easy: &mut Easy,
+    progress: Option<&ProgressCallback>,
+    decompression_limits: Option<&DecompressionLimits>,
+    abort: &AbortReason,
+) -> nyquest_interface::Result<Option<Box<ProgressFunction>>> {
+    if progress.is_none() && decompression_limits.is_none() {
+        easy.progress(false)
+            .into_nyquest_result("set CURLOPT_NOPROGRESS")?;
+        return Ok(None);
+    }
+    let function = Box::new(ProgressFunction {
+        reporter: progress.map(|progress| (progress.clone(), Throttle::new(progress.min_interval))),
+        limits: decompression_limits
+            .cloned()
+            .map(|limits| (limits, Arc::new(AtomicU64::new(0)))),
+        abort: abort.clone(),
+    });
+    let callback: extern "C" fn(_, _, _, _, _) -> _ = xferinfo_function;
+    // The data option is shared with CURLOPT_PROGRESSDATA, which is unused once the function is set
+    let data = &*function as *const ProgressFunction as *mut c_void;
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_XFERINFOFUNCTION, callback) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_XFERINFOFUNCTION");
+    }
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_XFERINFODATA, data) };
+    if res != CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_XFERINFODATA");
+    }
+    easy.progress(true)
+        .into_nyquest_result("set CURLOPT_NOPROGRESS")?;
+    Ok(Some(function))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle() {
+        let mut throttle = Throttle::new(Duration::from_secs(1));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let progress = |uploaded, downloaded| to_progress(100, downloaded, 10, uploaded);
+
+        assert!(throttle.should_report(progress(0, 0), at(0)));
+        assert!(!throttle.should_report(progress(0, 0), at(2000)));
+        assert!(throttle.should_report(progress(5, 0), at(2500)));
+        assert!(throttle.should_report(progress(10, 0), at(2600)));
+        assert!(!throttle.should_report(progress(10, 50), at(3000)));
+        assert!(throttle.should_report(progress(10, 60), at(3600)));
+        assert!(throttle.should_report(progress(10, 100), at(3700)));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use curl::multi::Multi;
+use curl_sys::{curl_multi_setopt, CURL, CURLMOPT_PUSHDATA, CURLMOPT_PUSHFUNCTION};
+use nyquest_interface::client::{PushPolicy, PushPromise};
+
+const CURL_PUSH_DENY: c_int = 1;
+
+pub(crate) type PushCallback = Arc<dyn Fn(&PushPromise) + Send + Sync>;
+
+type PushHeaderByNum = unsafe extern "C" fn(h: *mut c_void, num: usize) -> *mut c_char;
+
+/// `curl_pushheader_bynum`, looked up at runtime since libcurl only has it when built with
+/// HTTP/2.
+static PUSHHEADER_BYNUM: LazyLock<Option<PushHeaderByNum>> = LazyLock::new(|| {
+    #[cfg(unix)]
+    unsafe {
+        let sym = libc::dlsym(libc::RTLD_DEFAULT, c"curl_pushheader_bynum".as_ptr());
+        (!sym.is_null()).then(|| std::mem::transmute::<*mut c_void, PushHeaderByNum>(sym))
+    }
+    #[cfg(not(unix))]
+    None
+});
+
+/// Returns whether push promises can be reported.
+pub(crate) fn is_supported() -> bool {
+    PUSHHEADER_BYNUM.is_some()
+}
+
+/// Returns the callback to report push promises to, if any.
+pub(crate) fn push_callback(policy: &PushPolicy) -> Option<PushCallback> {
+    match policy {
+        PushPolicy::Refuse => None,
+        PushPolicy::Notify(callback) => Some(callback.clone()),
+    }
+}
+
+/// Reports the HTTP/2 push promises on a multi handle to the callbacks of the transfers they
+/// belong to, and refuses the pushes.
+///
+/// The push function is only installed once a transfer asks for the promises, since libcurl
+/// disables pushes in the HTTP/2 settings without one.
+#[derive(Default)]
+pub(crate) struct PushHook {
+    installed: AtomicBool,
+    callbacks: Mutex<HashMap<usize, PushCallback>>,
+}
+
+impl PushHook {
+    /// Creates a hook at a stable address, as libcurl keeps a pointer to it.
+    pub(crate) fn new() -> Box<Self> {
+        Box::default()
+    }
+
+    /// Sets the callback of the transfer of the handle, installing the push function on the
+    /// multi handle when needed. The callback must be unset before the handle is freed.
+    pub(crate) fn set(&self, multi: &Multi, easy: *mut CURL, callback: Option<PushCallback>) {
+        let mut callbacks = self.callbacks.lock().unwrap();
+        let Some(callback) = callback else {
+            callbacks.remove(&(easy as usize));
+            return;
+        };
+        if !self.installed.swap(true, Ordering::Relaxed) {
+            let push_function: extern "C" fn(_, _, _, _, _) -> _ = push_function;
+            unsafe {
+                curl_multi_setopt(multi.raw(), CURLMOPT_PUSHFUNCTION, push_function);
+                curl_multi_setopt(multi.raw(), CURLMOPT_PUSHDATA, self as *const Self);
+            }
+        }
+        callbacks.insert(easy as usize, callback);
+    }
+}
+
+extern "C" fn push_function(
+    parent: *mut CURL,
+    _easy: *mut CURL,
+    num_headers: usize,
+    headers: *mut c_void,
+    userdata: *mut c_void,
+) -> c_int {
+    let hook = unsafe { &*(userdata as *const PushHook) };
+    let callback = hook
+        .callbacks
+        .lock()
+        .unwrap()
+        .get(&(parent as usize))
+        .cloned();
+    if let (Some(callback), Some(bynum)) = (callback, *PUSHHEADER_BYNUM) {
+        let promise = unsafe { read_promise(bynum, num_headers, headers) };
+        // Unwinding into libcurl is not allowed
+        panic::catch_unwind(AssertUnwindSafe(|| callback(&promise))).ok();
+    }
+    CURL_PUSH_DENY
+}
+
+unsafe fn read_promise(
+    bynum: PushHeaderByNum,
+    num_headers: usize,
+    headers: *mut c_void,
+) -> PushPromise {
+    let headers: Vec<(String, String)> = (0..num_headers)
+        .filter_map(|i| {
+            let header = bynum(headers, i);
+            if header.is_null() {
+                return None;
+            }
+            let header = CStr::from_ptr(header).to_string_lossy();
+            // Headers come as `name:value`, where pseudo-header names start with a colon
+            let (name, value) = header
+                .get(1..)?
+                .split_once(':')
+                .map(|(name, value)| (&header[..name.len() + 1], value))?;
+            Some((name.into(), value.trim_start().into()))
+        })
+        .collect();
+    let pseudo_header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_default()
+    };
+    PushPromise {
+        url: format!(
+            "{}://{}{}",
+            pseudo_header(":scheme"),
+            pseudo_header(":authority"),
+            pseudo_header(":path")
+        ),
+        headers,
+    }
+}
@@ -1,20 +1,313 @@
-use std::io::ErrorKind;
+use std::net::IpAddr;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::Duration;
 
-use curl::easy::{Easy, List};
+use std::os::raw::c_long;
+
+use curl::easy::{Easy, HttpVersion as CurlHttpVersion, List};
+use curl_sys::{
+    curl_easy_setopt, CURLoption, CURLE_OK, CURLOPTTYPE_LONG, CURLOPT_SSL_OPTIONS,
+    CURLSSLOPT_NO_REVOKE, CURLSSLOPT_REVOKE_BEST_EFFORT,
+};
+use nyquest_interface::client::{
+    Auth, BuildClientError, BuildClientResult, ClientOptions, HttpVersion, PushPolicy,
+    ResponseHeaderLimits, RevocationPolicy,
+};
 use nyquest_interface::{Body, Method, Request};
 
-use crate::{error::IntoNyquestResult, urlencoded::curl_escape};
+#[cfg(feature = "multipart")]
+use crate::mime::Mime;
+use crate::{
+    cache_files::{set_alt_svc, set_hsts},
+    dns_cache::DnsCache,
+    error::IntoNyquestResult,
+    features::FEATURES,
+    header_list::{check_default_headers, header_line, DefaultHeaders, HeaderList},
+    progress::{set_progress, AbortReason, ProgressFunction},
+    push::{self, push_callback, PushCallback},
+    upkeep::set_upkeep_interval,
+    url::{host_of, host_port_of},
+    urlencoded::encode_form,
+};
+
+const CURLE_NOT_BUILT_IN: curl_sys::CURLcode = 4;
+const CURLSSLOPT_EARLYDATA: c_long = 1 << 7;
+const CURLOPT_SSL_VERIFYSTATUS: CURLoption = CURLOPTTYPE_LONG + 232;
+
+/// Checks whether libcurl supports the options, so that building the client fails instead of
+/// every request.
+pub fn check_options(options: &ClientOptions) -> BuildClientResult<()> {
+    let unsupported_http_version = match options.http_version {
+        Some(HttpVersion::Http2) => !FEATURES.http2,
+        Some(HttpVersion::Http3) => !FEATURES.http3,
+        Some(HttpVersion::Http1_1) | None => false,
+    };
+    if unsupported_http_version {
+        return Err(BuildClientError::Unsupported("http_version"));
+    }
+    if options.proxy.as_deref().is_some_and(is_https_proxy) && !FEATURES.https_proxy {
+        return Err(BuildClientError::Unsupported("proxy"));
+    }
+    if matches!(options.http2_push, PushPolicy::Notify(_))
+        && !(FEATURES.http2 && push::is_supported())
+    {
+        return Err(BuildClientError::Unsupported("http2_push"));
+    }
+    if let Some(keep_alive) = options.http2_keep_alive {
+        if !FEATURES.http2 || set_upkeep_interval(&mut Easy::new(), keep_alive.interval).is_err() {
+            return Err(BuildClientError::Unsupported("http2_keep_alive"));
+        }
+    }
+    if options.tls_early_data && !FEATURES.early_data {
+        return Err(BuildClientError::Unsupported("tls_early_data"));
+    }
+    if options.alt_svc_cache.is_some() && !FEATURES.altsvc {
+        return Err(BuildClientError::Unsupported("alt_svc_cache"));
+    }
+    if options.hsts_cache.is_some() && !FEATURES.hsts {
+        return Err(BuildClientError::Unsupported("hsts_cache"));
+    }
+    if matches!(options.auth, Some(Auth::NativeSso { .. })) && !FEATURES.spnego {
+        return Err(BuildClientError::Unsupported("auth"));
+    }
+    if !options.allow_cellular_access {
+        return Err(BuildClientError::Unsupported("allow_cellular_access"));
+    }
+    if !options.allow_expensive_network_access {
+        return Err(BuildClientError::Unsupported(
+            "allow_expensive_network_access",
+        ));
+    }
+    if !options.allow_constrained_network_access {
+        return Err(BuildClientError::Unsupported(
+            "allow_constrained_network_access",
+        ));
+    }
+    if options.revocation_check == Some(RevocationPolicy::Enforced) {
+        match set_verify_status(&mut Easy::new()) {
+            Err(e) if e.code() == CURLE_NOT_BUILT_IN => {
+                return Err(BuildClientError::Unsupported("revocation_check"))
+            }
+            res => res.into_nyquest_result("set CURLOPT_SSL_VERIFYSTATUS")?,
+        }
+    }
+    let unsupported = [
+        // Certificates of the system store are not accessible through libcurl
+        ("client_certificate", options.client_certificate.is_some()),
+        // Options only `nsurlsession` backend supports
+        (
+            "cache_capacity",
+            options.cache_memory_capacity.is_some() || options.cache_disk_capacity.is_some(),
+        ),
+        ("cache_directory", options.cache_directory.is_some()),
+        (
+            "callback_quality_of_service",
+            options.callback_quality_of_service.is_some(),
+        ),
+    ];
+    if let Some((option, _)) = unsupported.into_iter().find(|(_, set)| *set) {
+        return Err(BuildClientError::Unsupported(option));
+    }
+    check_default_headers(&options.default_headers)?;
+    if !options.dns_servers.is_empty() {
+        let mut easy = Easy::new();
+        match easy.dns_servers(&dns_servers_csv(&options.dns_servers)) {
+            Err(e) if e.code() == CURLE_NOT_BUILT_IN => {
+                return Err(BuildClientError::Unsupported("dns_servers"))
+            }
+            res => res.into_nyquest_result("set CURLOPT_DNS_SERVERS")?,
+        }
+    }
+    Ok(())
+}
+
+fn dns_servers_csv(servers: &[IpAddr]) -> String {
+    servers
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Limits URLs to HTTP(S), plus `file:` if local schemes are allowed.
+fn restrict_protocols(easy: &mut Easy, allow_local_schemes: bool) -> nyquest_interface::Result<()> {
+    use curl_sys::{CURLPROTO_FILE, CURLPROTO_HTTP, CURLPROTO_HTTPS};
+
+    let mut protocols = CURLPROTO_HTTP | CURLPROTO_HTTPS;
+    if allow_local_schemes {
+        protocols |= CURLPROTO_FILE;
+    }
+    // CURLOPT_PROTOCOLS_STR is only available since 7.85.0
+    let res = unsafe {
+        curl_sys::curl_easy_setopt(
+            easy.raw(),
+            curl_sys::CURLOPT_PROTOCOLS,
+            protocols as std::os::raw::c_long,
+        )
+    };
+    if res != curl_sys::CURLE_OK {
+        return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_PROTOCOLS");
+    }
+    Ok(())
+}
+
+/// Resources referenced by the easy handle, which must be kept alive until the handle is reset
+/// or dropped.
+#[derive(Default)]
+pub struct RequestResources {
+    #[cfg(feature = "multipart")]
+    _mime: Option<Mime>,
+    _headers: Option<Arc<HeaderList>>,
+    _progress: Option<Box<ProgressFunction>>,
+    /// Why a callback aborted the transfer, if it did.
+    pub(crate) abort_reason: AbortReason,
+    /// Counter of the decompressed bytes of the response body, when the decompression limits
+    /// are enforced.
+    pub(crate) decompressed: Option<Arc<AtomicU64>>,
+    /// Limits on the response headers, checked by the header function.
+    pub(crate) header_limits: Option<ResponseHeaderLimits>,
+    /// Callback to report the push promises of the transfer to.
+    pub(crate) push: Option<PushCallback>,
+    /// Interval of PING frames on the HTTP/2 connections of the client.
+    pub(crate) upkeep_interval: Option<Duration>,
+}
+
+/// Counts the headers of the current response against the limits.
+#[derive(Debug, Default)]
+pub(crate) struct HeaderCounter {
+    size: u64,
+    count: usize,
+}
+
+impl HeaderCounter {
+    /// Starts counting the headers of a new response, such as the one following a redirect.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Counts a header line without its line break.
+    pub(crate) fn add(
+        &mut self,
+        limits: Option<&ResponseHeaderLimits>,
+        line: &[u8],
+    ) -> nyquest_interface::Result<()> {
+        let Some(limits) = limits else {
+            return Ok(());
+        };
+        self.size += line.len() as u64;
+        self.count += 1;
+        limits.check(self.size, self.count)
+    }
+}
+
+/// Requires a valid stapled OCSP response from servers.
+fn set_verify_status(easy: &mut Easy) -> Result<(), curl::Error> {
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_SSL_VERIFYSTATUS, 1 as c_long) };
+    match res {
+        CURLE_OK => Ok(()),
+        res => Err(curl::Error::new(res)),
+    }
+}
+
+fn is_https_proxy(proxy: &str) -> bool {
+    proxy
+        .get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+}
+
+/// Looks up the host of the URL with the custom resolver, returning the `CURLOPT_RESOLVE` entry
+/// pinning its addresses.
+fn resolve_with(options: &ClientOptions, url: &str) -> nyquest_interface::Result<Option<String>> {
+    let Some(resolver) = options.resolver.as_deref() else {
+        return Ok(None);
+    };
+    let Some((host, port)) = host_port_of(url) else {
+        return Ok(None);
+    };
+    let overridden = options
+        .resolve
+        .iter()
+        .any(|(h, addr)| h.eq_ignore_ascii_case(host) && addr.port() == port);
+    if overridden || host.parse::<IpAddr>().is_ok() {
+        return Ok(None);
+    }
+    let addrs = resolver.resolve(host, port)?;
+    if addrs.is_empty() {
+        return Ok(None);
+    }
+    let addrs = addrs
+        .iter()
+        .map(|addr| match addr {
+            IpAddr::V4(addr) => addr.to_string(),
+            IpAddr::V6(addr) => format!("[{addr}]"),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(Some(format!("{host}:{port}:{addrs}")))
+}
 
 pub fn populate_request<S>(
     url: &str,
     req: &Request<S>,
-    options: &nyquest_interface::client::ClientOptions,
+    options: &ClientOptions,
+    default_headers: &DefaultHeaders,
+    dns_cache: &DnsCache,
     easy: &mut Easy,
-) -> nyquest_interface::Result<()> {
-    if !options.use_default_proxy {
+) -> nyquest_interface::Result<RequestResources> {
+    if req.cache_policy.is_some() {
+        return Err(nyquest_interface::Error::Unsupported("cache_policy"));
+    }
+    // A proxy of the request takes precedence over that of the client, with `None` bypassing both
+    // it and the proxy of the environment
+    let proxy = match &req.proxy {
+        Some(proxy) => proxy.as_ref().map(|proxy| &*proxy.url),
+        None => options.proxy.as_deref(),
+    };
+    if let Some(proxy) = proxy {
+        if is_https_proxy(proxy) && !FEATURES.https_proxy {
+            return Err(nyquest_interface::Error::Unsupported("proxy"));
+        }
+        // libcurl picks the proxy type from the scheme, e.g. CURLPROXY_SOCKS5_HOSTNAME for
+        // socks5h://
+        easy.proxy(proxy).into_nyquest_result("set CURLOPT_PROXY")?;
+        if let Some(tls) = &options.proxy_tls {
+            if let Some(ca_file) = &tls.ca_file {
+                easy.proxy_cainfo(&ca_file.to_string_lossy())
+                    .into_nyquest_result("set CURLOPT_PROXY_CAINFO")?;
+            }
+            easy.proxy_ssl_verify_peer(tls.verify)
+                .into_nyquest_result("set CURLOPT_PROXY_SSL_VERIFYPEER")?;
+            easy.proxy_ssl_verify_host(tls.verify)
+                .into_nyquest_result("set CURLOPT_PROXY_SSL_VERIFYHOST")?;
+        }
+    } else if req.proxy.is_some() || !options.use_default_proxy {
         easy.noproxy("*")
             .into_nyquest_result("set CURLOPT_NOPROXY")?;
     }
+    if !options.tls_session_resumption {
+        easy.ssl_sessionid_cache(false)
+            .into_nyquest_result("set CURLOPT_SSL_SESSIONID_CACHE")?;
+    }
+    let mut ssl_options = 0;
+    match options.revocation_check {
+        Some(RevocationPolicy::Enforced) => {
+            set_verify_status(easy).into_nyquest_result("set CURLOPT_SSL_VERIFYSTATUS")?
+        }
+        // Only Schannel checks revocation without OCSP stapling
+        Some(RevocationPolicy::BestEffort) => ssl_options |= CURLSSLOPT_REVOKE_BEST_EFFORT,
+        Some(RevocationPolicy::Disabled) => ssl_options |= CURLSSLOPT_NO_REVOKE,
+        None => {}
+    }
+    if options.tls_early_data && req.early_data {
+        ssl_options |= CURLSSLOPT_EARLYDATA;
+    }
+    if ssl_options != 0 {
+        let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_SSL_OPTIONS, ssl_options) };
+        if res != CURLE_OK {
+            return Err(curl::Error::new(res)).into_nyquest_result("set CURLOPT_SSL_OPTIONS");
+        }
+    }
     if let Some(user_agent) = options.user_agent.as_deref() {
         easy.useragent(user_agent).expect("set curl user agent");
     }
@@ -22,99 +315,161 @@ pub fn populate_request<S>(
         easy.cookie_file("")
             .into_nyquest_result("set CURLOPT_COOKIEFILE")?;
     }
-    if let Some(timeout) = options.request_timeout {
-        easy.timeout(timeout)
+    let resolved = resolve_with(options, url)?;
+    if !options.resolve.is_empty() || resolved.is_some() {
+        let mut resolve = List::new();
+        for (host, addr) in &options.resolve {
+            resolve
+                .append(&format!("{}:{}:{}", host, addr.port(), addr.ip()))
+                .into_nyquest_result("resolve curl_slist_append")?;
+        }
+        if let Some(entry) = resolved {
+            resolve
+                .append(&entry)
+                .into_nyquest_result("resolve curl_slist_append")?;
+        }
+        easy.resolve(resolve)
+            .into_nyquest_result("set CURLOPT_RESOLVE")?;
+    }
+    if !options.dns_servers.is_empty() {
+        easy.dns_servers(&dns_servers_csv(&options.dns_servers))
+            .into_nyquest_result("set CURLOPT_DNS_SERVERS")?;
+    }
+    if let Some(interval) = options.tcp_keepalive {
+        easy.tcp_keepalive(true)
+            .into_nyquest_result("set CURLOPT_TCP_KEEPALIVE")?;
+        easy.tcp_keepidle(interval)
+            .into_nyquest_result("set CURLOPT_TCP_KEEPIDLE")?;
+        easy.tcp_keepintvl(interval)
+            .into_nyquest_result("set CURLOPT_TCP_KEEPINTVL")?;
+    }
+    if let Some(nodelay) = options.tcp_nodelay {
+        easy.tcp_nodelay(nodelay)
+            .into_nyquest_result("set CURLOPT_TCP_NODELAY")?;
+    }
+    if let Some(doh_url) = options.doh_url.as_deref() {
+        easy.doh_url(Some(doh_url))
+            .into_nyquest_result("set CURLOPT_DOH_URL")?;
+    }
+    if let Some(timeout) = dns_cache.timeout() {
+        easy.dns_cache_timeout(timeout)
+            .into_nyquest_result("set CURLOPT_DNS_CACHE_TIMEOUT")?;
+    }
+    if options.verbose {
+        easy.verbose(true)
+            .into_nyquest_result("set CURLOPT_VERBOSE")?;
+        let redaction = options.redaction.clone();
+        easy.debug_function(move |kind, data| crate::debug::log_debug(&redaction, kind, data))
+            .into_nyquest_result("set CURLOPT_DEBUGFUNCTION")?;
+    }
+    if let Some(timeout) = req.effective_timeout(options.request_timeout) {
+        // A zero timeout disables it in libcurl
+        easy.timeout(timeout.max(Duration::from_millis(1)))
             .into_nyquest_result("set CURLOPT_TIMEOUT")?;
     }
+    if let Some(read_timeout) = options.read_timeout {
+        // libcurl checks the transfer speed once per second
+        easy.low_speed_limit(1)
+            .into_nyquest_result("set CURLOPT_LOW_SPEED_LIMIT")?;
+        easy.low_speed_time(Duration::from_secs(read_timeout.as_secs_f64().ceil() as u64))
+            .into_nyquest_result("set CURLOPT_LOW_SPEED_TIME")?;
+    }
+    let allow_h3 = match options.http_version {
+        Some(HttpVersion::Http1_1) => {
+            easy.http_version(CurlHttpVersion::V11)
+                .into_nyquest_result("set CURLOPT_HTTP_VERSION")?;
+            false
+        }
+        Some(HttpVersion::Http2) => {
+            easy.http_version(CurlHttpVersion::V2TLS)
+                .into_nyquest_result("set CURLOPT_HTTP_VERSION")?;
+            false
+        }
+        Some(HttpVersion::Http3) => {
+            easy.http_version(CurlHttpVersion::V3)
+                .into_nyquest_result("set CURLOPT_HTTP_VERSION")?;
+            true
+        }
+        None => false,
+    };
+    if allow_h3 || options.alt_svc_cache.is_some() {
+        set_alt_svc(easy, options.alt_svc_cache.as_deref(), allow_h3)?;
+    }
+    if let Some(hsts_cache) = &options.hsts_cache {
+        set_hsts(easy, hsts_cache)?;
+    }
+    if let Some(Auth::NativeSso { allowed_hosts }) = &options.auth {
+        let mut auth = curl::easy::Auth::new();
+        auth.gssnegotiate(true);
+        // An empty user name makes libcurl pick up the credentials of the logged-in user
+        easy.proxy_auth(&auth)
+            .into_nyquest_result("set CURLOPT_PROXYAUTH")?;
+        easy.proxy_username("")
+            .into_nyquest_result("set CURLOPT_PROXYUSERNAME")?;
+        let is_host_allowed = allowed_hosts.is_empty()
+            || host_of(url).is_some_and(|host| {
+                allowed_hosts
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(host))
+            });
+        if is_host_allowed {
+            easy.http_auth(&auth)
+                .into_nyquest_result("set CURLOPT_HTTPAUTH")?;
+            easy.username("")
+                .into_nyquest_result("set CURLOPT_USERNAME")?;
+        }
+    }
+    let abort_reason = AbortReason::default();
+    let progress = set_progress(
+        easy,
+        req.progress.as_ref(),
+        options.decompression_limits.as_ref(),
+        &abort_reason,
+    )?;
+    restrict_protocols(easy, options.allow_local_schemes)?;
     easy.url(url).into_nyquest_result("set CURLOPT_URL")?;
     let require_body = match &req.method {
         Method::Get if req.body.is_none() => easy.get(true).map(|()| false),
-        Method::Get => easy.custom_request("get").map(|()| false),
+        // The body is sent as if it were POST, so the method is overridden
+        Method::Get => easy.custom_request("GET").map(|()| false),
         Method::Post => easy.post(true).map(|()| true),
         Method::Put => easy.put(true).map(|()| true),
-        Method::Delete => easy.custom_request("delete").map(|()| false),
-        Method::Patch => easy.custom_request("patch").map(|()| false),
+        Method::Delete => easy.custom_request("DELETE").map(|()| false),
+        Method::Patch => easy.custom_request("PATCH").map(|()| false),
+        // A custom HEAD request would wait for the body announced by the response
+        Method::Other(method) if method.eq_ignore_ascii_case("head") => {
+            easy.nobody(true).map(|()| false)
+        }
         Method::Other(method) => easy.custom_request(method).map(|()| false),
     }
     .into_nyquest_result("set CURLOPT_CUSTOMREQUEST")?;
-    let mut headers = List::new();
-    for (name, value) in &options.default_headers {
-        headers
-            .append(&format!("{}: {}", name, value))
-            .into_nyquest_result("default_headers curl_slist_append")?;
-    }
+    let mut lines = Vec::new();
     for (name, value) in &req.additional_headers {
-        headers
-            .append(&format!("{}: {}", name, value))
-            .into_nyquest_result("additional_headers curl_slist_append")?;
+        lines.push(header_line(
+            format!("{}: {}", name, value),
+            "additional_headers",
+        )?);
     }
     match &req.body {
         Some(Body::Bytes {
             content,
             content_type,
         }) => {
-            headers
-                .append(&format!("content-type: {}", content_type))
-                .into_nyquest_result("set content-type curl_slist_append")?;
+            lines.push(header_line(
+                format!("content-type: {}", content_type),
+                "set content-type",
+            )?);
             easy.post_fields_copy(content)
                 .into_nyquest_result("set CURLOPT_COPYPOSTFIELDS")?;
         }
         Some(Body::Stream(_)) => unimplemented!(),
         Some(Body::Form { fields }) => {
-            let mut buf =
-                Vec::with_capacity(fields.iter().map(|(k, v)| k.len() + v.len() + 2).sum());
-            for (name, value) in fields {
-                buf.extend_from_slice(&curl_escape(easy, &**name));
-                buf.push(b'=');
-                buf.extend_from_slice(&curl_escape(easy, &**value));
-                buf.push(b'&');
-            }
-            buf.pop();
-            easy.post_fields_copy(&buf)
+            easy.post_fields_copy(&encode_form(fields))
                 .into_nyquest_result("set CURLOPT_COPYPOSTFIELDS")?;
         }
         #[cfg(feature = "multipart")]
-        Some(Body::Multipart { parts }) => {
-            use std::io;
-
-            use nyquest_interface::PartBody;
-
-            let mut form = curl::easy::Form::new();
-            for part in parts {
-                let mut formpart = form.part(&part.name);
-                if !part.headers.is_empty() {
-                    let mut list = List::new();
-                    for (name, value) in &part.headers {
-                        list.append(&format!("{}: {}", name, value))
-                            .into_nyquest_result("multipart header curl_slist_append")?;
-                    }
-                    formpart.content_header(list);
-                }
-                match &part.body {
-                    PartBody::Bytes { content } => {
-                        formpart.buffer(
-                            part.filename.as_deref().unwrap_or_default(),
-                            content.to_vec(),
-                        );
-                        formpart.content_type(&part.content_type);
-                    }
-                    PartBody::Stream(_) => {
-                        if let Some(filename) = &part.filename {
-                            formpart.filename(&**filename);
-                        }
-                        return Err(nyquest_interface::Error::Io(io::Error::new(
-                            ErrorKind::InvalidInput,
-                            "unsupported body type",
-                        )));
-                    }
-                }
-                formpart.add().map_err(|e| {
-                    nyquest_interface::Error::Io(io::Error::new(ErrorKind::Other, e.to_string()))
-                })?;
-            }
-            easy.httppost(form)
-                .into_nyquest_result("set CURLOPT_HTTPPOST")?;
-        }
+        // Attached below so that the mime structure can be kept alive
+        Some(Body::Multipart { .. }) => {}
         None if require_body => {
             // Workaround for https://github.com/curl/curl/issues/1625
             easy.post_fields_copy(b"")
@@ -122,9 +477,24 @@ pub fn populate_request<S>(
         }
         None => {}
     }
-    easy.http_headers(headers)
-        .into_nyquest_result("set CURLOPT_HTTPHEADER")?;
+    let headers = default_headers.with_lines(lines, &req.additional_headers)?;
+    headers.apply(easy)?;
     easy.accept_encoding("")
         .into_nyquest_result("set CURLOPT_ACCEPT_ENCODING")?;
-    Ok(())
+    #[cfg(feature = "multipart")]
+    let mime = match &req.body {
+        Some(Body::Multipart { parts }) => Some(Mime::attach(easy, parts)?),
+        _ => None,
+    };
+    Ok(RequestResources {
+        #[cfg(feature = "multipart")]
+        _mime: mime,
+        _headers: Some(headers),
+        decompressed: progress.as_ref().and_then(|p| p.decompressed()),
+        _progress: progress,
+        abort_reason,
+        header_limits: options.max_response_headers,
+        push: push_callback(&options.http2_push),
+        upkeep_interval: options.http2_keep_alive.map(|k| k.interval),
+    })
 }
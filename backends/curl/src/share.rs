@@ -25,6 +25,7 @@ pub(super) struct Share {
     raw: Arc<RawShare>,
 }
 
+#[cfg(feature = "async")]
 pub(super) struct ShareHandle {
     _share: Arc<RawShare>,
 }
@@ -57,6 +58,7 @@ impl Share {
         Share { raw }
     }
 
+    #[cfg(feature = "async")]
     pub fn get_handle(&self) -> ShareHandle {
         ShareHandle {
             _share: self.raw.clone(),
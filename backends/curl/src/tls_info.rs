@@ -0,0 +1,102 @@
+use std::ffi::{c_int, c_long, c_void};
+use std::ptr;
+
+use curl_sys::{curl_easy_getinfo, CURL, CURLE_OK, CURLINFO, CURLINFO_LONG, CURLINFO_SLIST};
+use nyquest_interface::TlsInfo;
+
+const CURLINFO_HTTP_VERSION: CURLINFO = CURLINFO_LONG + 46;
+// CURLINFO_PTR shares its type bits with CURLINFO_SLIST
+const CURLINFO_TLS_SSL_PTR: CURLINFO = CURLINFO_SLIST + 45;
+const CURLSSLBACKEND_NONE: c_int = 0;
+#[cfg(all(feature = "ssl", unix, not(target_os = "macos")))]
+const CURLSSLBACKEND_OPENSSL: c_int = 1;
+const CURL_HTTP_VERSION_1_1: c_long = 2;
+const CURL_HTTP_VERSION_2_0: c_long = 3;
+const CURL_HTTP_VERSION_3: c_long = 30;
+
+#[repr(C)]
+struct CurlTlsSessionInfo {
+    backend: c_int,
+    internals: *mut c_void,
+}
+
+/// Reads the TLS parameters of the connection of a transfer from its callbacks, while the
+/// connection is still attached to the transfer.
+pub(crate) struct TlsProbe(*mut CURL);
+
+impl TlsProbe {
+    pub(crate) fn new(handle: *mut CURL) -> Self {
+        Self(handle)
+    }
+
+    /// ## Safety
+    /// The caller must ensure:
+    /// 1. The handle is a valid CURL handle.
+    /// 2. The handle is either within the same thread or we are in a callback.
+    pub(crate) unsafe fn tls_info(&self) -> Option<TlsInfo> {
+        let mut session: *mut CurlTlsSessionInfo = ptr::null_mut();
+        let res = curl_easy_getinfo(self.0, CURLINFO_TLS_SSL_PTR, &mut session);
+        if res != CURLE_OK || session.is_null() {
+            return None;
+        }
+        let session = &*session;
+        // Plain connections have no TLS internals
+        if session.backend == CURLSSLBACKEND_NONE || session.internals.is_null() {
+            return None;
+        }
+        #[cfg(all(feature = "ssl", unix, not(target_os = "macos")))]
+        if session.backend == CURLSSLBACKEND_OPENSSL {
+            return Some(openssl_tls_info(session.internals.cast()));
+        }
+        Some(TlsInfo {
+            alpn_protocol: self.alpn_from_http_version(),
+            ..Default::default()
+        })
+    }
+
+    /// Infers the ALPN protocol from the HTTP version, for TLS libraries that cannot be queried.
+    unsafe fn alpn_from_http_version(&self) -> Option<String> {
+        let mut version: c_long = 0;
+        if curl_easy_getinfo(self.0, CURLINFO_HTTP_VERSION, &mut version) != CURLE_OK {
+            return None;
+        }
+        let alpn = match version {
+            CURL_HTTP_VERSION_1_1 => "http/1.1",
+            CURL_HTTP_VERSION_2_0 => "h2",
+            CURL_HTTP_VERSION_3 => "h3",
+            _ => return None,
+        };
+        Some(alpn.into())
+    }
+}
+
+// Safety: Nothing can happen when the handle is moved between threads without "unsafe"
+unsafe impl Send for TlsProbe {}
+
+#[cfg(all(feature = "ssl", unix, not(target_os = "macos")))]
+unsafe fn openssl_tls_info(ssl: *mut openssl_sys::SSL) -> TlsInfo {
+    use std::ffi::{c_char, c_uint, CStr};
+
+    use openssl_sys::{
+        SSL_CIPHER_get_name, SSL_get0_alpn_selected, SSL_get_current_cipher, SSL_get_version,
+        SSL_session_reused,
+    };
+
+    let to_string =
+        |s: *const c_char| (!s.is_null()).then(|| CStr::from_ptr(s).to_string_lossy().into_owned());
+    let cipher = SSL_get_current_cipher(ssl);
+    let mut alpn = ptr::null();
+    let mut alpn_len: c_uint = 0;
+    SSL_get0_alpn_selected(ssl, &mut alpn, &mut alpn_len);
+    TlsInfo {
+        protocol_version: to_string(SSL_get_version(ssl)),
+        cipher_suite: (!cipher.is_null())
+            .then(|| to_string(SSL_CIPHER_get_name(cipher)))
+            .flatten(),
+        alpn_protocol: (!alpn.is_null() && alpn_len > 0).then(|| {
+            String::from_utf8_lossy(std::slice::from_raw_parts(alpn, alpn_len as usize))
+                .into_owned()
+        }),
+        resumed: Some(SSL_session_reused(ssl as _) != 0),
+    }
+}
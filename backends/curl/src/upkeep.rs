@@ -0,0 +1,58 @@
+use std::ffi::c_long;
+use std::time::{Duration, Instant};
+
+use curl::easy::Easy;
+use curl_sys::{curl_easy_setopt, CURLoption, CURLE_OK, CURLOPTTYPE_LONG};
+
+use crate::share::Share;
+
+const CURLOPT_UPKEEP_INTERVAL_MS: CURLoption = CURLOPTTYPE_LONG + 281;
+
+/// Sets the interval of connection upkeep.
+pub(crate) fn set_upkeep_interval(easy: &mut Easy, interval: Duration) -> Result<(), curl::Error> {
+    let ms = interval.as_millis().clamp(1, c_long::MAX as u128) as c_long;
+    let res = unsafe { curl_easy_setopt(easy.raw(), CURLOPT_UPKEEP_INTERVAL_MS, ms) };
+    if res == CURLE_OK {
+        Ok(())
+    } else {
+        Err(curl::Error::new(res))
+    }
+}
+
+/// Sends HTTP/2 PING frames on the connections of a share.
+///
+/// Upkeep briefly attaches the handle to every connection, so it is done with a handle of its own
+/// that never performs transfers, on the thread driving the transfers of the share.
+pub(crate) struct Upkeep {
+    easy: Easy,
+    interval: Duration,
+    last: Instant,
+}
+
+impl Upkeep {
+    /// ## Safety
+    /// The caller must ensure that the share outlives the upkeep.
+    pub(crate) unsafe fn new(share: &Share, interval: Duration) -> Option<Self> {
+        let mut easy = Easy::new();
+        share.bind_easy(&mut easy).ok()?;
+        set_upkeep_interval(&mut easy, interval).ok()?;
+        Some(Self {
+            easy,
+            interval,
+            last: Instant::now(),
+        })
+    }
+
+    /// Sends PING frames on the connections that are due, and returns how long until it should
+    /// be called again.
+    pub(crate) fn perform(&mut self) -> Duration {
+        let elapsed = self.last.elapsed();
+        if elapsed < self.interval {
+            return self.interval - elapsed;
+        }
+        // Dead connections are detected again before they are reused
+        self.easy.upkeep().ok();
+        self.last = Instant::now();
+        self.interval
+    }
+}
@@ -1,38 +1,68 @@
-use std::ffi::c_char;
-
-use curl::easy::Easy;
-use curl_sys::curl_free;
-use memchr::memmem;
-
-pub fn curl_escape(easy: &Easy, str: impl AsRef<[u8]>) -> Vec<u8> {
-    struct CurlString(*mut c_char);
-    impl Drop for CurlString {
-        fn drop(&mut self) {
-            unsafe {
-                curl_free(self.0 as _);
-            }
+use std::borrow::Cow;
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Whether the byte is left as is, matching what `curl_easy_escape` considers unreserved.
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+fn escaped_len(str: &[u8]) -> usize {
+    str.iter()
+        .map(|&b| if is_unreserved(b) || b == b' ' { 1 } else { 3 })
+        .sum()
+}
+
+/// Appends `str` to `buf` in `application/x-www-form-urlencoded` form, with spaces as `+`.
+fn form_escape_into(buf: &mut Vec<u8>, str: &[u8]) {
+    for &b in str {
+        match b {
+            b' ' => buf.push(b'+'),
+            b if is_unreserved(b) => buf.push(b),
+            b => buf.extend_from_slice(&[b'%', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]]),
         }
     }
-    let str = str.as_ref();
-    if str.is_empty() {
-        return vec![];
-    }
-    let mut buf = unsafe {
-        let raw_str = CurlString(curl_sys::curl_easy_escape(
-            easy.raw(),
-            str.as_ptr() as _,
-            str.len().try_into().expect("str escaped too long"),
-        ));
-        std::ffi::CStr::from_ptr(raw_str.0).to_bytes().to_vec()
-    };
-    {
-        // replace %20 with +
-        let mut idx = 0;
-        while let Some(pos) = memmem::find(&buf[idx..], b"%20") {
-            buf.splice(idx + pos..idx + pos + 3, *b"+");
-            idx += pos + 1;
+}
+
+/// Encodes form fields as an `application/x-www-form-urlencoded` body in a single allocation.
+pub fn encode_form(fields: &[(Cow<'static, str>, Cow<'static, str>)]) -> Vec<u8> {
+    let len = fields
+        .iter()
+        .map(|(k, v)| escaped_len(k.as_bytes()) + escaped_len(v.as_bytes()) + 2)
+        .sum::<usize>()
+        .saturating_sub(1);
+    let mut buf = Vec::with_capacity(len);
+    for (i, (name, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            buf.push(b'&');
         }
+        form_escape_into(&mut buf, name.as_bytes());
+        buf.push(b'=');
+        form_escape_into(&mut buf, value.as_bytes());
     }
-
+    debug_assert_eq!(buf.len(), len);
     buf
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_escape_into() {
+        let mut buf = vec![];
+        form_escape_into(&mut buf, "a b&c=d/é~".as_bytes());
+        assert_eq!(buf, b"a+b%26c%3Dd%2F%C3%A9~");
+    }
+
+    #[test]
+    fn test_encode_form() {
+        let fields = [
+            ("key 1".into(), "value 1".into()),
+            ("empty".into(), "".into()),
+            ("x".into(), "%20+".into()),
+        ];
+        assert_eq!(encode_form(&fields), b"key+1=value+1&empty=&x=%2520%2B");
+        assert_eq!(encode_form(&[]), b"");
+    }
+}
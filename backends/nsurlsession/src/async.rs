@@ -2,15 +2,16 @@ use std::future::poll_fn;
 use std::task::Poll;
 
 use nyquest_interface::client::{BuildClientResult, ClientOptions};
+use nyquest_interface::r#async::body_pump::BodyPump;
 use nyquest_interface::r#async::{AsyncBackend, AsyncClient, AsyncResponse};
-use nyquest_interface::Result as NyquestResult;
-use objc2::runtime::ProtocolObject;
+use nyquest_interface::{Bytes, Result as NyquestResult};
+use objc2_foundation::NSURLSessionDataTask;
 use waker::AsyncWaker;
 
 pub(crate) mod waker;
 
 use crate::client::NSUrlSessionClient;
-use crate::datatask::{DataTaskDelegate, GenericWaker};
+use crate::datatask::{ChunkState, DataTaskDelegate, GenericWaker};
 use crate::error::IntoNyquestResult;
 use crate::response::NSUrlSessionResponse;
 use crate::NSUrlSessionBackend;
@@ -37,29 +38,37 @@ impl AsyncResponse for NSUrlSessionAsyncResponse {
         self.inner.get_header(header)
     }
 
+    fn headers(&self) -> NyquestResult<Vec<(String, String)>> {
+        Ok(self.inner.headers())
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
     async fn text(&mut self) -> NyquestResult<String> {
         let bytes = self.bytes().await?;
-        self.inner.convert_bytes_to_string(bytes)
+        self.inner.convert_bytes_to_string(bytes.into())
     }
 
-    async fn bytes(&mut self) -> NyquestResult<Vec<u8>> {
+    async fn bytes(&mut self) -> NyquestResult<Bytes> {
         let inner_waker = coerce_waker(self.inner.shared.waker_ref());
-        unsafe {
-            self.inner.task.resume();
-        }
+        let mut buffer = self.inner.body_buffer();
         poll_fn(|cx| {
-            if self.inner.shared.is_completed() {
-                return Poll::Ready(());
-            }
             inner_waker.register(cx);
-            Poll::Pending
+            loop {
+                match self.inner.shared.try_take_chunk(&self.inner.task)? {
+                    ChunkState::Chunk(chunk) => buffer.extend_from_slice(&chunk),
+                    ChunkState::Pending => return Poll::Pending,
+                    ChunkState::End => return Poll::Ready(NyquestResult::Ok(())),
+                }
+            }
         })
-        .await;
-        let res = self.inner.shared.take_response_buffer()?;
+        .await?;
         unsafe {
             self.inner.task.error().into_nyquest_result()?;
         }
-        Ok(res)
+        Ok(buffer.into())
     }
 }
 
@@ -68,28 +77,35 @@ impl AsyncClient for NSUrlSessionAsyncClient {
 
     async fn request(
         &self,
-        req: nyquest_interface::r#async::Request,
+        mut req: nyquest_interface::r#async::Request,
     ) -> NyquestResult<Self::Response> {
-        let task = self.inner.build_data_task(req)?;
-        let shared = unsafe {
-            let delegate = DataTaskDelegate::new(
-                GenericWaker::Async(AsyncWaker::new()),
-                self.inner.max_response_buffer_size,
-            );
-            task.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
-            task.resume();
-            DataTaskDelegate::into_shared(delegate)
-        };
+        let timings = req.timings.take();
+        let mut pump = BodyPump::default();
+        let (task, upload_body) = self
+            .inner
+            .build_data_task(req, |s| Box::new(pump.pump(Box::into_pin(s))))?;
+        let delegate = DataTaskDelegate::new(
+            GenericWaker::Async(AsyncWaker::new()),
+            &self.inner,
+            upload_body,
+            timings,
+        );
+        self.inner.start_task(&task, &delegate);
+        let shared = DataTaskDelegate::into_shared(delegate);
         let inner_waker = coerce_waker(shared.waker_ref());
-        // TODO: cancellation
-        let response = poll_fn(|cx| {
-            if let Some(response) = shared.try_take_response().into_nyquest_result().transpose() {
-                return Poll::Ready(response);
-            }
-            inner_waker.register(cx);
-            Poll::Pending
-        })
-        .await?;
+        let guard = CancelOnDrop(Some(&task));
+        // Streamed bodies are read on this task while the upload thread sends them
+        let response = pump
+            .run_until(poll_fn(|cx| {
+                if let Some(response) = shared.try_take_response().into_nyquest_result().transpose()
+                {
+                    return Poll::Ready(response);
+                }
+                inner_waker.register(cx);
+                Poll::Pending
+            }))
+            .await?;
+        guard.defuse();
         unsafe {
             task.error().into_nyquest_result()?;
         }
@@ -101,6 +117,15 @@ impl AsyncClient for NSUrlSessionAsyncClient {
             },
         })
     }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
+
+    fn waits_for_connectivity(&self) -> bool {
+        // Through `waitsForConnectivity` of the session configuration
+        true
+    }
 }
 
 impl AsyncBackend for NSUrlSessionBackend {
@@ -116,6 +141,23 @@ impl AsyncBackend for NSUrlSessionBackend {
     }
 }
 
+/// Cancels a task whose response is still pending when the future is dropped.
+struct CancelOnDrop<'a>(Option<&'a NSURLSessionDataTask>);
+
+impl CancelOnDrop<'_> {
+    fn defuse(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            unsafe { task.cancel() }
+        }
+    }
+}
+
 #[allow(irrefutable_let_patterns)]
 fn coerce_waker(waker: &GenericWaker) -> &AsyncWaker {
     if let GenericWaker::Async(waker) = waker {
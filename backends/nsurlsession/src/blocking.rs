@@ -1,12 +1,14 @@
+use std::io;
+
 use nyquest_interface::blocking::{BlockingBackend, BlockingClient, BlockingResponse, Request};
 use nyquest_interface::client::{BuildClientResult, ClientOptions};
-use objc2::runtime::ProtocolObject;
+use nyquest_interface::{Bytes, Error as NyquestError};
 use waker::BlockingWaker;
 
 pub(crate) mod waker;
 
 use crate::client::NSUrlSessionClient;
-use crate::datatask::{DataTaskDelegate, GenericWaker};
+use crate::datatask::{ChunkState, DataChunk, DataTaskDelegate, GenericWaker};
 use crate::error::IntoNyquestResult;
 use crate::response::NSUrlSessionResponse;
 use crate::NSUrlSessionBackend;
@@ -17,11 +19,55 @@ pub struct NSUrlSessionBlockingClient {
 }
 pub struct NSUrlSessionBlockingResponse {
     inner: NSUrlSessionResponse,
+    chunk: Option<DataChunk>,
+    chunk_offset: usize,
+}
+
+impl NSUrlSessionBlockingResponse {
+    /// Waits for the next chunk of the response body, or `None` at the end of it.
+    fn next_chunk(&mut self) -> nyquest_interface::Result<Option<DataChunk>> {
+        let inner_waker = coerce_waker(self.inner.shared.waker_ref());
+        inner_waker.register_current_thread();
+        loop {
+            match self.inner.shared.try_take_chunk(&self.inner.task)? {
+                ChunkState::Chunk(chunk) => return Ok(Some(chunk)),
+                ChunkState::Pending => std::thread::park(),
+                ChunkState::End => {
+                    unsafe {
+                        self.inner.task.error().into_nyquest_result()?;
+                    }
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// The unread part of the current chunk.
+    fn remaining(&self) -> &[u8] {
+        self.chunk
+            .as_deref()
+            .map_or(&[], |chunk| &chunk[self.chunk_offset..])
+    }
 }
 
-impl std::io::Read for NSUrlSessionBlockingResponse {
-    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
-        todo!()
+impl io::Read for NSUrlSessionBlockingResponse {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.remaining().is_empty() {
+            match self.next_chunk() {
+                Ok(Some(chunk)) => {
+                    self.chunk = Some(chunk);
+                    self.chunk_offset = 0;
+                }
+                Ok(None) => return Ok(0),
+                Err(NyquestError::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+        let remaining = self.remaining();
+        let len = buf.len().min(remaining.len());
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.chunk_offset += len;
+        Ok(len)
     }
 }
 
@@ -38,43 +84,45 @@ impl BlockingResponse for NSUrlSessionBlockingResponse {
         self.inner.get_header(header)
     }
 
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.inner.headers())
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
     fn text(&mut self) -> nyquest_interface::Result<String> {
         let bytes = self.bytes()?;
-        self.inner.convert_bytes_to_string(bytes)
+        self.inner.convert_bytes_to_string(bytes.into())
     }
 
-    fn bytes(&mut self) -> nyquest_interface::Result<Vec<u8>> {
-        let inner_waker = coerce_waker(self.inner.shared.waker_ref());
-        unsafe {
-            self.inner.task.resume();
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        let mut buffer = self.inner.body_buffer();
+        buffer.extend_from_slice(self.remaining());
+        self.chunk = None;
+        self.chunk_offset = 0;
+        while let Some(chunk) = self.next_chunk()? {
+            buffer.extend_from_slice(&chunk);
         }
-        inner_waker.register_current_thread();
-
-        while !self.inner.shared.is_completed() {
-            std::thread::park();
-        }
-        let res = self.inner.shared.take_response_buffer()?;
-        unsafe {
-            self.inner.task.error().into_nyquest_result()?;
-        }
-        Ok(res)
+        Ok(buffer.into())
     }
 }
 
 impl BlockingClient for NSUrlSessionBlockingClient {
     type Response = NSUrlSessionBlockingResponse;
 
-    fn request(&self, req: Request) -> nyquest_interface::Result<Self::Response> {
-        let task = self.inner.build_data_task(req)?;
-        let shared = unsafe {
-            let delegate = DataTaskDelegate::new(
-                GenericWaker::Blocking(BlockingWaker::new_from_current_thread()),
-                self.inner.max_response_buffer_size,
-            );
-            task.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
-            task.resume();
-            DataTaskDelegate::into_shared(delegate)
-        };
+    fn request(&self, mut req: Request) -> nyquest_interface::Result<Self::Response> {
+        let timings = req.timings.take();
+        let (task, upload_body) = self.inner.build_data_task(req, |s| Box::new(s))?;
+        let delegate = DataTaskDelegate::new(
+            GenericWaker::Blocking(BlockingWaker::new_from_current_thread()),
+            &self.inner,
+            upload_body,
+            timings,
+        );
+        self.inner.start_task(&task, &delegate);
+        let shared = DataTaskDelegate::into_shared(delegate);
         loop {
             if let Some(response) = shared.try_take_response().into_nyquest_result()? {
                 return Ok(NSUrlSessionBlockingResponse {
@@ -83,6 +131,8 @@ impl BlockingClient for NSUrlSessionBlockingClient {
                         task,
                         shared,
                     },
+                    chunk: None,
+                    chunk_offset: 0,
                 });
             }
             unsafe {
@@ -91,6 +141,15 @@ impl BlockingClient for NSUrlSessionBlockingClient {
             std::thread::park();
         }
     }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
+
+    fn waits_for_connectivity(&self) -> bool {
+        // Through `waitsForConnectivity` of the session configuration
+        true
+    }
 }
 
 impl BlockingBackend for NSUrlSessionBackend {
@@ -0,0 +1,286 @@
+use std::ffi::c_void;
+use std::io;
+use std::ptr;
+
+use nyquest_interface::client::{Auth, ClientCertificate, ClientOptions, RevocationPolicy};
+use objc2::encode::{Encoding, RefEncode};
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2::{msg_send, ClassType};
+use objc2_foundation::{
+    NSArray, NSData, NSDictionary, NSNumber, NSString, NSURLAuthenticationChallenge,
+    NSURLAuthenticationMethodClientCertificate, NSURLAuthenticationMethodNTLM,
+    NSURLAuthenticationMethodNegotiate, NSURLAuthenticationMethodServerTrust, NSURLCredential,
+    NSURLCredentialPersistence, NSURLSessionAuthChallengeDisposition,
+};
+
+#[repr(C)]
+struct SecIdentity {
+    _private: [u8; 0],
+}
+
+unsafe impl RefEncode for SecIdentity {
+    const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct("__SecIdentity", &[]));
+}
+
+#[repr(C)]
+struct SecTrust {
+    _private: [u8; 0],
+}
+
+unsafe impl RefEncode for SecTrust {
+    const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct("__SecTrust", &[]));
+}
+
+const ERR_SEC_SUCCESS: i32 = 0;
+const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+const SHA1_DIGEST_LENGTH: usize = 20;
+const SEC_REVOCATION_USE_ANY_AVAILABLE_METHOD: usize = 3;
+const SEC_REVOCATION_REQUIRE_POSITIVE_RESPONSE: usize = 1 << 3;
+
+// The CF types are toll-free bridged to the Foundation ones
+#[link(name = "Security", kind = "framework")]
+unsafe extern "C" {
+    static kSecClass: &'static NSString;
+    static kSecClassIdentity: &'static NSString;
+    static kSecMatchLimit: &'static NSString;
+    static kSecMatchLimitAll: &'static NSString;
+    static kSecReturnRef: &'static NSString;
+
+    fn SecItemCopyMatching(
+        query: &NSDictionary<NSString, AnyObject>,
+        result: *mut *mut AnyObject,
+    ) -> i32;
+    fn SecIdentityCopyCertificate(identity: &AnyObject, certificate: *mut *mut AnyObject) -> i32;
+    fn SecCertificateCopyData(certificate: &AnyObject) -> *mut NSData;
+    fn SecCertificateCopySubjectSummary(certificate: &AnyObject) -> *mut NSString;
+    fn SecPolicyCreateSSL(server: u8, hostname: &NSString) -> *mut AnyObject;
+    fn SecPolicyCreateRevocation(flags: usize) -> *mut AnyObject;
+    fn SecTrustSetPolicies(trust: *mut SecTrust, policies: &NSArray<AnyObject>) -> i32;
+    fn SecTrustEvaluateWithError(trust: *mut SecTrust, error: *mut *mut AnyObject) -> bool;
+}
+
+unsafe extern "C" {
+    fn CC_SHA1(data: *const c_void, len: u32, md: *mut u8) -> *mut u8;
+}
+
+/// Decides how the tasks of a client answer authentication challenges.
+pub(crate) struct ChallengePolicy {
+    client_identity: Option<Retained<NSURLCredential>>,
+    sso_allowed_hosts: Option<Vec<String>>,
+    revocation_check: Option<RevocationPolicy>,
+}
+
+// Safety: credentials are immutable
+unsafe impl Send for ChallengePolicy {}
+unsafe impl Sync for ChallengePolicy {}
+
+impl ChallengePolicy {
+    pub(crate) fn new(options: &ClientOptions) -> io::Result<Self> {
+        let client_identity = options
+            .client_certificate
+            .as_ref()
+            .map(find_client_identity)
+            .transpose()?;
+        let sso_allowed_hosts = options
+            .auth
+            .as_ref()
+            .map(|Auth::NativeSso { allowed_hosts }| allowed_hosts.clone());
+        Ok(Self {
+            client_identity,
+            sso_allowed_hosts,
+            revocation_check: options.revocation_check,
+        })
+    }
+
+    /// Picks the disposition of the challenge, along with the credential to use if any.
+    pub(crate) fn respond(
+        &self,
+        challenge: &NSURLAuthenticationChallenge,
+    ) -> (
+        NSURLSessionAuthChallengeDisposition,
+        Option<Retained<NSURLCredential>>,
+    ) {
+        let space = unsafe { challenge.protectionSpace() };
+        let method = unsafe { space.authenticationMethod() };
+        unsafe {
+            if *method == *NSURLAuthenticationMethodServerTrust {
+                if let Some(policy) = self.revocation_check {
+                    return evaluate_server_trust(
+                        &space.host(),
+                        msg_send![&*space, serverTrust],
+                        policy,
+                    );
+                }
+            }
+            if *method == *NSURLAuthenticationMethodClientCertificate {
+                return match &self.client_identity {
+                    Some(identity) => (
+                        NSURLSessionAuthChallengeDisposition::UseCredential,
+                        Some(identity.clone()),
+                    ),
+                    None => (
+                        NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+                        None,
+                    ),
+                };
+            }
+            if *method == *NSURLAuthenticationMethodNegotiate
+                || *method == *NSURLAuthenticationMethodNTLM
+            {
+                // The system answers with the credentials of the logged-in user by default.
+                // Proxies are not subject to the allowed hosts, as with the other backends.
+                let is_allowed = self.sso_allowed_hosts.as_ref().is_some_and(|hosts| {
+                    let host = space.host().to_string();
+                    space.isProxy()
+                        || hosts.is_empty()
+                        || hosts
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(&host))
+                });
+                if !is_allowed {
+                    // Lets the task fall back to other authentication methods offered
+                    return (
+                        NSURLSessionAuthChallengeDisposition::RejectProtectionSpace,
+                        None,
+                    );
+                }
+            }
+        }
+        // Server trust is otherwise evaluated by the system, and Basic and Digest challenges are
+        // answered with the credentials in the URL or the credential storage
+        (
+            NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+            None,
+        )
+    }
+}
+
+/// Evaluates the trust of the server with the revocation policy, instead of the default policies
+/// of the system.
+///
+/// # Safety
+///
+/// `trust` must be the server trust of the protection space of the challenge, or null.
+unsafe fn evaluate_server_trust(
+    host: &NSString,
+    trust: *mut SecTrust,
+    policy: RevocationPolicy,
+) -> (
+    NSURLSessionAuthChallengeDisposition,
+    Option<Retained<NSURLCredential>>,
+) {
+    if trust.is_null() {
+        return (
+            NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+            None,
+        );
+    }
+    let revocation_flags = match policy {
+        RevocationPolicy::Enforced => {
+            SEC_REVOCATION_USE_ANY_AVAILABLE_METHOD | SEC_REVOCATION_REQUIRE_POSITIVE_RESPONSE
+        }
+        RevocationPolicy::BestEffort => SEC_REVOCATION_USE_ANY_AVAILABLE_METHOD,
+        RevocationPolicy::Disabled => 0,
+    };
+    let trusted = unsafe {
+        let mut policies = vec![];
+        policies.extend(Retained::from_raw(SecPolicyCreateSSL(1, host)));
+        if revocation_flags != 0 {
+            policies.extend(Retained::from_raw(SecPolicyCreateRevocation(
+                revocation_flags,
+            )));
+        }
+        let policies = NSArray::from_retained_slice(&policies);
+        SecTrustSetPolicies(trust, &policies) == ERR_SEC_SUCCESS
+            && SecTrustEvaluateWithError(trust, ptr::null_mut())
+    };
+    if !trusted {
+        return (
+            NSURLSessionAuthChallengeDisposition::CancelAuthenticationChallenge,
+            None,
+        );
+    }
+    let credential: Retained<NSURLCredential> =
+        unsafe { msg_send![NSURLCredential::class(), credentialForTrust: trust] };
+    (
+        NSURLSessionAuthChallengeDisposition::UseCredential,
+        Some(credential),
+    )
+}
+
+/// Looks up the identity of the client certificate in the keychain.
+fn find_client_identity(selector: &ClientCertificate) -> io::Result<Retained<NSURLCredential>> {
+    let identities = unsafe {
+        let query = NSDictionary::<NSString, AnyObject>::from_slices(
+            &[kSecClass, kSecMatchLimit, kSecReturnRef],
+            &[
+                kSecClassIdentity.as_ref(),
+                kSecMatchLimitAll.as_ref(),
+                NSNumber::new_bool(true).as_ref(),
+            ],
+        );
+        let mut result = ptr::null_mut();
+        match SecItemCopyMatching(&query, &mut result) {
+            ERR_SEC_SUCCESS => {}
+            ERR_SEC_ITEM_NOT_FOUND => result = ptr::null_mut(),
+            status => {
+                return Err(io::Error::other(format!(
+                    "failed to query keychain identities: OSStatus {status}"
+                )))
+            }
+        }
+        Retained::<NSArray<AnyObject>>::from_raw(result.cast())
+    };
+    for identity in identities
+        .map(|identities| identities.to_vec())
+        .unwrap_or_default()
+    {
+        let certificate = unsafe {
+            let mut certificate = ptr::null_mut();
+            if SecIdentityCopyCertificate(&identity, &mut certificate) != ERR_SEC_SUCCESS {
+                continue;
+            }
+            let Some(certificate) = Retained::<AnyObject>::from_raw(certificate) else {
+                continue;
+            };
+            certificate
+        };
+        let is_match = match selector {
+            ClientCertificate::Thumbprint(thumbprint) => {
+                let Some(der) =
+                    (unsafe { Retained::from_raw(SecCertificateCopyData(&certificate)) })
+                else {
+                    continue;
+                };
+                let der = der.to_vec();
+                let mut digest = [0; SHA1_DIGEST_LENGTH];
+                unsafe {
+                    CC_SHA1(der.as_ptr().cast(), der.len() as _, digest.as_mut_ptr());
+                }
+                digest[..] == thumbprint[..]
+            }
+            ClientCertificate::Subject(subject) => {
+                unsafe { Retained::from_raw(SecCertificateCopySubjectSummary(&certificate)) }
+                    .is_some_and(|summary| summary.to_string() == *subject)
+            }
+        };
+        if !is_match {
+            continue;
+        }
+        let identity = Retained::as_ptr(&identity).cast::<SecIdentity>();
+        let credential: Retained<NSURLCredential> = unsafe {
+            msg_send![
+                NSURLCredential::class(),
+                credentialWithIdentity: identity,
+                certificates: None::<&NSArray>,
+                persistence: NSURLCredentialPersistence::ForSession,
+            ]
+        };
+        return Ok(credential);
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "client certificate not found in the keychain",
+    ))
+}
@@ -1,29 +1,128 @@
 use std::borrow::Cow;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use nyquest_interface::client::{
-    BuildClientError, BuildClientResult, CachingBehavior, ClientOptions,
+    BuildClientError, BuildClientResult, CachingBehavior, ClientOptions, DecompressionLimits,
+    PushPolicy, QualityOfService, ResponseHeaderLimits,
 };
 
-use nyquest_interface::{Body, Error as NyquestError, Method, Request, Result as NyquestResult};
+use nyquest_interface::{
+    header, Body, CachePolicy, Error as NyquestError, Method, Request, Result as NyquestResult,
+};
 use objc2::rc::Retained;
+use objc2::runtime::ProtocolObject;
 use objc2::AllocAnyThread;
 use objc2_foundation::{
     ns_string, NSCharacterSet, NSData, NSDictionary, NSMutableCharacterSet, NSMutableURLRequest,
-    NSString, NSURLRequestCachePolicy, NSUTF8StringEncoding, NSURL,
+    NSOperationQueue, NSQualityOfService, NSString, NSURLCache, NSURLRequestCachePolicy,
+    NSURLSession, NSURLSessionConfiguration, NSURLSessionDataTask, NSURLSessionTask,
+    NSUTF8StringEncoding, NSURL,
 };
 
+use crate::challenge::ChallengePolicy;
+use crate::datatask::{DataTaskDelegate, SessionDelegate};
+use crate::upload::{BoxedReadChunk, ReadChunk};
+
+/// The session shared by the clones of a client, which is invalidated once all of them are
+/// dropped.
+struct Session {
+    session: Retained<NSURLSession>,
+    delegate: Retained<SessionDelegate>,
+}
+
+// Safety: `NSURLSession` is thread-safe, and the delegate synchronizes its state
+unsafe impl Send for Session {}
+unsafe impl Sync for Session {}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // The session retains its delegate until invalidated
+        unsafe { self.session.finishTasksAndInvalidate() }
+    }
+}
+
 #[derive(Clone)]
 pub struct NSUrlSessionClient {
-    pub(crate) session: Retained<objc2_foundation::NSURLSession>,
+    session: Arc<Session>,
     pub(crate) base_url: Option<Retained<NSURL>>,
     pub(crate) max_response_buffer_size: Option<u64>,
+    pub(crate) decompression_limits: Option<DecompressionLimits>,
+    pub(crate) header_limits: Option<ResponseHeaderLimits>,
+    pub(crate) follow_redirects: bool,
+    idle_timeout: Option<Duration>,
+    pub(crate) challenge_policy: Arc<ChallengePolicy>,
 }
 
 impl NSUrlSessionClient {
     pub(crate) fn create(options: ClientOptions) -> BuildClientResult<Self> {
+        if options.proxy.is_some() {
+            return Err(BuildClientError::Unsupported("proxy"));
+        }
+        if !options.tls_session_resumption {
+            return Err(BuildClientError::Unsupported("tls_session_resumption"));
+        }
+        if options.tls_early_data {
+            return Err(BuildClientError::Unsupported("tls_early_data"));
+        }
+        if matches!(options.http2_push, PushPolicy::Notify(_)) {
+            return Err(BuildClientError::Unsupported("http2_push"));
+        }
+        if options.http2_keep_alive.is_some() {
+            return Err(BuildClientError::Unsupported("http2_keep_alive"));
+        }
+        // Options only `curl` backend supports
+        let unsupported = [
+            ("proxy_tls", options.proxy_tls.is_some()),
+            ("alt_svc_cache", options.alt_svc_cache.is_some()),
+            ("hsts_cache", options.hsts_cache.is_some()),
+            ("verbose", options.verbose),
+            ("resolve", !options.resolve.is_empty()),
+            ("dns_resolver", options.resolver.is_some()),
+            ("dns_servers", !options.dns_servers.is_empty()),
+            ("doh_url", options.doh_url.is_some()),
+            ("dns_cache_timeout", options.dns_cache_timeout.is_some()),
+            ("tcp_keepalive", options.tcp_keepalive.is_some()),
+            ("tcp_nodelay", options.tcp_nodelay.is_some()),
+        ];
+        if let Some((option, _)) = unsupported.into_iter().find(|(_, set)| *set) {
+            return Err(BuildClientError::Unsupported(option));
+        }
+        let challenge_policy = ChallengePolicy::new(&options)
+            .map_err(|e| BuildClientError::BackendError(NyquestError::Io(e)))?;
         let session = unsafe {
-            let config = objc2_foundation::NSURLSessionConfiguration::defaultSessionConfiguration();
+            let config = if options.ephemeral {
+                NSURLSessionConfiguration::ephemeralSessionConfiguration()
+            } else {
+                NSURLSessionConfiguration::defaultSessionConfiguration()
+            };
+            config.setWaitsForConnectivity(options.wait_for_connectivity);
+            config.setAllowsCellularAccess(options.allow_cellular_access);
+            config.setAllowsExpensiveNetworkAccess(options.allow_expensive_network_access);
+            config.setAllowsConstrainedNetworkAccess(options.allow_constrained_network_access);
+            if options.cache_memory_capacity.is_some()
+                || options.cache_disk_capacity.is_some()
+                || options.cache_directory.is_some()
+            {
+                let shared = NSURLCache::sharedURLCache();
+                let directory = options.cache_directory.as_deref().map(|path| {
+                    NSURL::fileURLWithPath_isDirectory(
+                        &NSString::from_str(&path.to_string_lossy()),
+                        true,
+                    )
+                });
+                let cache = NSURLCache::initWithMemoryCapacity_diskCapacity_directoryURL(
+                    NSURLCache::alloc(),
+                    options
+                        .cache_memory_capacity
+                        .map_or_else(|| shared.memoryCapacity(), |c| c as _),
+                    options
+                        .cache_disk_capacity
+                        .map_or_else(|| shared.diskCapacity(), |c| c as _),
+                    directory.as_deref(),
+                );
+                config.setURLCache(Some(&cache));
+            }
             if options.caching_behavior == CachingBehavior::Disabled {
                 config.setRequestCachePolicy(NSURLRequestCachePolicy::ReloadIgnoringLocalCacheData);
             }
@@ -34,7 +133,11 @@ impl NSUrlSessionClient {
                 config.setHTTPShouldSetCookies(false);
             }
             if let Some(request_timeout) = options.request_timeout {
-                config.setTimeoutIntervalForRequest(request_timeout.as_secs_f64());
+                config.setTimeoutIntervalForResource(request_timeout.as_secs_f64());
+            }
+            // The request timeout interval of the session is how long a task may stay idle
+            if let Some(idle_timeout) = options.read_timeout.or(options.request_timeout) {
+                config.setTimeoutIntervalForRequest(idle_timeout.as_secs_f64());
             }
             if !options.default_headers.is_empty() || options.user_agent.is_some() {
                 let headers = options
@@ -56,7 +159,25 @@ impl NSUrlSessionClient {
                 ));
             }
             // TODO: set options
-            objc2_foundation::NSURLSession::sessionWithConfiguration(&config)
+            // Callbacks are delivered in order on a serial queue
+            let queue = NSOperationQueue::new();
+            queue.setMaxConcurrentOperationCount(1);
+            queue.setName(Some(ns_string!("nyquest.nsurlsession.delegate")));
+            if let Some(qos) = options.callback_quality_of_service {
+                queue.setQualityOfService(match qos {
+                    QualityOfService::UserInteractive => NSQualityOfService::UserInteractive,
+                    QualityOfService::UserInitiated => NSQualityOfService::UserInitiated,
+                    QualityOfService::Utility => NSQualityOfService::Utility,
+                    QualityOfService::Background => NSQualityOfService::Background,
+                });
+            }
+            let delegate = SessionDelegate::new();
+            let session = NSURLSession::sessionWithConfiguration_delegate_delegateQueue(
+                &config,
+                Some(ProtocolObject::from_ref(&*delegate)),
+                Some(&queue),
+            );
+            Session { session, delegate }
         };
         let base_url = options
             .base_url
@@ -66,16 +187,53 @@ impl NSUrlSessionClient {
             })
             .transpose()?;
         Ok(Self {
-            session,
+            session: Arc::new(session),
             base_url,
             max_response_buffer_size: options.max_response_buffer_size,
+            decompression_limits: options.decompression_limits,
+            header_limits: options.max_response_headers,
+            follow_redirects: options.follow_redirects,
+            idle_timeout: options.read_timeout.or(options.request_timeout),
+            challenge_policy: Arc::new(challenge_policy),
         })
     }
 
+    /// Starts the task, with its callbacks routed to the delegate.
+    pub(crate) fn start_task(&self, task: &NSURLSessionTask, delegate: &DataTaskDelegate) {
+        self.session.delegate.register_task(task, delegate);
+        unsafe { task.resume() }
+    }
+
+    /// Builds the task for the request. Streamed bodies are returned separately, to be handed
+    /// over to the task delegate.
     pub(crate) fn build_data_task<S>(
         &self,
         req: Request<S>,
-    ) -> NyquestResult<Retained<objc2_foundation::NSURLSessionDataTask>> {
+        mut map_stream: impl FnMut(S) -> Box<dyn ReadChunk>,
+    ) -> NyquestResult<(Retained<NSURLSessionDataTask>, Option<BoxedReadChunk>)> {
+        // The timeouts of the client apply through the session configuration otherwise
+        let deadline_timeout = req
+            .deadline
+            .and_then(|_| req.effective_timeout(self.idle_timeout));
+        let is_get = match &req.method {
+            Method::Get => true,
+            Method::Other(method) => {
+                method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD")
+            }
+            _ => false,
+        };
+        if is_get && req.body.is_some() {
+            // NSURLSession fails such requests with an opaque error
+            return Err(NyquestError::Unsupported("body on GET requests"));
+        }
+        if req.additional_headers.contains(header::HOST.as_str()) {
+            // NSURLSession sets the header from the URL, whatever the request says
+            return Err(NyquestError::Unsupported("host header"));
+        }
+        if req.proxy.is_some() {
+            // Proxies are part of the session configuration
+            return Err(NyquestError::Unsupported("proxy"));
+        }
         let nsreq = NSMutableURLRequest::alloc();
         unsafe {
             let url = NSURL::URLWithString_relativeToURL(
@@ -95,12 +253,22 @@ impl NSUrlSessionClient {
                     Method::Other(method) => &*method_storage.insert(NSString::from_str(&method)),
                 });
             }
+            if let Some(timeout) = deadline_timeout {
+                nsreq.setTimeoutInterval(timeout.as_secs_f64());
+            }
+            if let Some(cache_policy) = req.cache_policy {
+                nsreq.setCachePolicy(match cache_policy {
+                    CachePolicy::Reload => NSURLRequestCachePolicy::ReloadIgnoringLocalCacheData,
+                    CachePolicy::PreferCache => NSURLRequestCachePolicy::ReturnCacheDataElseLoad,
+                });
+            }
             for (name, value) in &req.additional_headers {
                 nsreq.setValue_forHTTPHeaderField(
                     Some(&NSString::from_str(value)),
                     &NSString::from_str(name),
                 );
             }
+            let mut upload_body = None::<(Box<dyn ReadChunk>, Option<u64>)>;
             if let Some(body) = req.body {
                 match body {
                     Body::Bytes {
@@ -126,7 +294,7 @@ impl NSUrlSessionClient {
                     #[cfg(feature = "multipart")]
                     Body::Multipart { parts } => {
                         use crate::multipart::{
-                            generate_multipart_body, generate_multipart_boundary,
+                            generate_multipart_body, generate_multipart_boundary, MultipartBody,
                         };
                         let boundary = generate_multipart_boundary();
                         let content_type = format!("multipart/form-data; boundary={}", boundary);
@@ -134,12 +302,32 @@ impl NSUrlSessionClient {
                             Some(&NSString::from_str(&content_type)),
                             ns_string!("content-type"),
                         );
-                        nsreq.setHTTPBody(Some(&generate_multipart_body(&boundary, parts)));
+                        match generate_multipart_body(&boundary, parts, &mut map_stream)? {
+                            MultipartBody::Data(body) => nsreq.setHTTPBody(Some(&body)),
+                            MultipartBody::Streamed {
+                                reader,
+                                content_length,
+                            } => upload_body = Some((Box::new(reader), content_length)),
+                        }
+                    }
+                    Body::Stream(stream) => {
+                        upload_body = Some((map_stream(stream.stream), stream.content_length));
                     }
-                    _ => todo!("body types"),
                 }
             }
-            Ok(self.session.dataTaskWithRequest(&nsreq))
+            let Some((upload_body, content_length)) = upload_body else {
+                return Ok((self.session.session.dataTaskWithRequest(&nsreq), None));
+            };
+            // Without a declared length, the body is sent with chunked encoding
+            if let Some(content_length) = content_length {
+                nsreq.setValue_forHTTPHeaderField(
+                    Some(&NSString::from_str(&content_length.to_string())),
+                    ns_string!("content-length"),
+                );
+            }
+            // Upload tasks are data tasks that ask their delegate for the body stream
+            let task = self.session.session.uploadTaskWithStreamedRequest(&nsreq);
+            Ok((Retained::into_super(task), Some(upload_body)))
         }
     }
 }
@@ -3,6 +3,10 @@ mod generic_waker;
 mod ivars;
 mod rcblock_ext;
 mod retained_ext;
+mod session_delegate;
 
-pub(crate) use delegate::{DataTaskDelegate, DataTaskSharedContextRetained};
+pub(crate) use delegate::{ChunkState, DataTaskDelegate, DataTaskSharedContextRetained};
 pub(crate) use generic_waker::GenericWaker;
+#[cfg(feature = "blocking")]
+pub(crate) use ivars::DataChunk;
+pub(crate) use session_delegate::SessionDelegate;
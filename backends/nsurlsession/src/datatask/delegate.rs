@@ -1,22 +1,29 @@
 #![allow(non_snake_case)]
 
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 use arc_swap::ArcSwapAny;
 use block2::DynBlock;
-use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
+use nyquest_interface::client::ResponseHeaderLimits;
+use nyquest_interface::{Error as NyquestError, Result as NyquestResult, TimingsCallback, TlsInfo};
 use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 use objc2::{define_class, msg_send, AllocAnyThread, DefinedClass};
 use objc2_foundation::{
-    NSCopying, NSData, NSError, NSHTTPURLResponse, NSObject, NSObjectProtocol, NSURLResponse,
-    NSURLSession, NSURLSessionDataDelegate, NSURLSessionDataTask, NSURLSessionDelegate,
-    NSURLSessionResponseDisposition, NSURLSessionTask, NSURLSessionTaskDelegate,
+    NSCopying, NSData, NSError, NSHTTPURLResponse, NSInputStream, NSObject, NSObjectProtocol,
+    NSString, NSURLAuthenticationChallenge, NSURLCredential, NSURLRequest, NSURLResponse,
+    NSURLSession, NSURLSessionAuthChallengeDisposition, NSURLSessionDataTask,
+    NSURLSessionResponseDisposition, NSURLSessionTask, NSURLSessionTaskMetrics,
 };
 
+use crate::client::NSUrlSessionClient;
 use crate::error::IntoNyquestResult;
+use crate::metrics::{timings_from_metrics, tls_info_from_metrics};
+use crate::upload::{bind_input_stream, ReadChunk};
 
 use super::generic_waker::GenericWaker;
-use super::ivars::{DataTaskIvars, DataTaskIvarsShared};
+use super::ivars::{chunk_queue, DataChunk, DataTaskIvars, DataTaskIvarsShared};
 
 define_class!(
     // SAFETY:
@@ -26,64 +33,31 @@ define_class!(
     // #[thread_kind = MainThreadOnly]
     #[name = "Nyquest_DataTaskDelegate"]
     #[ivars = DataTaskIvars]
+    /// The state of a single task, which receives the callbacks of the task from the session
+    /// delegate.
     pub(crate) struct DataTaskDelegate;
 
     // SAFETY: `NSObjectProtocol` has no safety requirements.
     unsafe impl NSObjectProtocol for DataTaskDelegate {}
-
-    // SAFETY: `NSApplicationDelegate` has no safety requirements.
-    unsafe impl NSURLSessionDelegate for DataTaskDelegate {}
-
-    // SAFETY: `NSApplicationDelegate` has no safety requirements.
-    unsafe impl NSURLSessionTaskDelegate for DataTaskDelegate {
-        #[unsafe(method(URLSession:task:didCompleteWithError:))]
-        fn URLSession_task_didCompleteWithError(
-            &self,
-            session: &NSURLSession,
-            task: &NSURLSessionTask,
-            error: Option<&NSError>,
-        ) {
-            self.callback_URLSession_task_didCompleteWithError(session, task, error);
-        }
-    }
-
-    unsafe impl NSURLSessionDataDelegate for DataTaskDelegate {
-        #[unsafe(method(URLSession:dataTask:didReceiveResponse:completionHandler:))]
-        fn URLSession_dataTask_didReceiveResponse_completionHandler(
-            &self,
-            session: &NSURLSession,
-            data_task: &NSURLSessionDataTask,
-            response: &NSURLResponse,
-            completion_handler: &DynBlock<dyn Fn(NSURLSessionResponseDisposition)>,
-        ) {
-            self.callback_URLSession_dataTask_didReceiveResponse_completionHandler(
-                session,
-                data_task,
-                response,
-                completion_handler,
-            );
-        }
-
-        #[unsafe(method(URLSession:dataTask:didReceiveData:))]
-        fn URLSession_dataTask_didReceiveData(
-            &self,
-            session: &NSURLSession,
-            data_task: &NSURLSessionDataTask,
-            data: &NSData,
-        ) {
-            self.callback_URLSession_dataTask_didReceiveData(session, data_task, data);
-        }
-    }
 );
 
+pub(crate) enum ChunkState {
+    Chunk(DataChunk),
+    Pending,
+    End,
+}
+
 pub(crate) struct DataTaskSharedContextRetained {
     retained: Retained<DataTaskDelegate>,
 }
 
 impl DataTaskDelegate {
+    /// Creates the delegate of a task, with the limits and policies of the client.
     pub(crate) fn new(
         waker: GenericWaker,
-        max_response_buffer_size: Option<u64>,
+        client: &NSUrlSessionClient,
+        upload_body: Option<Box<dyn ReadChunk>>,
+        timings: Option<TimingsCallback>,
     ) -> Retained<Self> {
         let this = Self::alloc().set_ivars(DataTaskIvars {
             // continue_response_block: ArcSwapAny::new(None),
@@ -92,9 +66,16 @@ impl DataTaskDelegate {
                 waker,
                 completed: AtomicBool::new(false),
                 received_error: Default::default(),
-                response_buffer: Default::default(),
+                response_chunks: Mutex::new(chunk_queue()),
+                tls_info: Mutex::new(None),
             },
-            max_response_buffer_size,
+            max_response_buffer_size: client.max_response_buffer_size,
+            decompression_limits: client.decompression_limits.clone(),
+            header_limits: client.header_limits,
+            follow_redirects: client.follow_redirects,
+            upload_body: Mutex::new(upload_body),
+            challenge_policy: client.challenge_policy.clone(),
+            timings,
         });
         // SAFETY: The signature of `NSObject`'s `init` method is correct.
         unsafe { msg_send![super(this), init] }
@@ -104,22 +85,29 @@ impl DataTaskDelegate {
         DataTaskSharedContextRetained { retained }
     }
 
-    fn callback_URLSession_dataTask_didReceiveResponse_completionHandler(
+    pub(super) fn callback_URLSession_dataTask_didReceiveResponse_completionHandler(
         &self,
         _session: &NSURLSession,
         data_task: &NSURLSessionDataTask,
         response: &NSURLResponse,
         completion_handler: &DynBlock<dyn Fn(NSURLSessionResponseDisposition)>,
     ) {
+        let ivars = self.ivars();
+        if let Some(limits) = &ivars.header_limits {
+            if let Err(err) = check_header_limits(limits, response) {
+                completion_handler.call((NSURLSessionResponseDisposition::Cancel,));
+                ivars.set_error(err);
+                return;
+            }
+        }
         unsafe {
             data_task.suspend();
         }
         completion_handler.call((NSURLSessionResponseDisposition::Allow,));
-        let ivars = self.ivars();
         ivars.shared.response.store(Some(response.copy().into()));
         ivars.shared.waker.wake();
     }
-    fn callback_URLSession_task_didCompleteWithError(
+    pub(super) fn callback_URLSession_task_didCompleteWithError(
         &self,
         _session: &NSURLSession,
         _task: &NSURLSessionTask,
@@ -132,59 +120,176 @@ impl DataTaskDelegate {
         }
         ivars.shared.waker.wake();
     }
-    fn callback_URLSession_dataTask_didReceiveData(
+    pub(super) fn callback_URLSession_task_didFinishCollectingMetrics(
+        &self,
+        _session: &NSURLSession,
+        _task: &NSURLSessionTask,
+        metrics: &NSURLSessionTaskMetrics,
+    ) {
+        *self.ivars().shared.tls_info.lock().unwrap() = tls_info_from_metrics(metrics);
+        if let Some(TimingsCallback(callback)) = &self.ivars().timings {
+            callback(timings_from_metrics(metrics));
+        }
+    }
+    pub(super) fn callback_URLSession_task_willPerformHTTPRedirection_newRequest_completionHandler(
+        &self,
+        _session: &NSURLSession,
+        _task: &NSURLSessionTask,
+        _response: &NSHTTPURLResponse,
+        request: &NSURLRequest,
+        completion_handler: &DynBlock<dyn Fn(*mut NSURLRequest)>,
+    ) {
+        // Without a new request, the redirect response is delivered as the final one
+        let request_ptr = if self.ivars().follow_redirects {
+            (request as *const NSURLRequest).cast_mut()
+        } else {
+            std::ptr::null_mut()
+        };
+        completion_handler.call((request_ptr,));
+    }
+    pub(super) fn callback_URLSession_task_didReceiveChallenge_completionHandler(
+        &self,
+        _session: &NSURLSession,
+        _task: &NSURLSessionTask,
+        challenge: &NSURLAuthenticationChallenge,
+        completion_handler: &DynBlock<
+            dyn Fn(NSURLSessionAuthChallengeDisposition, *mut NSURLCredential),
+        >,
+    ) {
+        let (disposition, credential) = self.ivars().challenge_policy.respond(challenge);
+        let credential_ptr = credential.as_deref().map_or(std::ptr::null_mut(), |c| {
+            (c as *const NSURLCredential).cast_mut()
+        });
+        completion_handler.call((disposition, credential_ptr));
+    }
+    pub(super) fn callback_URLSession_task_needNewBodyStream(
+        &self,
+        _session: &NSURLSession,
+        _task: &NSURLSessionTask,
+        completion_handler: &DynBlock<dyn Fn(*mut NSInputStream)>,
+    ) {
+        let ivars = self.ivars();
+        // The body can only be read once, so a second request for it (e.g. on redirection or
+        // authentication) fails the task
+        let upload_body = ivars.upload_body.lock().unwrap().take();
+        let stream = match upload_body.map(bind_input_stream).transpose() {
+            Ok(stream) => stream,
+            Err(e) => {
+                ivars.set_error(NyquestError::Io(e));
+                None
+            }
+        };
+        let stream_ptr = stream
+            .as_ref()
+            .map_or(std::ptr::null_mut(), |s| Retained::as_ptr(s).cast_mut());
+        completion_handler.call((stream_ptr,));
+    }
+    pub(super) fn callback_URLSession_dataTask_didReceiveData(
         &self,
         _session: &NSURLSession,
         data_task: &NSURLSessionDataTask,
         data: &NSData,
     ) {
         let ivars = self.ivars();
-        let mut buffer = ivars.shared.response_buffer.lock().unwrap();
-        let data = unsafe { data.as_bytes_unchecked() };
-        if let Some(max_response_buffer_size) = ivars.max_response_buffer_size {
-            if buffer.len() + data.len() > max_response_buffer_size as usize {
-                drop(buffer);
-                ivars.set_error(NyquestError::ResponseTooLarge);
-                unsafe {
-                    data_task.cancel();
-                }
-                return;
+        let mut chunks = ivars.shared.response_chunks.lock().unwrap();
+        let received = chunks.flow().received() + data.length() as u64;
+        let buffer_exceeded = ivars
+            .max_response_buffer_size
+            .is_some_and(|max_response_buffer_size| received > max_response_buffer_size);
+        // The task counts the bytes received before decompression
+        let decompression_exceeded = ivars.decompression_limits.as_ref().is_some_and(|limits| {
+            let compressed = unsafe { data_task.countOfBytesReceived() } as u64;
+            limits.check(compressed, received).is_err()
+        });
+        if buffer_exceeded || decompression_exceeded {
+            drop(chunks);
+            ivars.set_error(NyquestError::ResponseTooLarge);
+            unsafe {
+                data_task.cancel();
             }
+            return;
         }
-        buffer.extend_from_slice(data);
+        if chunks.push(DataChunk::new(data)) {
+            unsafe {
+                data_task.suspend();
+            }
+        }
+        drop(chunks);
+        ivars.shared.waker.wake();
     }
 }
 
+/// Checks the headers of the response, which are only exposed once received entirely.
+fn check_header_limits(
+    limits: &ResponseHeaderLimits,
+    response: &NSURLResponse,
+) -> NyquestResult<()> {
+    let Some(response) = response.downcast_ref::<NSHTTPURLResponse>() else {
+        return Ok(());
+    };
+    let (names, values) = unsafe { response.allHeaderFields() }.to_vecs();
+    let size = names
+        .iter()
+        .zip(&values)
+        .map(|(name, value)| {
+            let len = |s: &AnyObject| s.downcast_ref::<NSString>().map_or(0, NSString::len);
+            ResponseHeaderLimits::header_size(len(name), len(value))
+        })
+        .sum();
+    limits.check(size, names.len())
+}
+
 impl DataTaskSharedContextRetained {
     pub(crate) fn waker_ref(&self) -> &GenericWaker {
         &self.retained.ivars().shared.waker
     }
 
+    pub(crate) fn tls_info(&self) -> Option<TlsInfo> {
+        self.retained
+            .ivars()
+            .shared
+            .tls_info
+            .lock()
+            .unwrap()
+            .clone()
+    }
+
     pub(crate) fn try_take_response(&self) -> NyquestResult<Option<Retained<NSHTTPURLResponse>>> {
         let shared = &self.retained.ivars().shared;
         if let Some(error) = shared.received_error.lock().unwrap().take() {
             return Err(error);
         }
-        let response = shared.response.swap(None);
-        Ok(response.and_then(|res| res.0.downcast::<NSHTTPURLResponse>().ok()))
+        let Some(response) = shared.response.swap(None) else {
+            return Ok(None);
+        };
+        // Responses for `file:` URLs are not HTTP responses, and would never be taken otherwise
+        let response = response.0.downcast::<NSHTTPURLResponse>();
+        response
+            .map(Some)
+            .map_err(|_| NyquestError::Unsupported("non-HTTP URL schemes"))
     }
 
-    pub(crate) fn is_completed(&self) -> bool {
-        self.retained
-            .ivars()
-            .shared
-            .completed
-            .load(Ordering::SeqCst)
-    }
-
-    pub(crate) fn take_response_buffer(&self) -> NyquestResult<Vec<u8>> {
+    /// Takes the next chunk of the response body, resuming the task if it is suspended and
+    /// enough of the body has been consumed.
+    pub(crate) fn try_take_chunk(&self, task: &NSURLSessionDataTask) -> NyquestResult<ChunkState> {
         let shared = &self.retained.ivars().shared;
 
         let err = shared.received_error.lock().unwrap().take();
         err.map(Err::<(), _>).transpose().into_nyquest_result()?;
 
-        let mut buffer = self.retained.ivars().shared.response_buffer.lock().unwrap();
-        Ok(std::mem::take(&mut *buffer))
+        // All the chunks are queued before the task completes
+        let completed = shared.completed.load(Ordering::SeqCst);
+        let (chunk, resume) = shared.response_chunks.lock().unwrap().pop();
+        if resume {
+            unsafe {
+                task.resume();
+            }
+        }
+        Ok(match chunk {
+            Some(chunk) => ChunkState::Chunk(chunk),
+            None if completed => ChunkState::End,
+            None => ChunkState::Pending,
+        })
     }
 }
 
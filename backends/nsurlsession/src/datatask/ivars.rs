@@ -1,11 +1,17 @@
+use std::ops::Deref;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use arc_swap::ArcSwapAny;
-use nyquest_interface::Error as NyquestError;
-use objc2_foundation::NSURLResponse;
+use nyquest_interface::chunk_queue::{ChunkQueue, FlowControl};
+use nyquest_interface::client::{DecompressionLimits, ResponseHeaderLimits};
+use nyquest_interface::{Error as NyquestError, TimingsCallback, TlsInfo};
+use objc2::rc::Retained;
+use objc2_foundation::{NSCopying, NSData, NSURLResponse};
 
+use crate::challenge::ChallengePolicy;
 use crate::error::IntoNyquestResult;
+use crate::upload::ReadChunk;
 
 use super::generic_waker::GenericWaker;
 use super::retained_ext::SwappableRetained;
@@ -15,6 +21,13 @@ pub(crate) struct DataTaskIvars {
     //     ArcSwapAny<Option<SwappableRcBlock<dyn Fn(NSURLSessionResponseDisposition)>>>,
     pub(super) shared: DataTaskIvarsShared,
     pub(super) max_response_buffer_size: Option<u64>,
+    pub(super) decompression_limits: Option<DecompressionLimits>,
+    pub(super) header_limits: Option<ResponseHeaderLimits>,
+    pub(super) follow_redirects: bool,
+    /// The streamed request body, handed over to the task when it asks for a body stream.
+    pub(super) upload_body: Mutex<Option<Box<dyn ReadChunk>>>,
+    pub(super) challenge_policy: Arc<ChallengePolicy>,
+    pub(super) timings: Option<TimingsCallback>,
 }
 
 pub(super) struct DataTaskIvarsShared {
@@ -22,7 +35,45 @@ pub(super) struct DataTaskIvarsShared {
     pub(super) waker: GenericWaker,
     pub(super) completed: AtomicBool,
     pub(super) received_error: Mutex<Option<NyquestError>>,
-    pub(super) response_buffer: Mutex<Vec<u8>>,
+    pub(super) response_chunks: Mutex<ChunkQueue<DataChunk>>,
+    /// The TLS parameters of the connection, known once the task collects its metrics.
+    pub(super) tls_info: Mutex<Option<TlsInfo>>,
+}
+
+/// A chunk of the response body, kept as received from the task until it is consumed.
+pub(crate) struct DataChunk(Retained<NSData>);
+
+// Safety: the data is immutable
+unsafe impl Send for DataChunk {}
+unsafe impl Sync for DataChunk {}
+
+impl DataChunk {
+    pub(super) fn new(data: &NSData) -> Self {
+        // Copying an immutable `NSData` only retains it
+        Self(data.copy())
+    }
+}
+
+impl Deref for DataChunk {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // Safety: the data is immutable
+        unsafe { self.0.as_bytes_unchecked() }
+    }
+}
+
+impl AsRef<[u8]> for DataChunk {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Response body chunks passed from the delegate to the consumer of the response.
+///
+/// The task is suspended initially, once the response headers arrive.
+pub(super) fn chunk_queue() -> ChunkQueue<DataChunk> {
+    ChunkQueue::new(FlowControl::default().starting_suspended())
 }
 
 impl DataTaskIvars {
@@ -0,0 +1,199 @@
+#![allow(non_snake_case)]
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use block2::DynBlock;
+use objc2::rc::Retained;
+use objc2::{define_class, msg_send, AllocAnyThread, DefinedClass, Message};
+use objc2_foundation::{
+    NSData, NSError, NSHTTPURLResponse, NSInputStream, NSObject, NSObjectProtocol, NSUInteger,
+    NSURLAuthenticationChallenge, NSURLCredential, NSURLRequest, NSURLResponse, NSURLSession,
+    NSURLSessionAuthChallengeDisposition, NSURLSessionDataDelegate, NSURLSessionDataTask,
+    NSURLSessionDelegate, NSURLSessionResponseDisposition, NSURLSessionTask,
+    NSURLSessionTaskDelegate, NSURLSessionTaskMetrics,
+};
+
+use super::delegate::DataTaskDelegate;
+
+pub(crate) struct SessionDelegateIvars {
+    /// The state of the tasks in flight, keyed by task identifiers.
+    tasks: Mutex<HashMap<NSUInteger, Retained<DataTaskDelegate>>>,
+}
+
+define_class!(
+    // SAFETY:
+    // - The superclass NSObject does not have any subclassing requirements.
+    // - `SessionDelegate` does not implement `Drop`.
+    #[unsafe(super = NSObject)]
+    #[name = "Nyquest_SessionDelegate"]
+    #[ivars = SessionDelegateIvars]
+    /// The delegate of the session of a client, which dispatches the callbacks of all its tasks.
+    pub(crate) struct SessionDelegate;
+
+    // SAFETY: `NSObjectProtocol` has no safety requirements.
+    unsafe impl NSObjectProtocol for SessionDelegate {}
+
+    // SAFETY: `NSURLSessionDelegate` has no safety requirements.
+    unsafe impl NSURLSessionDelegate for SessionDelegate {}
+
+    // SAFETY: `NSURLSessionTaskDelegate` has no safety requirements.
+    unsafe impl NSURLSessionTaskDelegate for SessionDelegate {
+        #[unsafe(method(URLSession:task:didCompleteWithError:))]
+        fn URLSession_task_didCompleteWithError(
+            &self,
+            session: &NSURLSession,
+            task: &NSURLSessionTask,
+            error: Option<&NSError>,
+        ) {
+            // No more callbacks are made for the task
+            let delegate = self
+                .ivars()
+                .tasks
+                .lock()
+                .unwrap()
+                .remove(&unsafe { task.taskIdentifier() });
+            if let Some(delegate) = delegate {
+                delegate.callback_URLSession_task_didCompleteWithError(session, task, error);
+            }
+        }
+
+        #[unsafe(method(URLSession:task:didFinishCollectingMetrics:))]
+        fn URLSession_task_didFinishCollectingMetrics(
+            &self,
+            session: &NSURLSession,
+            task: &NSURLSessionTask,
+            metrics: &NSURLSessionTaskMetrics,
+        ) {
+            if let Some(delegate) = self.task_delegate(task) {
+                delegate
+                    .callback_URLSession_task_didFinishCollectingMetrics(session, task, metrics);
+            }
+        }
+
+        #[unsafe(method(URLSession:task:willPerformHTTPRedirection:newRequest:completionHandler:))]
+        fn URLSession_task_willPerformHTTPRedirection_newRequest_completionHandler(
+            &self,
+            session: &NSURLSession,
+            task: &NSURLSessionTask,
+            response: &NSHTTPURLResponse,
+            request: &NSURLRequest,
+            completion_handler: &DynBlock<dyn Fn(*mut NSURLRequest)>,
+        ) {
+            match self.task_delegate(task) {
+                Some(delegate) => delegate
+                    .callback_URLSession_task_willPerformHTTPRedirection_newRequest_completionHandler(
+                        session,
+                        task,
+                        response,
+                        request,
+                        completion_handler,
+                    ),
+                None => completion_handler.call(((request as *const NSURLRequest).cast_mut(),)),
+            }
+        }
+
+        #[unsafe(method(URLSession:task:didReceiveChallenge:completionHandler:))]
+        fn URLSession_task_didReceiveChallenge_completionHandler(
+            &self,
+            session: &NSURLSession,
+            task: &NSURLSessionTask,
+            challenge: &NSURLAuthenticationChallenge,
+            completion_handler: &DynBlock<
+                dyn Fn(NSURLSessionAuthChallengeDisposition, *mut NSURLCredential),
+            >,
+        ) {
+            match self.task_delegate(task) {
+                Some(delegate) => delegate
+                    .callback_URLSession_task_didReceiveChallenge_completionHandler(
+                        session,
+                        task,
+                        challenge,
+                        completion_handler,
+                    ),
+                None => completion_handler.call((
+                    NSURLSessionAuthChallengeDisposition::PerformDefaultHandling,
+                    std::ptr::null_mut(),
+                )),
+            }
+        }
+
+        #[unsafe(method(URLSession:task:needNewBodyStream:))]
+        fn URLSession_task_needNewBodyStream(
+            &self,
+            session: &NSURLSession,
+            task: &NSURLSessionTask,
+            completion_handler: &DynBlock<dyn Fn(*mut NSInputStream)>,
+        ) {
+            match self.task_delegate(task) {
+                Some(delegate) => delegate.callback_URLSession_task_needNewBodyStream(
+                    session,
+                    task,
+                    completion_handler,
+                ),
+                None => completion_handler.call((std::ptr::null_mut(),)),
+            }
+        }
+    }
+
+    // SAFETY: `NSURLSessionDataDelegate` has no safety requirements.
+    unsafe impl NSURLSessionDataDelegate for SessionDelegate {
+        #[unsafe(method(URLSession:dataTask:didReceiveResponse:completionHandler:))]
+        fn URLSession_dataTask_didReceiveResponse_completionHandler(
+            &self,
+            session: &NSURLSession,
+            data_task: &NSURLSessionDataTask,
+            response: &NSURLResponse,
+            completion_handler: &DynBlock<dyn Fn(NSURLSessionResponseDisposition)>,
+        ) {
+            match self.task_delegate(data_task) {
+                Some(delegate) => delegate
+                    .callback_URLSession_dataTask_didReceiveResponse_completionHandler(
+                        session,
+                        data_task,
+                        response,
+                        completion_handler,
+                    ),
+                None => completion_handler.call((NSURLSessionResponseDisposition::Cancel,)),
+            }
+        }
+
+        #[unsafe(method(URLSession:dataTask:didReceiveData:))]
+        fn URLSession_dataTask_didReceiveData(
+            &self,
+            session: &NSURLSession,
+            data_task: &NSURLSessionDataTask,
+            data: &NSData,
+        ) {
+            if let Some(delegate) = self.task_delegate(data_task) {
+                delegate.callback_URLSession_dataTask_didReceiveData(session, data_task, data);
+            }
+        }
+    }
+);
+
+impl SessionDelegate {
+    pub(crate) fn new() -> Retained<Self> {
+        let this = Self::alloc().set_ivars(SessionDelegateIvars {
+            tasks: Mutex::new(HashMap::new()),
+        });
+        // SAFETY: The signature of `NSObject`'s `init` method is correct.
+        unsafe { msg_send![super(this), init] }
+    }
+
+    /// Routes the callbacks of the task to the delegate until the task completes. Must be called
+    /// before the task is resumed.
+    pub(crate) fn register_task(&self, task: &NSURLSessionTask, delegate: &DataTaskDelegate) {
+        self.ivars()
+            .tasks
+            .lock()
+            .unwrap()
+            .insert(unsafe { task.taskIdentifier() }, delegate.retain());
+    }
+
+    fn task_delegate(&self, task: &NSURLSessionTask) -> Option<Retained<DataTaskDelegate>> {
+        // Released before calling into the delegate, which may take a while
+        let tasks = self.ivars().tasks.lock().unwrap();
+        tasks.get(&unsafe { task.taskIdentifier() }).cloned()
+    }
+}
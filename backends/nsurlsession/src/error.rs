@@ -1,8 +1,129 @@
+#![allow(non_upper_case_globals)]
+
+use std::fmt;
 use std::io::{self, ErrorKind};
 
 use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
 use objc2::rc::{autoreleasepool, Retained};
-use objc2_foundation::{NSError, NSURLErrorTimedOut};
+use objc2_foundation::{
+    NSError, NSInteger, NSPOSIXErrorDomain, NSURLErrorBackgroundSessionWasDisconnected,
+    NSURLErrorBadServerResponse, NSURLErrorBadURL, NSURLErrorCallIsActive, NSURLErrorCancelled,
+    NSURLErrorCannotConnectToHost, NSURLErrorCannotDecodeContentData,
+    NSURLErrorCannotDecodeRawData, NSURLErrorCannotFindHost, NSURLErrorCannotParseResponse,
+    NSURLErrorClientCertificateRejected, NSURLErrorClientCertificateRequired,
+    NSURLErrorDNSLookupFailed, NSURLErrorDataLengthExceedsMaximum, NSURLErrorDataNotAllowed,
+    NSURLErrorDomain, NSURLErrorInternationalRoamingOff, NSURLErrorNetworkConnectionLost,
+    NSURLErrorNotConnectedToInternet, NSURLErrorSecureConnectionFailed,
+    NSURLErrorServerCertificateHasBadDate, NSURLErrorServerCertificateHasUnknownRoot,
+    NSURLErrorServerCertificateNotYetValid, NSURLErrorServerCertificateUntrusted,
+    NSURLErrorTimedOut, NSURLErrorUnsupportedURL, NSURLErrorUserAuthenticationRequired,
+    NSURLErrorUserCancelledAuthentication,
+};
+
+/// An error reported by `NSURLSession`.
+///
+/// Errors that do not map to a dedicated [`nyquest_interface::Error`] variant are reported as
+/// [`nyquest_interface::Error::Io`], with the [`io::ErrorKind`] derived from the error code and
+/// this error as the inner error, from which the original domain and code can be retrieved.
+#[derive(Debug, Clone)]
+pub struct NSUrlSessionError {
+    domain: String,
+    code: isize,
+    description: String,
+}
+
+impl NSUrlSessionError {
+    /// The domain of the `NSError`, such as `NSURLErrorDomain`.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// The code of the `NSError`, such as `NSURLErrorCannotFindHost`.
+    pub fn code(&self) -> isize {
+        self.code
+    }
+}
+
+impl fmt::Display for NSUrlSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NSURLSession error {} {}: {}",
+            self.domain, self.code, self.description
+        )
+    }
+}
+
+impl std::error::Error for NSUrlSessionError {}
+
+/// The error variant for errors of `NSURLErrorDomain` that have a dedicated one.
+fn url_error_variant(code: NSInteger) -> Option<NyquestError> {
+    match code {
+        NSURLErrorTimedOut => Some(NyquestError::RequestTimeout),
+        NSURLErrorBadURL | NSURLErrorUnsupportedURL => Some(NyquestError::InvalidUrl),
+        NSURLErrorDataLengthExceedsMaximum => Some(NyquestError::ResponseTooLarge),
+        _ => None,
+    }
+}
+
+fn url_error_kind(code: NSInteger) -> ErrorKind {
+    match code {
+        NSURLErrorCannotFindHost | NSURLErrorDNSLookupFailed => ErrorKind::NotFound,
+        NSURLErrorCannotConnectToHost => ErrorKind::ConnectionRefused,
+        NSURLErrorNetworkConnectionLost => ErrorKind::ConnectionReset,
+        NSURLErrorNotConnectedToInternet
+        | NSURLErrorInternationalRoamingOff
+        | NSURLErrorCallIsActive
+        | NSURLErrorDataNotAllowed => ErrorKind::NotConnected,
+        NSURLErrorCancelled | NSURLErrorBackgroundSessionWasDisconnected => {
+            ErrorKind::ConnectionAborted
+        }
+        NSURLErrorSecureConnectionFailed
+        | NSURLErrorServerCertificateHasBadDate
+        | NSURLErrorServerCertificateUntrusted
+        | NSURLErrorServerCertificateHasUnknownRoot
+        | NSURLErrorServerCertificateNotYetValid
+        | NSURLErrorBadServerResponse
+        | NSURLErrorCannotParseResponse
+        | NSURLErrorCannotDecodeRawData
+        | NSURLErrorCannotDecodeContentData => ErrorKind::InvalidData,
+        NSURLErrorClientCertificateRejected
+        | NSURLErrorClientCertificateRequired
+        | NSURLErrorUserAuthenticationRequired
+        | NSURLErrorUserCancelledAuthentication => ErrorKind::PermissionDenied,
+        _ => ErrorKind::Other,
+    }
+}
+
+/// Maps the error into the closest error variant, keeping the original error where possible.
+fn map_ns_error(e: &NSError) -> NyquestError {
+    let domain = e.domain();
+    let code = e.code();
+    let (is_url_error, is_posix_error) =
+        unsafe { (*domain == *NSURLErrorDomain, *domain == *NSPOSIXErrorDomain) };
+    let kind = if is_url_error {
+        if let Some(error) = url_error_variant(code) {
+            return error;
+        }
+        url_error_kind(code)
+    } else if is_posix_error {
+        i32::try_from(code).map_or(ErrorKind::Other, |code| {
+            io::Error::from_raw_os_error(code).kind()
+        })
+    } else {
+        ErrorKind::Other
+    };
+    let description =
+        autoreleasepool(|pool| unsafe { e.localizedDescription().to_str(pool).to_owned() });
+    NyquestError::Io(io::Error::new(
+        kind,
+        NSUrlSessionError {
+            domain: domain.to_string(),
+            code,
+            description,
+        },
+    ))
+}
 
 pub(crate) trait IntoNyquestResult<T> {
     fn into_nyquest_result(self) -> NyquestResult<T>;
@@ -10,17 +131,7 @@ pub(crate) trait IntoNyquestResult<T> {
 
 impl<T> IntoNyquestResult<T> for Result<T, Retained<NSError>> {
     fn into_nyquest_result(self) -> NyquestResult<T> {
-        self.map_err(|e| {
-            if e.code() == NSURLErrorTimedOut {
-                return NyquestError::RequestTimeout;
-            }
-            let msg =
-                autoreleasepool(|pool| unsafe { e.localizedDescription().to_str(pool).to_owned() });
-            NyquestError::Io(io::Error::new(
-                ErrorKind::Other,
-                format!("NSURLSession error {}: {}", e.code(), msg),
-            ))
-        })
+        self.map_err(|e| map_ns_error(&e))
     }
 }
 
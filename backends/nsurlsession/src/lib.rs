@@ -14,13 +14,19 @@ cfg_if::cfg_if! {
         #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
         mod blocking;
 
+        mod challenge;
         mod client;
         mod datatask;
         mod error;
+        mod metrics;
         #[cfg(feature = "multipart")]
         #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
         mod multipart;
+        mod network;
         mod response;
+        mod upload;
+
+        pub use error::NSUrlSessionError;
 
         /// Registers [`NSUrlSessionBackend`] as global default.
         pub fn register() {
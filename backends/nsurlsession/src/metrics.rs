@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use nyquest_interface::{Timings, TlsInfo};
+use objc2::rc::Retained;
+use objc2_foundation::{NSDate, NSURLSessionTaskMetrics};
+
+fn interval(start: Option<Retained<NSDate>>, end: Option<Retained<NSDate>>) -> Option<Duration> {
+    let (start, end) = (start?, end?);
+    let seconds = unsafe { end.timeIntervalSinceDate(&start) };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Converts the metrics of the final transaction of the task, which carries the response.
+pub(crate) fn timings_from_metrics(metrics: &NSURLSessionTaskMetrics) -> Timings {
+    let total = unsafe { metrics.taskInterval().duration() };
+    let mut timings = Timings {
+        total: Duration::try_from_secs_f64(total).unwrap_or_default(),
+        ..Default::default()
+    };
+    let Some(transaction) = unsafe { metrics.transactionMetrics() }.lastObject() else {
+        return timings;
+    };
+    unsafe {
+        timings.dns = interval(
+            transaction.domainLookupStartDate(),
+            transaction.domainLookupEndDate(),
+        );
+        timings.connect = interval(transaction.connectStartDate(), transaction.connectEndDate());
+        timings.tls = interval(
+            transaction.secureConnectionStartDate(),
+            transaction.secureConnectionEndDate(),
+        );
+        timings.request = interval(transaction.requestStartDate(), transaction.requestEndDate());
+        timings.wait = interval(
+            transaction.requestEndDate(),
+            transaction.responseStartDate(),
+        );
+        timings.response = interval(
+            transaction.responseStartDate(),
+            transaction.responseEndDate(),
+        );
+        timings.protocol = transaction
+            .networkProtocolName()
+            .map(|protocol| protocol.to_string());
+        timings.reused_connection = transaction.isReusedConnection();
+    }
+    timings
+}
+
+/// Converts the TLS parameters of the final transaction of the task, or `None` if the connection
+/// is not encrypted. Whether the session was resumed is not reported by the system.
+pub(crate) fn tls_info_from_metrics(metrics: &NSURLSessionTaskMetrics) -> Option<TlsInfo> {
+    let transaction = unsafe { metrics.transactionMetrics() }.lastObject()?;
+    let version = unsafe { transaction.negotiatedTLSProtocolVersion() }?.unsignedShortValue();
+    let cipher = unsafe { transaction.negotiatedTLSCipherSuite() }.map(|n| n.unsignedShortValue());
+    Some(TlsInfo {
+        protocol_version: Some(match version {
+            0x0301 => "TLSv1".into(),
+            0x0302 => "TLSv1.1".into(),
+            0x0303 => "TLSv1.2".into(),
+            0x0304 => "TLSv1.3".into(),
+            version => format!("0x{version:04X}"),
+        }),
+        cipher_suite: cipher.map(cipher_suite_name),
+        alpn_protocol: unsafe { transaction.networkProtocolName() }
+            .map(|protocol| protocol.to_string()),
+        resumed: None,
+    })
+}
+
+/// Names the common cipher suites as registered by IANA, and others by their code.
+fn cipher_suite_name(code: u16) -> String {
+    let name = match code {
+        0x1301 => "TLS_AES_128_GCM_SHA256",
+        0x1302 => "TLS_AES_256_GCM_SHA384",
+        0x1303 => "TLS_CHACHA20_POLY1305_SHA256",
+        0xC02B => "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        0xC02C => "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        0xC02F => "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        0xC030 => "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        0xCCA8 => "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xCCA9 => "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        code => return format!("0x{code:04X}"),
+    };
+    name.into()
+}
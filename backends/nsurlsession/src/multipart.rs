@@ -1,9 +1,12 @@
 use std::borrow::Cow;
+use std::io;
 
 use nyquest_interface::{Part, PartBody};
 use objc2::rc::Retained;
 use objc2_foundation::NSData;
 
+use crate::upload::{ChainedReader, ReadChunk};
+
 unsafe extern "C" {
     fn arc4random() -> u32;
 }
@@ -29,9 +32,10 @@ fn estimate_multipart_body_size<S>(boundary: &str, parts: &[Part<S>]) -> usize {
     let size: usize = parts
         .iter()
         .map(|part| {
-            let partbody = match &part.body {
-                PartBody::Bytes { content } => content,
-                _ => unimplemented!("nsurlsession multipart body type"),
+            let partbody_len = match &part.body {
+                PartBody::Bytes { content } => content.len(),
+                // Streamed from their sources
+                PartBody::File { .. } | PartBody::Stream(_) => 0,
             };
             80 + boundary.len()
                 + part.name.len()
@@ -42,20 +46,32 @@ fn estimate_multipart_body_size<S>(boundary: &str, parts: &[Part<S>]) -> usize {
                     .iter()
                     .map(|(k, v)| k.len() + v.len() + 4)
                     .sum::<usize>()
-                + partbody.len()
+                + partbody_len
         })
         .sum();
     size + boundary.len() + 6
 }
 
-pub fn generate_multipart_body<S>(boundary: &str, parts: Vec<Part<S>>) -> Retained<NSData> {
+/// A multipart body, which is kept in memory unless any of the parts is read from a file or a
+/// stream.
+pub enum MultipartBody {
+    Data(Retained<NSData>),
+    Streamed {
+        reader: ChainedReader,
+        content_length: Option<u64>,
+    },
+}
+
+pub fn generate_multipart_body<S>(
+    boundary: &str,
+    parts: Vec<Part<S>>,
+    mut map_stream: impl FnMut(S) -> Box<dyn ReadChunk>,
+) -> io::Result<MultipartBody> {
     let mut body = Vec::with_capacity(estimate_multipart_body_size(boundary, &parts));
+    let mut reader = ChainedReader::default();
+    let mut content_length = Some(0);
 
     for part in parts {
-        let partbody = match part.body {
-            PartBody::Bytes { content } => content,
-            _ => unimplemented!("nsurlsession multipart body type"),
-        };
         body.extend_from_slice(b"--");
         body.extend_from_slice(boundary.as_bytes());
         body.extend_from_slice(b"\r\nContent-Disposition: form-data; name=\"");
@@ -82,12 +98,36 @@ pub fn generate_multipart_body<S>(boundary: &str, parts: Vec<Part<S>>) -> Retain
             body.extend_from_slice(b"\r\n");
         }
         body.extend_from_slice(b"\r\n");
-        body.extend_from_slice(&partbody);
+        let (source, len): (Box<dyn ReadChunk>, _) = match part.body {
+            PartBody::Bytes { content } => {
+                body.extend_from_slice(&content);
+                body.extend_from_slice(b"\r\n");
+                continue;
+            }
+            PartBody::File { path } => {
+                let file = std::fs::File::open(path)?;
+                let len = file.metadata()?.len();
+                (Box::new(file), Some(len))
+            }
+            PartBody::Stream(stream) => (map_stream(stream.stream), stream.content_length),
+        };
+        content_length = content_length.zip(len).map(|(total, len)| total + len);
+        content_length = content_length.map(|total| total + body.len() as u64);
+        reader.push(Box::new(io::Cursor::new(std::mem::take(&mut body))));
+        reader.push(source);
         body.extend_from_slice(b"\r\n");
     }
     body.extend_from_slice(b"--");
     body.extend_from_slice(boundary.as_bytes());
     body.extend_from_slice(b"--\r\n");
 
-    unsafe { NSData::dataWithBytes_length(body.as_ptr() as *const _, body.len() as _) }
+    if reader.is_empty() {
+        return Ok(MultipartBody::Data(NSData::from_vec(body)));
+    }
+    let content_length = content_length.map(|total| total + body.len() as u64);
+    reader.push(Box::new(io::Cursor::new(body)));
+    Ok(MultipartBody::Streamed {
+        reader,
+        content_length,
+    })
 }
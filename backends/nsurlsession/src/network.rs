@@ -0,0 +1,61 @@
+use std::ffi::{c_int, c_void};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Once;
+use std::thread;
+use std::time::Duration;
+
+use block2::{Block, RcBlock};
+use nyquest_interface::NetworkStatus;
+
+const NW_PATH_STATUS_INVALID: c_int = 0;
+const NW_PATH_STATUS_SATISFIED: c_int = 1;
+const NW_PATH_STATUS_UNSATISFIED: c_int = 2;
+const QOS_CLASS_UTILITY: isize = 0x11;
+
+#[link(name = "Network", kind = "framework")]
+unsafe extern "C" {
+    fn nw_path_monitor_create() -> *mut c_void;
+    fn nw_path_monitor_set_update_handler(
+        monitor: *mut c_void,
+        update_handler: &Block<dyn Fn(*mut c_void)>,
+    );
+    fn nw_path_monitor_set_queue(monitor: *mut c_void, queue: *mut c_void);
+    fn nw_path_monitor_start(monitor: *mut c_void);
+    fn nw_path_get_status(path: *mut c_void) -> c_int;
+}
+
+unsafe extern "C" {
+    fn dispatch_get_global_queue(identifier: isize, flags: usize) -> *mut c_void;
+}
+
+/// The status of the current network path, as last reported by the monitor.
+static PATH_STATUS: AtomicI32 = AtomicI32::new(NW_PATH_STATUS_INVALID);
+static START_MONITOR: Once = Once::new();
+
+/// Returns the status of the network path watched by a `NWPathMonitor`, which is started on the
+/// first call and runs for the rest of the process.
+pub(crate) fn network_status() -> NetworkStatus {
+    START_MONITOR.call_once(|| {
+        unsafe {
+            let monitor = nw_path_monitor_create();
+            let update_handler = RcBlock::new(|path: *mut c_void| {
+                PATH_STATUS.store(nw_path_get_status(path), Ordering::Relaxed);
+            });
+            nw_path_monitor_set_update_handler(monitor, &update_handler);
+            nw_path_monitor_set_queue(monitor, dispatch_get_global_queue(QOS_CLASS_UTILITY, 0));
+            nw_path_monitor_start(monitor);
+        }
+        // The current path is reported right after the monitor starts
+        for _ in 0..50 {
+            if PATH_STATUS.load(Ordering::Relaxed) != NW_PATH_STATUS_INVALID {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    });
+    match PATH_STATUS.load(Ordering::Relaxed) {
+        NW_PATH_STATUS_SATISFIED => NetworkStatus::Online,
+        NW_PATH_STATUS_UNSATISFIED => NetworkStatus::Offline,
+        _ => NetworkStatus::Unknown,
+    }
+}
@@ -2,6 +2,7 @@ use std::ptr::NonNull;
 
 use objc2::{
     rc::{autoreleasepool, Retained},
+    runtime::AnyObject,
     AnyThread,
 };
 use objc2_core_foundation::{kCFStringEncodingInvalidId, CFString};
@@ -9,12 +10,24 @@ use objc2_foundation::{ns_string, NSString, NSStringEncoding, NSUTF8StringEncodi
 
 use crate::datatask::DataTaskSharedContextRetained;
 
+/// Upper bound of the buffer allocated upfront from the content length of a response, which the
+/// server may not honor.
+const MAX_BUFFER_PREALLOCATION: u64 = 16 * 1024 * 1024;
+
 pub(crate) struct NSUrlSessionResponse {
     pub(crate) response: Retained<objc2_foundation::NSHTTPURLResponse>,
     pub(crate) task: Retained<objc2_foundation::NSURLSessionDataTask>,
     pub(crate) shared: DataTaskSharedContextRetained,
 }
 
+impl Drop for NSUrlSessionResponse {
+    fn drop(&mut self) {
+        // The task may be suspended for backpressure, and is only released by the session once
+        // it completes. This is a no-op if it is completed already.
+        unsafe { self.task.cancel() }
+    }
+}
+
 impl NSUrlSessionResponse {
     pub(crate) fn status(&self) -> u16 {
         unsafe { self.response.statusCode() as u16 }
@@ -27,6 +40,21 @@ impl NSUrlSessionResponse {
         }
     }
 
+    /// Gets the TLS parameters of the connection, which the system only reports once the task
+    /// completes.
+    pub(crate) fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.shared.tls_info()
+    }
+
+    /// Allocates a buffer for the whole body, so that it is not reallocated and copied as
+    /// chunks are appended.
+    pub(crate) fn body_buffer(&self) -> Vec<u8> {
+        let capacity = self
+            .content_length()
+            .map_or(0, |len| len.min(MAX_BUFFER_PREALLOCATION));
+        Vec::with_capacity(capacity as usize)
+    }
+
     pub(crate) fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
         let value = unsafe {
             self.response
@@ -38,6 +66,21 @@ impl NSUrlSessionResponse {
             .collect())
     }
 
+    /// Gets all the headers, with the values of repeated headers merged into one.
+    pub(crate) fn headers(&self) -> Vec<(String, String)> {
+        let (names, values) = unsafe { self.response.allHeaderFields() }.to_vecs();
+        let to_string = |s: &AnyObject| {
+            s.downcast_ref::<NSString>()
+                .map(NSString::to_string)
+                .unwrap_or_default()
+        };
+        names
+            .iter()
+            .zip(&values)
+            .map(|(name, value)| (to_string(name), to_string(value)))
+            .collect()
+    }
+
     fn detect_response_encoding(&self) -> Option<NSStringEncoding> {
         let content_type = unsafe {
             self.response
@@ -0,0 +1,136 @@
+#[cfg(feature = "multipart")]
+use std::collections::VecDeque;
+use std::io;
+use std::ptr::NonNull;
+
+use objc2::rc::Retained;
+use objc2_foundation::{NSInputStream, NSOutputStream, NSStream};
+
+const BOUND_STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A request body source that is pulled chunk by chunk while uploading.
+pub(crate) trait ReadChunk: Send {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+pub(crate) type BoxedReadChunk = Box<dyn ReadChunk>;
+
+fn read_uninterrupted(reader: &mut impl io::Read, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            res => return res,
+        }
+    }
+}
+
+impl ReadChunk for std::fs::File {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl ReadChunk for io::Cursor<Vec<u8>> {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl ReadChunk for nyquest_interface::blocking::BoxedStream {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl ReadChunk for nyquest_interface::r#async::body_pump::PumpedReader {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+/// Reads the sources one after another.
+#[cfg(feature = "multipart")]
+#[derive(Default)]
+pub(crate) struct ChainedReader(VecDeque<Box<dyn ReadChunk>>);
+
+#[cfg(feature = "multipart")]
+impl ChainedReader {
+    pub(crate) fn push(&mut self, reader: Box<dyn ReadChunk>) {
+        self.0.push_back(reader);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl ReadChunk for ChainedReader {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(reader) = self.0.front_mut() {
+            match reader.read_chunk(buf)? {
+                0 if !buf.is_empty() => self.0.pop_front(),
+                len => return Ok(len),
+            };
+        }
+        Ok(0)
+    }
+}
+
+struct SendOutputStream(Retained<NSOutputStream>);
+
+// Safety: the output stream is only used by the upload thread once it is sent there
+unsafe impl Send for SendOutputStream {}
+
+/// Bridges the reader to an `NSInputStream` with a bound stream pair. The reader is pumped into
+/// the output end on a dedicated thread, as fast as the task consumes the input end.
+pub(crate) fn bind_input_stream(
+    mut reader: Box<dyn ReadChunk>,
+) -> io::Result<Retained<NSInputStream>> {
+    let (mut input, mut output) = (None, None);
+    unsafe {
+        NSStream::getBoundStreamsWithBufferSize_inputStream_outputStream(
+            BOUND_STREAM_BUFFER_SIZE as _,
+            Some(&mut input),
+            Some(&mut output),
+        );
+    }
+    let (Some(input), Some(output)) = (input, output) else {
+        return Err(io::Error::other("failed to create bound stream pair"));
+    };
+    let output = SendOutputStream(output);
+    std::thread::Builder::new()
+        .name("nyquest-nsurlsession-upload".into())
+        .spawn(move || {
+            let output = output;
+            let mut buf = vec![0; BOUND_STREAM_BUFFER_SIZE];
+            unsafe {
+                output.0.open();
+            }
+            // A failed read ends the body early, which fails the upload if the content length is
+            // declared
+            'pump: while let Ok(len @ 1..) = reader.read_chunk(&mut buf) {
+                let mut written = 0;
+                while written < len {
+                    // Blocks until the task reads from the input end, as the output end is not
+                    // scheduled on a run loop
+                    let res = unsafe {
+                        output
+                            .0
+                            .write_maxLength(NonNull::from(&mut buf[written]), (len - written) as _)
+                    };
+                    if res <= 0 {
+                        break 'pump;
+                    }
+                    written += res as usize;
+                }
+            }
+            unsafe {
+                output.0.close();
+            }
+        })?;
+    Ok(input)
+}
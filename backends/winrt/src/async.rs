@@ -1,44 +1,59 @@
 use std::io;
 
 use nyquest_interface::client::ClientOptions;
+use nyquest_interface::r#async::body_pump::BodyPump;
 use nyquest_interface::r#async::{AsyncBackend, AsyncClient, AsyncResponse, Request};
-use nyquest_interface::Result as NyquestResult;
+use nyquest_interface::{Bytes, Result as NyquestResult};
 use windows::Web::Http::HttpCompletionOption;
 
 mod timer_ext;
 
+use crate::certificate::find_client_certificate_async;
 use crate::client::WinrtClient;
 use crate::error::IntoNyquestResult;
 use crate::ibuffer::IBufferExt;
 use crate::request::create_body;
-use crate::response::WinrtResponse;
+use crate::response::{all_headers, WinrtResponse};
 use crate::response_size_limiter::ResponseSizeLimiter;
-use crate::timer::Timer;
+use crate::stream::BodyInputStream;
+use crate::timer::{Timer, Watchdog};
 use timer_ext::AsyncTimeoutExt;
 
 impl crate::WinrtBackend {
-    pub fn create_async_client(&self, options: ClientOptions) -> io::Result<WinrtClient> {
-        WinrtClient::create(options)
+    pub async fn create_async_client(&self, options: ClientOptions) -> io::Result<WinrtClient> {
+        let certificate = match &options.client_certificate {
+            Some(selector) => Some(find_client_certificate_async(selector).await?),
+            None => None,
+        };
+        WinrtClient::create(options, certificate)
     }
 }
 
 impl WinrtClient {
     async fn send_request_async(&self, req: Request) -> NyquestResult<WinrtResponse> {
+        let timeout = req.effective_timeout(self.request_timeout);
         let req_msg = self.create_request(&req)?;
-        // TODO: stream
+        let mut pump = BodyPump::default();
         if let Some(body) = req.body {
-            let body = create_body(body, &mut |_| unimplemented!())?;
+            let body = create_body(body, &mut |stream| {
+                Ok(BodyInputStream::create(pump.pump(Box::into_pin(stream))))
+            })?;
             self.append_content_headers(&body, &req.additional_headers)?;
             req_msg.SetContent(&body).into_nyquest_result()?;
         }
-        let mut timer = Timer::new(self.request_timeout);
-        let res = self
+        let mut timer = Timer::new(timeout);
+        let task = self
             .client
             .SendRequestWithOptionAsync(&req_msg, HttpCompletionOption::ResponseHeadersRead)
-            .into_nyquest_result()?
-            .timeout_by(&mut timer)
-            .await?;
-        WinrtResponse::new(res, self.max_response_buffer_size, timer).into_nyquest_result()
+            .into_nyquest_result()?;
+        let watchdog = Watchdog::watch(self.read_timeout, &task)?;
+        // Streamed bodies are read on this task while the thread pool uploads them
+        let res = pump.run_until(task.timeout_by(&mut timer)).await;
+        watchdog.map(Watchdog::finish).transpose()?;
+        let res = res?;
+        WinrtResponse::check_header_limits(&res, self.header_limits.as_ref())?;
+        WinrtResponse::new(res, self.max_response_buffer_size, timer, self.read_timeout)
+            .into_nyquest_result()
     }
 }
 
@@ -55,6 +70,14 @@ impl AsyncResponse for WinrtResponse {
         self.get_header(header).into_nyquest_result()
     }
 
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        let headers = all_headers(&self.response).into_nyquest_result()?;
+        Ok(headers
+            .into_iter()
+            .map(|(name, value)| (name.to_string_lossy(), value.to_string_lossy()))
+            .collect())
+    }
+
     async fn text(&mut self) -> nyquest_interface::Result<String> {
         let task = self
             .response
@@ -62,31 +85,47 @@ impl AsyncResponse for WinrtResponse {
             .into_nyquest_result()?
             .ReadAsStringAsync()
             .into_nyquest_result()?;
-        let size_limiter =
-            ResponseSizeLimiter::hook_progress(self.max_response_buffer_size, &task)?;
+        let watchdog = self
+            .read_timeout
+            .map(|read_timeout| Watchdog::start(read_timeout, task.clone()))
+            .transpose()?;
+        let size_limiter = ResponseSizeLimiter::hook_progress(
+            self.max_response_buffer_size,
+            watchdog.as_ref().map(Watchdog::feeder),
+            &task,
+        )?;
         let res = task
             .timeout_by(&mut self.request_timer)
             .await
             .map(|r| r.to_string_lossy());
+        watchdog.map(Watchdog::finish).transpose()?;
         let content = size_limiter.assert_size(res)?;
         Ok(content)
     }
 
-    async fn bytes(&mut self) -> nyquest_interface::Result<Vec<u8>> {
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
         let task = self
             .response
             .Content()
             .into_nyquest_result()?
             .ReadAsBufferAsync()
             .into_nyquest_result()?;
-        let size_limiter =
-            ResponseSizeLimiter::hook_progress(self.max_response_buffer_size, &task)?;
+        let watchdog = self
+            .read_timeout
+            .map(|read_timeout| Watchdog::start(read_timeout, task.clone()))
+            .transpose()?;
+        let size_limiter = ResponseSizeLimiter::hook_progress(
+            self.max_response_buffer_size,
+            watchdog.as_ref().map(Watchdog::feeder),
+            &task,
+        )?;
         let res = task
             .timeout_by(&mut self.request_timer)
             .await
             .and_then(|b| b.to_vec());
+        watchdog.map(Watchdog::finish).transpose()?;
         let arr = size_limiter.assert_size(res)?;
-        Ok(arr)
+        Ok(arr.into())
     }
 }
 
@@ -96,6 +135,10 @@ impl AsyncClient for WinrtClient {
     async fn request(&self, req: Request) -> nyquest_interface::Result<Self::Response> {
         self.send_request_async(req).await
     }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
 }
 
 impl AsyncBackend for crate::WinrtBackend {
@@ -105,6 +148,10 @@ impl AsyncBackend for crate::WinrtBackend {
         &self,
         options: ClientOptions,
     ) -> nyquest_interface::client::BuildClientResult<Self::AsyncClient> {
-        Ok(self.create_async_client(options).into_nyquest_result()?)
+        crate::client::check_options(&options)?;
+        Ok(self
+            .create_async_client(options)
+            .await
+            .into_nyquest_result()?)
     }
 }
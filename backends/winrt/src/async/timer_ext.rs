@@ -7,7 +7,7 @@ use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
 use windows::System::Threading::{ThreadPoolTimer, TimerElapsedHandler};
 
 use crate::error::IntoNyquestResult;
-use crate::timer::{Cancel, Timer};
+use crate::timer::{Cancel, CancelOnDrop, Timer};
 
 pub(crate) trait AsyncTimeoutExt {
     type Output;
@@ -20,8 +20,12 @@ where
 {
     type Output = T;
     async fn timeout_by(self, timer: &mut Timer) -> NyquestResult<Self::Output> {
+        // The operation is not cancelled by dropping its future, so do it when we are dropped
+        let guard = CancelOnDrop::new(self.clone());
         let Some(remaining) = &mut timer.remaining else {
-            return self.await.into_nyquest_result();
+            let res = self.await;
+            guard.defuse();
+            return res.into_nyquest_result();
         };
         if remaining.is_zero() {
             return Err(NyquestError::RequestTimeout);
@@ -42,6 +46,7 @@ where
         )
         .into_nyquest_result()?;
         let res = self.await; // TODO: select on the timer
+        guard.defuse();
         timer.Cancel().ok();
         if cancelled.load(Ordering::SeqCst) {
             return Err(NyquestError::RequestTimeout);
@@ -2,42 +2,54 @@ use std::io;
 
 use nyquest_interface::blocking::{BlockingBackend, BlockingClient, BlockingResponse, Request};
 use nyquest_interface::client::{BuildClientResult, ClientOptions};
-use nyquest_interface::Result as NyquestResult;
+use nyquest_interface::{Bytes, Error as NyquestError, Result as NyquestResult};
 use timer_ext::BlockingTimeoutExt;
 use windows::Web::Http::HttpCompletionOption;
 
 mod timer_ext;
 
+use crate::certificate::find_client_certificate;
 use crate::client::WinrtClient;
 use crate::error::IntoNyquestResult;
 use crate::ibuffer::IBufferExt;
 use crate::request::create_body;
-use crate::response::WinrtResponse;
+use crate::response::{all_headers, WinrtResponse};
 use crate::response_size_limiter::ResponseSizeLimiter;
-use crate::timer::Timer;
+use crate::stream::BodyInputStream;
+use crate::timer::{Timer, Watchdog};
 
 impl crate::WinrtBackend {
     pub fn create_blocking_client(&self, options: ClientOptions) -> io::Result<WinrtClient> {
-        WinrtClient::create(options)
+        let certificate = options
+            .client_certificate
+            .as_ref()
+            .map(find_client_certificate)
+            .transpose()?;
+        WinrtClient::create(options, certificate)
     }
 }
 
 impl WinrtClient {
     fn send_request(&self, req: Request) -> NyquestResult<WinrtResponse> {
+        let timeout = req.effective_timeout(self.request_timeout);
         let req_msg = self.create_request(&req)?;
-        // TODO: stream
         if let Some(body) = req.body {
-            let body = create_body(body, &mut |_| unimplemented!())?;
+            let body = create_body(body, &mut |stream| Ok(BodyInputStream::create(stream)))?;
             self.append_content_headers(&body, &req.additional_headers)?;
             req_msg.SetContent(&body).into_nyquest_result()?;
         }
-        let mut timer = Timer::new(self.request_timeout);
-        let res = self
+        let mut timer = Timer::new(timeout);
+        let task = self
             .client
             .SendRequestWithOptionAsync(&req_msg, HttpCompletionOption::ResponseHeadersRead)
-            .into_nyquest_result()?
-            .timeout_by(&mut timer)?;
-        WinrtResponse::new(res, self.max_response_buffer_size, timer).into_nyquest_result()
+            .into_nyquest_result()?;
+        let watchdog = Watchdog::watch(self.read_timeout, &task)?;
+        let res = task.timeout_by(&mut timer);
+        watchdog.map(Watchdog::finish).transpose()?;
+        let res = res?;
+        WinrtResponse::check_header_limits(&res, self.header_limits.as_ref())?;
+        WinrtResponse::new(res, self.max_response_buffer_size, timer, self.read_timeout)
+            .into_nyquest_result()
     }
 }
 
@@ -46,6 +58,10 @@ impl BlockingClient for WinrtClient {
     fn request(&self, req: Request) -> NyquestResult<Self::Response> {
         self.send_request(req)
     }
+
+    fn network_status(&self) -> nyquest_interface::NetworkStatus {
+        crate::network::network_status()
+    }
 }
 
 impl BlockingBackend for crate::WinrtBackend {
@@ -55,6 +71,7 @@ impl BlockingBackend for crate::WinrtBackend {
         &self,
         options: ClientOptions,
     ) -> BuildClientResult<Self::BlockingClient> {
+        crate::client::check_options(&options)?;
         Ok(self.create_blocking_client(options).into_nyquest_result()?)
     }
 }
@@ -68,6 +85,14 @@ impl BlockingResponse for WinrtResponse {
         self.get_header(header).into_nyquest_result()
     }
 
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        let headers = all_headers(&self.response).into_nyquest_result()?;
+        Ok(headers
+            .into_iter()
+            .map(|(name, value)| (name.to_string_lossy(), value.to_string_lossy()))
+            .collect())
+    }
+
     fn content_length(&self) -> Option<u64> {
         self.content_length
     }
@@ -78,38 +103,66 @@ impl BlockingResponse for WinrtResponse {
             .into_nyquest_result()?
             .ReadAsStringAsync()
             .into_nyquest_result()?;
-        let size_limiter =
-            ResponseSizeLimiter::hook_progress(self.max_response_buffer_size, &task)?;
+        let watchdog = self
+            .read_timeout
+            .map(|read_timeout| Watchdog::start(read_timeout, task.clone()))
+            .transpose()?;
+        let size_limiter = ResponseSizeLimiter::hook_progress(
+            self.max_response_buffer_size,
+            watchdog.as_ref().map(Watchdog::feeder),
+            &task,
+        )?;
         let res = task
             .timeout_by(&mut self.request_timer)
             .map(|r| r.to_string_lossy());
+        watchdog.map(Watchdog::finish).transpose()?;
         let content = size_limiter.assert_size(res)?;
         Ok(content)
     }
 
-    fn bytes(&mut self) -> NyquestResult<Vec<u8>> {
+    fn bytes(&mut self) -> NyquestResult<Bytes> {
         let task = self
             .content()
             .into_nyquest_result()?
             .ReadAsBufferAsync()
             .into_nyquest_result()?;
-        let size_limiter =
-            ResponseSizeLimiter::hook_progress(self.max_response_buffer_size, &task)?;
+        let watchdog = self
+            .read_timeout
+            .map(|read_timeout| Watchdog::start(read_timeout, task.clone()))
+            .transpose()?;
+        let size_limiter = ResponseSizeLimiter::hook_progress(
+            self.max_response_buffer_size,
+            watchdog.as_ref().map(Watchdog::feeder),
+            &task,
+        )?;
         let res = task
             .timeout_by(&mut self.request_timer)
             .and_then(|b| b.to_vec());
+        watchdog.map(Watchdog::finish).transpose()?;
         let arr = size_limiter.assert_size(res)?;
-        Ok(arr)
+        Ok(arr.into())
     }
 }
 
 impl io::Read for WinrtResponse {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read_timeout = self.read_timeout;
         let reader = self.reader_mut()?;
 
         let mut size = reader.UnconsumedBufferLength()?;
         if size == 0 {
-            let loaded = reader.LoadAsync(buf.len() as u32)?.get()?;
+            let task = reader.LoadAsync(buf.len() as u32)?;
+            // Partial loads complete as soon as any data arrives
+            let watchdog = read_timeout
+                .map(|read_timeout| Watchdog::start(read_timeout, task.clone()))
+                .transpose()
+                .map_err(into_io_error)?;
+            let loaded = task.get();
+            watchdog
+                .map(Watchdog::finish)
+                .transpose()
+                .map_err(into_io_error)?;
+            let loaded = loaded?;
             if loaded == 0 {
                 return Ok(0);
             }
@@ -121,3 +174,11 @@ impl io::Read for WinrtResponse {
         Ok(size)
     }
 }
+
+fn into_io_error(e: NyquestError) -> io::Error {
+    match e {
+        NyquestError::Io(e) => e,
+        NyquestError::RequestTimeout => io::ErrorKind::TimedOut.into(),
+        e => io::Error::other(e),
+    }
+}
@@ -0,0 +1,54 @@
+use std::io;
+
+use nyquest_interface::client::ClientCertificate;
+use windows::core::h;
+use windows::Security::Cryptography::Certificates::{
+    Certificate, CertificateQuery, CertificateStores,
+};
+use windows_collections::IVectorView;
+use windows_future::IAsyncOperation;
+
+#[cfg(feature = "blocking")]
+pub(crate) fn find_client_certificate(selector: &ClientCertificate) -> io::Result<Certificate> {
+    select_client_certificate(query_client_certificates(selector)?.get()?, selector)
+}
+
+#[cfg(feature = "async")]
+pub(crate) async fn find_client_certificate_async(
+    selector: &ClientCertificate,
+) -> io::Result<Certificate> {
+    select_client_certificate(query_client_certificates(selector)?.await?, selector)
+}
+
+fn query_client_certificates(
+    selector: &ClientCertificate,
+) -> io::Result<IAsyncOperation<IVectorView<Certificate>>> {
+    let query = CertificateQuery::new()?;
+    // The personal store of the current user
+    query.SetStoreName(h!("MY"))?;
+    if let ClientCertificate::Thumbprint(thumbprint) = selector {
+        query.SetThumbprint(thumbprint)?;
+    }
+    Ok(CertificateStores::FindAllWithQueryAsync(&query)?)
+}
+
+fn select_client_certificate(
+    certificates: IVectorView<Certificate>,
+    selector: &ClientCertificate,
+) -> io::Result<Certificate> {
+    for certificate in certificates {
+        if !certificate.HasPrivateKey()? {
+            continue;
+        }
+        if let ClientCertificate::Subject(subject) = selector {
+            if certificate.Subject()?.to_string_lossy() != *subject {
+                continue;
+            }
+        }
+        return Ok(certificate);
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "client certificate not found in the certificate store",
+    ))
+}
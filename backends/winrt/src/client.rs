@@ -1,12 +1,17 @@
 use std::io;
 use std::time::Duration;
 
-use nyquest_interface::client::{CachingBehavior, ClientOptions};
-use windows::core::{h, HSTRING};
+use nyquest_interface::client::{
+    BuildClientError, BuildClientResult, CachingBehavior, ClientOptions, HttpVersion, PushPolicy,
+    ResponseHeaderLimits, RevocationPolicy,
+};
+use nyquest_interface::{header, HeaderMap};
+use windows::core::HSTRING;
+use windows::Security::Cryptography::Certificates::{Certificate, ChainValidationResult};
 use windows::Web::Http::Filters::{
     HttpBaseProtocolFilter, HttpCacheReadBehavior, HttpCacheWriteBehavior, HttpCookieUsageBehavior,
 };
-use windows::Web::Http::HttpClient;
+use windows::Web::Http::{HttpClient, HttpVersion as WinrtHttpVersion};
 
 use crate::request::is_header_name_content_related;
 
@@ -15,12 +20,81 @@ pub struct WinrtClient {
     pub(crate) base_url: Option<HSTRING>,
     pub(crate) max_response_buffer_size: Option<u64>,
     pub(crate) request_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) header_limits: Option<ResponseHeaderLimits>,
     pub(crate) client: HttpClient,
-    pub(crate) default_content_headers: Vec<(HSTRING, HSTRING)>,
+    /// Added to each request instead of `DefaultRequestHeaders`, so that those set by the
+    /// request itself are skipped.
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) default_content_headers: HeaderMap,
+}
+
+/// Rejects options that `HttpClient` has no way to honor.
+pub(crate) fn check_options(options: &ClientOptions) -> BuildClientResult<()> {
+    if options.proxy.is_some() {
+        return Err(BuildClientError::Unsupported("proxy"));
+    }
+    if !options.tls_session_resumption {
+        return Err(BuildClientError::Unsupported("tls_session_resumption"));
+    }
+    if options.tls_early_data {
+        return Err(BuildClientError::Unsupported("tls_early_data"));
+    }
+    if matches!(options.http2_push, PushPolicy::Notify(_)) {
+        return Err(BuildClientError::Unsupported("http2_push"));
+    }
+    if options.http2_keep_alive.is_some() {
+        return Err(BuildClientError::Unsupported("http2_keep_alive"));
+    }
+    if options.ephemeral {
+        return Err(BuildClientError::Unsupported("ephemeral"));
+    }
+    if !options.allow_cellular_access {
+        return Err(BuildClientError::Unsupported("allow_cellular_access"));
+    }
+    if !options.allow_expensive_network_access {
+        return Err(BuildClientError::Unsupported(
+            "allow_expensive_network_access",
+        ));
+    }
+    if !options.allow_constrained_network_access {
+        return Err(BuildClientError::Unsupported(
+            "allow_constrained_network_access",
+        ));
+    }
+    // Options only `curl` or `nsurlsession` backend supports
+    let unsupported = [
+        ("proxy_tls", options.proxy_tls.is_some()),
+        ("alt_svc_cache", options.alt_svc_cache.is_some()),
+        ("hsts_cache", options.hsts_cache.is_some()),
+        ("verbose", options.verbose),
+        ("resolve", !options.resolve.is_empty()),
+        ("dns_resolver", options.resolver.is_some()),
+        ("dns_servers", !options.dns_servers.is_empty()),
+        ("doh_url", options.doh_url.is_some()),
+        ("dns_cache_timeout", options.dns_cache_timeout.is_some()),
+        ("tcp_keepalive", options.tcp_keepalive.is_some()),
+        ("tcp_nodelay", options.tcp_nodelay.is_some()),
+        (
+            "cache_capacity",
+            options.cache_memory_capacity.is_some() || options.cache_disk_capacity.is_some(),
+        ),
+        ("cache_directory", options.cache_directory.is_some()),
+        (
+            "callback_quality_of_service",
+            options.callback_quality_of_service.is_some(),
+        ),
+    ];
+    if let Some((option, _)) = unsupported.into_iter().find(|(_, set)| *set) {
+        return Err(BuildClientError::Unsupported(option));
+    }
+    Ok(())
 }
 
 impl WinrtClient {
-    pub fn create(options: ClientOptions) -> io::Result<Self> {
+    /// Creates a client with the client certificate, if any, looked up from
+    /// [`ClientOptions::client_certificate`] by the caller.
+    pub fn create(options: ClientOptions, certificate: Option<Certificate>) -> io::Result<Self> {
         let base_url = options.base_url.as_ref().map(HSTRING::from);
         let filter = HttpBaseProtocolFilter::new()?;
         filter.SetAutomaticDecompression(true)?;
@@ -35,28 +109,48 @@ impl WinrtClient {
         if !options.use_cookies {
             filter.SetCookieUsageBehavior(HttpCookieUsageBehavior::NoCookies)?;
         }
-        let client = HttpClient::Create(&filter)?;
-        if let Some(user_agent) = &options.user_agent {
-            client
-                .DefaultRequestHeaders()?
-                .Append(h!("user-agent"), &HSTRING::from(user_agent))?;
+        if options.http_version == Some(HttpVersion::Http1_1) {
+            filter.SetMaxVersion(WinrtHttpVersion::Http11)?;
+        }
+        if let Some(certificate) = &certificate {
+            filter.SetClientCertificate(certificate)?;
         }
-        let mut default_content_headers = vec![];
-        for (name, value) in options.default_headers {
-            if is_header_name_content_related(&name) {
-                default_content_headers.push((HSTRING::from(name), HSTRING::from(value)));
-            } else {
-                client
-                    .DefaultRequestHeaders()?
-                    .TryAppendWithoutValidation(&HSTRING::from(name), &HSTRING::from(value))?;
-            }
+        if let Some(RevocationPolicy::BestEffort | RevocationPolicy::Disabled) =
+            options.revocation_check
+        {
+            // Revoked certificates cannot be ignored, only failures to check them
+            let ignorable = filter.IgnorableServerCertificateErrors()?;
+            ignorable.Append(ChainValidationResult::RevocationInformationMissing)?;
+            ignorable.Append(ChainValidationResult::RevocationFailure)?;
+        }
+        let client = HttpClient::Create(&filter)?;
+        let mut default_headers = HeaderMap::new();
+        if let Some(user_agent) = options.user_agent {
+            default_headers.append(header::USER_AGENT, user_agent);
         }
+        let (default_content_headers, other_headers): (HeaderMap, HeaderMap) = options
+            .default_headers
+            .into_iter()
+            .partition(|(name, _)| is_header_name_content_related(name));
+        default_headers.extend(other_headers);
+        // The compressed size of a body is not exposed, so only the decompressed size is limited
+        let max_decompressed_size = options
+            .decompression_limits
+            .and_then(|limits| limits.max_decompressed_size);
+        let max_response_buffer_size =
+            match (options.max_response_buffer_size, max_decompressed_size) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
         // TODO: options
         Ok(Self {
             base_url,
-            max_response_buffer_size: options.max_response_buffer_size,
+            max_response_buffer_size,
             request_timeout: options.request_timeout,
+            read_timeout: options.read_timeout,
+            header_limits: options.max_response_headers,
             client,
+            default_headers,
             default_content_headers,
         })
     }
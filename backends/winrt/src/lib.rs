@@ -14,12 +14,15 @@ cfg_if::cfg_if! {
         #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
         mod blocking;
         mod buffer;
+        mod certificate;
         mod client;
         mod error;
         mod ibuffer;
+        mod network;
         mod request;
         mod response;
         mod response_size_limiter;
+        mod stream;
         mod string_pair;
         mod timer;
         mod uri;
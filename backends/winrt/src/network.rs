@@ -0,0 +1,17 @@
+use nyquest_interface::NetworkStatus;
+use windows::Networking::Connectivity::{NetworkConnectivityLevel, NetworkInformation};
+
+/// Reads the connectivity level of the connection profile used for the internet, which the
+/// network list service keeps up to date.
+pub(crate) fn network_status() -> NetworkStatus {
+    let Ok(profile) = NetworkInformation::GetInternetConnectionProfile() else {
+        // There is no profile at all without any network connection
+        return NetworkStatus::Offline;
+    };
+    match profile.GetNetworkConnectivityLevel() {
+        Ok(NetworkConnectivityLevel::InternetAccess)
+        | Ok(NetworkConnectivityLevel::ConstrainedInternetAccess) => NetworkStatus::Online,
+        Ok(_) => NetworkStatus::Offline,
+        Err(_) => NetworkStatus::Unknown,
+    }
+}
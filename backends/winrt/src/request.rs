@@ -1,12 +1,15 @@
 use std::borrow::Cow;
 use std::io;
 
-use nyquest_interface::{Body, Method, Request, Result as NyquestResult};
+use nyquest_interface::{
+    Body, Bytes, HeaderMap, Method, Request, Result as NyquestResult, StreamReader,
+};
 use windows::Foundation::{IReference, PropertyValue};
-use windows::Storage::Streams::IBuffer;
+use windows::Storage::Streams::{IBuffer, IInputStream};
 use windows::Web::Http::Headers::HttpMediaTypeHeaderValue;
 use windows::Web::Http::{
-    HttpBufferContent, HttpFormUrlEncodedContent, HttpMethod, HttpRequestMessage, IHttpContent,
+    HttpBufferContent, HttpFormUrlEncodedContent, HttpMethod, HttpRequestMessage,
+    HttpStreamContent, IHttpContent,
 };
 use windows_collections::{IIterable, IKeyValuePair};
 use windows_core::{Interface, HSTRING};
@@ -14,11 +17,19 @@ use windows_core::{Interface, HSTRING};
 use crate::buffer::VecBuffer;
 use crate::client::WinrtClient;
 use crate::error::IntoNyquestResult;
+#[cfg(feature = "multipart")]
+use crate::stream::BodyInputStream;
 use crate::string_pair::StringPair;
 use crate::uri::build_uri;
 
 impl WinrtClient {
     pub(crate) fn create_request<B>(&self, req: &Request<B>) -> NyquestResult<HttpRequestMessage> {
+        if req.proxy.is_some() {
+            return Err(nyquest_interface::Error::Unsupported("proxy"));
+        }
+        if req.cache_policy.is_some() {
+            return Err(nyquest_interface::Error::Unsupported("cache_policy"));
+        }
         let uri = build_uri(&self.base_url, &req.relative_uri)
             .map_err(|_| nyquest_interface::Error::InvalidUrl)?;
         let method = match &req.method {
@@ -32,9 +43,13 @@ impl WinrtClient {
         .into_nyquest_result()?;
         let req_msg = HttpRequestMessage::Create(&method, &uri).into_nyquest_result()?;
         // TODO: cache method
-        if !req.additional_headers.is_empty() || !self.default_content_headers.is_empty() {
+        if !req.additional_headers.is_empty() || !self.default_headers.is_empty() {
             let headers = req_msg.Headers().into_nyquest_result()?;
-            for (name, value) in &req.additional_headers {
+            let default_headers = self
+                .default_headers
+                .iter()
+                .filter(|(name, _)| !req.additional_headers.contains(name));
+            for (name, value) in req.additional_headers.iter().chain(default_headers) {
                 headers
                     .TryAppendWithoutValidation(&HSTRING::from(&**name), &HSTRING::from(&**value))
                     .into_nyquest_result()?;
@@ -46,7 +61,7 @@ impl WinrtClient {
     pub(crate) fn append_content_headers(
         &self,
         content: &IHttpContent,
-        additional_headers: &[(Cow<'static, str>, Cow<'static, str>)],
+        additional_headers: &HeaderMap,
     ) -> io::Result<()> {
         let headers = content.Headers()?;
         for (name, value) in additional_headers {
@@ -58,7 +73,12 @@ impl WinrtClient {
             }
         }
         for (name, value) in &self.default_content_headers {
-            headers.TryAppendWithoutValidation(name, value)?;
+            if !additional_headers.contains(name) {
+                headers.TryAppendWithoutValidation(
+                    &HSTRING::from(&**name),
+                    &HSTRING::from(&**value),
+                )?;
+            }
         }
         Ok(())
     }
@@ -74,12 +94,12 @@ pub(crate) fn is_header_name_content_related(name: &str) -> bool {
 }
 
 fn create_content_from_bytes(
-    content: Cow<'static, [u8]>,
+    content: Bytes,
     content_type: Cow<'static, str>,
 ) -> io::Result<IHttpContent> {
     let content_len = content.len();
     let content =
-        HttpBufferContent::CreateFromBuffer(&IBuffer::from(VecBuffer::new(content.into_owned())))?;
+        HttpBufferContent::CreateFromBuffer(&IBuffer::from(VecBuffer::new(Vec::from(content))))?;
     let content_type = HttpMediaTypeHeaderValue::Create(&HSTRING::from(&*content_type))?;
     let headers = content.Headers()?;
     headers.SetContentType(&content_type)?;
@@ -88,9 +108,30 @@ fn create_content_from_bytes(
     Ok(content.cast()?)
 }
 
+#[cfg(feature = "multipart")]
+fn set_content_type(content: &IHttpContent, content_type: &str) -> io::Result<()> {
+    let content_type = HttpMediaTypeHeaderValue::Create(&HSTRING::from(content_type))?;
+    content.Headers()?.SetContentType(&content_type)?;
+    Ok(())
+}
+
+fn create_content_from_stream<S>(
+    stream: StreamReader<S>,
+    map_stream: &mut impl FnMut(S) -> io::Result<IInputStream>,
+) -> io::Result<IHttpContent> {
+    let content = HttpStreamContent::CreateFromInputStream(&map_stream(stream.stream)?)?;
+    if let Some(content_length) = stream.content_length {
+        let len = PropertyValue::CreateUInt64(content_length)?;
+        content
+            .Headers()?
+            .SetContentLength(&len.cast::<IReference<u64>>()?)?;
+    }
+    Ok(content.cast()?)
+}
+
 pub(crate) fn create_body<S>(
     body: Body<S>,
-    map_stream: &mut impl FnMut(S) -> io::Result<IHttpContent>,
+    map_stream: &mut impl FnMut(S) -> io::Result<IInputStream>,
 ) -> io::Result<IHttpContent> {
     Ok(match body {
         Body::Bytes {
@@ -120,7 +161,21 @@ pub(crate) fn create_body<S>(
                     PartBody::Bytes { content } => {
                         create_content_from_bytes(content, part.content_type)?
                     }
-                    PartBody::Stream(stream) => map_stream(stream.stream)?,
+                    PartBody::File { path } => {
+                        let file = std::fs::File::open(path)?;
+                        let stream = StreamReader {
+                            content_length: Some(file.metadata()?.len()),
+                            stream: BodyInputStream::create(file),
+                        };
+                        let content = create_content_from_stream(stream, &mut Ok)?;
+                        set_content_type(&content, &part.content_type)?;
+                        content
+                    }
+                    PartBody::Stream(stream) => {
+                        let content = create_content_from_stream(stream, map_stream)?;
+                        set_content_type(&content, &part.content_type)?;
+                        content
+                    }
                 };
                 let headers = part_content.Headers()?;
                 for (name, value) in part.headers {
@@ -143,6 +198,6 @@ pub(crate) fn create_body<S>(
             }
             content.cast()?
         }
-        Body::Stream(stream) => map_stream(stream.stream)?,
+        Body::Stream(stream) => create_content_from_stream(stream, map_stream)?,
     })
 }
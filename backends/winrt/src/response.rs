@@ -1,9 +1,14 @@
 use std::io;
+use std::time::Duration;
 
-use windows::core::HSTRING;
+use nyquest_interface::client::ResponseHeaderLimits;
+use nyquest_interface::Result as NyquestResult;
+use windows::core::{Interface, HSTRING};
 use windows::Storage::Streams::{DataReader, InputStreamOptions};
 use windows::Web::Http::{HttpResponseMessage, IHttpContent};
+use windows_collections::{IIterable, IKeyValuePair};
 
+use crate::error::IntoNyquestResult;
 use crate::timer::Timer;
 
 pub struct WinrtResponse {
@@ -11,15 +16,24 @@ pub struct WinrtResponse {
     pub(crate) content_length: Option<u64>,
     pub(crate) max_response_buffer_size: Option<u64>,
     pub(crate) request_timer: Timer,
+    pub(crate) read_timeout: Option<Duration>,
     pub(crate) response: HttpResponseMessage,
     pub(crate) reader: Option<DataReader>,
 }
 
+impl Drop for WinrtResponse {
+    fn drop(&mut self) {
+        // Aborts the transfer of any unread body, releasing the connection
+        self.response.Close().ok();
+    }
+}
+
 impl WinrtResponse {
     pub(crate) fn new(
         res: HttpResponseMessage,
         response_size_limit: Option<u64>,
         request_timer: Timer,
+        read_timeout: Option<Duration>,
     ) -> io::Result<WinrtResponse> {
         let content_length = match res.Content() {
             Ok(content) => content
@@ -34,11 +48,28 @@ impl WinrtResponse {
             content_length,
             max_response_buffer_size: response_size_limit,
             request_timer,
+            read_timeout,
             response: res,
             reader: None,
         })
     }
 
+    /// Checks the headers of the response, which are only exposed once received entirely.
+    pub(crate) fn check_header_limits(
+        res: &HttpResponseMessage,
+        limits: Option<&ResponseHeaderLimits>,
+    ) -> NyquestResult<()> {
+        let Some(limits) = limits else {
+            return Ok(());
+        };
+        let headers = all_headers(res).into_nyquest_result()?;
+        let size = headers
+            .iter()
+            .map(|(name, value)| ResponseHeaderLimits::header_size(name.len(), value.len()))
+            .sum();
+        limits.check(size, headers.len())
+    }
+
     pub(crate) fn get_header(&self, header: &str) -> io::Result<Vec<String>> {
         let headers = self.response.Headers()?;
         let header_name = HSTRING::from(header);
@@ -64,3 +95,19 @@ impl WinrtResponse {
         Ok(self.reader.as_mut().expect("DataReader is None"))
     }
 }
+
+/// Gets the headers of the response followed by those of its content.
+pub(crate) fn all_headers(res: &HttpResponseMessage) -> io::Result<Vec<(HSTRING, HSTRING)>> {
+    let mut all = vec![];
+    let mut add = |headers: IIterable<IKeyValuePair<HSTRING, HSTRING>>| -> io::Result<()> {
+        for header in headers.First()? {
+            all.push((header.Key()?, header.Value()?));
+        }
+        Ok(())
+    };
+    add(res.Headers()?.cast()?)?;
+    if let Ok(content) = res.Content() {
+        add(content.Headers()?.cast()?)?;
+    }
+    Ok(all)
+}
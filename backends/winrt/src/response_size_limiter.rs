@@ -8,6 +8,7 @@ use windows_core::RuntimeType;
 use windows_future::{AsyncOperationProgressHandler, IAsyncOperationWithProgress};
 
 use crate::error::IntoNyquestResult;
+use crate::timer::WatchdogFeeder;
 
 pub(crate) struct ResponseSizeLimiterInner {
     max_size: u64,
@@ -20,18 +21,24 @@ pub(crate) struct ResponseSizeLimiter {
 }
 
 impl ResponseSizeLimiter {
+    /// Hooks the progress of the task, which is also reported to the watchdog if any, as a task
+    /// has only one progress handler.
     pub fn hook_progress<TResult: RuntimeType>(
         max_size: Option<u64>,
+        watchdog: Option<WatchdogFeeder>,
         task: &IAsyncOperationWithProgress<TResult, u64>,
     ) -> NyquestResult<Self> {
-        let Some(max_size) = max_size else {
+        if max_size.is_none() && watchdog.is_none() {
             return Ok(Self { inner: None });
-        };
+        }
         let size_exceeded = Arc::new(AtomicBool::new(false));
         let size_exceeded_cloned = size_exceeded.clone();
         task.SetProgress(&AsyncOperationProgressHandler::new(
             move |task, progress| {
-                if *progress > max_size {
+                if let Some(watchdog) = &watchdog {
+                    watchdog.feed();
+                }
+                if max_size.is_some_and(|max_size| *progress > max_size) {
                     size_exceeded.store(true, std::sync::atomic::Ordering::SeqCst);
                     task.as_ref().map(|t| t.Cancel().ok());
                 }
@@ -39,6 +46,9 @@ impl ResponseSizeLimiter {
             },
         ))
         .into_nyquest_result()?;
+        let Some(max_size) = max_size else {
+            return Ok(Self { inner: None });
+        };
         let limiter = Self {
             inner: Some(ResponseSizeLimiterInner {
                 max_size,
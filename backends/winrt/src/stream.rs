@@ -0,0 +1,93 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use windows::core::*;
+use windows::Foundation::{IClosable, IClosable_Impl};
+use windows::Storage::Streams::{IBuffer, IInputStream, IInputStream_Impl, InputStreamOptions};
+use windows::Win32::System::WinRT::IBufferByteAccess;
+use windows_future::IAsyncOperationWithProgress;
+
+/// A request body source that is pulled chunk by chunk on the thread pool.
+pub(crate) trait ReadChunk: Send {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+/// Exposes a [`ReadChunk`] as an `IInputStream`, so that `HttpStreamContent` can upload the
+/// body as it is being read instead of requiring all of it up front.
+#[implement(IInputStream, IClosable)]
+pub(crate) struct BodyInputStream {
+    reader: Arc<Mutex<Option<Box<dyn ReadChunk>>>>,
+}
+
+impl BodyInputStream {
+    pub fn create(reader: impl ReadChunk + 'static) -> IInputStream {
+        BodyInputStream {
+            reader: Arc::new(Mutex::new(Some(Box::new(reader)))),
+        }
+        .into()
+    }
+}
+
+#[allow(non_snake_case)]
+impl IInputStream_Impl for BodyInputStream_Impl {
+    fn ReadAsync(
+        &self,
+        buffer: Ref<'_, IBuffer>,
+        count: u32,
+        _options: InputStreamOptions,
+    ) -> Result<IAsyncOperationWithProgress<IBuffer, u32>> {
+        // The buffer may only be used from another thread through an agile reference
+        let buffer = AgileReference::new(buffer.ok()?)?;
+        let reader = self.reader.clone();
+        Ok(IAsyncOperationWithProgress::spawn(move || {
+            let buffer = buffer.resolve()?;
+            let len = count.min(buffer.Capacity()?) as usize;
+            let bytes = buffer.cast::<IBufferByteAccess>()?;
+            // The caller does not touch the buffer until the operation completes
+            let buf = unsafe { std::slice::from_raw_parts_mut(bytes.Buffer()?, len) };
+            let len = match &mut *reader.lock().unwrap() {
+                Some(reader) => reader.read_chunk(buf)?,
+                None => 0,
+            };
+            buffer.SetLength(len as u32)?;
+            Ok(buffer)
+        }))
+    }
+}
+
+#[allow(non_snake_case)]
+impl IClosable_Impl for BodyInputStream_Impl {
+    fn Close(&self) -> Result<()> {
+        self.reader.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+fn read_uninterrupted(reader: &mut impl io::Read, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            res => return res,
+        }
+    }
+}
+
+impl ReadChunk for std::fs::File {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl ReadChunk for nyquest_interface::blocking::BoxedStream {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl ReadChunk for nyquest_interface::r#async::body_pump::PumpedReader {
+    fn read_chunk(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read_uninterrupted(self, buf)
+    }
+}
@@ -1,7 +1,14 @@
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use nyquest_interface::{Error as NyquestError, Result as NyquestResult};
+use windows::Storage::Streams::DataReaderLoadOperation;
+use windows::System::Threading::{ThreadPoolTimer, TimerElapsedHandler};
 use windows_core::RuntimeType;
-use windows_future::IAsyncOperationWithProgress;
+use windows_future::{AsyncOperationProgressHandler, IAsyncOperationWithProgress};
+
+use crate::error::IntoNyquestResult;
 
 pub(crate) struct Timer {
     pub(crate) remaining: Option<Duration>,
@@ -13,6 +20,111 @@ impl Timer {
     }
 }
 
+struct WatchdogState {
+    last_progress: Mutex<Instant>,
+    fired: AtomicBool,
+}
+
+/// Cancels an operation once it reports no progress for the read timeout.
+pub(crate) struct Watchdog {
+    state: Arc<WatchdogState>,
+    timer: ThreadPoolTimer,
+}
+
+/// Reports the progress of the operation guarded by a [`Watchdog`].
+#[derive(Clone)]
+pub(crate) struct WatchdogFeeder(Arc<WatchdogState>);
+
+impl Watchdog {
+    pub(crate) fn start<T: Cancel + Send + 'static>(
+        read_timeout: Duration,
+        task: T,
+    ) -> NyquestResult<Self> {
+        let state = Arc::new(WatchdogState {
+            last_progress: Mutex::new(Instant::now()),
+            fired: AtomicBool::new(false),
+        });
+        // Checks a few times per interval, so that it fires no later than a quarter past it
+        let period = (read_timeout / 4).max(Duration::from_millis(10));
+        let timer = ThreadPoolTimer::CreatePeriodicTimer(
+            &TimerElapsedHandler::new({
+                let state = state.clone();
+                move |_| {
+                    let idle = state.last_progress.lock().unwrap().elapsed();
+                    if idle >= read_timeout && !state.fired.swap(true, Ordering::SeqCst) {
+                        task.cancel().ok();
+                    }
+                    Ok(())
+                }
+            }),
+            period.into(),
+        )
+        .into_nyquest_result()?;
+        Ok(Self { state, timer })
+    }
+
+    /// Starts a watchdog fed by the progress of the task, if there is a read timeout.
+    pub(crate) fn watch<T: RuntimeType, P: RuntimeType>(
+        read_timeout: Option<Duration>,
+        task: &IAsyncOperationWithProgress<T, P>,
+    ) -> NyquestResult<Option<Self>> {
+        let Some(read_timeout) = read_timeout else {
+            return Ok(None);
+        };
+        let watchdog = Self::start(read_timeout, task.clone())?;
+        let feeder = watchdog.feeder();
+        task.SetProgress(&AsyncOperationProgressHandler::new(move |_, _| {
+            feeder.feed();
+            Ok(())
+        }))
+        .into_nyquest_result()?;
+        Ok(Some(watchdog))
+    }
+
+    pub(crate) fn feeder(&self) -> WatchdogFeeder {
+        WatchdogFeeder(self.state.clone())
+    }
+
+    /// Stops the watchdog, and fails if it has cancelled the operation.
+    pub(crate) fn finish(self) -> NyquestResult<()> {
+        self.timer.Cancel().ok();
+        if self.state.fired.load(Ordering::SeqCst) {
+            return Err(NyquestError::RequestTimeout);
+        }
+        Ok(())
+    }
+}
+
+impl WatchdogFeeder {
+    pub(crate) fn feed(&self) {
+        *self.0.last_progress.lock().unwrap() = Instant::now();
+    }
+}
+
+/// Cancels an operation when dropped, unless it is defused once the operation completes.
+#[cfg(feature = "async")]
+pub(crate) struct CancelOnDrop<T: Cancel>(Option<T>);
+
+#[cfg(feature = "async")]
+impl<T: Cancel> CancelOnDrop<T> {
+    pub(crate) fn new(task: T) -> Self {
+        Self(Some(task))
+    }
+
+    pub(crate) fn defuse(mut self) {
+        self.0 = None;
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Cancel> Drop for CancelOnDrop<T> {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            task.cancel().ok();
+        }
+    }
+}
+
 pub(crate) trait Cancel {
     fn cancel(&self) -> windows_core::Result<()>;
 }
@@ -22,3 +134,9 @@ impl<T: RuntimeType, P: RuntimeType> Cancel for IAsyncOperationWithProgress<T, P
         self.Cancel()
     }
 }
+
+impl Cancel for DataReaderLoadOperation {
+    fn cancel(&self) -> windows_core::Result<()> {
+        self.Cancel()
+    }
+}
@@ -0,0 +1,84 @@
+//! Counts the heap allocations of the client per request with `dhat`, for the scenarios of the
+//! `throughput` benchmark. The server runs in a child process so that only the client is counted.
+//!
+//! Run with a backend and a client flavor, e.g.
+//! `cargo bench -p nyquest-backend-tests --features curl,async,blocking --bench allocations`.
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        any(feature = "curl", feature = "nsurlsession", feature = "winrt"),
+        any(feature = "async", feature = "blocking"),
+    ))] {
+        mod common;
+
+        use common::{path, ServerProcess, CONCURRENT_STREAMS, LARGE_SIZE, SMALL_SIZE, STREAM_SIZE};
+
+        #[global_allocator]
+        static ALLOC: dhat::Alloc = dhat::Alloc;
+
+        const ITERATIONS: u64 = 20;
+
+        /// Runs the scenario a few times and prints the allocations of each run on average.
+        fn report(name: &str, mut run: impl FnMut()) {
+            // Warms up connections and lazily initialized state
+            run();
+            let before = dhat::HeapStats::get();
+            for _ in 0..ITERATIONS {
+                run();
+            }
+            let after = dhat::HeapStats::get();
+            println!(
+                "{name:<32} {:>10} allocations {:>14} bytes",
+                (after.total_blocks - before.total_blocks) / ITERATIONS,
+                (after.total_bytes - before.total_bytes) / ITERATIONS,
+            );
+        }
+
+        fn main() {
+            common::serve_if_child();
+            let server = ServerProcess::spawn().unwrap();
+            let _profiler = dhat::Profiler::builder().testing().build();
+
+            #[cfg(feature = "blocking")]
+            {
+                let client = common::blocking_client(server.base_url());
+                let get = |size| {
+                    let req = nyquest::blocking::Request::get(path(size));
+                    client.request(req).unwrap().bytes().unwrap();
+                };
+                report("blocking/small_requests", || get(SMALL_SIZE));
+                report("blocking/large_download", || get(LARGE_SIZE));
+                report("blocking/concurrent_streams", || {
+                    std::thread::scope(|s| {
+                        for _ in 0..CONCURRENT_STREAMS {
+                            s.spawn(|| get(STREAM_SIZE));
+                        }
+                    })
+                });
+            }
+
+            #[cfg(feature = "async")]
+            {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let client = common::async_client(&rt, server.base_url());
+                let get = |size| {
+                    let req = nyquest::r#async::Request::get(path(size));
+                    let res = client.request(req);
+                    async { res.await.unwrap().bytes().await.unwrap() }
+                };
+                report("async/small_requests", || {
+                    rt.block_on(get(SMALL_SIZE));
+                });
+                report("async/large_download", || {
+                    rt.block_on(get(LARGE_SIZE));
+                });
+                report("async/concurrent_streams", || {
+                    let streams = (0..CONCURRENT_STREAMS).map(|_| get(STREAM_SIZE));
+                    rt.block_on(futures::future::join_all(streams));
+                });
+            }
+        }
+    } else {
+        fn main() {}
+    }
+}
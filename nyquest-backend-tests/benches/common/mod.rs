@@ -0,0 +1,123 @@
+//! Setup shared by the benchmarks: the backend, the clients and the server they request.
+
+// Each benchmark uses a part of the setup
+#![allow(dead_code)]
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::Once;
+
+use nyquest_test_server::{Reply, TestServer};
+
+/// Size of the responses to small requests.
+pub const SMALL_SIZE: usize = 2;
+/// Size of the responses of concurrent streams.
+pub const STREAM_SIZE: usize = 256 * 1024;
+/// Size of the large downloads.
+pub const LARGE_SIZE: usize = 16 * 1024 * 1024;
+/// Number of requests sent at once by the concurrent benchmarks.
+pub const CONCURRENT_STREAMS: usize = 32;
+
+const SIZES: [usize; 4] = [SMALL_SIZE, 64 * 1024, STREAM_SIZE, LARGE_SIZE];
+/// Set in the environment of the child process serving the responses.
+const SERVER_ENV: &str = "NYQUEST_BENCH_SERVER";
+
+/// Registers the backend selected with the features, once.
+pub fn register_backend() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "curl")] {
+                nyquest_backend_curl::register();
+            } else if #[cfg(feature = "nsurlsession")] {
+                nyquest_backend_nsurlsession::register();
+            } else {
+                nyquest_backend_winrt::register();
+            }
+        }
+    });
+}
+
+/// Returns the path answered with a body of the size.
+pub fn path(size: usize) -> String {
+    format!("/{size}")
+}
+
+/// Starts a server answering [`path`] for each of the sizes of the benchmarks.
+pub fn start_server() -> TestServer {
+    let server = TestServer::start().unwrap();
+    for size in SIZES {
+        let body = vec![b'x'; size];
+        server.route("GET", path(size), move |_| Reply::ok().body(body.clone()));
+    }
+    server
+}
+
+/// A server from [`start_server`] running in a child process, so that its allocations are not
+/// counted with those of the client.
+pub struct ServerProcess {
+    child: Child,
+    base_url: String,
+}
+
+impl ServerProcess {
+    /// Re-runs the current benchmark executable as the server.
+    pub fn spawn() -> io::Result<Self> {
+        let mut child = Command::new(std::env::current_exe()?)
+            .env(SERVER_ENV, "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+        let mut base_url = String::new();
+        stdout.read_line(&mut base_url)?;
+        Ok(Self {
+            child,
+            base_url: base_url.trim().to_owned(),
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        // Closing stdin stops the server
+        drop(self.child.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+/// Serves the responses and exits instead of running the benchmarks, if the process is spawned
+/// by [`ServerProcess::spawn`].
+pub fn serve_if_child() {
+    if std::env::var_os(SERVER_ENV).is_none() {
+        return;
+    }
+    let server = start_server();
+    println!("{}", server.base_url());
+    let _ = io::stdin().read_to_end(&mut vec![]);
+    std::process::exit(0);
+}
+
+#[cfg(feature = "blocking")]
+pub fn blocking_client(base_url: &str) -> nyquest::BlockingClient {
+    register_backend();
+    nyquest::ClientBuilder::default()
+        .base_url(base_url.to_owned())
+        .build_blocking()
+        .unwrap()
+}
+
+#[cfg(feature = "async")]
+pub fn async_client(rt: &tokio::runtime::Runtime, base_url: &str) -> nyquest::AsyncClient {
+    register_backend();
+    rt.block_on(
+        nyquest::ClientBuilder::default()
+            .base_url(base_url.to_owned())
+            .build_async(),
+    )
+    .unwrap()
+}
@@ -0,0 +1,56 @@
+//! Measures receiving large response bodies with `Response::bytes()`, which should not copy the
+//! body once it is received by the backend.
+//!
+//! Run with a backend and a client flavor, e.g.
+//! `cargo bench -p nyquest-backend-tests --features curl,async,blocking`.
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        any(feature = "curl", feature = "nsurlsession", feature = "winrt"),
+        any(feature = "async", feature = "blocking"),
+    ))] {
+        mod common;
+
+        use common::{path, LARGE_SIZE};
+        use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+        const SIZES: [usize; 2] = [64 * 1024, LARGE_SIZE];
+
+        fn bench_response_bytes(c: &mut Criterion) {
+            let server = common::start_server();
+            let mut group = c.benchmark_group("response_bytes");
+            for size in SIZES {
+                group.throughput(Throughput::Bytes(size as u64));
+                #[cfg(feature = "blocking")]
+                {
+                    let client = common::blocking_client(&server.base_url());
+                    group.bench_function(format!("blocking/{size}"), |b| {
+                        b.iter(|| {
+                            let req = nyquest::blocking::Request::get(path(size));
+                            client.request(req).unwrap().bytes().unwrap()
+                        })
+                    });
+                }
+                #[cfg(feature = "async")]
+                {
+                    let rt = tokio::runtime::Runtime::new().unwrap();
+                    let client = common::async_client(&rt, &server.base_url());
+                    group.bench_function(format!("async/{size}"), |b| {
+                        b.iter(|| {
+                            rt.block_on(async {
+                                let req = nyquest::r#async::Request::get(path(size));
+                                client.request(req).await.unwrap().bytes().await.unwrap()
+                            })
+                        })
+                    });
+                }
+            }
+            group.finish();
+        }
+
+        criterion_group!(benches, bench_response_bytes);
+        criterion_main!(benches);
+    } else {
+        fn main() {}
+    }
+}
@@ -0,0 +1,114 @@
+//! Measures the requests per second of small requests, the throughput of large downloads and
+//! that of many concurrent streams, against a local server.
+//!
+//! Run with a backend and a client flavor, e.g.
+//! `cargo bench -p nyquest-backend-tests --features curl,async,blocking --bench throughput`.
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        any(feature = "curl", feature = "nsurlsession", feature = "winrt"),
+        any(feature = "async", feature = "blocking"),
+    ))] {
+        mod common;
+
+        use common::{path, CONCURRENT_STREAMS, LARGE_SIZE, SMALL_SIZE, STREAM_SIZE};
+        use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+        #[cfg(feature = "blocking")]
+        fn get_blocking(client: &nyquest::BlockingClient, size: usize) {
+            let req = nyquest::blocking::Request::get(path(size));
+            let body = client.request(req).unwrap().bytes().unwrap();
+            assert_eq!(body.len(), size);
+        }
+
+        #[cfg(feature = "async")]
+        async fn get_async(client: &nyquest::AsyncClient, size: usize) {
+            let req = nyquest::r#async::Request::get(path(size));
+            let body = client.request(req).await.unwrap().bytes().await.unwrap();
+            assert_eq!(body.len(), size);
+        }
+
+        fn bench_small_requests(c: &mut Criterion) {
+            let server = common::start_server();
+            let mut group = c.benchmark_group("small_requests");
+            group.throughput(Throughput::Elements(1));
+            #[cfg(feature = "blocking")]
+            {
+                let client = common::blocking_client(&server.base_url());
+                group.bench_function("blocking", |b| b.iter(|| get_blocking(&client, SMALL_SIZE)));
+            }
+            #[cfg(feature = "async")]
+            {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let client = common::async_client(&rt, &server.base_url());
+                group.bench_function("async", |b| {
+                    b.iter(|| rt.block_on(get_async(&client, SMALL_SIZE)))
+                });
+            }
+            group.finish();
+        }
+
+        fn bench_large_download(c: &mut Criterion) {
+            let server = common::start_server();
+            let mut group = c.benchmark_group("large_download");
+            group.throughput(Throughput::Bytes(LARGE_SIZE as u64));
+            group.sample_size(20);
+            #[cfg(feature = "blocking")]
+            {
+                let client = common::blocking_client(&server.base_url());
+                group.bench_function("blocking", |b| b.iter(|| get_blocking(&client, LARGE_SIZE)));
+            }
+            #[cfg(feature = "async")]
+            {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let client = common::async_client(&rt, &server.base_url());
+                group.bench_function("async", |b| {
+                    b.iter(|| rt.block_on(get_async(&client, LARGE_SIZE)))
+                });
+            }
+            group.finish();
+        }
+
+        fn bench_concurrent_streams(c: &mut Criterion) {
+            let server = common::start_server();
+            let mut group = c.benchmark_group("concurrent_streams");
+            group.throughput(Throughput::Bytes((CONCURRENT_STREAMS * STREAM_SIZE) as u64));
+            group.sample_size(20);
+            #[cfg(feature = "blocking")]
+            {
+                let client = common::blocking_client(&server.base_url());
+                group.bench_function("blocking", |b| {
+                    b.iter(|| {
+                        std::thread::scope(|s| {
+                            for _ in 0..CONCURRENT_STREAMS {
+                                s.spawn(|| get_blocking(&client, STREAM_SIZE));
+                            }
+                        })
+                    })
+                });
+            }
+            #[cfg(feature = "async")]
+            {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let client = common::async_client(&rt, &server.base_url());
+                group.bench_function("async", |b| {
+                    b.iter(|| {
+                        let streams = (0..CONCURRENT_STREAMS).map(|_| get_async(&client, STREAM_SIZE));
+                        rt.block_on(futures::future::join_all(streams))
+                    })
+                });
+            }
+            group.finish();
+        }
+
+        criterion_group!(
+            benches,
+            bench_small_requests,
+            bench_large_download,
+            bench_concurrent_streams
+        );
+        criterion_main!(benches);
+    } else {
+        fn main() {}
+    }
+}
@@ -1,5 +1,18 @@
 mod cache;
 mod cookies;
+mod decompression;
+mod dns_cache;
 mod headers;
+mod http2_keep_alive;
+mod http2_push;
+mod local_schemes;
+mod network_status;
+mod proxy;
+mod read_timeout;
+mod request_map;
 mod request_timeout;
+mod resolve;
+mod response_headers;
 mod response_size;
+mod revocation;
+mod tls_session;
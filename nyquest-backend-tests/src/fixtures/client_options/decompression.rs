@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use http_body_util::Full;
+    use hyper::header::{HeaderValue, CONTENT_ENCODING};
+    use nyquest::client::DecompressionLimits;
+    use nyquest::Error;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    /// Size of the body after decompression, about 160 times the compressed size.
+    const DECOMPRESSED_SIZE: usize = 1024 * 1024;
+
+    /// Writes bits into bytes starting from the least significant bit, as deflate does.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        acc: u32,
+        len: u32,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, len: u32) {
+            self.acc |= value << self.len;
+            self.len += len;
+            while self.len >= 8 {
+                self.bytes.push(self.acc as u8);
+                self.acc >>= 8;
+                self.len -= 8;
+            }
+        }
+
+        /// Huffman codes are packed starting from the most significant bit.
+        fn write_code(&mut self, code: u32, len: u32) {
+            self.write(code.reverse_bits() >> (32 - len), len);
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            if self.len > 0 {
+                self.bytes.push(self.acc as u8);
+            }
+            self.bytes
+        }
+    }
+
+    fn crc32(len: usize) -> u32 {
+        let mut crc = !0u32;
+        for _ in 0..len {
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    /// Compresses zeros into a gzip stream with a single block of fixed Huffman codes, made of a
+    /// literal followed by back-references of the maximum length.
+    fn gzip_zeros(len: usize) -> Vec<u8> {
+        const LITERAL_ZERO: (u32, u32) = (0b0011_0000, 8);
+        const LENGTH_258: (u32, u32) = (0b1100_0101, 8);
+        const DISTANCE_1: (u32, u32) = (0, 5);
+        const END_OF_BLOCK: (u32, u32) = (0, 7);
+
+        let mut bits = BitWriter::default();
+        // BFINAL, then BTYPE of fixed Huffman codes
+        bits.write(1, 1);
+        bits.write(1, 2);
+        bits.write_code(LITERAL_ZERO.0, LITERAL_ZERO.1);
+        let mut remaining = len - 1;
+        while remaining >= 258 {
+            bits.write_code(LENGTH_258.0, LENGTH_258.1);
+            bits.write_code(DISTANCE_1.0, DISTANCE_1.1);
+            remaining -= 258;
+        }
+        for _ in 0..remaining {
+            bits.write_code(LITERAL_ZERO.0, LITERAL_ZERO.1);
+        }
+        bits.write_code(END_OF_BLOCK.0, END_OF_BLOCK.1);
+
+        let mut gzip = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+        gzip.extend(bits.finish());
+        gzip.extend(crc32(len).to_le_bytes());
+        gzip.extend((len as u32).to_le_bytes());
+        gzip
+    }
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |_| async {
+            let mut res = Response::new(Full::new(Bytes::from(gzip_zeros(DECOMPRESSED_SIZE))));
+            res.headers_mut()
+                .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+            (res, Ok(()))
+        })
+    }
+
+    fn assert_too_large(path: &'static str, limits: DecompressionLimits) {
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .decompression_limits(limits.clone());
+            let client = builder.build_blocking().unwrap();
+            let err = client
+                .request(NyquestRequest::get(path))
+                .and_then(|r| r.bytes())
+                .unwrap_err();
+            assert!(matches!(err, Error::ResponseTooLarge));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .decompression_limits(limits);
+                let client = builder.build_async().await.unwrap();
+                let res = match client.request(NyquestRequest::get(path)).await {
+                    Ok(res) => res.bytes().await,
+                    Err(e) => Err(e),
+                };
+                assert!(matches!(res.unwrap_err(), Error::ResponseTooLarge));
+            });
+        }
+    }
+
+    #[test]
+    fn test_decompression_within_limits() {
+        const PATH: &str = "client_options/decompression_within_limits";
+        let _handle = add_fixture(PATH);
+        let limits = DecompressionLimits {
+            max_compressed_size: Some(64 * 1024),
+            max_decompressed_size: Some(DECOMPRESSED_SIZE as u64),
+            max_ratio: NonZeroU32::new(1000),
+        };
+
+        let assertions = |body: &[u8]| {
+            assert_eq!(body.len(), DECOMPRESSED_SIZE);
+            assert!(body.iter().all(|b| *b == 0));
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .decompression_limits(limits.clone());
+            let client = builder.build_blocking().unwrap();
+            let body = client
+                .request(NyquestRequest::get(PATH))
+                .unwrap()
+                .bytes()
+                .unwrap();
+            assertions(&body);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let body = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .decompression_limits(limits);
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(NyquestRequest::get(PATH))
+                    .await
+                    .unwrap()
+                    .bytes()
+                    .await
+                    .unwrap()
+            });
+            assertions(&body);
+        }
+    }
+
+    #[test]
+    fn test_decompressed_size_exceeded() {
+        const PATH: &str = "client_options/decompressed_size_exceeded";
+        let _handle = add_fixture(PATH);
+        assert_too_large(
+            PATH,
+            DecompressionLimits {
+                max_decompressed_size: Some(DECOMPRESSED_SIZE as u64 / 2),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[cfg(not(feature = "winrt"))] // WinRT does not expose the compressed size
+    #[test]
+    fn test_decompression_ratio_exceeded() {
+        const PATH: &str = "client_options/decompression_ratio_exceeded";
+        let _handle = add_fixture(PATH);
+        assert_too_large(
+            PATH,
+            DecompressionLimits {
+                max_ratio: NonZeroU32::new(100),
+                ..Default::default()
+            },
+        );
+    }
+}
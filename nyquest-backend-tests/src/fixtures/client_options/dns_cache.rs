@@ -0,0 +1,54 @@
+#[cfg(all(test, feature = "curl"))] // Only libcurl caches resolved addresses
+mod tests {
+    use std::time::Duration;
+
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    /// Returns the base URL of the server with a host name to look up instead of an address.
+    fn localhost_url(server: &TestServer) -> String {
+        format!("http://localhost:{}", server.addr().port())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_dns_cache_blocking() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/dns", |_| Reply::ok().body("resolved"));
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(localhost_url(&server))
+            .dns_cache_timeout(Duration::from_secs(300))
+            .build_blocking()
+            .unwrap();
+
+        for _ in 0..2 {
+            let res = client.request(NyquestRequest::get("/dns")).unwrap();
+            assert_eq!(res.text().unwrap(), "resolved");
+            client.flush_dns_cache().unwrap();
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_dns_cache_async() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/dns", |_| Reply::ok().body("resolved"));
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(localhost_url(&server))
+                .dns_cache_timeout(Duration::ZERO)
+                .build_async()
+                .await
+                .unwrap();
+
+            for _ in 0..2 {
+                let res = client.request(NyquestRequest::get("/dns")).await.unwrap();
+                assert_eq!(res.text().await.unwrap(), "resolved");
+                client.flush_dns_cache().unwrap();
+            }
+        });
+    }
+}
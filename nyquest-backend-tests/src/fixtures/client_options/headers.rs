@@ -141,4 +141,102 @@ mod tests {
             assertions(res);
         }
     }
+
+    #[test]
+    fn test_request_header_overrides_default() {
+        const PATH: &str = "client_options/request_header_overrides_default";
+        const ACCEPT_VALUE: &str = "application/json";
+
+        let _handle = crate::add_hyper_fixture(PATH, {
+            move |req| async move {
+                let accept = req
+                    .headers()
+                    .get_all(ACCEPT)
+                    .iter()
+                    .map(|v| v.to_str().unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let res = Response::new(Full::new(Bytes::from(accept.into_bytes())));
+                (res, Ok(()))
+            }
+        });
+
+        let assertions = |accept: String| {
+            assert_eq!(accept, ACCEPT_VALUE);
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .with_header("Accept", "text/plain");
+            let client = builder.build_blocking().unwrap();
+            let res = client
+                .request(
+                    NyquestRequest::get(PATH).with_header(nyquest::header::ACCEPT, ACCEPT_VALUE),
+                )
+                .unwrap()
+                .text()
+                .unwrap();
+            assertions(res);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let res = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .with_header("Accept", "text/plain");
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(
+                        NyquestRequest::get(PATH)
+                            .with_header(nyquest::header::ACCEPT, ACCEPT_VALUE),
+                    )
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap()
+            });
+            assertions(res);
+        }
+    }
+
+    #[cfg(feature = "curl")] // libcurl cannot send header lines containing nul bytes
+    #[test]
+    fn test_default_header_with_nul_rejected() {
+        #[cfg(feature = "blocking")]
+        {
+            let res = crate::init_builder_blocking()
+                .unwrap()
+                .with_header("X-Nul", "a\0b")
+                .build_blocking();
+            assert!(matches!(
+                res,
+                Err(nyquest::client::BuildClientError::BackendError(
+                    nyquest::Error::Io(_)
+                ))
+            ));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let res = TOKIO_RT.block_on(async {
+                crate::init_builder()
+                    .await
+                    .unwrap()
+                    .with_header("X-Nul", "a\0b")
+                    .build_async()
+                    .await
+            });
+            assert!(matches!(
+                res,
+                Err(nyquest::client::BuildClientError::BackendError(
+                    nyquest::Error::Io(_)
+                ))
+            ));
+        }
+    }
 }
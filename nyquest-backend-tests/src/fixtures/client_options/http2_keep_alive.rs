@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nyquest::client::BuildClientError;
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    const PATH: &str = "/client_options/http2_keep_alive";
+    const INTERVAL: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        // Outlasts the interval so that upkeep runs during the transfer
+        server.route("GET", PATH, |_| {
+            Reply::ok().body("kept alive").delay(INTERVAL * 3)
+        });
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_http2_keep_alive_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .http2_keep_alive(INTERVAL, TIMEOUT)
+            .build_blocking();
+        let client = match client {
+            Err(BuildClientError::Unsupported(_)) => return,
+            res => res.unwrap(),
+        };
+        for _ in 0..2 {
+            let res = client.request(NyquestRequest::get(PATH)).unwrap();
+            assert_eq!(res.text().unwrap(), "kept alive");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_http2_keep_alive_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .http2_keep_alive(INTERVAL, TIMEOUT)
+                .build_async()
+                .await;
+            let client = match client {
+                Err(BuildClientError::Unsupported(_)) => return,
+                res => res.unwrap(),
+            };
+            for _ in 0..2 {
+                let res = client.request(NyquestRequest::get(PATH)).await.unwrap();
+                assert_eq!(res.text().await.unwrap(), "kept alive");
+            }
+        });
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use nyquest::client::{BuildClientError, PushPolicy};
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    const PATH: &str = "/client_options/http2_push";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", PATH, |_| Reply::ok().body("pushed nothing"));
+        server
+    }
+
+    fn notify_policy() -> (PushPolicy, Arc<AtomicUsize>) {
+        let promises = Arc::new(AtomicUsize::new(0));
+        let policy = PushPolicy::Notify(Arc::new({
+            let promises = promises.clone();
+            move |_| {
+                promises.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+        (policy, promises)
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_http2_push_notify_blocking() {
+        let server = start_server();
+        let (policy, promises) = notify_policy();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .http2_push(policy)
+            .build_blocking();
+        let client = match client {
+            Err(BuildClientError::Unsupported(_)) => return,
+            res => res.unwrap(),
+        };
+        for _ in 0..2 {
+            let res = client.request(NyquestRequest::get(PATH)).unwrap();
+            assert_eq!(res.text().unwrap(), "pushed nothing");
+        }
+        assert_eq!(promises.load(Ordering::Relaxed), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_http2_push_notify_async() {
+        let server = start_server();
+        let (policy, promises) = notify_policy();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .http2_push(policy)
+                .build_async()
+                .await;
+            let client = match client {
+                Err(BuildClientError::Unsupported(_)) => return,
+                res => res.unwrap(),
+            };
+            for _ in 0..2 {
+                let res = client.request(NyquestRequest::get(PATH)).await.unwrap();
+                assert_eq!(res.text().await.unwrap(), "pushed nothing");
+            }
+        });
+        assert_eq!(promises.load(Ordering::Relaxed), 0);
+    }
+}
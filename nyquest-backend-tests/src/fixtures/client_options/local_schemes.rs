@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Error, Request as NyquestRequest};
+
+    #[cfg(feature = "async")]
+    use crate::TOKIO_RT;
+
+    const DATA_URL: &str = "data:text/plain;base64,SGVsbG8sIG55cXVlc3Qh";
+
+    #[test]
+    fn test_data_url() {
+        let assertions = |(content_type, text): (Option<String>, String)| {
+            assert_eq!(content_type.as_deref(), Some("text/plain"));
+            assert_eq!(text, "Hello, nyquest!");
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .allow_local_schemes(true);
+            let client = builder.build_blocking().unwrap();
+            let res = client.request(NyquestRequest::get(DATA_URL)).unwrap();
+            let content_type = res.get_header("content-type").unwrap().pop();
+            assertions((content_type, res.text().unwrap()));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let res = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .allow_local_schemes(true);
+                let client = builder.build_async().await.unwrap();
+                let res = client.request(NyquestRequest::get(DATA_URL)).await.unwrap();
+                let content_type = res.get_header("content-type").unwrap().pop();
+                (content_type, res.text().await.unwrap())
+            });
+            assertions(res);
+        }
+    }
+
+    #[test]
+    fn test_local_schemes_rejected_by_default() {
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .build_blocking()
+                .unwrap();
+            for url in [DATA_URL, "file:///etc/hostname"] {
+                let res = client.request(NyquestRequest::get(url));
+                assert!(matches!(res, Err(Error::InvalidUrl)), "{url} not rejected");
+            }
+        }
+
+        #[cfg(feature = "async")]
+        TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .build_async()
+                .await
+                .unwrap();
+            for url in [DATA_URL, "file:///etc/hostname"] {
+                let res = client.request(NyquestRequest::get(url)).await;
+                assert!(matches!(res, Err(Error::InvalidUrl)), "{url} not rejected");
+            }
+        });
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nyquest::{NetworkStatus, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    /// Checks that a request waiting for connectivity is only held back while offline.
+    fn check_waited(status: NetworkStatus, res: nyquest::Result<String>) {
+        match status {
+            NetworkStatus::Offline => {
+                assert!(matches!(res, Err(nyquest::Error::RequestTimeout)));
+            }
+            NetworkStatus::Online | NetworkStatus::Unknown => {
+                assert_eq!(res.unwrap(), "connected");
+            }
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_wait_for_connectivity_blocking() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/connected", |_| Reply::ok().body("connected"));
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .wait_for_connectivity(true)
+            .request_timeout(Duration::from_secs(1))
+            .build_blocking()
+            .unwrap();
+
+        let status = client.network_status();
+        let res = client
+            .request(NyquestRequest::get("/connected"))
+            .and_then(|res| res.text());
+        check_waited(status, res);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_wait_for_connectivity_async() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/connected", |_| Reply::ok().body("connected"));
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .wait_for_connectivity(true)
+                .request_timeout(Duration::from_secs(1))
+                .build_async()
+                .await
+                .unwrap();
+
+            let status = client.network_status();
+            let res = match client.request(NyquestRequest::get("/connected")).await {
+                Ok(res) => res.text().await,
+                Err(e) => Err(e),
+            };
+            check_waited(status, res);
+        });
+    }
+}
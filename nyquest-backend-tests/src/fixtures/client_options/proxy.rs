@@ -0,0 +1,176 @@
+#[cfg(all(test, feature = "curl"))] // Only libcurl supports custom proxies
+mod tests {
+    use http_body_util::Full;
+    use hyper::{body, Request, Response};
+    use nyquest::{Proxy, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    use crate::*;
+
+    const TARGET_URL: &str = "http://nyquest.test/client_options/proxy";
+
+    // The fixture server acts as an HTTP proxy, receiving the absolute target URL
+    async fn proxy_handler(req: Request<body::Incoming>) -> FixtureAssertionResult {
+        let uri = req.uri().to_string();
+        let res = Response::new(Full::new(Bytes::from(uri)));
+        (res.into(), Ok(()))
+    }
+
+    #[test]
+    fn test_proxy() {
+        const PATH: &str = "client_options/proxy";
+
+        let _handle = crate::add_hyper_fixture(PATH, proxy_handler);
+        let proxy_url = TOKIO_RT.block_on(crate::hyper_base_url()).unwrap();
+
+        let assertions = |uri: String| {
+            assert_eq!(uri, TARGET_URL);
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .proxy(proxy_url.clone());
+            let client = builder.build_blocking().unwrap();
+            let uri = client
+                .request(NyquestRequest::get(TARGET_URL))
+                .unwrap()
+                .text()
+                .unwrap();
+            assertions(uri);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let uri = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .proxy(proxy_url.clone());
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(NyquestRequest::get(TARGET_URL))
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap()
+            });
+            assertions(uri);
+        }
+    }
+
+    #[test]
+    fn test_request_proxy() {
+        const PATH: &str = "client_options/request_proxy";
+        const REQUEST_TARGET_URL: &str = "http://nyquest.test/client_options/request_proxy";
+
+        let _handle = crate::add_hyper_fixture(PATH, proxy_handler);
+        let proxy_url = TOKIO_RT.block_on(crate::hyper_base_url()).unwrap();
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/direct", |_| Reply::ok().body("direct"));
+        let direct_url = format!("{}/direct", server.base_url());
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .build_blocking()
+                .unwrap();
+            let req =
+                NyquestRequest::get(REQUEST_TARGET_URL).proxy(Some(Proxy::new(proxy_url.clone())));
+            assert_eq!(
+                client.request(req).unwrap().text().unwrap(),
+                REQUEST_TARGET_URL
+            );
+
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .proxy(proxy_url.clone())
+                .build_blocking()
+                .unwrap();
+            let req = NyquestRequest::get(direct_url.clone()).proxy(None);
+            assert_eq!(client.request(req).unwrap().text().unwrap(), "direct");
+        }
+
+        #[cfg(feature = "async")]
+        TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .build_async()
+                .await
+                .unwrap();
+            let req =
+                NyquestRequest::get(REQUEST_TARGET_URL).proxy(Some(Proxy::new(proxy_url.clone())));
+            let res = client.request(req).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), REQUEST_TARGET_URL);
+
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .proxy(proxy_url.clone())
+                .build_async()
+                .await
+                .unwrap();
+            let req = NyquestRequest::get(direct_url.clone()).proxy(None);
+            let res = client.request(req).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), "direct");
+        });
+    }
+
+    /// Accepts one connection and returns its first byte, which begins a TLS handshake if the
+    /// client speaks TLS to the proxy.
+    fn first_byte_listener() -> (String, std::thread::JoinHandle<u8>) {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let url = format!("https://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut byte = [0];
+            std::io::Read::read_exact(&mut stream, &mut byte).unwrap();
+            byte[0]
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_https_proxy() {
+        const TLS_HANDSHAKE: u8 = 0x16;
+        let proxy_tls = nyquest::client::ProxyTlsConfig {
+            verify: false,
+            ..Default::default()
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let (proxy_url, handle) = first_byte_listener();
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .proxy(proxy_url)
+                .proxy_tls(proxy_tls.clone());
+            let client = builder.build_blocking().unwrap();
+            assert!(client.request(NyquestRequest::get(TARGET_URL)).is_err());
+            assert_eq!(handle.join().unwrap(), TLS_HANDSHAKE);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let (proxy_url, handle) = first_byte_listener();
+            let res = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .proxy(proxy_url)
+                    .proxy_tls(proxy_tls.clone());
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(NyquestRequest::get(TARGET_URL))
+                    .await
+                    .map(drop)
+            });
+            assert!(res.is_err());
+            assert_eq!(handle.join().unwrap(), TLS_HANDSHAKE);
+        }
+    }
+}
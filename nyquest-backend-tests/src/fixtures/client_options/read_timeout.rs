@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::stream::{self, StreamExt as _};
+    use http_body_util::{BodyExt, StreamBody};
+    use nyquest::Error;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    const READ_TIMEOUT: Duration = Duration::from_secs(1);
+    const CHUNK: &str = "chunk";
+
+    /// Responds with the chunks, each sent after the given delay.
+    fn add_fixture(path: &'static str, delays: &'static [u64]) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, move |_req| async move {
+            let stream = stream::iter(delays).then(|delay| async move {
+                tokio::time::sleep(Duration::from_millis(*delay)).await;
+                Ok::<_, hyper::Error>(hyper::body::Frame::data(Bytes::from(CHUNK)))
+            });
+            let res = Response::new(BodyExt::boxed(StreamBody::new(stream)));
+            (res, Ok(()))
+        })
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        const PATH: &str = "client_options/read_timeout";
+        // The body stalls right after the response headers
+        let _handle = add_fixture(PATH, &[3000]);
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .read_timeout(READ_TIMEOUT);
+            let client = builder.build_blocking().unwrap();
+            let err = client
+                .request(NyquestRequest::get(PATH))
+                .and_then(|r| r.text())
+                .unwrap_err();
+            assert!(matches!(err, Error::RequestTimeout));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .read_timeout(READ_TIMEOUT);
+                let client = builder.build_async().await.unwrap();
+                let res = match client.request(NyquestRequest::get(PATH)).await {
+                    Ok(res) => res.text().await,
+                    Err(e) => Err(e),
+                };
+                assert!(matches!(res.unwrap_err(), Error::RequestTimeout));
+            });
+        }
+    }
+
+    #[test]
+    fn test_read_timeout_streaming() {
+        const PATH: &str = "client_options/read_timeout_streaming";
+        // The whole body takes longer than the read timeout, but data keeps arriving
+        let _handle = add_fixture(PATH, &[300; 6]);
+        let expected = CHUNK.repeat(6);
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .read_timeout(READ_TIMEOUT);
+            let client = builder.build_blocking().unwrap();
+            let res = client
+                .request(NyquestRequest::get(PATH))
+                .unwrap()
+                .text()
+                .unwrap();
+            assert_eq!(res, expected);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let res = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .read_timeout(READ_TIMEOUT);
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(NyquestRequest::get(PATH))
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap()
+            });
+            assert_eq!(res, expected);
+        }
+    }
+}
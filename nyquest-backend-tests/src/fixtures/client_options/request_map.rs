@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{ClientBuilder, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("*", "/v2/items", |_| Reply::ok().body("items"));
+        server
+    }
+
+    fn with_maps(builder: ClientBuilder) -> ClientBuilder {
+        builder
+            .map_request(|req| {
+                *req.uri = format!("/v2{}", req.uri).into();
+            })
+            .map_request(|req| {
+                req.headers.insert("x-tenant", "acme");
+                if req.method == "POST" {
+                    req.append_query("api-key", "a b");
+                }
+            })
+    }
+
+    fn assert_mapped(server: &TestServer) {
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for req in &requests {
+            assert_eq!(req.path(), "/v2/items");
+            assert_eq!(req.header("x-tenant"), Some("acme"));
+        }
+        assert_eq!(requests[0].query(), Some("page=1"));
+        assert_eq!(requests[1].query(), Some("page=1&api-key=a%20b"));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_map_request_blocking() {
+        let server = start_server();
+        let builder = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url());
+        let client = with_maps(builder).build_blocking().unwrap();
+        for req in [
+            NyquestRequest::get("/items?page=1"),
+            NyquestRequest::post("/items?page=1"),
+        ] {
+            assert_eq!(client.request(req).unwrap().text().unwrap(), "items");
+        }
+        assert_mapped(&server);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_map_request_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let builder = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url());
+            let client = with_maps(builder).build_async().await.unwrap();
+            for req in [
+                NyquestRequest::get("/items?page=1"),
+                NyquestRequest::post("/items?page=1"),
+            ] {
+                assert_eq!(
+                    client.request(req).await.unwrap().text().await.unwrap(),
+                    "items"
+                );
+            }
+            assert_mapped(&server);
+        });
+    }
+}
@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use http_body_util::Full;
     use nyquest::Error;
     use nyquest::Request as NyquestRequest;
@@ -86,4 +88,48 @@ mod tests {
             });
         }
     }
+
+    #[test]
+    fn test_request_deadline() {
+        const PATH: &str = "client_options/request_deadline";
+
+        let _handle = crate::add_hyper_fixture(PATH, |_| delayed_response_handler());
+        let deadline = || Instant::now() + Duration::from_secs(1);
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .request_timeout(Duration::from_secs(10));
+            let client = builder.build_blocking().unwrap();
+            let err = client
+                .request(NyquestRequest::get(PATH).with_deadline(deadline()))
+                .and_then(|r| r.text())
+                .unwrap_err();
+            assert!(matches!(err, Error::RequestTimeout));
+            let err = client
+                .request(NyquestRequest::get(PATH).with_deadline(Instant::now()))
+                .unwrap_err();
+            assert!(matches!(err, Error::RequestTimeout));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .request_timeout(Duration::from_secs(10));
+                let client = builder.build_async().await.unwrap();
+                let res = client
+                    .request(NyquestRequest::get(PATH).with_deadline(deadline()))
+                    .await;
+                assert!(matches!(res.unwrap_err(), Error::RequestTimeout));
+                let res = client
+                    .request(NyquestRequest::get(PATH).with_deadline(Instant::now()))
+                    .await;
+                assert!(matches!(res.unwrap_err(), Error::RequestTimeout));
+            });
+        }
+    }
 }
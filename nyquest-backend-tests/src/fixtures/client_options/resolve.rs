@@ -0,0 +1,178 @@
+#[cfg(all(test, feature = "curl"))] // Only libcurl supports resolve overrides
+mod tests {
+    use std::io;
+    use std::net::SocketAddr;
+
+    use http_body_util::Full;
+    use hyper::header::HOST;
+    use hyper::{body, Request, Response};
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    const HOST_NAME: &str = "nyquest.test";
+
+    async fn host_handler(req: Request<body::Incoming>) -> FixtureAssertionResult {
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let res = Response::new(Full::new(Bytes::from(host)));
+        (res.into(), Ok(()))
+    }
+
+    fn resolved_url_and_addr(path: &str) -> (String, SocketAddr) {
+        let base_url = TOKIO_RT.block_on(crate::hyper_base_url()).unwrap();
+        let port: u16 = base_url.rsplit(':').next().unwrap().parse().unwrap();
+        (
+            format!("http://{HOST_NAME}:{port}/{path}"),
+            SocketAddr::from(([127, 0, 0, 1], port)),
+        )
+    }
+
+    #[test]
+    fn test_resolve() {
+        const PATH: &str = "client_options/resolve";
+
+        let _handle = crate::add_hyper_fixture(PATH, host_handler);
+        let (url, addr) = resolved_url_and_addr(PATH);
+
+        let assertions = |host: String| {
+            assert!(host.starts_with(HOST_NAME), "unexpected host {host}");
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .resolve(HOST_NAME, addr);
+            let client = builder.build_blocking().unwrap();
+            let host = client
+                .request(NyquestRequest::get(url.clone()))
+                .unwrap()
+                .text()
+                .unwrap();
+            assertions(host);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let host = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .resolve(HOST_NAME, addr);
+                let client = builder.build_async().await.unwrap();
+                client
+                    .request(NyquestRequest::get(url.clone()))
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap()
+            });
+            assertions(host);
+        }
+    }
+
+    #[test]
+    fn test_resolve_with_host_header() {
+        const PATH: &str = "client_options/resolve_with_host_header";
+        const VIRTUAL_HOST: &str = "vhost.nyquest.test";
+
+        fn requests<S>(urls: &[String; 2]) -> [NyquestRequest<S>; 2] {
+            urls.clone()
+                .map(|url| NyquestRequest::get(url).host_header(VIRTUAL_HOST))
+        }
+
+        let _handle = crate::add_hyper_fixture(PATH, host_handler);
+        let (url, addr) = resolved_url_and_addr(PATH);
+        let urls = [url.clone(), url.replace(HOST_NAME, "127.0.0.1")];
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .resolve(HOST_NAME, addr);
+            let client = builder.build_blocking().unwrap();
+            for req in requests(&urls) {
+                let host = client.request(req).unwrap().text().unwrap();
+                assert_eq!(host, VIRTUAL_HOST);
+            }
+        }
+
+        #[cfg(feature = "async")]
+        TOKIO_RT.block_on(async {
+            let builder = crate::init_builder()
+                .await
+                .unwrap()
+                .resolve(HOST_NAME, addr);
+            let client = builder.build_async().await.unwrap();
+            for req in requests(&urls) {
+                let host = client.request(req).await.unwrap().text().await.unwrap();
+                assert_eq!(host, VIRTUAL_HOST);
+            }
+        });
+    }
+
+    #[test]
+    fn test_dns_resolver() {
+        const PATH: &str = "client_options/dns_resolver";
+
+        let _handle = crate::add_hyper_fixture(PATH, host_handler);
+        let (url, addr) = resolved_url_and_addr(PATH);
+        let failing_url = url.replace(HOST_NAME, "unknown.nyquest.test");
+        let resolver = move |host: &str, port: u16| match host {
+            HOST_NAME if port == addr.port() => Ok(vec![addr.ip()]),
+            _ => Err(io::Error::new(io::ErrorKind::NotFound, "unknown host")),
+        };
+
+        let assertions = |host: String, failed: nyquest::Result<()>| {
+            assert!(host.starts_with(HOST_NAME), "unexpected host {host}");
+            assert!(
+                matches!(failed, Err(nyquest::Error::Io(ref e)) if e.kind() == io::ErrorKind::NotFound),
+                "unexpected result {failed:?}"
+            );
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .dns_resolver(resolver);
+            let client = builder.build_blocking().unwrap();
+            let host = client
+                .request(NyquestRequest::get(url.clone()))
+                .unwrap()
+                .text()
+                .unwrap();
+            let failed = client
+                .request(NyquestRequest::get(failing_url.clone()))
+                .map(drop);
+            assertions(host, failed);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let (host, failed) = TOKIO_RT.block_on(async {
+                let builder = crate::init_builder().await.unwrap().dns_resolver(resolver);
+                let client = builder.build_async().await.unwrap();
+                let host = client
+                    .request(NyquestRequest::get(url.clone()))
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap();
+                let failed = client
+                    .request(NyquestRequest::get(failing_url.clone()))
+                    .await
+                    .map(drop);
+                (host, failed)
+            });
+            assertions(host, failed);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use http_body_util::Full;
+    use hyper::header::{HeaderName, HeaderValue};
+    use nyquest::Error;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    const HEADER_COUNT: usize = 50;
+    const BODY: &str = "body";
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |_| async {
+            let mut res = Response::new(Full::new(Bytes::from(BODY)));
+            for i in 0..HEADER_COUNT {
+                res.headers_mut().insert(
+                    HeaderName::try_from(format!("x-filler-{i}")).unwrap(),
+                    HeaderValue::from_static("0123456789"),
+                );
+            }
+            (res, Ok(()))
+        })
+    }
+
+    fn request_with_limits(
+        path: &'static str,
+        bytes: u64,
+        count: usize,
+    ) -> Vec<Result<String, Error>> {
+        let mut results = vec![];
+
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking()
+                .unwrap()
+                .max_response_headers(bytes, count);
+            let client = builder.build_blocking().unwrap();
+            results.push(
+                client
+                    .request(NyquestRequest::get(path))
+                    .and_then(|r| r.text()),
+            );
+        }
+
+        #[cfg(feature = "async")]
+        {
+            results.push(TOKIO_RT.block_on(async {
+                let builder = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .max_response_headers(bytes, count);
+                let client = builder.build_async().await.unwrap();
+                match client.request(NyquestRequest::get(path)).await {
+                    Ok(res) => res.text().await,
+                    Err(e) => Err(e),
+                }
+            }));
+        }
+
+        results
+    }
+
+    #[test]
+    fn test_response_headers_within_limits() {
+        const PATH: &str = "client_options/response_headers_within_limits";
+        let _handle = add_fixture(PATH);
+        for res in request_with_limits(PATH, 64 * 1024, 100) {
+            assert_eq!(res.unwrap(), BODY);
+        }
+    }
+
+    #[test]
+    fn test_response_headers_count_exceeded() {
+        const PATH: &str = "client_options/response_headers_count_exceeded";
+        let _handle = add_fixture(PATH);
+        for res in request_with_limits(PATH, 64 * 1024, HEADER_COUNT / 2) {
+            assert!(matches!(res.unwrap_err(), Error::ResponseHeadersTooLarge));
+        }
+    }
+
+    #[test]
+    fn test_response_headers_size_exceeded() {
+        const PATH: &str = "client_options/response_headers_size_exceeded";
+        let _handle = add_fixture(PATH);
+        for res in request_with_limits(PATH, 256, 100) {
+            assert!(matches!(res.unwrap_err(), Error::ResponseHeadersTooLarge));
+        }
+    }
+}
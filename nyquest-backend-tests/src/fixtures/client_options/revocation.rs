@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::client::{BuildClientError, RevocationPolicy};
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    const POLICIES: [RevocationPolicy; 3] = [
+        RevocationPolicy::Enforced,
+        RevocationPolicy::BestEffort,
+        RevocationPolicy::Disabled,
+    ];
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/revocation", |_| Reply::ok().body("checked"));
+        server
+    }
+
+    /// Returns whether building the client failed only because the backend lacks the policy.
+    fn is_unsupported<T>(res: &Result<T, BuildClientError>) -> bool {
+        match res {
+            Err(BuildClientError::Unsupported(_)) => true,
+            Err(e) => panic!("unexpected error: {e:?}"),
+            Ok(_) => false,
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_revocation_check_blocking() {
+        let server = start_server();
+        for policy in POLICIES {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .base_url(server.base_url())
+                .revocation_check(policy)
+                .build_blocking();
+            if is_unsupported(&client) {
+                continue;
+            }
+            let res = client
+                .unwrap()
+                .request(NyquestRequest::get("/revocation"))
+                .unwrap();
+            assert_eq!(res.text().unwrap(), "checked");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_revocation_check_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            for policy in POLICIES {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .base_url(server.base_url())
+                    .revocation_check(policy)
+                    .build_async()
+                    .await;
+                if is_unsupported(&client) {
+                    continue;
+                }
+                let res = client
+                    .unwrap()
+                    .request(NyquestRequest::get("/revocation"))
+                    .await
+                    .unwrap();
+                assert_eq!(res.text().await.unwrap(), "checked");
+            }
+        });
+    }
+}
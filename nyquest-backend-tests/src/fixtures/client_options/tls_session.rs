@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::client::BuildClientError;
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("*", "/tls", |req| Reply::ok().body(req.method.clone()));
+        server
+    }
+
+    /// Returns whether building the client failed only because the backend lacks the option.
+    fn is_unsupported<T>(res: &Result<T, BuildClientError>) -> bool {
+        match res {
+            Err(BuildClientError::Unsupported(_)) => true,
+            Err(e) => panic!("unexpected error: {e:?}"),
+            Ok(_) => false,
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_tls_session_options_blocking() {
+        let server = start_server();
+        for builder in [
+            crate::init_builder_blocking()
+                .unwrap()
+                .no_tls_session_resumption(),
+            crate::init_builder_blocking().unwrap().tls_early_data(),
+        ] {
+            let client = builder.base_url(server.base_url()).build_blocking();
+            if is_unsupported(&client) {
+                continue;
+            }
+            let client = client.unwrap();
+            for (req, method) in [
+                (NyquestRequest::get("/tls"), "GET"),
+                (NyquestRequest::post("/tls"), "POST"),
+            ] {
+                let res = client.request(req).unwrap();
+                assert_eq!(res.text().unwrap(), method);
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_tls_session_options_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            for builder in [
+                crate::init_builder()
+                    .await
+                    .unwrap()
+                    .no_tls_session_resumption(),
+                crate::init_builder().await.unwrap().tls_early_data(),
+            ] {
+                let client = builder.base_url(server.base_url()).build_async().await;
+                if is_unsupported(&client) {
+                    continue;
+                }
+                let client = client.unwrap();
+                for (req, method) in [
+                    (NyquestRequest::get("/tls"), "GET"),
+                    (NyquestRequest::post("/tls"), "POST"),
+                ] {
+                    let res = client.request(req).await.unwrap();
+                    assert_eq!(res.text().await.unwrap(), method);
+                }
+            }
+        });
+    }
+}
@@ -301,4 +301,81 @@ mod tests {
             assertions(&*received_facts[0].get().unwrap());
         }
     }
+
+    #[test]
+    fn test_body_multipart_file() {
+        const PATH: &str = "requests/body_multipart_file";
+        const CONTENT: &[u8] = b"file content\r\n--not a boundary\r\n";
+        let received_facts = Arc::new([const { OnceLock::new() }; 2]);
+        let _handle = crate::add_hyper_fixture(PATH, {
+            let received_facts = Arc::clone(&received_facts);
+            move |req: Request<body::Incoming>| {
+                let received_facts = Arc::clone(&received_facts);
+                async move {
+                    let boundary = req
+                        .headers()
+                        .get(CONTENT_TYPE)
+                        .and_then(|ct| ct.to_str().ok())
+                        .and_then(|ct| multer::parse_boundary(ct).ok());
+                    let is_blocking = req.is_blocking();
+                    let body_stream =
+                        BodyStream::new(req.into_body()).filter_map(|result| async move {
+                            result.map(|frame| frame.into_data().ok()).transpose()
+                        });
+                    let mut multipart = Multipart::new(body_stream, boundary.unwrap_or_default());
+                    let mut items = vec![];
+                    while let Some(field) = multipart.next_field().await.unwrap() {
+                        items.push((
+                            field.name().unwrap_or_default().to_owned(),
+                            field.file_name().unwrap_or_default().to_owned(),
+                            field.bytes().await.unwrap_or_default(),
+                        ));
+                    }
+                    received_facts[is_blocking as usize].set(items).ok();
+                    let res = Response::new(Full::new(Default::default()));
+                    (res, Ok(()))
+                }
+            }
+        });
+        let path = std::env::temp_dir().join(format!(
+            "nyquest-body-multipart-file-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, CONTENT).unwrap();
+        let assertions = |items: &Vec<(String, String, Bytes)>| {
+            assert_eq!(
+                items,
+                &[(
+                    "file".to_owned(),
+                    "upload.txt".to_owned(),
+                    Bytes::from_static(CONTENT)
+                )]
+            );
+        };
+        #[cfg(feature = "blocking")]
+        {
+            let builder = crate::init_builder_blocking().unwrap();
+            let req = NyquestRequest::post(PATH).with_body(NyquestBlockingBody::multipart([
+                Part::new_with_content_type("file", "text/plain", PartBody::file(&path))
+                    .with_filename("upload.txt"),
+            ]));
+            let client = builder.build_blocking().unwrap();
+            client.request(req).unwrap();
+            assertions(received_facts[1].get().unwrap());
+        }
+        #[cfg(feature = "async")]
+        {
+            let req = NyquestRequest::post(PATH).with_body(NyquestAsyncBody::multipart([
+                Part::new_with_content_type("file", "text/plain", PartBody::file(&path))
+                    .with_filename("upload.txt"),
+            ]));
+            TOKIO_RT.block_on(async move {
+                let builder = crate::init_builder().await.unwrap();
+                let client = builder.build_async().await.unwrap();
+                client.request(req).await.unwrap();
+            });
+            assertions(received_facts[0].get().unwrap());
+        }
+        std::fs::remove_file(&path).ok();
+    }
 }
@@ -1 +1,36 @@
+mod binary_formats;
+mod body_methods;
+mod body_replay;
+mod cancellation;
+mod cassette;
 mod chunked_encoding;
+mod circuit_breaker;
+mod content_negotiation;
+mod extensions;
+mod fallback_urls;
+mod fault_injection;
+mod foreign_executor;
+mod grpc_web;
+mod gzip_upload;
+mod har;
+mod json;
+mod long_poll;
+mod metrics;
+mod multipart_response;
+mod outbox;
+mod preconnect;
+mod presets;
+mod progress;
+mod protobuf;
+mod raw_tunnel;
+mod request_group;
+mod request_id;
+mod revalidate;
+mod shared_client;
+mod tee;
+mod test_server;
+mod tls_info;
+mod url;
+mod user_agent;
+mod wire_debug;
+mod xml;
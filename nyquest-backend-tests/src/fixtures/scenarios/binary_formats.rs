@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Body, Error, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    type Value = (String, Vec<u32>);
+
+    fn value() -> Value {
+        ("nyquest".to_owned(), vec![1, 2, 300, 70000])
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/echo", |req| {
+            Reply::ok()
+                .header(
+                    "content-type",
+                    req.header("content-type").unwrap_or_default(),
+                )
+                .body(req.body.clone())
+        });
+        server.route("GET", "/garbage", |_| Reply::ok().body(vec![0xc1]));
+        server
+    }
+
+    fn assert_content_types(server: &TestServer) {
+        let requests = server.requests();
+        let content_types = requests
+            .iter()
+            .map(|req| req.header("content-type").unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(content_types, ["application/cbor", "application/msgpack"]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_binary_formats_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let req = NyquestRequest::post("/echo").with_body(Body::cbor(&value()).unwrap());
+        let res = client.request(req).unwrap().cbor::<Value>();
+        assert_eq!(res.unwrap(), value());
+        let req = NyquestRequest::post("/echo").with_body(Body::msgpack(&value()).unwrap());
+        let res = client.request(req).unwrap().msgpack::<Value>();
+        assert_eq!(res.unwrap(), value());
+        assert_content_types(&server);
+
+        let res = client.request(NyquestRequest::get("/garbage")).unwrap();
+        assert!(matches!(res.cbor::<Value>(), Err(Error::Cbor(_))));
+        let res = client.request(NyquestRequest::get("/garbage")).unwrap();
+        assert!(matches!(res.msgpack::<Value>(), Err(Error::MessagePack(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_binary_formats_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let req = NyquestRequest::post("/echo").with_body(Body::cbor(&value()).unwrap());
+            let res = client.request(req).await.unwrap().cbor::<Value>().await;
+            assert_eq!(res.unwrap(), value());
+            let req = NyquestRequest::post("/echo").with_body(Body::msgpack(&value()).unwrap());
+            let res = client.request(req).await.unwrap().msgpack::<Value>().await;
+            assert_eq!(res.unwrap(), value());
+            assert_content_types(&server);
+
+            let res = client.request(NyquestRequest::get("/garbage")).await;
+            let res = res.unwrap().cbor::<Value>().await;
+            assert!(matches!(res, Err(Error::Cbor(_))));
+            let res = client.request(NyquestRequest::get("/garbage")).await;
+            let res = res.unwrap().msgpack::<Value>().await;
+            assert!(matches!(res, Err(Error::MessagePack(_))));
+        });
+    }
+}
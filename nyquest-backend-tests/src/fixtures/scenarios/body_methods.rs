@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Body, Method, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const BODY: &str = r#"{"query":{"match_all":{}}}"#;
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("*", "/search", |req| {
+            Reply::ok().body(format!("{} {}", req.method, req.text()))
+        });
+        server
+    }
+
+    fn requests<S>() -> Vec<(NyquestRequest<S>, String)> {
+        let mut requests = vec![(Method::delete(), "DELETE")];
+        // NSURLSession refuses to send bodies with GET
+        if cfg!(not(feature = "nsurlsession")) {
+            requests.push((Method::get(), "GET"));
+        }
+        requests
+            .into_iter()
+            .map(|(method, name)| {
+                let req = NyquestRequest::new(method, "/search")
+                    .with_body(Body::text(BODY, "application/json"));
+                (req, format!("{name} {BODY}"))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_body_methods_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        for (req, expected) in requests() {
+            let text = client.request(req).unwrap().text().unwrap();
+            assert_eq!(text, expected);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_body_methods_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            for (req, expected) in requests() {
+                let text = client.request(req).await.unwrap().text().await.unwrap();
+                assert_eq!(text, expected);
+            }
+        });
+    }
+}
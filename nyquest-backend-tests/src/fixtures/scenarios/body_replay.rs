@@ -0,0 +1,88 @@
+#[cfg(test)]
+#[cfg(not(feature = "curl"))] // libcurl backend does not support streaming request bodies
+mod tests {
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use nyquest::client::LongPollConfig;
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    const BODY: &str = "streamed body";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/poll", |req| Reply::ok().body(req.body.clone()));
+        server
+    }
+
+    fn config() -> LongPollConfig {
+        LongPollConfig {
+            jitter: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_body_replay_blocking() {
+        use nyquest::blocking::Body;
+
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let req = |limit| {
+            let body = Body::stream(Box::new(Cursor::new(BODY)), Some(BODY.len() as u64));
+            NyquestRequest::post("/poll").with_body(body.buffered_replayable(limit))
+        };
+
+        let mut poll = client.long_poll(req(1024), config()).unwrap();
+        assert_eq!(poll.next().unwrap().unwrap().text().unwrap(), BODY);
+        assert_eq!(poll.next().unwrap().unwrap().text().unwrap(), BODY);
+
+        let mut poll = client.long_poll(req(4), config()).unwrap();
+        assert_eq!(poll.next().unwrap().unwrap().text().unwrap(), BODY);
+        assert!(matches!(
+            poll.next().unwrap(),
+            Err(nyquest::Error::Unsupported(_))
+        ));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_body_replay_async() {
+        use nyquest::r#async::Body;
+
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let req = |limit| {
+                let stream = futures::io::Cursor::new(BODY);
+                let body = Body::stream(Box::new(stream), Some(BODY.len() as u64));
+                NyquestRequest::post("/poll").with_body(body.buffered_replayable(limit))
+            };
+
+            let mut poll = client.long_poll(req(1024), config()).unwrap();
+            assert_eq!(poll.next().await.unwrap().text().await.unwrap(), BODY);
+            assert_eq!(poll.next().await.unwrap().text().await.unwrap(), BODY);
+
+            let mut poll = client.long_poll(req(4), config()).unwrap();
+            assert_eq!(poll.next().await.unwrap().text().await.unwrap(), BODY);
+            assert!(matches!(
+                poll.next().await,
+                Err(nyquest::Error::Unsupported(_))
+            ));
+        });
+        assert_eq!(server.requests().len(), 3);
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use nyquest::r#async::Request;
+    use nyquest::AsyncClient;
+    use nyquest_test_server::{Fault, Reply, TestServer};
+
+    const STALL: Duration = Duration::from_secs(10);
+    const GRACE: Duration = Duration::from_millis(300);
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/slow-headers", |_| {
+            Reply::ok().body("late").delay(STALL)
+        });
+        server.route("GET", "/slow-body", |_| {
+            Reply::ok()
+                .body(vec![b'x'; 4096])
+                .fault(Fault::StallBody(STALL))
+        });
+        server.route("GET", "/held", |_| Reply::ok().body("held").delay(GRACE));
+        server.route("GET", "/fast", |_| Reply::ok().body("fast"));
+        server
+    }
+
+    async fn build_client(server: &TestServer) -> AsyncClient {
+        crate::init_builder()
+            .await
+            .unwrap()
+            .base_url(server.base_url())
+            .build_async()
+            .await
+            .unwrap()
+    }
+
+    async fn assert_still_usable(client: &AsyncClient, started: Instant) {
+        let text = client
+            .request(Request::get("/fast"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(text, "fast");
+        assert!(started.elapsed() < STALL / 2);
+    }
+
+    #[test]
+    fn test_drop_after_start() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = build_client(&server).await;
+            let started = Instant::now();
+
+            let mut fut = Box::pin(client.request(Request::get("/held")));
+            while server.requests().is_empty() {
+                assert!(futures::poll!(fut.as_mut()).is_pending());
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            drop(fut);
+            // The server notices the close once it has replied to the request
+            tokio::time::sleep(GRACE * 2).await;
+            let connection = server.requests()[0].connection;
+            assert!(server.closed_connections().contains(&connection));
+
+            assert_still_usable(&client, started).await;
+        });
+    }
+
+    #[test]
+    fn test_drop_while_awaiting_headers() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = build_client(&server).await;
+            let started = Instant::now();
+
+            let res = tokio::time::timeout(GRACE, client.request(Request::get("/slow-headers")));
+            assert!(res.await.is_err());
+            assert_eq!(server.requests().len(), 1);
+
+            assert_still_usable(&client, started).await;
+        });
+    }
+
+    #[test]
+    fn test_drop_while_reading_body() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = build_client(&server).await;
+            let started = Instant::now();
+
+            let res = client.request(Request::get("/slow-body")).await.unwrap();
+            assert_eq!(res.status(), 200);
+            // The response is dropped along with the future reading its body
+            assert!(tokio::time::timeout(GRACE, res.bytes()).await.is_err());
+
+            assert_still_usable(&client, started).await;
+        });
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use hyper::Response;
+    use nyquest::client::{CassetteConfig, CassetteMode};
+    use nyquest::{Error, Request as NyquestRequest};
+
+    use crate::*;
+
+    const SECRET: &str = "Bearer secret-token";
+
+    fn add_fixture(path: &'static str, hits: Arc<AtomicUsize>) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, move |_| {
+            let hit = hits.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                (
+                    Response::new(Full::new(Bytes::from(hit.to_string()))),
+                    Ok(()),
+                )
+            }
+        })
+    }
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nyquest-{}-{name}.json", std::process::id()))
+    }
+
+    fn request<S>(path: &'static str) -> NyquestRequest<S> {
+        NyquestRequest::get(path).with_header("authorization", SECRET)
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_cassette_blocking() {
+        const PATH: &str = "scenarios/cassette_blocking";
+        let hits = Arc::new(AtomicUsize::new(0));
+        let _handle = add_fixture(PATH, hits.clone());
+        let path = cassette_path("blocking");
+        let client = |mode| {
+            let config = CassetteConfig {
+                mode,
+                ..CassetteConfig::new(&path)
+            };
+            crate::init_builder_blocking()
+                .unwrap()
+                .cassette(config)
+                .build_blocking()
+                .unwrap()
+        };
+
+        let recording = client(CassetteMode::Record);
+        for expected in ["1", "2"] {
+            let text = recording.request(request(PATH)).unwrap().text().unwrap();
+            assert_eq!(text, expected);
+        }
+        let cassette = std::fs::read_to_string(&path).unwrap();
+        assert!(!cassette.contains(SECRET));
+
+        let replaying = client(CassetteMode::Auto);
+        for expected in ["1", "2", "2"] {
+            let text = replaying.request(request(PATH)).unwrap().text().unwrap();
+            assert_eq!(text, expected);
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        let err = replaying
+            .request(NyquestRequest::get("scenarios/cassette_unrecorded"))
+            .unwrap_err();
+        assert!(matches!(err, Error::UnrecordedRequest));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_cassette_async() {
+        const PATH: &str = "scenarios/cassette_async";
+        let hits = Arc::new(AtomicUsize::new(0));
+        let _handle = add_fixture(PATH, hits.clone());
+        let path = cassette_path("async");
+        TOKIO_RT.block_on(async {
+            let client = |mode| {
+                let config = CassetteConfig {
+                    mode,
+                    ..CassetteConfig::new(&path)
+                };
+                async {
+                    crate::init_builder()
+                        .await
+                        .unwrap()
+                        .cassette(config)
+                        .build_async()
+                        .await
+                        .unwrap()
+                }
+            };
+            let recording = client(CassetteMode::Record).await;
+            for expected in ["1", "2"] {
+                let res = recording.request(request(PATH)).await.unwrap();
+                assert_eq!(res.text().await.unwrap(), expected);
+            }
+
+            let replaying = client(CassetteMode::Replay).await;
+            for expected in ["1", "2", "2"] {
+                let res = replaying.request(request(PATH)).await.unwrap();
+                assert_eq!(res.text().await.unwrap(), expected);
+            }
+            let err = replaying
+                .request(NyquestRequest::get("scenarios/cassette_unrecorded"))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::UnrecordedRequest));
+        });
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        let _ = std::fs::remove_file(&path);
+    }
+}
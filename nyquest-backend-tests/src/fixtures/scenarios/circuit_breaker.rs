@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    use hyper::Response;
+    use nyquest::client::CircuitBreakerConfig;
+    use nyquest::{Error as NyquestError, Request as NyquestRequest};
+
+    use crate::*;
+
+    // Nothing listens on the port, so that connections are refused
+    const UNREACHABLE_URL: &str = "http://127.0.0.1:1/";
+    const COOLDOWN: Duration = Duration::from_millis(200);
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: NonZeroU32::new(2).unwrap(),
+            cooldown: COOLDOWN,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        const PATH: &str = "scenarios/circuit_breaker";
+        const BODY: &str = "still reachable";
+
+        let _handle = crate::add_hyper_fixture(PATH, |_req| async {
+            (Response::new(Full::new(Bytes::from(BODY))), Ok(()))
+        });
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .circuit_breaker(config())
+                .build_blocking()
+                .unwrap();
+            let send = |uri: &'static str| client.request(NyquestRequest::get(uri));
+            for _ in 0..2 {
+                assert!(matches!(
+                    send(UNREACHABLE_URL),
+                    Err(NyquestError::Io(_) | NyquestError::RequestTimeout)
+                ));
+            }
+            assert!(matches!(
+                send(UNREACHABLE_URL),
+                Err(NyquestError::CircuitOpen)
+            ));
+            // Other hosts are not affected
+            assert_eq!(send(PATH).unwrap().text().unwrap(), BODY);
+            std::thread::sleep(COOLDOWN);
+            // A failed probe trips the breaker again
+            assert!(matches!(
+                send(UNREACHABLE_URL),
+                Err(NyquestError::Io(_) | NyquestError::RequestTimeout)
+            ));
+            assert!(matches!(
+                send(UNREACHABLE_URL),
+                Err(NyquestError::CircuitOpen)
+            ));
+        }
+
+        #[cfg(feature = "async")]
+        {
+            TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .circuit_breaker(config())
+                    .build_async()
+                    .await
+                    .unwrap();
+                let send = |uri: &'static str| client.request(NyquestRequest::get(uri));
+                for _ in 0..2 {
+                    assert!(matches!(
+                        send(UNREACHABLE_URL).await,
+                        Err(NyquestError::Io(_) | NyquestError::RequestTimeout)
+                    ));
+                }
+                assert!(matches!(
+                    send(UNREACHABLE_URL).await,
+                    Err(NyquestError::CircuitOpen)
+                ));
+                let content = send(PATH).await.unwrap().text().await.unwrap();
+                assert_eq!(content, BODY);
+                tokio::time::sleep(COOLDOWN).await;
+                assert!(matches!(
+                    send(UNREACHABLE_URL).await,
+                    Err(NyquestError::Io(_) | NyquestError::RequestTimeout)
+                ));
+                assert!(matches!(
+                    send(UNREACHABLE_URL).await,
+                    Err(NyquestError::CircuitOpen)
+                ));
+            });
+        }
+    }
+}
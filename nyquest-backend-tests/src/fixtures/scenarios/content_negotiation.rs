@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{MediaType, MediaTypeQ, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const ACCEPT: &str = "application/vnd.api+json, application/json;q=0.8, */*;q=0.125";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/article", |_| {
+            Reply::ok()
+                .header("content-type", "Application/JSON; Charset=\"UTF-8\"")
+                .body("{}")
+        });
+        server
+    }
+
+    fn build_request<S>() -> NyquestRequest<S> {
+        NyquestRequest::get("/article").accept(&[
+            MediaTypeQ::new("application/vnd.api+json"),
+            MediaTypeQ::new("application/json").with_q(0.8),
+            MediaTypeQ::new("*/*").with_q(0.125),
+        ])
+    }
+
+    fn assert_negotiated(server: &TestServer, media_type: Option<MediaType>) {
+        assert_eq!(server.requests()[0].header("accept"), Some(ACCEPT));
+        let media_type = media_type.unwrap();
+        assert_eq!(media_type.essence(), "application/json");
+        assert_eq!(media_type.charset(), Some("UTF-8"));
+        assert_eq!(media_type.to_string(), "application/json;charset=UTF-8");
+        assert!(!MediaTypeQ::new("application/vnd.api+json").matches(&media_type));
+        assert!(MediaTypeQ::new("application/*").matches(&media_type));
+    }
+
+    #[test]
+    fn test_parse_media_type() {
+        let media_type = MediaType::parse("text/plain; format=flowed; title=\"a \\\"b\\\"; c\"");
+        let media_type = media_type.unwrap();
+        assert_eq!(
+            (media_type.main_type(), media_type.subtype()),
+            ("text", "plain")
+        );
+        assert_eq!(media_type.param("Format"), Some("flowed"));
+        assert_eq!(media_type.param("title"), Some("a \"b\"; c"));
+        assert_eq!(MediaType::parse("text"), None);
+        assert_eq!(MediaType::parse("text/plain; charset=\"utf-8"), None);
+        assert_eq!(
+            MediaTypeQ::new("text/html").with_q(0.0).to_string(),
+            "text/html;q=0"
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_content_negotiation_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let res = client.request(build_request()).unwrap();
+        assert_negotiated(&server, res.negotiated_content_type().unwrap());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_content_negotiation_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let res = client.request(build_request()).await.unwrap();
+            assert_negotiated(&server, res.negotiated_content_type().unwrap());
+        });
+    }
+}
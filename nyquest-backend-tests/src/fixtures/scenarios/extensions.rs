@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct CorrelationId(u64);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct RetryCount(u32);
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/extensions", |_| Reply::ok().body("ok"));
+        server
+    }
+
+    fn build_request<S>() -> NyquestRequest<S> {
+        let mut req = NyquestRequest::get("/extensions").with_extension(CorrelationId(42));
+        req.extensions_mut().insert(RetryCount(1));
+        *req.extensions_mut().get_mut::<RetryCount>().unwrap() = RetryCount(2);
+        assert_eq!(req.extensions().len(), 2);
+        req
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_extensions_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let mut res = client.request(build_request()).unwrap();
+        assert_eq!(res.extensions().get(), Some(&CorrelationId(42)));
+        assert_eq!(res.extensions_mut().remove(), Some(RetryCount(2)));
+        assert!(!res.extensions().contains::<RetryCount>());
+        assert_eq!(res.text().unwrap(), "ok");
+
+        let res = client.request(NyquestRequest::get("/extensions")).unwrap();
+        assert!(res.extensions().is_empty());
+        // Extensions are never sent to the server
+        let requests = server.requests();
+        assert_eq!(requests[0].headers.len(), requests[1].headers.len());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_extensions_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let mut res = client.request(build_request()).await.unwrap();
+            assert_eq!(res.extensions().get(), Some(&CorrelationId(42)));
+            assert_eq!(res.extensions_mut().remove(), Some(RetryCount(2)));
+            assert!(!res.extensions().contains::<RetryCount>());
+            assert_eq!(res.text().await.unwrap(), "ok");
+
+            let res = client.request(NyquestRequest::get("/extensions")).await;
+            assert!(res.unwrap().extensions().is_empty());
+            // Extensions are never sent to the server
+            let requests = server.requests();
+            assert_eq!(requests[0].headers.len(), requests[1].headers.len());
+        });
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/artifact", |_| Reply::ok().body("mirrored"));
+        server.route("GET", "/missing", |_| Reply::new(404));
+        server.route("GET", "/slow", |_| {
+            Reply::ok().body("slow").delay(Duration::from_millis(1500))
+        });
+        server
+    }
+
+    /// Returns the URL of a port nothing listens on, so that connecting to it fails.
+    fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    fn requests<S>() -> [NyquestRequest<S>; 3] {
+        let dead = unreachable_url();
+        [
+            NyquestRequest::get(format!("{dead}/artifact"))
+                .with_fallback_urls([format!("{dead}/artifact"), "/artifact".into()]),
+            NyquestRequest::get("/missing").with_fallback_urls(["/artifact"]),
+            NyquestRequest::get(format!("{dead}/artifact"))
+                .with_fallback_urls([format!("{}/missing", unreachable_url())]),
+        ]
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_fallback_urls_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let [fallback, not_found, unreachable] = requests();
+        let res = client.request(fallback).unwrap();
+        assert_eq!(res.text().unwrap(), "mirrored");
+        // Responses of any status are not retried
+        let res = client.request(not_found).unwrap();
+        assert_eq!(res.status(), 404);
+        assert!(matches!(
+            client.request(unreachable),
+            Err(nyquest::Error::Io(_))
+        ));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_fallback_urls_async() {
+        use std::time::Instant;
+
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let [fallback, not_found, unreachable] = requests();
+            let res = client.request(fallback).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), "mirrored");
+            let res = client.request(not_found).await.unwrap();
+            assert_eq!(res.status(), 404);
+            assert!(matches!(
+                client.request(unreachable).await,
+                Err(nyquest::Error::Io(_))
+            ));
+
+            // The second URL is raced once the first one has not responded for the stagger delay
+            let req = NyquestRequest::get("/slow")
+                .with_fallback_urls(["/artifact"])
+                .with_fallback_stagger(Duration::from_millis(100));
+            let start = Instant::now();
+            let res = client.request(req).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), "mirrored");
+            assert!(start.elapsed() < Duration::from_secs(1));
+
+            // Failed URLs are replaced right away
+            let dead = unreachable_url();
+            let req = NyquestRequest::get(format!("{dead}/artifact"))
+                .with_fallback_urls(["/artifact"])
+                .with_fallback_stagger(Duration::from_secs(10));
+            let start = Instant::now();
+            let res = client.request(req).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), "mirrored");
+            assert!(start.elapsed() < Duration::from_secs(5));
+        });
+    }
+}
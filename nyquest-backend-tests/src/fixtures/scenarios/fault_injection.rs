@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use std::time::{Duration, Instant};
+
+    use hyper::Response;
+    use nyquest::client::FaultInjectionConfig;
+    use nyquest::{Error, Request as NyquestRequest};
+
+    use crate::*;
+
+    const BODY: &str = "fault injection body";
+    const LATENCY: Duration = Duration::from_millis(200);
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |_| async {
+            (Response::new(Full::new(Bytes::from(BODY))), Ok(()))
+        })
+    }
+
+    fn is_injected(err: &Error) -> bool {
+        matches!(err, Error::Io(e) if e.kind() == ErrorKind::ConnectionReset)
+    }
+
+    /// A fault injection config and the assertion on the body text it results in.
+    type Case = (FaultInjectionConfig, fn(nyquest::Result<String>));
+
+    fn configs() -> [Case; 3] {
+        [
+            (
+                FaultInjectionConfig {
+                    latency_probability: 1.,
+                    latency: LATENCY..LATENCY,
+                    ..Default::default()
+                },
+                |res| assert_eq!(res.unwrap(), BODY),
+            ),
+            (
+                FaultInjectionConfig {
+                    error_probability: 1.,
+                    ..Default::default()
+                },
+                |res| assert!(is_injected(&res.unwrap_err())),
+            ),
+            (
+                FaultInjectionConfig {
+                    truncate_probability: 1.,
+                    ..Default::default()
+                },
+                |res| assert!(is_injected(&res.unwrap_err())),
+            ),
+        ]
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_fault_injection_blocking() {
+        const PATH: &str = "scenarios/fault_injection_blocking";
+        let _handle = add_fixture(PATH);
+        for (config, assertion) in configs() {
+            let injects_latency = config.latency_probability > 0.;
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .with_fault_injection(config)
+                .build_blocking()
+                .unwrap();
+            let start = Instant::now();
+            assertion(
+                client
+                    .request(NyquestRequest::get(PATH))
+                    .and_then(|res| res.text()),
+            );
+            if injects_latency {
+                assert!(start.elapsed() >= LATENCY);
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_fault_injection_async() {
+        const PATH: &str = "scenarios/fault_injection_async";
+        let _handle = add_fixture(PATH);
+        for (config, assertion) in configs() {
+            let injects_latency = config.latency_probability > 0.;
+            let start = Instant::now();
+            let res = TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .with_fault_injection(config)
+                    .build_async()
+                    .await
+                    .unwrap();
+                match client.request(NyquestRequest::get(PATH)).await {
+                    Ok(res) => res.text().await,
+                    Err(e) => Err(e),
+                }
+            });
+            assertion(res);
+            if injects_latency {
+                assert!(start.elapsed() >= LATENCY);
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use std::time::Duration;
+
+    use futures::stream::{self, StreamExt as _};
+    use http_body_util::BodyExt;
+    use hyper::Response;
+    use nyquest::r#async::Body as NyquestAsyncBody;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    #[test]
+    fn test_foreign_executor() {
+        const PATH: &str = "scenarios/foreign_executor";
+        const CHUNKS: [&str; 3] = ["driven ", "without ", "tokio"];
+
+        let _handle = crate::add_hyper_fixture(PATH, {
+            move |req| async move {
+                // Echo the request body, followed by the chunks
+                let echo = req.into_body().collect().await.unwrap().to_bytes();
+                let chunks = CHUNKS.map(Bytes::from);
+                // Delay the chunks so that the client has to wait for the backend to wake it
+                let stream = stream::iter([echo].into_iter().chain(chunks)).then(|chunk| async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok::<_, hyper::Error>(hyper::body::Frame::data(chunk))
+                });
+                let res = Response::new(BodyExt::boxed(http_body_util::StreamBody::new(stream)));
+                (res, Ok(()))
+            }
+        });
+
+        let builder = crate::init_builder_blocking().unwrap();
+
+        // The client is driven by an executor other than tokio, on a thread without any runtime
+        let content = futures::executor::block_on(async {
+            let client = builder.build_async().await.unwrap();
+            let res = client
+                .request(NyquestRequest::post(PATH).with_body(NyquestAsyncBody::plain_text("ping")))
+                .await
+                .unwrap();
+            res.text().await.unwrap()
+        });
+        assert_eq!(
+            content,
+            ["ping"].iter().chain(&CHUNKS).copied().collect::<String>()
+        );
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::grpc_web::{self, Frame, FrameDecoder, GrpcWebBody, Status};
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn trailers_frame(trailers: &str) -> Vec<u8> {
+        let mut frame = grpc_web::encode_frame(trailers.as_bytes());
+        frame[0] = 0x80;
+        frame
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server
+            .route("POST", "/echo.Echo/Twice", |req| {
+                let message = GrpcWebBody::decode(&req.body).unwrap().messages[0].clone();
+                let mut body = grpc_web::encode_frame(&message);
+                body.extend(grpc_web::encode_frame(&message));
+                body.extend(trailers_frame(
+                    "grpc-status:0\r\ngrpc-message:OK%20%E2%9C%93\r\n",
+                ));
+                Reply::ok()
+                    .header("content-type", grpc_web::CONTENT_TYPE)
+                    .header(
+                        "x-content-type",
+                        req.header("content-type").unwrap_or_default(),
+                    )
+                    .body(body)
+            })
+            .route("POST", "/echo.Echo/Missing", |_| {
+                Reply::ok()
+                    .header("content-type", grpc_web::CONTENT_TYPE)
+                    .header("grpc-status", "12")
+                    .header("grpc-message", "unimplemented")
+            });
+        server
+    }
+
+    fn check_body(body: GrpcWebBody) {
+        assert_eq!(body.messages, [&b"\x08\x01"[..], &b"\x08\x01"[..]]);
+        let status = body.status().unwrap();
+        assert!(status.is_ok());
+        assert_eq!(status.message, "OK ✓");
+    }
+
+    fn check_missing(body: GrpcWebBody) {
+        assert!(body.messages.is_empty());
+        let status = body.status().unwrap();
+        assert_eq!(
+            status,
+            Status {
+                code: 12,
+                message: "unimplemented".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_grpc_web_frame_decoder() {
+        let mut body = grpc_web::encode_frame(b"hello");
+        body.extend(trailers_frame("Grpc-Status: 0\r\n"));
+        let mut decoder = FrameDecoder::new();
+        let mut frames = vec![];
+        for chunk in body.chunks(3) {
+            decoder.push(chunk);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+        decoder.finish().unwrap();
+        assert_eq!(
+            frames,
+            [
+                Frame::Message(b"hello"[..].into()),
+                Frame::Trailers(vec![("grpc-status".into(), "0".into())]),
+            ]
+        );
+
+        assert!(GrpcWebBody::decode(&body[..body.len() - 1]).is_err());
+        let mut compressed = grpc_web::encode_frame(b"hello");
+        compressed[0] = 0x01;
+        assert!(GrpcWebBody::decode(&compressed).is_err());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_grpc_web_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let res = client
+            .request(grpc_web::request("/echo.Echo/Twice", b"\x08\x01"))
+            .unwrap();
+        let content_type = res.get_header("x-content-type").unwrap();
+        assert_eq!(content_type, [grpc_web::CONTENT_TYPE]);
+        check_body(res.grpc_web().unwrap());
+        let res = client
+            .request(grpc_web::request("/echo.Echo/Missing", b""))
+            .unwrap();
+        check_missing(res.grpc_web().unwrap());
+        assert_eq!(server.requests()[0].header("x-grpc-web"), Some("1"));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_grpc_web_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let res = client
+                .request(grpc_web::request("/echo.Echo/Twice", b"\x08\x01"))
+                .await
+                .unwrap();
+            let content_type = res.get_header("x-content-type").unwrap();
+            assert_eq!(content_type, [grpc_web::CONTENT_TYPE]);
+            check_body(res.grpc_web().await.unwrap());
+            let res = client
+                .request(grpc_web::request("/echo.Echo/Missing", b""))
+                .await
+                .unwrap();
+            check_missing(res.grpc_web().await.unwrap());
+            assert_eq!(server.requests()[0].header("x-grpc-web"), Some("1"));
+        });
+    }
+}
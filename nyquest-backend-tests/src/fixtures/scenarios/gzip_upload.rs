@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use nyquest::client::{GzipProbe, GzipUpload};
+    use nyquest::Request as NyquestRequest;
+    use nyquest::{Body, Bytes};
+    use nyquest_test_server::{CapturedRequest, Reply, TestServer};
+
+    const CONTENT: &str = "compressible content, compressible content, compressible content";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/accepting", |_| Reply::ok());
+        server.route("POST", "/rejecting", |req| {
+            match req.header("content-encoding") {
+                Some(_) => Reply::new(415),
+                None => Reply::ok(),
+            }
+        });
+        server.route("OPTIONS", "/accepting", |_| {
+            Reply::ok().header("accept-encoding", "identity, gzip;q=0.5")
+        });
+        server.route("OPTIONS", "/rejecting", |_| {
+            Reply::ok().header("accept-encoding", "gzip;q=0, identity")
+        });
+        server
+    }
+
+    fn request<S>(path: &'static str) -> NyquestRequest<S> {
+        NyquestRequest::post(path).with_body(Body::plain_text(CONTENT))
+    }
+
+    fn assert_compressed(req: &CapturedRequest) {
+        assert_eq!(req.header("content-encoding"), Some("gzip"));
+        assert_eq!(req.header("content-type"), Some("text/plain"));
+        let mut content = String::new();
+        GzDecoder::new(&*req.body)
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, CONTENT);
+    }
+
+    fn assert_identity(req: &CapturedRequest) {
+        assert_eq!(req.header("content-encoding"), None);
+        assert_eq!(Bytes::from(req.body.clone()), CONTENT.as_bytes());
+    }
+
+    fn host(server: &TestServer) -> String {
+        server.addr().to_string()
+    }
+
+    fn check_optimistic(server: &TestServer, upload: &GzipUpload) {
+        let requests = server.requests();
+        let [accepted, rejected, fallback, later] = &requests[..] else {
+            panic!("unexpected requests: {requests:?}");
+        };
+        assert_compressed(accepted);
+        assert_compressed(rejected);
+        assert_identity(fallback);
+        assert_identity(later);
+        // Both paths are on the same host, which is remembered as rejecting at last
+        assert_eq!(upload.is_supported(&host(server)), Some(false));
+    }
+
+    fn check_probing(server: &TestServer) {
+        let requests = server.requests();
+        let methods: Vec<_> = requests.iter().map(|req| &*req.method).collect();
+        assert_eq!(methods, ["OPTIONS", "POST", "POST"]);
+        assert_compressed(&requests[1]);
+        assert_compressed(&requests[2]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_gzip_upload_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let upload = GzipUpload::new().with_level(9);
+        for path in ["/accepting", "/rejecting", "/accepting"] {
+            let res = client.request_gzip(request(path), &upload).unwrap();
+            assert_eq!(res.status(), 200);
+        }
+        check_optimistic(&server, &upload);
+
+        server.clear_requests();
+        let upload = GzipUpload::new().with_probe(GzipProbe::Options);
+        for _ in 0..2 {
+            let res = client.request_gzip(request("/accepting"), &upload).unwrap();
+            assert_eq!(res.status(), 200);
+        }
+        check_probing(&server);
+
+        server.clear_requests();
+        let upload = GzipUpload::new().with_probe(GzipProbe::Options);
+        client.request_gzip(request("/rejecting"), &upload).unwrap();
+        assert_identity(&server.requests()[1]);
+        assert_eq!(upload.is_supported(&host(&server)), Some(false));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_gzip_upload_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let upload = GzipUpload::new().with_level(9);
+            for path in ["/accepting", "/rejecting", "/accepting"] {
+                let res = client.request_gzip(request(path), &upload).await.unwrap();
+                assert_eq!(res.status(), 200);
+            }
+            check_optimistic(&server, &upload);
+
+            server.clear_requests();
+            let upload = GzipUpload::new().with_probe(GzipProbe::Options);
+            for _ in 0..2 {
+                let req = request("/accepting");
+                let res = client.request_gzip(req, &upload).await.unwrap();
+                assert_eq!(res.status(), 200);
+            }
+            check_probing(&server);
+
+            server.clear_requests();
+            let upload = GzipUpload::new().with_probe(GzipProbe::Options);
+            upload.set_supported(host(&server), true);
+            let res = client
+                .request_gzip(request("/rejecting"), &upload)
+                .await
+                .unwrap();
+            assert_eq!(res.status(), 200);
+            // Configured hosts are not probed
+            assert_eq!(server.requests().len(), 2);
+        });
+    }
+}
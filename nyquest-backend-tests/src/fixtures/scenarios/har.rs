@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http_body_util::BodyExt;
+    use hyper::header::{HeaderValue, CONTENT_TYPE};
+    use hyper::Response;
+    use nyquest::client::RedactionPolicy;
+    use nyquest::{Body, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    use crate::*;
+
+    const REQUEST_BODY: &str = "request body";
+    const RESPONSE_BODY: &str = "response body";
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |req| async {
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body, REQUEST_BODY.as_bytes());
+            let mut res = Response::new(Full::new(Bytes::from(RESPONSE_BODY)));
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+            (res, Ok(()))
+        })
+    }
+
+    fn har_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nyquest-{}-{name}.har", std::process::id()))
+    }
+
+    fn assert_recorded(har: &str, path: &str, with_bodies: bool) {
+        assert!(har.starts_with(r#"{"log":{"version":"1.2","#));
+        assert!(har.ends_with("]}}\n"));
+        assert!(har.contains(r#""method":"POST""#));
+        assert!(har.contains(&format!(r#"{path}","#)));
+        assert!(har.contains(r#""status":200"#));
+        assert!(har.contains(r#"{"name":"content-type","value":"text/plain"}"#));
+        assert_eq!(
+            har.contains(&format!(r#""text":"{REQUEST_BODY}""#)),
+            with_bodies
+        );
+        assert_eq!(
+            har.contains(&format!(r#""text":"{RESPONSE_BODY}""#)),
+            with_bodies
+        );
+    }
+
+    #[test]
+    fn test_har() {
+        const PATH: &str = "scenarios/har";
+        let _handle = add_fixture(PATH);
+
+        for with_bodies in [false, true] {
+            #[cfg(feature = "blocking")]
+            {
+                let har_path = har_path(&format!("blocking-{with_bodies}"));
+                let mut builder = crate::init_builder_blocking()
+                    .unwrap()
+                    .record_har(&har_path);
+                if with_bodies {
+                    builder = builder.record_har_bodies(1024);
+                }
+                let client = builder.build_blocking().unwrap();
+                let req = NyquestRequest::post(PATH).with_body(Body::plain_text(REQUEST_BODY));
+                let text = client.request(req).unwrap().text().unwrap();
+                assert_eq!(text, RESPONSE_BODY);
+                let har = std::fs::read_to_string(&har_path).unwrap();
+                let _ = std::fs::remove_file(&har_path);
+                assert_recorded(&har, PATH, with_bodies);
+            }
+
+            #[cfg(feature = "async")]
+            {
+                let har_path = har_path(&format!("async-{with_bodies}"));
+                let text = TOKIO_RT.block_on(async {
+                    let mut builder = crate::init_builder().await.unwrap().record_har(&har_path);
+                    if with_bodies {
+                        builder = builder.record_har_bodies(1024);
+                    }
+                    let client = builder.build_async().await.unwrap();
+                    let req = NyquestRequest::post(PATH).with_body(Body::plain_text(REQUEST_BODY));
+                    client.request(req).await.unwrap().text().await.unwrap()
+                });
+                assert_eq!(text, RESPONSE_BODY);
+                let har = std::fs::read_to_string(&har_path).unwrap();
+                let _ = std::fs::remove_file(&har_path);
+                assert_recorded(&har, PATH, with_bodies);
+            }
+        }
+    }
+
+    fn redaction_policy() -> RedactionPolicy {
+        let mut policy = RedactionPolicy {
+            denied_query_params: vec!["token".into()],
+            ..Default::default()
+        };
+        policy.denied_headers.push("x-api-key".into());
+        policy
+    }
+
+    fn build_redacted_request<S>() -> NyquestRequest<S> {
+        NyquestRequest::get("/redacted?token=secret&page=1")
+            .with_header("authorization", "Bearer secret")
+            .with_header("x-api-key", "secret")
+            .with_header("x-request-id", "42")
+    }
+
+    fn assert_redacted(har: &str) {
+        assert!(!har.contains("secret"), "{har}");
+        assert!(har.contains("/redacted?token=[REDACTED]&page=1"));
+        assert!(har.contains(r#"{"name":"token","value":"[REDACTED]"}"#));
+        assert!(har.contains(r#"{"name":"page","value":"1"}"#));
+        for header in ["authorization", "x-api-key", "set-cookie"] {
+            assert!(har.contains(&format!(r#"{{"name":"{header}","value":"[REDACTED]"}}"#)));
+        }
+        assert!(har.contains(r#"{"name":"x-request-id","value":"42"}"#));
+    }
+
+    #[test]
+    fn test_har_redaction() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/redacted", |_| {
+            Reply::ok().header("set-cookie", "session=secret")
+        });
+
+        #[cfg(feature = "blocking")]
+        {
+            let har_path = har_path("blocking-redaction");
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .base_url(server.base_url())
+                .redaction_policy(redaction_policy())
+                .record_har(&har_path)
+                .build_blocking()
+                .unwrap();
+            client
+                .request(build_redacted_request())
+                .unwrap()
+                .bytes()
+                .unwrap();
+            let har = std::fs::read_to_string(&har_path).unwrap();
+            let _ = std::fs::remove_file(&har_path);
+            assert_redacted(&har);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let har_path = har_path("async-redaction");
+            TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .base_url(server.base_url())
+                    .redaction_policy(redaction_policy())
+                    .record_har(&har_path)
+                    .build_async()
+                    .await
+                    .unwrap();
+                let res = client.request(build_redacted_request()).await.unwrap();
+                res.bytes().await.unwrap();
+            });
+            let har = std::fs::read_to_string(&har_path).unwrap();
+            let _ = std::fs::remove_file(&har_path);
+            assert_redacted(&har);
+        }
+    }
+}
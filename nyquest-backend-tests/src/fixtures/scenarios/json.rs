@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Error, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const LARGE_LEN: usize = 20_000;
+
+    fn items(len: usize) -> Vec<String> {
+        (0..len).map(|i| format!("item \"{i}\"")).collect()
+    }
+
+    fn to_json(items: &[String]) -> String {
+        let items = items
+            .iter()
+            .map(|item| format!("\"{}\"", item.replace('"', "\\\"")))
+            .collect::<Vec<_>>();
+        format!("[{}]", items.join(","))
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/small", |_| Reply::ok().body(to_json(&items(2))));
+        server.route("GET", "/large", |_| {
+            Reply::ok().body(to_json(&items(LARGE_LEN)))
+        });
+        server.route("GET", "/invalid", |_| {
+            let json = to_json(&items(LARGE_LEN));
+            Reply::ok().body(json[..json.len() - 1].to_owned())
+        });
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_json_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let json = |path: &'static str| {
+            client
+                .request(NyquestRequest::get(path))
+                .unwrap()
+                .json::<Vec<String>>()
+        };
+        assert_eq!(json("/small").unwrap(), items(2));
+        assert_eq!(json("/large").unwrap(), items(LARGE_LEN));
+        assert!(matches!(json("/invalid"), Err(Error::Json(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_json_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let json = |path: &'static str| {
+                let req = client.request(NyquestRequest::get(path));
+                async { req.await.unwrap().json::<Vec<String>>().await }
+            };
+            assert_eq!(json("/small").await.unwrap(), items(2));
+            assert_eq!(json("/large").await.unwrap(), items(LARGE_LEN));
+            assert!(matches!(json("/invalid").await, Err(Error::Json(_))));
+        });
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    use nyquest::client::LongPollConfig;
+    use nyquest::{Error, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        let polls = AtomicUsize::new(0);
+        server.route("GET", "/poll", move |_| {
+            match polls.fetch_add(1, Ordering::SeqCst) {
+                0 => Reply::ok()
+                    .header("etag", "\"v1\"")
+                    .header("last-modified", LAST_MODIFIED)
+                    .body("v1"),
+                1 => Reply::new(304),
+                _ => Reply::ok().header("etag", "\"v2\"").body("v2"),
+            }
+        });
+        server
+    }
+
+    fn config() -> LongPollConfig {
+        LongPollConfig {
+            jitter: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    fn assert_conditional_requests(server: &TestServer) {
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].header("if-none-match"), None);
+        for req in &requests[1..] {
+            assert_eq!(req.header("if-none-match"), Some("\"v1\""));
+            assert_eq!(req.header("if-modified-since"), Some(LAST_MODIFIED));
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_long_poll_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let mut poll = client
+            .long_poll(NyquestRequest::get("/poll"), config())
+            .unwrap();
+        assert_eq!(poll.next().unwrap().unwrap().text().unwrap(), "v1");
+        // The 304 response is skipped
+        assert_eq!(poll.next().unwrap().unwrap().text().unwrap(), "v2");
+        assert_conditional_requests(&server);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_long_poll_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let mut poll = client
+                .long_poll(NyquestRequest::get("/poll"), config())
+                .unwrap();
+            assert_eq!(poll.next().await.unwrap().text().await.unwrap(), "v1");
+            // The 304 response is skipped
+            assert_eq!(poll.next().await.unwrap().text().await.unwrap(), "v2");
+            assert_conditional_requests(&server);
+        });
+    }
+
+    fn start_hanging_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/hang", |_| {
+            Reply::ok().delay(Duration::from_secs(5)).body("late")
+        });
+        server
+    }
+
+    fn deadline_config() -> LongPollConfig {
+        LongPollConfig {
+            jitter: Duration::ZERO,
+            poll_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_long_poll_deadline_blocking() {
+        let server = start_hanging_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let req =
+            NyquestRequest::get("/hang").with_deadline(Instant::now() + Duration::from_millis(700));
+        let mut poll = client.long_poll(req, deadline_config()).unwrap();
+        // Polls time out silently until the deadline expires
+        assert!(matches!(poll.next().unwrap(), Err(Error::RequestTimeout)));
+        assert!(server.requests().len() > 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_long_poll_deadline_async() {
+        let server = start_hanging_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let req = NyquestRequest::get("/hang")
+                .with_deadline(Instant::now() + Duration::from_millis(700));
+            let mut poll = client.long_poll(req, deadline_config()).unwrap();
+            // Polls time out silently until the deadline expires
+            assert!(matches!(poll.next().await, Err(Error::RequestTimeout)));
+            assert!(server.requests().len() > 1);
+        });
+    }
+}
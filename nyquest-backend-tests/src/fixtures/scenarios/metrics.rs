@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use nyquest::client::{
+        MetricsSink, PrometheusMetrics, RequestDurations, RequestLabels, TransferSizes,
+    };
+    use nyquest::{Body, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+    use prometheus_client::encoding::text::encode;
+    use prometheus_client::registry::Registry;
+
+    type Recorded = (RequestLabels, TransferSizes, Option<u16>);
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<Recorded>>);
+
+    impl MetricsSink for RecordingSink {
+        fn on_request_complete(
+            &self,
+            labels: &RequestLabels,
+            durations: &RequestDurations,
+            sizes: &TransferSizes,
+            status: Option<u16>,
+        ) {
+            assert!(durations.headers <= durations.total);
+            let recorded = (labels.clone(), *sizes, status);
+            self.0.lock().unwrap().push(recorded);
+        }
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/metrics", |_| Reply::ok().body("12345"));
+        server
+    }
+
+    fn build_request<S>() -> NyquestRequest<S> {
+        NyquestRequest::post("/metrics").with_body(Body::plain_text("abc"))
+    }
+
+    // Nothing listens on port 1, so that connecting fails right away
+    const UNREACHABLE: &str = "http://127.0.0.1:1/";
+
+    fn assert_recorded(sink: &RecordingSink) {
+        let labels = RequestLabels {
+            method: "POST".into(),
+            host: "127.0.0.1".into(),
+        };
+        let recorded = sink.0.lock().unwrap();
+        assert_eq!(recorded.len(), 2);
+        let sizes = TransferSizes {
+            request_body: Some(3),
+            response_body: 5,
+        };
+        assert_eq!(recorded[0], (labels, sizes, Some(200)));
+        assert_eq!(recorded[1].0.method, "GET");
+        assert_eq!(recorded[1].2, None);
+    }
+
+    fn assert_exported(registry: &Registry) {
+        let mut text = String::new();
+        encode(&mut text, registry).unwrap();
+        let labels = r#"method="POST",host="127.0.0.1",status="200""#;
+        assert!(text.contains(&format!("http_client_requests_total{{{labels}}} 1")));
+        assert!(text.contains(&format!(
+            "http_client_response_body_size_bytes_sum{{{labels}}} 5"
+        )));
+        assert!(text.contains(&format!(
+            "http_client_request_duration_seconds_count{{{labels}}} 1"
+        )));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_metrics_blocking() {
+        let server = start_server();
+        let sink = Arc::new(RecordingSink::default());
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .metrics_sink(sink.clone())
+            .build_blocking()
+            .unwrap();
+
+        assert_eq!(
+            client.request(build_request()).unwrap().text().unwrap(),
+            "12345"
+        );
+        client
+            .request(NyquestRequest::get(UNREACHABLE))
+            .unwrap_err();
+        assert_recorded(&sink);
+
+        let mut registry = Registry::default();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .metrics_sink(PrometheusMetrics::register(&mut registry))
+            .build_blocking()
+            .unwrap();
+        client.request(build_request()).unwrap().bytes().unwrap();
+        assert_exported(&registry);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_metrics_async() {
+        let server = start_server();
+        let sink = Arc::new(RecordingSink::default());
+        let mut registry = Registry::default();
+        let metrics = PrometheusMetrics::register(&mut registry);
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .metrics_sink(sink.clone())
+                .build_async()
+                .await
+                .unwrap();
+
+            let res = client.request(build_request()).await.unwrap();
+            assert_eq!(res.text().await.unwrap(), "12345");
+            client
+                .request(NyquestRequest::get(UNREACHABLE))
+                .await
+                .unwrap_err();
+            assert_recorded(&sink);
+
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .metrics_sink(metrics)
+                .build_async()
+                .await
+                .unwrap();
+            let res = client.request(build_request()).await.unwrap();
+            res.bytes().await.unwrap();
+        });
+        assert_exported(&registry);
+    }
+}
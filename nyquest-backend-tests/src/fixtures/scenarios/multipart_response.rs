@@ -0,0 +1,126 @@
+#[cfg(test)]
+#[cfg(feature = "multipart")]
+mod tests {
+    use std::io::{self, Read};
+
+    use nyquest::multipart_reader::MultipartReader;
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    const BOUNDARY: &str = "batch_boundary";
+    const BODY: &str = "preamble\r\n\
+        --batch_boundary\r\n\
+        Content-Type: application/json\r\n\
+        Content-ID: <1>\r\n\
+        \r\n\
+        {\"id\":1}\r\n\
+        --batch_boundary  \r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        skipped\r\n\
+        --batch_boundary\r\n\
+        \r\n\
+        line\r\n--batch\r\n\
+        --batch_boundary--\r\n\
+        epilogue";
+
+    /// A reader returning a single byte at a time, to split boundaries across reads.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((&first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    fn check_parts<R: Read>(mut parts: MultipartReader<R>) {
+        let mut part = parts.next_part().unwrap().unwrap();
+        assert_eq!(part.header("Content-ID"), Some("<1>"));
+        assert_eq!(part.content_type().unwrap().essence(), "application/json");
+        let mut body = String::new();
+        part.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "{\"id\":1}");
+
+        // Left unread
+        let part = parts.next_part().unwrap().unwrap();
+        assert_eq!(part.header("content-type"), Some("text/plain"));
+
+        let mut part = parts.next_part().unwrap().unwrap();
+        assert!(part.headers().is_empty());
+        let mut body = String::new();
+        part.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "line\r\n--batch");
+
+        assert!(parts.next_part().unwrap().is_none());
+        assert!(parts.next_part().unwrap().is_none());
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/batch", |_| {
+            Reply::ok()
+                .header(
+                    "content-type",
+                    format!("multipart/mixed; boundary=\"{BOUNDARY}\""),
+                )
+                .body(BODY)
+        });
+        server.route("GET", "/plain", |_| Reply::ok().body("plain"));
+        server
+    }
+
+    #[test]
+    fn test_multipart_reader() {
+        check_parts(MultipartReader::new(BODY.as_bytes(), BOUNDARY));
+        check_parts(MultipartReader::new(ByteReader(BODY.as_bytes()), BOUNDARY));
+
+        let mut parts = MultipartReader::new(&b"--batch_boundary\r\n\r\ntruncated"[..], BOUNDARY);
+        let mut part = parts.next_part().unwrap().unwrap();
+        let err = part.read_to_end(&mut vec![]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_multipart_response_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        #[cfg(not(feature = "curl"))] // libcurl backend does not support streaming response bodies
+        {
+            let res = client.request(NyquestRequest::get("/batch")).unwrap();
+            check_parts(res.multipart().unwrap());
+        }
+        let res = client.request(NyquestRequest::get("/plain")).unwrap();
+        assert!(matches!(res.multipart(), Err(nyquest::Error::Io(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_multipart_response_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let res = client.request(NyquestRequest::get("/batch")).await.unwrap();
+            check_parts(res.multipart().await.unwrap());
+            let res = client.request(NyquestRequest::get("/plain")).await.unwrap();
+            assert!(matches!(res.multipart().await, Err(nyquest::Error::Io(_))));
+        });
+    }
+}
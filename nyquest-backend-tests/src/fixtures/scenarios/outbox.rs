@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use nyquest::client::{ConflictHook, ConflictResolution, OutboxConfig};
+    use nyquest::{Body, Error, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const NOTES: &str = "/scenarios/outbox/notes";
+    const NOTE: &str = "/scenarios/outbox/notes/1";
+
+    /// Answers new notes with the given status, and updates of the note with a conflict.
+    fn start_server(status: Arc<AtomicU16>) -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", NOTES, move |_| {
+            Reply::new(status.load(Ordering::Relaxed))
+        });
+        server.route("PATCH", NOTE, |_| Reply::new(409).body("newer"));
+        server
+    }
+
+    fn outbox_config(name: &str, conflicts: Arc<AtomicUsize>) -> OutboxConfig {
+        let path =
+            std::env::temp_dir().join(format!("nyquest-{}-outbox-{name}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        OutboxConfig {
+            max_body_size: 16,
+            // Keeps the first conflict to merge it, and discards the update afterwards
+            on_conflict: Some(ConflictHook(Arc::new(move |conflict| {
+                assert_eq!(conflict.status, 409);
+                assert_eq!(&conflict.body[..], b"newer");
+                match conflicts.fetch_add(1, Ordering::Relaxed) {
+                    0 => ConflictResolution::Keep,
+                    _ => ConflictResolution::Discard,
+                }
+            }))),
+            ..OutboxConfig::new(path)
+        }
+    }
+
+    fn note<S>(text: &'static str) -> NyquestRequest<S> {
+        NyquestRequest::post(NOTES)
+            .with_header("authorization", "Bearer secret")
+            .with_body(Body::plain_text(text))
+    }
+
+    fn check_redacted(config: &OutboxConfig) {
+        let data = std::fs::read_to_string(&config.path).unwrap();
+        assert!(data.contains("first"));
+        assert!(!data.contains("secret"));
+    }
+
+    fn check_delivered(server: &TestServer) {
+        let bodies: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| (r.text(), r.header("content-type").map(str::to_owned)))
+            .collect();
+        let plain = Some("text/plain".to_owned());
+        assert_eq!(
+            bodies,
+            [
+                ("first".to_owned(), plain.clone()),
+                ("first".to_owned(), plain.clone()),
+                ("first".to_owned(), plain.clone()),
+                ("second".to_owned(), plain),
+            ]
+        );
+    }
+
+    fn remove_file(config: &OutboxConfig) {
+        let _ = std::fs::remove_file(&config.path);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_outbox_blocking() {
+        let status = Arc::new(AtomicU16::new(503));
+        let server = start_server(status.clone());
+        let conflicts = Arc::new(AtomicUsize::new(0));
+        let config = outbox_config("blocking", conflicts.clone());
+        let client = || {
+            crate::init_builder_blocking()
+                .unwrap()
+                .base_url(server.base_url())
+                .outbox(config.clone())
+                .build_blocking()
+                .unwrap()
+        };
+
+        let offline = client();
+        assert_eq!(offline.enqueue(note("first")).unwrap(), 1);
+        assert_eq!(offline.enqueue(note("second")).unwrap(), 2);
+        let err = offline.enqueue(note("longer than the limit")).unwrap_err();
+        assert!(matches!(err, Error::RequestBodyTooLarge));
+        drop(offline);
+        check_redacted(&config);
+
+        // The requests are loaded from the file by a new client
+        status.store(201, Ordering::Relaxed);
+        let online = client();
+        assert_eq!(online.flush_outbox().unwrap(), 0);
+        check_delivered(&server);
+
+        let update = NyquestRequest::patch(NOTE).with_body(Body::plain_text("update"));
+        assert_eq!(online.enqueue(update).unwrap(), 1);
+        assert_eq!(online.flush_outbox().unwrap(), 0);
+        assert_eq!(conflicts.load(Ordering::Relaxed), 2);
+        remove_file(&config);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_outbox_async() {
+        let status = Arc::new(AtomicU16::new(503));
+        let server = start_server(status.clone());
+        let conflicts = Arc::new(AtomicUsize::new(0));
+        let config = outbox_config("async", conflicts.clone());
+        crate::TOKIO_RT.block_on(async {
+            let client = || async {
+                crate::init_builder()
+                    .await
+                    .unwrap()
+                    .base_url(server.base_url())
+                    .outbox(config.clone())
+                    .build_async()
+                    .await
+                    .unwrap()
+            };
+
+            let offline = client().await;
+            assert_eq!(offline.enqueue(note("first")).await.unwrap(), 1);
+            assert_eq!(offline.enqueue(note("second")).await.unwrap(), 2);
+            let err = offline
+                .enqueue(note("longer than the limit"))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::RequestBodyTooLarge));
+            drop(offline);
+            check_redacted(&config);
+
+            // The requests are loaded from the file by a new client
+            status.store(201, Ordering::Relaxed);
+            let online = client().await;
+            assert_eq!(online.flush_outbox().await.unwrap(), 0);
+            check_delivered(&server);
+
+            let update = NyquestRequest::patch(NOTE).with_body(Body::plain_text("update"));
+            assert_eq!(online.enqueue(update).await.unwrap(), 1);
+            assert_eq!(online.flush_outbox().await.unwrap(), 0);
+            assert_eq!(conflicts.load(Ordering::Relaxed), 2);
+        });
+        remove_file(&config);
+    }
+}
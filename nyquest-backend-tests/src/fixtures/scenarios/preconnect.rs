@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use hyper::Response;
+    use nyquest::{Method, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    use crate::*;
+
+    const BODY: &str = "preconnected";
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |req| async move {
+            // Announces the length of the body of a GET response for HEAD requests as well
+            let body = if req.method() == hyper::Method::HEAD {
+                Bytes::new()
+            } else {
+                Bytes::from(BODY)
+            };
+            let res = Response::builder()
+                .header("content-length", BODY.len())
+                .body(Full::new(body))
+                .unwrap();
+            (res, Ok(()))
+        })
+    }
+
+    #[test]
+    fn test_preconnect() {
+        const PATH: &str = "scenarios/preconnect";
+        let _handle = add_fixture(PATH);
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .build_blocking()
+                .unwrap();
+            client.preconnect(PATH).unwrap();
+            client.preconnect("data:,").unwrap();
+            let res = client.request(NyquestRequest::get(PATH)).unwrap();
+            assert_eq!(res.text().unwrap(), BODY);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let content = TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .build_async()
+                    .await
+                    .unwrap();
+                client.preconnect(PATH).await.unwrap();
+                client.preconnect("data:,").await.unwrap();
+                client
+                    .request(NyquestRequest::get(PATH))
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap()
+            });
+            assert_eq!(content, BODY);
+        }
+    }
+
+    fn assert_connection_reused(server: &TestServer) {
+        let requests = server.requests();
+        let methods: Vec<_> = requests.iter().map(|req| &*req.method).collect();
+        assert_eq!(methods, ["HEAD", "GET"]);
+        assert_eq!(requests[0].connection, requests[1].connection);
+    }
+
+    #[test]
+    fn test_preconnect_reuses_connection() {
+        let server = TestServer::start().unwrap();
+        server.route("*", "/warm", |_req| Reply::ok().body(BODY));
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .base_url(server.base_url())
+                .build_blocking()
+                .unwrap();
+            client.preconnect("/warm").unwrap();
+            let res = client.request(NyquestRequest::get("/warm")).unwrap();
+            assert_eq!(res.text().unwrap(), BODY);
+            assert_connection_reused(&server);
+            server.clear_requests();
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let content = TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .base_url(server.base_url())
+                    .build_async()
+                    .await
+                    .unwrap();
+                client.preconnect("/warm").await.unwrap();
+                let res = client.request(NyquestRequest::get("/warm")).await.unwrap();
+                res.text().await.unwrap()
+            });
+            assert_eq!(content, BODY);
+            assert_connection_reused(&server);
+        }
+    }
+
+    #[test]
+    fn test_head_request() {
+        const PATH: &str = "scenarios/preconnect/head";
+        let _handle = add_fixture(PATH);
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .build_blocking()
+                .unwrap();
+            let res = client
+                .request(NyquestRequest::new(Method::custom("HEAD"), PATH))
+                .unwrap();
+            assert_eq!(res.status(), 200);
+            assert!(res.bytes().unwrap().is_empty());
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let (status, bytes) = TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .build_async()
+                    .await
+                    .unwrap();
+                let res = client
+                    .request(NyquestRequest::new(Method::custom("HEAD"), PATH))
+                    .await
+                    .unwrap();
+                (res.status(), res.bytes().await.unwrap())
+            });
+            assert_eq!(status, 200);
+            assert!(bytes.is_empty());
+        }
+    }
+}
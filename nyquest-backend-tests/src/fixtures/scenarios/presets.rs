@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{ClientBuilder, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/accept", |req| {
+            Reply::ok().body(req.header("accept").unwrap_or_default().to_owned())
+        });
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_presets_blocking() {
+        let server = start_server();
+        // Registers the backend
+        crate::init_builder_blocking().unwrap();
+
+        let client = ClientBuilder::for_api()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let text = client
+            .request(NyquestRequest::get("/accept"))
+            .unwrap()
+            .text();
+        assert_eq!(text.unwrap(), "application/json");
+        let req = NyquestRequest::get("/accept").with_header("accept", "text/plain");
+        let text = client.request(req).unwrap().text();
+        assert_eq!(text.unwrap(), "text/plain");
+
+        let client = ClientBuilder::for_downloads()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let text = client
+            .request(NyquestRequest::get("/accept"))
+            .unwrap()
+            .text();
+        assert_ne!(text.unwrap(), "application/json");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_presets_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            // Registers the backend
+            crate::init_builder().await.unwrap();
+
+            let client = ClientBuilder::for_api()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let res = client.request(NyquestRequest::get("/accept")).await;
+            assert_eq!(res.unwrap().text().await.unwrap(), "application/json");
+            let req = NyquestRequest::get("/accept").with_header("accept", "text/plain");
+            let res = client.request(req).await;
+            assert_eq!(res.unwrap().text().await.unwrap(), "text/plain");
+
+            let client = ClientBuilder::for_downloads()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let res = client.request(NyquestRequest::get("/accept")).await;
+            assert_ne!(res.unwrap().text().await.unwrap(), "application/json");
+        });
+    }
+}
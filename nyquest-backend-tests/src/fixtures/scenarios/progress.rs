@@ -0,0 +1,83 @@
+#[cfg(all(test, feature = "curl"))] // Only libcurl reports progress
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use http_body_util::{BodyExt, Full};
+    use hyper::Response;
+    use nyquest::{Body, Progress, Request as NyquestRequest};
+
+    use crate::*;
+
+    const UPLOAD_SIZE: usize = 500;
+    const DOWNLOAD_SIZE: usize = 1000;
+
+    type Reports = Arc<Mutex<Vec<Progress>>>;
+
+    fn request<S>(path: &'static str) -> (NyquestRequest<S>, Reports) {
+        let reports = Reports::default();
+        let req = NyquestRequest::post(path)
+            .with_body(Body::binary_bytes(vec![b'y'; UPLOAD_SIZE]))
+            // Only the initial and final reports are expected
+            .with_progress(Duration::from_secs(3600), {
+                let reports = reports.clone();
+                move |progress| reports.lock().unwrap().push(progress)
+            });
+        (req, reports)
+    }
+
+    #[test]
+    fn test_progress() {
+        const PATH: &str = "scenarios/progress";
+
+        let _handle = crate::add_hyper_fixture(PATH, |req| async move {
+            // Drain the body so that the upload completes
+            req.into_body().collect().await.unwrap();
+            let res = Response::new(Full::new(Bytes::from(vec![b'x'; DOWNLOAD_SIZE])));
+            (res, Ok(()))
+        });
+
+        let assertions = |reports: Reports| {
+            let reports = reports.lock().unwrap();
+            assert!(reports.len() <= 3, "too many reports: {reports:?}");
+            assert_eq!(
+                reports.last(),
+                Some(&Progress {
+                    uploaded: UPLOAD_SIZE as u64,
+                    upload_total: Some(UPLOAD_SIZE as u64),
+                    downloaded: DOWNLOAD_SIZE as u64,
+                    download_total: Some(DOWNLOAD_SIZE as u64),
+                })
+            );
+        };
+
+        #[cfg(feature = "blocking")]
+        {
+            let client = crate::init_builder_blocking()
+                .unwrap()
+                .build_blocking()
+                .unwrap();
+            let (req, reports) = request(PATH);
+            let res = client.request(req).unwrap();
+            assert_eq!(res.bytes().unwrap().len(), DOWNLOAD_SIZE);
+            assertions(reports);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let reports = TOKIO_RT.block_on(async {
+                let client = crate::init_builder()
+                    .await
+                    .unwrap()
+                    .build_async()
+                    .await
+                    .unwrap();
+                let (req, reports) = request(PATH);
+                let res = client.request(req).await.unwrap();
+                assert_eq!(res.bytes().await.unwrap().len(), DOWNLOAD_SIZE);
+                reports
+            });
+            assertions(reports);
+        }
+    }
+}
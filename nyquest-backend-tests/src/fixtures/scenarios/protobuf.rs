@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Body, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Event {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(uint32, tag = "2")]
+        count: u32,
+    }
+
+    fn events() -> Vec<Event> {
+        (0..3)
+            .map(|i| Event {
+                name: format!("event {i}"),
+                count: i * 200,
+            })
+            .collect()
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/echo", |req| Reply::ok().body(req.body.clone()));
+        server.route("GET", "/events", |_| {
+            let mut body = vec![];
+            for event in events() {
+                event.encode_length_delimited(&mut body).unwrap();
+            }
+            Reply::ok().body(body)
+        });
+        server.route("GET", "/truncated", |_| {
+            let mut body = events()[0].encode_length_delimited_to_vec();
+            body.pop();
+            Reply::ok().body(body)
+        });
+        server
+    }
+
+    fn assert_content_type(server: &TestServer) {
+        let requests = server.requests();
+        let content_type = requests[0].header("content-type");
+        assert_eq!(content_type, Some("application/x-protobuf"));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_protobuf_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let req = NyquestRequest::post("/echo").with_body(Body::protobuf(&events()[1]));
+        let res = client.request(req).unwrap().protobuf::<Event>();
+        assert_eq!(res.unwrap(), events()[1]);
+        assert_content_type(&server);
+
+        // libcurl backend does not support streaming blocking response bodies
+        #[cfg(not(feature = "curl"))]
+        {
+            let res = client.request(NyquestRequest::get("/events")).unwrap();
+            let received = res
+                .protobuf_stream::<Event>()
+                .collect::<Result<Vec<_>, _>>();
+            assert_eq!(received.unwrap(), events());
+
+            let res = client.request(NyquestRequest::get("/truncated")).unwrap();
+            let received = res.protobuf_stream::<Event>().collect::<Vec<_>>();
+            assert!(matches!(received[..], [Err(nyquest::Error::Io(_))]));
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_protobuf_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let req = NyquestRequest::post("/echo").with_body(Body::protobuf(&events()[1]));
+            let res = client.request(req).await.unwrap().protobuf::<Event>().await;
+            assert_eq!(res.unwrap(), events()[1]);
+            assert_content_type(&server);
+
+            let res = client
+                .request(NyquestRequest::get("/events"))
+                .await
+                .unwrap();
+            let stream = res.protobuf_stream::<Event>().await.unwrap();
+            let received = stream.collect::<Result<Vec<_>, _>>();
+            assert_eq!(received.unwrap(), events());
+
+            let res = client.request(NyquestRequest::get("/truncated")).await;
+            let stream = res.unwrap().protobuf_stream::<Event>().await.unwrap();
+            let received = stream.collect::<Vec<_>>();
+            assert!(matches!(received[..], [Err(nyquest::Error::Io(_))]));
+        });
+    }
+}
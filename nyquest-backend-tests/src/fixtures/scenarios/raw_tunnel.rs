@@ -0,0 +1,25 @@
+#[cfg(all(test, feature = "curl"))] // Raw tunnels are an extension of the libcurl backend
+mod tests {
+    use std::io::{Read, Write};
+    use std::time::Duration;
+
+    use nyquest_backend_curl::ext::raw_tunnel;
+    use nyquest_test_server::{Reply, TestServer};
+
+    #[test]
+    fn test_raw_tunnel() {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/raw", |_| Reply::ok().body("raw body"));
+
+        let mut tunnel = raw_tunnel(&server.base_url(), Some(Duration::from_secs(5))).unwrap();
+        tunnel
+            .write_all(b"GET /raw HTTP/1.1\r\nhost: raw.test\r\nconnection: close\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        tunnel.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.ends_with("\r\n\r\nraw body"), "{response}");
+        assert_eq!(server.requests()[0].header("host"), Some("raw.test"));
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+
+    use hyper::Response;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    fn add_delayed_fixture(path: &'static str, delay: Duration) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, move |_req| async move {
+            tokio::time::sleep(delay).await;
+            let res = Response::new(Full::new(Bytes::from_static(path.as_bytes())));
+            (res, Ok(()))
+        })
+    }
+
+    #[test]
+    fn test_request_group_order() {
+        const PATHS: [&str; 3] = [
+            "scenarios/request_group/slow",
+            "scenarios/request_group/medium",
+            "scenarios/request_group/fast",
+        ];
+        let _handles = [
+            add_delayed_fixture(PATHS[0], Duration::from_millis(300)),
+            add_delayed_fixture(PATHS[1], Duration::from_millis(100)),
+            add_delayed_fixture(PATHS[2], Duration::from_millis(0)),
+        ];
+
+        let contents = TOKIO_RT.block_on(async {
+            let builder = crate::init_builder().await.unwrap();
+            let client = builder.build_async().await.unwrap();
+            let responses = client
+                .request_group(PATHS.map(NyquestRequest::get))
+                .max_concurrency(NonZeroUsize::new(2).unwrap())
+                .send()
+                .await
+                .unwrap();
+            let mut contents = vec![];
+            for res in responses {
+                contents.push(res.text().await.unwrap());
+            }
+            contents
+        });
+        assert_eq!(contents, PATHS);
+    }
+
+    #[test]
+    fn test_request_group_timeout() {
+        const FAST_PATH: &str = "scenarios/request_group_timeout/fast";
+        const SLOW_PATH: &str = "scenarios/request_group_timeout/slow";
+        let _handles = [
+            add_delayed_fixture(FAST_PATH, Duration::from_millis(0)),
+            add_delayed_fixture(SLOW_PATH, Duration::from_secs(5)),
+        ];
+
+        let res = TOKIO_RT.block_on(async {
+            let builder = crate::init_builder().await.unwrap();
+            let client = builder.build_async().await.unwrap();
+            client
+                .request_group([FAST_PATH, SLOW_PATH].map(NyquestRequest::get))
+                .timeout(Duration::from_millis(200))
+                .send()
+                .await
+        });
+        assert!(matches!(res, Err(nyquest::Error::RequestTimeout)));
+    }
+}
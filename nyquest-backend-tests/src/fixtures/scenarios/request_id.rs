@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Request as NyquestRequest, RequestId};
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/id", |_| Reply::ok());
+        server
+    }
+
+    fn requests<S>() -> [NyquestRequest<S>; 4] {
+        [
+            NyquestRequest::get("/id"),
+            NyquestRequest::get("/id"),
+            NyquestRequest::get("/id").with_header("x-request-id", "from-header"),
+            NyquestRequest::get("/id").with_extension(RequestId::from("from-extension".to_owned())),
+        ]
+    }
+
+    fn assert_request_ids(server: &TestServer, ids: &[Option<String>]) {
+        let sent: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|req| req.header("x-request-id").map(str::to_owned))
+            .collect();
+        assert_eq!(sent, ids);
+        for id in &ids[..2] {
+            let id = id.as_deref().unwrap();
+            assert_eq!(id.len(), 36);
+            assert_eq!(&id[14..15], "7");
+            assert!("89ab".contains(&id[19..20]), "{id}");
+        }
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(ids[2].as_deref(), Some("from-header"));
+        assert_eq!(ids[3].as_deref(), Some("from-extension"));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_request_id_blocking() {
+        let server = start_server();
+        let builder = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url());
+        let client = builder.clone().build_blocking().unwrap();
+        let res = client.request(NyquestRequest::get("/id")).unwrap();
+        assert_eq!(res.request_id(), None);
+        assert_eq!(server.requests()[0].header("x-request-id"), None);
+        server.clear_requests();
+
+        let client = builder
+            .request_id_header("x-request-id")
+            .build_blocking()
+            .unwrap();
+        let ids: Vec<_> = requests()
+            .into_iter()
+            .map(|req| client.request(req).unwrap().request_id().map(str::to_owned))
+            .collect();
+        assert_request_ids(&server, &ids);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_request_id_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let builder = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url());
+            let client = builder.clone().build_async().await.unwrap();
+            let res = client.request(NyquestRequest::get("/id")).await.unwrap();
+            assert_eq!(res.request_id(), None);
+            assert_eq!(server.requests()[0].header("x-request-id"), None);
+            server.clear_requests();
+
+            let client = builder
+                .request_id_header("x-request-id")
+                .build_async()
+                .await
+                .unwrap();
+            let mut ids = vec![];
+            for req in requests() {
+                let res = client.request(req).await.unwrap();
+                ids.push(res.request_id().map(str::to_owned));
+            }
+            assert_request_ids(&server, &ids);
+        });
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use nyquest::{Request as NyquestRequest, Revalidator, Validators};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const URI: &str = "/resource";
+    const LAST_MODIFIED: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", URI, |req| match req.header("if-none-match") {
+            Some("\"v1\"") => Reply::new(304),
+            _ => Reply::ok()
+                .header("etag", "\"v1\"")
+                .header("last-modified", LAST_MODIFIED)
+                .body("v1"),
+        });
+        server
+    }
+
+    fn expected_validators() -> Validators {
+        Validators {
+            etag: Some("\"v1\"".into()),
+            last_modified: Some(LAST_MODIFIED.into()),
+        }
+    }
+
+    fn modified_since_request<S>() -> NyquestRequest<S> {
+        NyquestRequest::get(URI)
+            .if_none_match("\"v0\"")
+            .if_modified_since(UNIX_EPOCH + Duration::from_secs(784_111_777))
+    }
+
+    fn assert_requests(server: &TestServer) {
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].header("if-none-match"), Some("\"v0\""));
+        assert_eq!(requests[0].header("if-modified-since"), Some(LAST_MODIFIED));
+        assert_eq!(requests[1].header("if-none-match"), None);
+        assert_eq!(requests[2].header("if-none-match"), Some("\"v1\""));
+        assert_eq!(requests[2].header("if-modified-since"), Some(LAST_MODIFIED));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_revalidate_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let revalidator = Revalidator::new();
+
+        let res = client.request(modified_since_request()).unwrap();
+        assert!(!res.is_not_modified());
+
+        let res = client
+            .request(revalidator.apply(NyquestRequest::get(URI)))
+            .unwrap();
+        assert!(!res.is_not_modified());
+        revalidator.update(URI, res.validators().unwrap());
+        assert_eq!(revalidator.get(URI), Some(expected_validators()));
+
+        let res = client
+            .request(revalidator.apply(NyquestRequest::get(URI)))
+            .unwrap();
+        assert!(res.is_not_modified());
+        assert_requests(&server);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_revalidate_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let revalidator = Revalidator::new();
+
+            let res = client.request(modified_since_request()).await.unwrap();
+            assert!(!res.is_not_modified());
+
+            let req = revalidator.apply(NyquestRequest::get(URI));
+            let res = client.request(req).await.unwrap();
+            assert!(!res.is_not_modified());
+            revalidator.update(URI, res.validators().unwrap());
+            assert_eq!(revalidator.get(URI), Some(expected_validators()));
+
+            let req = revalidator.apply(NyquestRequest::get(URI));
+            let res = client.request(req).await.unwrap();
+            assert!(res.is_not_modified());
+            assert_requests(&server);
+        });
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use hyper::Response;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    const BODY: &str = "shared client";
+    const THREADS: usize = 4;
+
+    fn add_fixture(path: &'static str) -> HyperFixtureHandle {
+        crate::add_hyper_fixture(path, |_| async {
+            (Response::new(Full::new(Bytes::from(BODY))), Ok(()))
+        })
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_shared_client_blocking() {
+        const PATH: &str = "scenarios/shared_client_blocking";
+        let _handle = add_fixture(PATH);
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .build_blocking()
+            .unwrap();
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let client = client.clone();
+                std::thread::spawn(move || client.request(NyquestRequest::get(PATH))?.text())
+            })
+            .collect();
+        drop(client);
+        for thread in threads {
+            assert_eq!(thread.join().unwrap().unwrap(), BODY);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_client_async() {
+        const PATH: &str = "scenarios/shared_client_async";
+        let _handle = add_fixture(PATH);
+        TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .build_async()
+                .await
+                .unwrap();
+            let tasks: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let client = client.clone();
+                    tokio::spawn(async move {
+                        client
+                            .request(NyquestRequest::get(PATH))
+                            .await?
+                            .text()
+                            .await
+                    })
+                })
+                .collect();
+            drop(client);
+            for task in tasks {
+                assert_eq!(task.await.unwrap().unwrap(), BODY);
+            }
+        });
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use hyper::Response;
+    use nyquest::Request as NyquestRequest;
+
+    use crate::*;
+
+    const PATH: &str = "scenarios/tee";
+    const BODY: &str = "teed response body";
+    const LATIN1_PATH: &str = "scenarios/tee_latin1";
+    const LATIN1_BODY: &[u8] = b"caf\xe9";
+
+    fn add_latin1_fixture() -> HyperFixtureHandle {
+        crate::add_hyper_fixture(LATIN1_PATH, |_req| async {
+            let res = Response::builder()
+                .header("content-type", "text/plain; charset=iso-8859-1")
+                .body(Full::new(Bytes::from_static(LATIN1_BODY)))
+                .unwrap();
+            (res, Ok(()))
+        })
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "blocking")]
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl futures::io::AsyncWrite for SharedBuffer {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_tee_blocking() {
+        let _handle = crate::add_hyper_fixture(PATH, |_req| async {
+            (Response::new(Full::new(Bytes::from(BODY))), Ok(()))
+        });
+        let _latin1_handle = add_latin1_fixture();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .build_blocking()
+            .unwrap();
+
+        let copy = SharedBuffer::default();
+        let res = client.request(NyquestRequest::get(PATH)).unwrap();
+        let bytes = res.tee(copy.clone()).bytes().unwrap();
+        assert_eq!(bytes, BODY.as_bytes());
+        assert_eq!(*copy.0.lock().unwrap(), BODY.as_bytes());
+
+        let copy = SharedBuffer::default();
+        let res = client.request(NyquestRequest::get(PATH)).unwrap();
+        let content = res.tee(copy.clone()).text().unwrap();
+        assert_eq!(content, BODY);
+        assert_eq!(*copy.0.lock().unwrap(), BODY.as_bytes());
+
+        let copy = SharedBuffer::default();
+        let res = client.request(NyquestRequest::get(LATIN1_PATH)).unwrap();
+        let content = res.tee(copy.clone()).text().unwrap();
+        assert_eq!(content, "café");
+        assert_eq!(*copy.0.lock().unwrap(), LATIN1_BODY);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_tee_async() {
+        let _handle = crate::add_hyper_fixture(PATH, |_req| async {
+            (Response::new(Full::new(Bytes::from(BODY))), Ok(()))
+        });
+        let _latin1_handle = add_latin1_fixture();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .build_async()
+                .await
+                .unwrap();
+
+            let copy = SharedBuffer::default();
+            let res = client.request(NyquestRequest::get(PATH)).await.unwrap();
+            let bytes = res.tee(copy.clone()).bytes().await.unwrap();
+            assert_eq!(bytes, BODY.as_bytes());
+            assert_eq!(*copy.0.lock().unwrap(), BODY.as_bytes());
+
+            let copy = SharedBuffer::default();
+            let res = client.request(NyquestRequest::get(PATH)).await.unwrap();
+            let content = res.tee(copy.clone()).text().await.unwrap();
+            assert_eq!(content, BODY);
+            assert_eq!(*copy.0.lock().unwrap(), BODY.as_bytes());
+
+            let copy = SharedBuffer::default();
+            let res = client
+                .request(NyquestRequest::get(LATIN1_PATH))
+                .await
+                .unwrap();
+            let content = res.tee(copy.clone()).text().await.unwrap();
+            assert_eq!(content, "café");
+            assert_eq!(*copy.0.lock().unwrap(), LATIN1_BODY);
+        });
+    }
+}
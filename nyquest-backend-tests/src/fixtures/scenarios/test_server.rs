@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Body, Request as NyquestRequest};
+    use nyquest_test_server::{Fault, Reply, TestServer};
+
+    const BODY: &str = "served by the test server";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server
+            .route("POST", "/echo", |req| Reply::ok().body(req.body.clone()))
+            .route("GET", "/reset", |_| Reply::ok().fault(Fault::Reset))
+            .route("GET", "/truncate", |_| {
+                Reply::ok().body(BODY).fault(Fault::Truncate(4))
+            })
+            .route("GET", "/malformed", |_| {
+                Reply::ok().fault(Fault::Malformed(b"HTTP/1.1 abc\r\n\r\n".to_vec()))
+            });
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_test_server_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+
+        let req = NyquestRequest::post("/echo").with_body(Body::plain_text(BODY));
+        let text = client.request(req).unwrap().text().unwrap();
+        assert_eq!(text, BODY);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path(), "/echo");
+        assert_eq!(requests[0].text(), BODY);
+
+        assert!(client.request(NyquestRequest::get("/reset")).is_err());
+        let res = client
+            .request(NyquestRequest::get("/truncate"))
+            .and_then(|res| res.text());
+        assert!(res.is_err());
+        assert!(client.request(NyquestRequest::get("/malformed")).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_test_server_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+
+            let req = NyquestRequest::post("/echo").with_body(Body::plain_text(BODY));
+            let text = client.request(req).await.unwrap().text().await.unwrap();
+            assert_eq!(text, BODY);
+
+            assert!(client.request(NyquestRequest::get("/reset")).await.is_err());
+            let res = match client.request(NyquestRequest::get("/truncate")).await {
+                Ok(res) => res.text().await,
+                Err(e) => Err(e),
+            };
+            assert!(res.is_err());
+            assert!(client
+                .request(NyquestRequest::get("/malformed"))
+                .await
+                .is_err());
+        });
+        let requests = server.requests();
+        assert_eq!(requests[0].path(), "/echo");
+        assert_eq!(requests[0].text(), BODY);
+    }
+}
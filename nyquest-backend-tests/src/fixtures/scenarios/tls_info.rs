@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::Request as NyquestRequest;
+    use nyquest_test_server::{Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/plain", |_| Reply::ok().body("plain"));
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_tls_info_plain_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let res = client.request(NyquestRequest::get("/plain")).unwrap();
+        assert_eq!(res.tls_info(), None);
+        assert_eq!(res.text().unwrap(), "plain");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_tls_info_plain_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let res = client.request(NyquestRequest::get("/plain")).await.unwrap();
+            assert_eq!(res.tls_info(), None);
+            assert_eq!(res.text().await.unwrap(), "plain");
+        });
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{Request as NyquestRequest, UserAgent};
+    use nyquest_test_server::{Reply, TestServer};
+
+    const CLIENT_UA: &str = "client/1.0";
+    const REQUEST_UA: &str = "my-app/1.2 (+https://example.com; \\(beta\\)) nyquest-tests";
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/ua", |_| Reply::ok());
+        server
+    }
+
+    fn request_user_agent() -> UserAgent {
+        UserAgent::product("my-app", "1.2")
+            .comment("+https://example.com; (beta)")
+            .and_product("nyquest tests", "")
+    }
+
+    fn assert_user_agents(server: &TestServer) {
+        let requests = server.requests();
+        assert_eq!(requests[0].header("user-agent"), Some(REQUEST_UA));
+        assert_eq!(requests[1].header("user-agent"), Some(CLIENT_UA));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_user_agent_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .user_agent(UserAgent::product("client", "1.0"))
+            .build_blocking()
+            .unwrap();
+
+        let req = NyquestRequest::get("/ua").user_agent(request_user_agent());
+        client.request(req).unwrap();
+        client.request(NyquestRequest::get("/ua")).unwrap();
+        assert_user_agents(&server);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_user_agent_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .user_agent(UserAgent::product("client", "1.0"))
+                .build_async()
+                .await
+                .unwrap();
+
+            let req = NyquestRequest::get("/ua").user_agent(request_user_agent());
+            client.request(req).await.unwrap();
+            client.request(NyquestRequest::get("/ua")).await.unwrap();
+            assert_user_agents(&server);
+        });
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use nyquest::{body_form, Body, Request as NyquestRequest};
+    use nyquest_test_server::{CapturedRequest, Reply, TestServer};
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("POST", "/echo", |_| Reply::ok());
+        server
+    }
+
+    fn requests<S>(server: &TestServer) -> [NyquestRequest<S>; 2] {
+        let url = format!("{}/echo?page=1", server.base_url());
+        [
+            NyquestRequest::post(url.clone())
+                .with_header("x-test", "value")
+                .with_body(Body::text("hello world", "text/plain")),
+            NyquestRequest::post(url).with_body(body_form! {
+                "key" => "a b&c",
+            }),
+        ]
+    }
+
+    /// Checks that the request line, the headers and the body of the approximation were sent.
+    fn assert_sent_as_formatted(wire: &str, sent: &CapturedRequest) {
+        let (head, body) = wire.split_once("\n\n").unwrap();
+        let mut lines = head.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            format!("{} {} HTTP/1.1", sent.method, sent.uri)
+        );
+        for line in lines {
+            let (name, value) = line.split_once(": ").unwrap();
+            assert_eq!(sent.header(name), Some(value), "{line}");
+        }
+        assert_eq!(body, sent.text());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_wire_debug_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .build_blocking()
+            .unwrap();
+        for req in requests(&server) {
+            let wire = req.to_wire_debug();
+            client.request(req).unwrap();
+            assert_sent_as_formatted(&wire, server.requests().last().unwrap());
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_wire_debug_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .build_async()
+                .await
+                .unwrap();
+            for req in requests(&server) {
+                let wire = req.to_wire_debug();
+                client.request(req).await.unwrap();
+                assert_sent_as_formatted(&wire, server.requests().last().unwrap());
+            }
+        });
+    }
+}
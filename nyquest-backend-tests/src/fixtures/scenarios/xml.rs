@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use nyquest::{Error, Request as NyquestRequest};
+    use nyquest_test_server::{Reply, TestServer};
+
+    type Object = BTreeMap<String, String>;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult><Name>bücket</Name><KeyCount>2</KeyCount></ListBucketResult>"#;
+
+    fn expected() -> Object {
+        [("Name", "bücket"), ("KeyCount", "2")]
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    fn start_server() -> TestServer {
+        let server = TestServer::start().unwrap();
+        server.route("GET", "/list", |_| {
+            Reply::ok()
+                .header("content-type", "application/xml; charset=utf-8")
+                .body(XML)
+        });
+        server.route("GET", "/invalid", |_| {
+            Reply::ok()
+                .header("content-type", "application/xml")
+                .body("<Name>bücket</Key>")
+        });
+        server
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_xml_blocking() {
+        let server = start_server();
+        let client = crate::init_builder_blocking()
+            .unwrap()
+            .base_url(server.base_url())
+            .build_blocking()
+            .unwrap();
+        let xml = |path: &'static str| {
+            client
+                .request(NyquestRequest::get(path))
+                .unwrap()
+                .xml::<Object>()
+        };
+        assert_eq!(xml("/list").unwrap(), expected());
+        assert!(matches!(xml("/invalid"), Err(Error::Xml(_))));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_xml_async() {
+        let server = start_server();
+        crate::TOKIO_RT.block_on(async {
+            let client = crate::init_builder()
+                .await
+                .unwrap()
+                .base_url(server.base_url())
+                .build_async()
+                .await
+                .unwrap();
+            let xml = |path: &'static str| {
+                let req = client.request(NyquestRequest::get(path));
+                async { req.await.unwrap().xml::<Object>().await }
+            };
+            assert_eq!(xml("/list").await.unwrap(), expected());
+            assert!(matches!(xml("/invalid").await, Err(Error::Xml(_))));
+        });
+    }
+}
@@ -1,4 +1,9 @@
-#![cfg(test)]
+// The fixtures need a backend and at least one of the client flavors to be meaningful.
+#![cfg(all(
+    test,
+    any(feature = "curl", feature = "nsurlsession", feature = "winrt"),
+    any(feature = "async", feature = "blocking"),
+))]
 
 use std::{
     collections::BTreeMap,
@@ -164,19 +169,22 @@ static TOKIO_RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
         .unwrap()
 });
 
-async fn init_builder() -> io::Result<ClientBuilder> {
+async fn hyper_base_url() -> io::Result<String> {
     use tokio::sync::OnceCell;
 
-    static BACKEND_INIT: Once = Once::new();
-    BACKEND_INIT.call_once(init_backend);
-
     static HYPER_SERVICE_INIT: OnceCell<io::Result<String>> = OnceCell::const_new();
-    let res = match HYPER_SERVICE_INIT.get_or_init(setup_hyper_impl).await {
-        Ok(url) => Ok(ClientBuilder::default().base_url(url.clone())),
+    match HYPER_SERVICE_INIT.get_or_init(setup_hyper_impl).await {
+        Ok(url) => Ok(url.clone()),
         Err(err) => Err(io::Error::new(err.kind(), err.to_string())),
-    };
+    }
+}
+
+async fn init_builder() -> io::Result<ClientBuilder> {
+    static BACKEND_INIT: Once = Once::new();
+    BACKEND_INIT.call_once(init_backend);
 
-    res
+    let url = hyper_base_url().await?;
+    Ok(ClientBuilder::default().base_url(url))
 }
 
 fn init_builder_blocking() -> io::Result<ClientBuilder> {
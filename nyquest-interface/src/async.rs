@@ -6,6 +6,7 @@
 mod any;
 mod backend;
 mod body;
+pub mod body_pump;
 
 pub use any::{AnyAsyncBackend, AnyAsyncClient, AnyAsyncResponse};
 pub use backend::{AsyncBackend, AsyncClient, AsyncResponse};
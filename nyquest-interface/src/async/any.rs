@@ -8,13 +8,16 @@
 //! to implement them directly.
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::fmt;
 
+use bytes::Bytes;
 use futures_core::future::BoxFuture;
 
 use super::backend::AsyncResponse;
 use super::Request;
 use crate::client::{BuildClientResult, ClientOptions};
+use crate::NetworkStatus;
 use crate::Result;
 
 /// Trait for type-erased async backend implementations.
@@ -25,7 +28,7 @@ pub trait AnyAsyncBackend: Send + Sync + 'static {
     fn create_async_client(
         &self,
         options: ClientOptions,
-    ) -> BoxFuture<BuildClientResult<Box<dyn AnyAsyncClient>>>;
+    ) -> BoxFuture<'_, BuildClientResult<Box<dyn AnyAsyncClient>>>;
 }
 
 /// Trait for type-erased async HTTP clients.
@@ -37,7 +40,15 @@ pub trait AnyAsyncClient: Any + Send + Sync + 'static {
     /// Creates a cloned boxed version of this client.
     fn clone_boxed(&self) -> Box<dyn AnyAsyncClient>;
     /// Sends an HTTP request and returns the response.
-    fn request(&self, req: Request) -> BoxFuture<Result<Box<dyn AnyAsyncResponse>>>;
+    fn request(&self, req: Request) -> BoxFuture<'_, Result<Box<dyn AnyAsyncResponse>>>;
+    /// Connects to the host of the URI ahead of the first request to it.
+    fn preconnect(&self, uri: Cow<'static, str>) -> BoxFuture<'_, Result<()>>;
+    /// Discards the resolved addresses cached by the client.
+    fn flush_dns_cache(&self) -> Result<()>;
+    /// Returns whether the network is available to send requests over.
+    fn network_status(&self) -> NetworkStatus;
+    /// Returns whether the client itself holds requests back while the network is unavailable.
+    fn waits_for_connectivity(&self) -> bool;
 }
 
 /// Trait for type-erased async HTTP responses.
@@ -52,10 +63,14 @@ pub trait AnyAsyncResponse: Any + Send + Sync + 'static {
     fn content_length(&self) -> Option<u64>;
     /// Gets all values for the specified header.
     fn get_header(&self, header: &str) -> Result<Vec<String>>;
+    /// Gets all the headers in the order received.
+    fn headers(&self) -> Result<Vec<(String, String)>>;
+    /// Returns the TLS parameters of the connection the response is received on.
+    fn tls_info(&self) -> Option<crate::TlsInfo>;
     /// Reads the response body as text.
-    fn text(&mut self) -> BoxFuture<Result<String>>;
+    fn text(&mut self) -> BoxFuture<'_, Result<String>>;
     /// Reads the response body as bytes.
-    fn bytes(&mut self) -> BoxFuture<Result<Vec<u8>>>;
+    fn bytes(&mut self) -> BoxFuture<'_, Result<Bytes>>;
 }
 
 // These implementations allow backend types implementing the base traits
@@ -77,11 +92,19 @@ where
         AsyncResponse::get_header(self, header)
     }
 
-    fn text(&mut self) -> BoxFuture<Result<String>> {
+    fn headers(&self) -> Result<Vec<(String, String)>> {
+        AsyncResponse::headers(self)
+    }
+
+    fn tls_info(&self) -> Option<crate::TlsInfo> {
+        AsyncResponse::tls_info(self)
+    }
+
+    fn text(&mut self) -> BoxFuture<'_, Result<String>> {
         Box::pin(AsyncResponse::text(self))
     }
 
-    fn bytes(&mut self) -> BoxFuture<Result<Vec<u8>>> {
+    fn bytes(&mut self) -> BoxFuture<'_, Result<Bytes>> {
         Box::pin(AsyncResponse::bytes(self))
     }
 
@@ -98,7 +121,7 @@ where
     fn create_async_client(
         &self,
         options: ClientOptions,
-    ) -> BoxFuture<BuildClientResult<Box<dyn AnyAsyncClient>>> {
+    ) -> BoxFuture<'_, BuildClientResult<Box<dyn AnyAsyncClient>>> {
         Box::pin(async {
             super::backend::AsyncBackend::create_async_client(self, options)
                 .await
@@ -119,11 +142,27 @@ where
         Box::new(self.clone())
     }
 
-    fn request(&self, req: Request) -> BoxFuture<Result<Box<dyn AnyAsyncResponse>>> {
+    fn request(&self, req: Request) -> BoxFuture<'_, Result<Box<dyn AnyAsyncResponse>>> {
         Box::pin(async {
             self.request(req)
                 .await
                 .map(|res| Box::new(res) as Box<dyn AnyAsyncResponse>)
         }) as _
     }
+
+    fn preconnect(&self, uri: Cow<'static, str>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(super::backend::AsyncClient::preconnect(self, uri))
+    }
+
+    fn flush_dns_cache(&self) -> Result<()> {
+        super::backend::AsyncClient::flush_dns_cache(self)
+    }
+
+    fn network_status(&self) -> NetworkStatus {
+        super::backend::AsyncClient::network_status(self)
+    }
+
+    fn waits_for_connectivity(&self) -> bool {
+        super::backend::AsyncClient::waits_for_connectivity(self)
+    }
 }
@@ -6,17 +6,25 @@
 //! Backend developers need to implement the `AsyncBackend` and `AsyncClient` traits,
 //! along with a custom `AsyncResponse` type.
 
+use std::borrow::Cow;
 use std::fmt;
 use std::future::Future;
 
+use bytes::Bytes;
+
 use super::Request as AsyncRequest;
 use crate::client::{BuildClientResult, ClientOptions};
+use crate::NetworkStatus;
 use crate::Result;
 
 /// Trait for asynchronous HTTP clients.
 ///
 /// Backend implementations must provide a concrete type that implements this trait
 /// to handle asynchronous HTTP requests.
+///
+/// Clones must share the session, connection pool and any other resources of the client, so
+/// that a client can be shared freely. Frontends may keep a single instance behind an `Arc`
+/// instead of cloning it.
 pub trait AsyncClient: Clone + Send + Sync + 'static {
     /// The type of response returned by this client.
     type Response: AsyncResponse + Send;
@@ -27,7 +35,45 @@ pub trait AsyncClient: Clone + Send + Sync + 'static {
     }
 
     /// Sends an HTTP request and returns a future that resolves to the response.
+    ///
+    /// Dropping the future before it resolves must cancel the transfer promptly, and release the
+    /// connection and any other resources held for it. The same applies to the response and the
+    /// futures reading its body.
     fn request(&self, req: AsyncRequest) -> impl Future<Output = Result<Self::Response>> + Send;
+
+    /// Connects to the host of the URI ahead of the first request to it.
+    ///
+    /// Sends a `HEAD` request and discards the response by default.
+    fn preconnect(&self, uri: Cow<'static, str>) -> impl Future<Output = Result<()>> + Send {
+        let req = AsyncRequest::head(uri);
+        async move { self.request(req).await.map(drop) }
+    }
+
+    /// Discards the resolved addresses cached by the client, so that host names are looked up
+    /// again.
+    ///
+    /// Fails with [`crate::Error::Unsupported`] by default.
+    fn flush_dns_cache(&self) -> Result<()> {
+        Err(crate::Error::Unsupported("flushing the DNS cache"))
+    }
+
+    /// Returns whether the network is available to send requests over.
+    ///
+    /// Returns [`NetworkStatus::Unknown`] by default.
+    fn network_status(&self) -> NetworkStatus {
+        NetworkStatus::Unknown
+    }
+
+    /// Returns whether the client itself holds requests back while the network is unavailable,
+    /// if [`ClientOptions::wait_for_connectivity`] is set, so that the frontend does not wait
+    /// for connectivity on top of it.
+    ///
+    /// Returns `false` by default.
+    ///
+    /// [`ClientOptions::wait_for_connectivity`]: crate::client::ClientOptions::wait_for_connectivity
+    fn waits_for_connectivity(&self) -> bool {
+        false
+    }
     // TODO: fn request_with_progress
     // TODO: fn request_file
 }
@@ -74,9 +120,24 @@ pub trait AsyncResponse: Send + Sync + 'static {
     /// Gets all values for the specified header.
     fn get_header(&self, header: &str) -> Result<Vec<String>>;
 
+    /// Gets all the headers in the order received.
+    ///
+    /// Returns no headers by default, for backends that are not able to enumerate them.
+    fn headers(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![])
+    }
+
+    /// Returns the TLS parameters of the connection the response is received on, or `None` if
+    /// the connection is not encrypted.
+    ///
+    /// Returns `None` by default, for backends that are not able to tell.
+    fn tls_info(&self) -> Option<crate::TlsInfo> {
+        None
+    }
+
     /// Reads the response body as text.
     fn text(&mut self) -> impl Future<Output = Result<String>> + Send;
 
     /// Reads the response body as bytes.
-    fn bytes(&mut self) -> impl Future<Output = Result<Vec<u8>>> + Send;
+    fn bytes(&mut self) -> impl Future<Output = Result<Bytes>> + Send;
 }
@@ -0,0 +1,158 @@
+//! Streaming request bodies read by backends on their own threads.
+//!
+//! Backends uploading from threads of their own, e.g. a system thread pool, cannot poll the
+//! caller's stream there, as the stream may rely on the async runtime of the caller. A
+//! [`BodyPump`] takes over the streams and hands out a blocking [`PumpedReader`] for each of
+//! them. The backend awaits the request with [`BodyPump::run_until`], which polls the streams on
+//! the caller's task whenever a reader asks for more bytes.
+
+use std::future::{poll_fn, Future};
+use std::io;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use super::BoxedStream;
+
+/// The streams of a request body, pumped to their readers while the request is being sent.
+#[derive(Default)]
+pub struct BodyPump {
+    streams: Vec<StreamPump>,
+}
+
+impl BodyPump {
+    /// Takes over the stream, and returns the reader for the backend to upload it from.
+    pub fn pump(&mut self, stream: Pin<BoxedStream>) -> PumpedReader {
+        let shared = Arc::new(Shared::default());
+        self.streams.push(StreamPump {
+            stream,
+            shared: shared.clone(),
+        });
+        PumpedReader { shared }
+    }
+
+    /// Awaits the future while polling the streams for their readers.
+    ///
+    /// The streams are no longer read once the future completes, so readers still uploading by
+    /// then fail.
+    pub async fn run_until<F: Future>(mut self, fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        poll_fn(|cx| {
+            self.streams
+                .retain_mut(|stream| stream.poll_pump(cx).is_pending());
+            fut.as_mut().poll(cx)
+        })
+        .await
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<PumpState>,
+    filled: Condvar,
+}
+
+#[derive(Default)]
+struct PumpState {
+    buf: Vec<u8>,
+    /// Bytes asked for by the reader and not read into `buf` yet.
+    wanted: usize,
+    /// Bytes read into `buf` and not taken by the reader yet.
+    read: Option<io::Result<usize>>,
+    waker: Option<Waker>,
+    eof: bool,
+    pump_dropped: bool,
+    reader_dropped: bool,
+}
+
+struct StreamPump {
+    stream: Pin<BoxedStream>,
+    shared: Arc<Shared>,
+}
+
+impl StreamPump {
+    /// Reads the stream for the pending request of the reader, and returns ready once the
+    /// stream ends or the reader is gone.
+    fn poll_pump(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.shared.state.lock().unwrap();
+        let state = &mut *state;
+        if state.reader_dropped {
+            return Poll::Ready(());
+        }
+        if state.wanted > 0 {
+            state.buf.resize(state.wanted, 0);
+            let res = match self.stream.as_mut().poll_read(cx, &mut state.buf) {
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Poll::Ready(res) => res,
+                Poll::Pending => return Poll::Pending,
+            };
+            let done = !matches!(res, Ok(1..));
+            state.wanted = 0;
+            state.read = Some(res);
+            self.shared.filled.notify_one();
+            if done {
+                return Poll::Ready(());
+            }
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for StreamPump {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().pump_dropped = true;
+        self.shared.filled.notify_one();
+    }
+}
+
+/// A blocking reader of a stream taken over by a [`BodyPump`].
+///
+/// Reads block until the pump reads the stream on the task awaiting the request.
+pub struct PumpedReader {
+    shared: Arc<Shared>,
+}
+
+impl io::Read for PumpedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            match state.read.take() {
+                Some(Ok(len)) => {
+                    buf[..len].copy_from_slice(&state.buf[..len]);
+                    state.eof = len == 0;
+                    return Ok(len);
+                }
+                Some(Err(e)) => return Err(e),
+                None if state.eof => return Ok(0),
+                None if state.pump_dropped => {
+                    return Err(io::Error::other("request body is no longer read"))
+                }
+                None => {}
+            }
+            if state.wanted == 0 {
+                state.wanted = buf.len();
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+            state = self.shared.filled.wait(state).unwrap();
+        }
+    }
+}
+
+impl Drop for PumpedReader {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.reader_dropped = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
@@ -7,12 +7,16 @@
 //! corresponding traits from the `blocking::backend` module, so backend developers don't need
 //! to implement them directly.
 
+use std::borrow::Cow;
 use std::fmt;
 use std::{any::Any, io};
 
+use bytes::Bytes;
+
 use super::backend::BlockingResponse;
 use super::Request;
 use crate::client::{BuildClientResult, ClientOptions};
+use crate::NetworkStatus;
 
 /// Trait for type-erased blocking backend implementations.
 ///
@@ -35,6 +39,14 @@ pub trait AnyBlockingClient: Any + Send + Sync + 'static {
     fn clone_boxed(&self) -> Box<dyn AnyBlockingClient>;
     /// Sends an HTTP request and returns the response.
     fn request(&self, req: Request) -> crate::Result<Box<dyn AnyBlockingResponse>>;
+    /// Connects to the host of the URI ahead of the first request to it.
+    fn preconnect(&self, uri: Cow<'static, str>) -> crate::Result<()>;
+    /// Discards the resolved addresses cached by the client.
+    fn flush_dns_cache(&self) -> crate::Result<()>;
+    /// Returns whether the network is available to send requests over.
+    fn network_status(&self) -> NetworkStatus;
+    /// Returns whether the client itself holds requests back while the network is unavailable.
+    fn waits_for_connectivity(&self) -> bool;
 }
 
 /// Trait for type-erased blocking HTTP responses.
@@ -50,10 +62,14 @@ pub trait AnyBlockingResponse: io::Read + Any + Send + Sync + 'static {
     fn content_length(&self) -> Option<u64>;
     /// Gets all values for the specified header.
     fn get_header(&self, header: &str) -> crate::Result<Vec<String>>;
+    /// Gets all the headers in the order received.
+    fn headers(&self) -> crate::Result<Vec<(String, String)>>;
+    /// Returns the TLS parameters of the connection the response is received on.
+    fn tls_info(&self) -> Option<crate::TlsInfo>;
     /// Reads the response body as text.
     fn text(&mut self) -> crate::Result<String>;
     /// Reads the response body as bytes.
-    fn bytes(&mut self) -> crate::Result<Vec<u8>>;
+    fn bytes(&mut self) -> crate::Result<Bytes>;
 }
 
 // These implementations allow backend types implementing the base traits
@@ -91,11 +107,19 @@ where
         BlockingResponse::get_header(self, header)
     }
 
+    fn headers(&self) -> crate::Result<Vec<(String, String)>> {
+        BlockingResponse::headers(self)
+    }
+
+    fn tls_info(&self) -> Option<crate::TlsInfo> {
+        BlockingResponse::tls_info(self)
+    }
+
     fn text(&mut self) -> crate::Result<String> {
         BlockingResponse::text(self)
     }
 
-    fn bytes(&mut self) -> crate::Result<Vec<u8>> {
+    fn bytes(&mut self) -> crate::Result<Bytes> {
         BlockingResponse::bytes(self)
     }
 }
@@ -113,4 +137,16 @@ where
     fn request(&self, req: Request) -> crate::Result<Box<dyn AnyBlockingResponse>> {
         Ok(Box::new(self.request(req)?))
     }
+    fn preconnect(&self, uri: Cow<'static, str>) -> crate::Result<()> {
+        super::backend::BlockingClient::preconnect(self, uri)
+    }
+    fn flush_dns_cache(&self) -> crate::Result<()> {
+        super::backend::BlockingClient::flush_dns_cache(self)
+    }
+    fn network_status(&self) -> NetworkStatus {
+        super::backend::BlockingClient::network_status(self)
+    }
+    fn waits_for_connectivity(&self) -> bool {
+        super::backend::BlockingClient::waits_for_connectivity(self)
+    }
 }
@@ -6,15 +6,23 @@
 //! Backend developers need to implement the `BlockingBackend` and `BlockingClient` traits,
 //! along with a custom `BlockingResponse` type.
 
+use std::borrow::Cow;
 use std::{fmt, io};
 
+use bytes::Bytes;
+
 use super::Request;
 use crate::client::{BuildClientResult, ClientOptions};
+use crate::NetworkStatus;
 
 /// Trait for blocking HTTP clients.
 ///
 /// Backend implementations must provide a concrete type that implements this trait
 /// to handle blocking HTTP requests.
+///
+/// Clones must share the session, connection pool and any other resources of the client, so
+/// that a client can be shared freely. Frontends may keep a single instance behind an `Arc`
+/// instead of cloning it.
 pub trait BlockingClient: Clone + Send + Sync + 'static {
     /// The type of response returned by this client.
     type Response: BlockingResponse;
@@ -26,6 +34,39 @@ pub trait BlockingClient: Clone + Send + Sync + 'static {
 
     /// Sends an HTTP request and returns the response.
     fn request(&self, req: Request) -> crate::Result<Self::Response>;
+
+    /// Connects to the host of the URI ahead of the first request to it.
+    ///
+    /// Sends a `HEAD` request and discards the response by default.
+    fn preconnect(&self, uri: Cow<'static, str>) -> crate::Result<()> {
+        self.request(Request::head(uri)).map(drop)
+    }
+
+    /// Discards the resolved addresses cached by the client, so that host names are looked up
+    /// again.
+    ///
+    /// Fails with [`crate::Error::Unsupported`] by default.
+    fn flush_dns_cache(&self) -> crate::Result<()> {
+        Err(crate::Error::Unsupported("flushing the DNS cache"))
+    }
+
+    /// Returns whether the network is available to send requests over.
+    ///
+    /// Returns [`NetworkStatus::Unknown`] by default.
+    fn network_status(&self) -> NetworkStatus {
+        NetworkStatus::Unknown
+    }
+
+    /// Returns whether the client itself holds requests back while the network is unavailable,
+    /// if [`ClientOptions::wait_for_connectivity`] is set, so that the frontend does not wait
+    /// for connectivity on top of it.
+    ///
+    /// Returns `false` by default.
+    ///
+    /// [`ClientOptions::wait_for_connectivity`]: crate::client::ClientOptions::wait_for_connectivity
+    fn waits_for_connectivity(&self) -> bool {
+        false
+    }
 }
 
 /// Trait for blocking HTTP backend implementations.
@@ -71,9 +112,24 @@ pub trait BlockingResponse: io::Read + Send + Sync + 'static {
     /// Gets all values for the specified header.
     fn get_header(&self, header: &str) -> crate::Result<Vec<String>>;
 
+    /// Gets all the headers in the order received.
+    ///
+    /// Returns no headers by default, for backends that are not able to enumerate them.
+    fn headers(&self) -> crate::Result<Vec<(String, String)>> {
+        Ok(vec![])
+    }
+
+    /// Returns the TLS parameters of the connection the response is received on, or `None` if
+    /// the connection is not encrypted.
+    ///
+    /// Returns `None` by default, for backends that are not able to tell.
+    fn tls_info(&self) -> Option<crate::TlsInfo> {
+        None
+    }
+
     /// Reads the response body as text.
     fn text(&mut self) -> crate::Result<String>;
 
     /// Reads the response body as bytes.
-    fn bytes(&mut self) -> crate::Result<Vec<u8>>;
+    fn bytes(&mut self) -> crate::Result<Bytes>;
 }
@@ -5,6 +5,8 @@
 
 use std::{borrow::Cow, fmt::Debug};
 
+use bytes::Bytes;
+
 #[cfg(feature = "multipart")]
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 mod multipart;
@@ -29,7 +31,7 @@ pub enum Body<S> {
     /// Raw byte content with a specified content type.
     Bytes {
         /// The actual byte content of the body.
-        content: Cow<'static, [u8]>,
+        content: Bytes,
         /// The MIME content type for the body.
         content_type: Cow<'static, str>,
     },
@@ -3,7 +3,9 @@
 //! This module defines types for creating multipart/form-data bodies,
 //! which allow sending complex data including files in HTTP requests.
 
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, fmt::Debug, path::PathBuf};
+
+use bytes::Bytes;
 
 use super::StreamReader;
 
@@ -26,12 +28,17 @@ pub struct Part<S> {
 
 /// Body content for a multipart form part.
 ///
-/// This can be either raw bytes or a stream.
+/// This can be raw bytes, a file or a stream.
 pub enum PartBody<S> {
     /// Raw byte content.
     Bytes {
         /// The bytes that make up this part's content.
-        content: Cow<'static, [u8]>,
+        content: Bytes,
+    },
+    /// Content read from a file when the request is sent.
+    File {
+        /// Path of the file.
+        path: PathBuf,
     },
     /// Streaming content for larger part bodies.
     #[doc(hidden)]
@@ -63,6 +70,10 @@ where
                 .debug_struct("PartBody::Bytes")
                 .field("content", content)
                 .finish(),
+            PartBody::File { path } => f
+                .debug_struct("PartBody::File")
+                .field("path", path)
+                .finish(),
             PartBody::Stream(stream) => f
                 .debug_struct("PartBody::Stream")
                 .field("stream", stream)
@@ -93,6 +104,7 @@ where
             PartBody::Bytes { content } => PartBody::Bytes {
                 content: content.clone(),
             },
+            PartBody::File { path } => PartBody::File { path: path.clone() },
             PartBody::Stream(stream) => PartBody::Stream(stream.clone()),
         }
     }
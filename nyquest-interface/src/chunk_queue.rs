@@ -0,0 +1,143 @@
+//! Flow control of response bodies passed from backends to their consumers.
+//!
+//! Backends receiving the body on their own, e.g. on a transfer loop or in delegate callbacks,
+//! queue the chunks with [`ChunkQueue`], or count them with [`FlowControl`] if they buffer them
+//! otherwise. Both tell the backend when to suspend the transfer because the consumer lags
+//! behind, and when to resume it, so that slow consumers do not cause unbounded buffering.
+
+use std::collections::VecDeque;
+
+/// The amounts of buffered bytes between which transfers are suspended and resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaterMarks {
+    /// The transfer is suspended once this many bytes are received but not consumed yet.
+    pub high: usize,
+    /// A suspended transfer is resumed once the unconsumed bytes drop to this many.
+    pub low: usize,
+}
+
+impl Default for WaterMarks {
+    fn default() -> Self {
+        Self {
+            high: 1024 * 1024,
+            low: 256 * 1024,
+        }
+    }
+}
+
+/// Counts the bytes buffered for the consumer, and decides when to suspend and resume the
+/// transfer.
+#[derive(Debug, Clone)]
+pub struct FlowControl {
+    water_marks: WaterMarks,
+    buffered: usize,
+    received: u64,
+    suspended: bool,
+}
+
+impl Default for FlowControl {
+    fn default() -> Self {
+        Self::new(WaterMarks::default())
+    }
+}
+
+impl FlowControl {
+    /// Creates a flow control for a running transfer.
+    pub fn new(water_marks: WaterMarks) -> Self {
+        Self {
+            water_marks,
+            buffered: 0,
+            received: 0,
+            suspended: false,
+        }
+    }
+
+    /// Marks the transfer as suspended initially, e.g. by the backend once the headers arrive,
+    /// so that it is resumed by the first consumption.
+    pub fn starting_suspended(mut self) -> Self {
+        self.suspended = true;
+        self
+    }
+
+    /// Records bytes received from the transfer, and returns whether the backend should suspend
+    /// it now.
+    pub fn produce(&mut self, len: usize) -> bool {
+        self.buffered += len;
+        self.received += len as u64;
+        let suspend = !self.suspended && self.buffered >= self.water_marks.high;
+        self.suspended |= suspend;
+        suspend
+    }
+
+    /// Records bytes taken by the consumer, and returns whether the backend should resume the
+    /// transfer now.
+    pub fn consume(&mut self, len: usize) -> bool {
+        self.buffered -= len.min(self.buffered);
+        let resume = self.suspended && self.buffered <= self.water_marks.low;
+        self.suspended &= !resume;
+        resume
+    }
+
+    /// Returns whether the transfer is suspended, i.e. it should not produce more bytes until
+    /// resumed.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Returns the bytes received but not consumed yet.
+    pub fn buffered(&self) -> usize {
+        self.buffered
+    }
+
+    /// Returns the bytes received in total.
+    pub fn received(&self) -> u64 {
+        self.received
+    }
+}
+
+/// A bounded queue of body chunks from the backend to the consumer.
+///
+/// The bound is not enforced by the queue: [`ChunkQueue::push`] always queues the chunk, and the
+/// backend is expected to suspend the transfer when asked to.
+#[derive(Debug)]
+pub struct ChunkQueue<C> {
+    chunks: VecDeque<C>,
+    flow: FlowControl,
+}
+
+impl<C> Default for ChunkQueue<C> {
+    fn default() -> Self {
+        Self::new(FlowControl::default())
+    }
+}
+
+impl<C> ChunkQueue<C> {
+    /// Creates an empty queue with the flow control.
+    pub fn new(flow: FlowControl) -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            flow,
+        }
+    }
+
+    /// Returns the flow control of the queue.
+    pub fn flow(&self) -> &FlowControl {
+        &self.flow
+    }
+}
+
+impl<C: AsRef<[u8]>> ChunkQueue<C> {
+    /// Queues a chunk and returns whether the backend should suspend the transfer.
+    pub fn push(&mut self, chunk: C) -> bool {
+        let suspend = self.flow.produce(chunk.as_ref().len());
+        self.chunks.push_back(chunk);
+        suspend
+    }
+
+    /// Takes the next chunk, along with whether the backend should resume the transfer.
+    pub fn pop(&mut self) -> (Option<C>, bool) {
+        let chunk = self.chunks.pop_front();
+        let len = chunk.as_ref().map_or(0, |chunk| chunk.as_ref().len());
+        (chunk, self.flow.consume(len))
+    }
+}
@@ -4,7 +4,20 @@
 //! HTTP clients in nyquest.
 
 mod error;
+mod metrics;
 mod options;
+mod outbox;
+mod request_map;
+mod resolve;
 
 pub use error::{BuildClientError, BuildClientResult};
-pub use options::{CachingBehavior, ClientOptions};
+pub use metrics::{MetricsSink, RequestDurations, RequestLabels, TransferSizes};
+pub use options::{
+    Auth, CachingBehavior, CassetteConfig, CassetteMode, CircuitBreakerConfig, ClientCertificate,
+    ClientOptions, DecompressionLimits, FaultInjectionConfig, Http2KeepAlive, HttpVersion,
+    ProxyTlsConfig, PushPolicy, PushPromise, QualityOfService, RedactionPolicy,
+    ResponseHeaderLimits, RevocationPolicy, UrlCredentials,
+};
+pub use outbox::{ConflictHook, ConflictResolution, OutboxConfig, OutboxConflict};
+pub use request_map::{RequestMap, RequestParts};
+pub use resolve::Resolve;
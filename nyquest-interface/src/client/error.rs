@@ -13,6 +13,9 @@ pub enum BuildClientError {
     /// An error occurred in the backend implementation.
     #[error("Error creating client: {0}")]
     BackendError(#[from] BackendError),
+    /// The backend does not support an option set on the client.
+    #[error("Unsupported option: {0}")]
+    Unsupported(&'static str),
 }
 
 /// Result type for client building operations.
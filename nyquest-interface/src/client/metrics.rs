@@ -0,0 +1,69 @@
+//! Hooks for collecting metrics of requests.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Labels identifying a request, with a low cardinality suitable for metrics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestLabels {
+    /// The uppercased request method, such as `GET`.
+    pub method: String,
+    /// The host of the request URL, or an empty string if the URL has none.
+    pub host: String,
+}
+
+/// Durations of a request, measured by the client from when it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestDurations {
+    /// Time until the status and headers of the response are received, or until the request
+    /// fails.
+    pub headers: Duration,
+    /// Time until the response body is read entirely, the response is dropped, or the request
+    /// fails.
+    pub total: Duration,
+}
+
+/// Sizes of the bodies of a request and its response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferSizes {
+    /// Size of the request body, if known in advance.
+    pub request_body: Option<u64>,
+    /// Bytes of the response body read by the caller.
+    pub response_body: u64,
+}
+
+/// Receives metrics of the requests of a client, e.g. to feed Prometheus histograms or StatsD
+/// counters.
+///
+/// The sink is called once per request, from the thread or task completing the request, so it
+/// should not block.
+pub trait MetricsSink: Send + Sync + 'static {
+    /// Called once the response body is read entirely, the response is dropped, or the request
+    /// fails. `status` is `None` if the request fails before a response is received.
+    fn on_request_complete(
+        &self,
+        labels: &RequestLabels,
+        durations: &RequestDurations,
+        sizes: &TransferSizes,
+        status: Option<u16>,
+    );
+}
+
+impl<T: MetricsSink + ?Sized> MetricsSink for Arc<T> {
+    fn on_request_complete(
+        &self,
+        labels: &RequestLabels,
+        durations: &RequestDurations,
+        sizes: &TransferSizes,
+        status: Option<u16>,
+    ) {
+        (**self).on_request_complete(labels, durations, sizes, status);
+    }
+}
+
+impl fmt::Debug for dyn MetricsSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsSink").finish_non_exhaustive()
+    }
+}
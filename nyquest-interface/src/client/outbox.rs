@@ -0,0 +1,73 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::header::{self, HeaderName};
+use crate::Bytes;
+
+/// Configures an outbox, which keeps the requests queued by a client in a file until they are
+/// delivered, so that changes made offline survive restarts of the app.
+#[derive(Debug, Clone)]
+pub struct OutboxConfig {
+    /// The file to keep the queued requests in.
+    pub path: PathBuf,
+    /// Maximum size of the body of a queued request.
+    pub max_body_size: u64,
+    /// Optional hook deciding what to do with requests the server answers with a conflict,
+    /// which are discarded without one.
+    pub on_conflict: Option<ConflictHook>,
+    /// Request headers that are not written to the file, so that requests loaded from it are
+    /// sent without them.
+    pub redact_headers: Vec<HeaderName>,
+}
+
+impl OutboxConfig {
+    /// Creates an outbox at the given path that accepts bodies up to 1 MiB, and redacts the
+    /// headers carrying credentials.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_body_size: 1 << 20,
+            on_conflict: None,
+            redact_headers: vec![
+                header::AUTHORIZATION,
+                header::COOKIE,
+                HeaderName::from_static("proxy-authorization"),
+            ],
+        }
+    }
+}
+
+/// A queued request the server answered with `409 Conflict` or `412 Precondition Failed`.
+#[derive(Debug, Clone)]
+pub struct OutboxConflict {
+    /// The method of the request.
+    pub method: String,
+    /// The URL the request was sent to.
+    pub url: String,
+    /// The status of the response.
+    pub status: u16,
+    /// The body of the response.
+    pub body: Bytes,
+}
+
+/// What to do with a queued request that conflicts with the state on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Drops the request and delivers the next ones.
+    Discard,
+    /// Keeps the request at the head of the outbox and stops delivering, such as until the app
+    /// has merged the changes from the server.
+    Keep,
+}
+
+/// A hook deciding what to do with the queued requests that conflict with the state on the
+/// server.
+#[derive(Clone)]
+pub struct ConflictHook(pub Arc<dyn Fn(&OutboxConflict) -> ConflictResolution + Send + Sync>);
+
+impl fmt::Debug for ConflictHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConflictHook").finish_non_exhaustive()
+    }
+}
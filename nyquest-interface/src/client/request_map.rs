@@ -0,0 +1,62 @@
+//! Hooks for rewriting requests.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::header::HeaderMap;
+
+/// The parts of a request rewritten by a [`RequestMap`] before the request is sent.
+#[derive(Debug)]
+pub struct RequestParts<'a> {
+    /// The request method, such as `GET`.
+    pub method: &'a str,
+    /// The URI of the request, which is resolved against the base URL of the client if relative.
+    pub uri: &'a mut Cow<'static, str>,
+    /// The headers of the request, sent in addition to the default headers of the client.
+    pub headers: &'a mut HeaderMap,
+}
+
+impl RequestParts<'_> {
+    /// Appends a parameter to the query string of the URI, percent-encoding the name and the
+    /// value.
+    pub fn append_query(&mut self, name: &str, value: &str) {
+        let uri = self.uri.to_mut();
+        let fragment = uri.find('#').map(|pos| uri.split_off(pos));
+        match uri.find('?') {
+            Some(pos) if pos + 1 < uri.len() && !uri.ends_with('&') => uri.push('&'),
+            Some(_) => {}
+            None => uri.push('?'),
+        }
+        push_query_encoded(uri, name);
+        uri.push('=');
+        push_query_encoded(uri, value);
+        if let Some(fragment) = fragment {
+            uri.push_str(&fragment);
+        }
+    }
+}
+
+/// A hook rewriting every request of a client, such as to prefix paths or add headers.
+#[derive(Clone)]
+pub struct RequestMap(pub Arc<dyn Fn(&mut RequestParts<'_>) + Send + Sync>);
+
+impl fmt::Debug for RequestMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestMap").finish_non_exhaustive()
+    }
+}
+
+/// Appends the string, percent-encoding all bytes but the unreserved ones of RFC 3986.
+fn push_query_encoded(out: &mut String, s: &str) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push('%');
+            out.push(HEX[(b >> 4) as usize] as char);
+            out.push(HEX[(b & 0xf) as usize] as char);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Hooks for resolving host names.
+
+use std::fmt;
+use std::io;
+use std::net::IpAddr;
+
+/// Resolves host names to addresses in place of the DNS lookups of the backend, e.g. with service
+/// discovery or another DNS client.
+///
+/// Closures taking the host and the port are resolvers as well.
+pub trait Resolve: Send + Sync + 'static {
+    /// Returns the addresses of the host to connect to on the port, in the order to try them.
+    ///
+    /// An empty list leaves the host to the lookup of the backend, and an error fails the
+    /// request. The resolver is called from the thread or task sending the request, so that
+    /// blocking in it blocks the request.
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<IpAddr>>;
+}
+
+impl<F> Resolve for F
+where
+    F: Fn(&str, u16) -> io::Result<Vec<IpAddr>> + Send + Sync + 'static,
+{
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<IpAddr>> {
+        self(host, port)
+    }
+}
+
+impl fmt::Debug for dyn Resolve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolve").finish_non_exhaustive()
+    }
+}
@@ -14,9 +14,15 @@ pub enum Error {
     /// The response body exceeds the maximum allowed size.
     #[error("Response body size exceeds max limit")]
     ResponseTooLarge,
+    /// The response headers exceed the maximum allowed size or count.
+    #[error("Response headers exceed max limit")]
+    ResponseHeadersTooLarge,
     /// The request timed out before completion.
     #[error("Request is not finished within timeout")]
     RequestTimeout,
+    /// The backend does not support a feature used by the request.
+    #[error("Unsupported by the backend: {0}")]
+    Unsupported(&'static str),
 }
 
 /// Result type for nyquest HTTP operations.
@@ -0,0 +1,260 @@
+//! HTTP header names and maps.
+//!
+//! Header names compare case-insensitively without being lowercased, and names of common headers
+//! are provided as constants that do not allocate.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use smallvec::SmallVec;
+
+/// The name of an HTTP header, compared case-insensitively.
+#[derive(Clone)]
+pub struct HeaderName(Cow<'static, str>);
+
+impl HeaderName {
+    /// Creates a header name from a static string without allocating.
+    pub const fn from_static(name: &'static str) -> Self {
+        Self(Cow::Borrowed(name))
+    }
+
+    /// Returns the header name as it was given.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for HeaderName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for HeaderName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for HeaderName {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for HeaderName {}
+
+impl PartialEq<str> for HeaderName {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+impl Hash for HeaderName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.0.bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+    }
+}
+
+impl fmt::Debug for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&'static str> for HeaderName {
+    fn from(name: &'static str) -> Self {
+        Self::from_static(name)
+    }
+}
+
+impl From<String> for HeaderName {
+    fn from(name: String) -> Self {
+        Self(Cow::Owned(name))
+    }
+}
+
+impl From<Cow<'static, str>> for HeaderName {
+    fn from(name: Cow<'static, str>) -> Self {
+        Self(name)
+    }
+}
+
+impl From<HeaderName> for String {
+    fn from(name: HeaderName) -> Self {
+        name.0.into_owned()
+    }
+}
+
+macro_rules! header_names {
+    ($($(#[$attr:meta])* $constant:ident => $name:literal,)*) => {
+        $(
+            $(#[$attr])*
+            pub const $constant: HeaderName = HeaderName::from_static($name);
+        )*
+    };
+}
+
+header_names! {
+    /// `accept`
+    ACCEPT => "accept",
+    /// `accept-encoding`
+    ACCEPT_ENCODING => "accept-encoding",
+    /// `accept-language`
+    ACCEPT_LANGUAGE => "accept-language",
+    /// `authorization`
+    AUTHORIZATION => "authorization",
+    /// `cache-control`
+    CACHE_CONTROL => "cache-control",
+    /// `content-encoding`
+    CONTENT_ENCODING => "content-encoding",
+    /// `content-language`
+    CONTENT_LANGUAGE => "content-language",
+    /// `content-length`
+    CONTENT_LENGTH => "content-length",
+    /// `content-type`
+    CONTENT_TYPE => "content-type",
+    /// `cookie`
+    COOKIE => "cookie",
+    /// `host`
+    HOST => "host",
+    /// `if-match`
+    IF_MATCH => "if-match",
+    /// `if-modified-since`
+    IF_MODIFIED_SINCE => "if-modified-since",
+    /// `if-none-match`
+    IF_NONE_MATCH => "if-none-match",
+    /// `origin`
+    ORIGIN => "origin",
+    /// `range`
+    RANGE => "range",
+    /// `referer`
+    REFERER => "referer",
+    /// `user-agent`
+    USER_AGENT => "user-agent",
+}
+
+type HeaderEntry = (HeaderName, Cow<'static, str>);
+
+/// An ordered multimap of HTTP headers.
+///
+/// A few headers are stored inline without allocating. Lookups match names case-insensitively.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: SmallVec<[HeaderEntry; 4]>,
+}
+
+impl HeaderMap {
+    /// Creates an empty header map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of header values.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds a header value, keeping the existing values of the header.
+    pub fn append(&mut self, name: impl Into<HeaderName>, value: impl Into<Cow<'static, str>>) {
+        self.entries.push((name.into(), value.into()));
+    }
+
+    /// Sets a header value, replacing all the existing values of the header.
+    pub fn insert(&mut self, name: impl Into<HeaderName>, value: impl Into<Cow<'static, str>>) {
+        let name = name.into();
+        self.remove(&name);
+        self.entries.push((name, value.into()));
+    }
+
+    /// Removes all the values of a header, and returns whether there were any.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|(n, _)| *n != *name);
+        self.entries.len() != len
+    }
+
+    /// Returns whether the header is present.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|(n, _)| *n == *name)
+    }
+
+    /// Returns the first value of a header.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _)| *n == *name)
+            .map(|(_, v)| &**v)
+    }
+
+    /// Returns all the values of a header in insertion order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(n, _)| *n == *name)
+            .map(|(_, v)| &**v)
+    }
+
+    /// Iterates over all the header values in insertion order.
+    pub fn iter(&self) -> std::slice::Iter<'_, HeaderEntry> {
+        self.entries.iter()
+    }
+}
+
+impl fmt::Debug for HeaderMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(n, v)| (n, v)))
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderMap {
+    type Item = &'a HeaderEntry;
+    type IntoIter = std::slice::Iter<'a, HeaderEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl IntoIterator for HeaderMap {
+    type Item = HeaderEntry;
+    type IntoIter = smallvec::IntoIter<[HeaderEntry; 4]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<N: Into<HeaderName>, V: Into<Cow<'static, str>>> FromIterator<(N, V)> for HeaderMap {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<N: Into<HeaderName>, V: Into<Cow<'static, str>>> Extend<(N, V)> for HeaderMap {
+    fn extend<I: IntoIterator<Item = (N, V)>>(&mut self, iter: I) {
+        self.entries
+            .extend(iter.into_iter().map(|(n, v)| (n.into(), v.into())));
+    }
+}
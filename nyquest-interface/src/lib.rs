@@ -27,16 +27,26 @@ pub mod r#async;
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 pub mod blocking;
 pub mod body;
+pub mod chunk_queue;
 pub mod client;
 mod error;
+pub mod header;
+mod network;
 #[doc(hidden)] // For nyquest facade only
 pub mod register;
 mod request;
+mod tls_info;
 
 pub use body::{Body, StreamReader};
 #[cfg(feature = "multipart")]
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 pub use body::{Part, PartBody};
+pub use bytes::Bytes;
 pub use error::{Error, Result};
+pub use header::{HeaderMap, HeaderName};
+pub use network::NetworkStatus;
 pub use register::register_backend;
-pub use request::{Method, Request};
+pub use request::{
+    CachePolicy, Method, Progress, ProgressCallback, Proxy, Request, Timings, TimingsCallback,
+};
+pub use tls_info::TlsInfo;
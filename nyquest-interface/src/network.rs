@@ -0,0 +1,13 @@
+//! Availability of the network, as seen by the backend.
+
+/// Whether the network is available to send requests over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkStatus {
+    /// A network path to the internet is available.
+    Online,
+    /// No network path is available, such as in airplane mode.
+    Offline,
+    /// The backend is not able to tell.
+    #[default]
+    Unknown,
+}
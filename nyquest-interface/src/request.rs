@@ -3,9 +3,13 @@
 //! This module provides the core request types used to construct and send
 //! HTTP requests through nyquest backends.
 
-use std::{borrow::Cow, fmt::Debug};
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::body::Body;
+use crate::header::HeaderMap;
 
 /// HTTP request methods supported by nyquest.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -24,6 +28,95 @@ pub enum Method {
     Other(Cow<'static, str>),
 }
 
+/// Progress of a transfer in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes of the request body sent so far
+    pub uploaded: u64,
+    /// Total size of the request body, if known
+    pub upload_total: Option<u64>,
+    /// Bytes of the response body received so far
+    pub downloaded: u64,
+    /// Total size of the response body, if known
+    pub download_total: Option<u64>,
+}
+
+/// A callback to report the progress of a request to.
+#[derive(Clone)]
+pub struct ProgressCallback {
+    /// The callback to invoke
+    pub callback: Arc<dyn Fn(Progress) + Send + Sync>,
+    /// Minimum interval between invocations, except for the final one
+    pub min_interval: Duration,
+}
+
+impl Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressCallback")
+            .field("min_interval", &self.min_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Timings of the phases of a completed transfer.
+///
+/// Phases that did not take place, such as connecting when a connection is reused, are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    /// Time spent resolving the host name
+    pub dns: Option<Duration>,
+    /// Time spent establishing the connection, including the TLS handshake
+    pub connect: Option<Duration>,
+    /// Time spent on the TLS handshake
+    pub tls: Option<Duration>,
+    /// Time spent sending the request
+    pub request: Option<Duration>,
+    /// Time between sending the request and receiving the first byte of the response
+    pub wait: Option<Duration>,
+    /// Time spent receiving the response
+    pub response: Option<Duration>,
+    /// Total time of the transfer, including redirects
+    pub total: Duration,
+    /// ALPN protocol identifier of the final response, such as `http/1.1` or `h2`
+    pub protocol: Option<String>,
+    /// Whether the final response was received over a reused connection
+    pub reused_connection: bool,
+}
+
+/// A callback to report the timings of a request to.
+#[derive(Clone)]
+pub struct TimingsCallback(pub Arc<dyn Fn(Timings) + Send + Sync>);
+
+impl Debug for TimingsCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimingsCallback").finish_non_exhaustive()
+    }
+}
+
+/// How a request uses cached responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Ignores cached responses and always loads from the origin.
+    Reload,
+    /// Uses a cached response regardless of its age or expiration, and loads from the origin
+    /// only if there is none.
+    PreferCache,
+}
+
+/// A proxy to send a request through, overriding the proxy of the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proxy {
+    /// The URL of the proxy, whose scheme selects the proxy protocol
+    pub url: Cow<'static, str>,
+}
+
+impl Proxy {
+    /// Creates a proxy with its URL, such as `http://proxy.example:3128`.
+    pub fn new(url: impl Into<Cow<'static, str>>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
 /// Represents an HTTP request to be sent by a nyquest client.
 pub struct Request<S> {
     /// The HTTP method for this request
@@ -31,9 +124,53 @@ pub struct Request<S> {
     /// The URI for this request, can be absolute or relative
     pub relative_uri: Cow<'static, str>,
     /// Additional HTTP headers to include with this request
-    pub additional_headers: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+    pub additional_headers: HeaderMap,
     /// Optional request body
     pub body: Option<Body<S>>,
+    /// Optional callback to report the transfer progress to
+    pub progress: Option<ProgressCallback>,
+    /// Optional cache policy overriding the one of the client
+    pub cache_policy: Option<CachePolicy>,
+    /// Optional callback to report the timings to once the transfer completes
+    pub timings: Option<TimingsCallback>,
+    /// Optional point in time by which the whole transfer must complete
+    pub deadline: Option<Instant>,
+    /// Optional proxy overriding the one of the client, where `Some(None)` bypasses any proxy
+    pub proxy: Option<Option<Proxy>>,
+    /// Whether the request may be sent in TLS 1.3 early data, if the client enables it
+    ///
+    /// The frontend only allows it for safe methods, as early data can be replayed.
+    pub early_data: bool,
+}
+
+impl<S> Request<S> {
+    /// Creates a `HEAD` request without a body, as sent to pre-connect to a host by default.
+    pub fn head(relative_uri: Cow<'static, str>) -> Self {
+        Self {
+            method: Method::Other(Cow::Borrowed("HEAD")),
+            relative_uri,
+            additional_headers: HeaderMap::new(),
+            body: None,
+            progress: None,
+            cache_policy: None,
+            timings: None,
+            deadline: None,
+            proxy: None,
+            early_data: false,
+        }
+    }
+
+    /// Returns the time the transfer may take, bounded by both the timeout of the client and the
+    /// deadline of the request.
+    pub fn effective_timeout(&self, request_timeout: Option<Duration>) -> Option<Duration> {
+        let remaining = self
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        match (request_timeout, remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        }
+    }
 }
 
 impl<S> Debug for Request<S>
@@ -46,6 +183,12 @@ where
             .field("relative_uri", &self.relative_uri)
             .field("additional_headers", &self.additional_headers)
             .field("body", &self.body)
+            .field("progress", &self.progress)
+            .field("cache_policy", &self.cache_policy)
+            .field("timings", &self.timings)
+            .field("deadline", &self.deadline)
+            .field("proxy", &self.proxy)
+            .field("early_data", &self.early_data)
             .finish()
     }
 }
@@ -60,6 +203,12 @@ where
             relative_uri: self.relative_uri.clone(),
             additional_headers: self.additional_headers.clone(),
             body: self.body.clone(),
+            progress: self.progress.clone(),
+            cache_policy: self.cache_policy,
+            timings: self.timings.clone(),
+            deadline: self.deadline,
+            proxy: self.proxy.clone(),
+            early_data: self.early_data,
         }
     }
 }
@@ -0,0 +1,17 @@
+//! TLS parameters of the connection a response is received on.
+
+/// TLS parameters negotiated for the connection a response is received on.
+///
+/// Each field is `None` when the backend is not able to tell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsInfo {
+    /// The protocol version, such as `TLSv1.3`.
+    pub protocol_version: Option<String>,
+    /// The cipher suite, such as `TLS_AES_128_GCM_SHA256`, which may be named after the TLS
+    /// library rather than IANA for older protocol versions.
+    pub cipher_suite: Option<String>,
+    /// The application protocol negotiated with ALPN, such as `h2`.
+    pub alpn_protocol: Option<String>,
+    /// Whether the TLS session was resumed from a previous connection.
+    pub resumed: Option<bool>,
+}
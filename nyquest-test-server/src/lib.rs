@@ -0,0 +1,182 @@
+//! A local HTTP/1.1 server for end-to-end tests of [`nyquest`] clients.
+//!
+//! The server runs on its own threads, so it serves blocking clients and async clients on any
+//! runtime alike. Routes are answered by closures, every request received is captured for
+//! assertions, and replies can inject faults such as delays, connection resets, truncated bodies
+//! and malformed responses to exercise error handling uniformly across backends.
+//!
+//! ## Quick Start
+//!
+//! ```no_run
+//! use nyquest_test_server::{Fault, Reply, TestServer};
+//!
+//! let server = TestServer::start().unwrap();
+//! server.route("GET", "/hello", |_req| Reply::ok().body("Hello"));
+//! server.route("GET", "/reset", |_req| Reply::ok().fault(Fault::Reset));
+//!
+//! let base_url = server.base_url();
+//! // Send requests with a client built with `ClientBuilder::base_url(base_url)`, then
+//! let requests = server.requests();
+//! ```
+//!
+//! Requests to paths without a route are answered with `404 Not Found`.
+//!
+//! [`nyquest`]: https://docs.rs/nyquest
+
+#![forbid(missing_docs)]
+
+use std::io::{self, BufReader};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod reply;
+mod request;
+
+pub use reply::{Fault, Reply};
+pub use request::CapturedRequest;
+
+type Handler = Arc<dyn Fn(&CapturedRequest) -> Reply + Send + Sync>;
+
+struct Route {
+    method: String,
+    path: String,
+    handler: Handler,
+}
+
+#[derive(Default)]
+struct Shared {
+    routes: Mutex<Vec<Route>>,
+    requests: Mutex<Vec<CapturedRequest>>,
+    closed: Mutex<Vec<usize>>,
+    shutdown: AtomicBool,
+}
+
+/// A running test server, which stops accepting connections when dropped.
+pub struct TestServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+impl TestServer {
+    /// Starts a server listening on a free port of the loopback interface.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let accept_shared = shared.clone();
+        thread::Builder::new()
+            .name("nyquest-test-server".into())
+            .spawn(move || accept_loop(listener, accept_shared))?;
+        Ok(Self { addr, shared })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the URL of the server, such as `http://127.0.0.1:12345`, to be used as the base
+    /// URL of clients.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answers requests with the given method and path with the handler. The query string is
+    /// ignored when matching the path, and the method `*` matches any method.
+    ///
+    /// Routes added later take precedence over earlier ones for the same method and path.
+    pub fn route(
+        &self,
+        method: impl Into<String>,
+        path: impl Into<String>,
+        handler: impl Fn(&CapturedRequest) -> Reply + Send + Sync + 'static,
+    ) -> &Self {
+        self.shared.routes.lock().unwrap().push(Route {
+            method: method.into(),
+            path: path.into(),
+            handler: Arc::new(handler),
+        });
+        self
+    }
+
+    /// Returns the requests received so far, in the order received.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.shared.requests.lock().unwrap().clone()
+    }
+
+    /// Forgets the requests received so far.
+    pub fn clear_requests(&self) {
+        self.shared.requests.lock().unwrap().clear();
+    }
+
+    /// Returns the connections closed so far, by either side, in the order closed. Connections
+    /// are numbered like [`CapturedRequest::connection`].
+    ///
+    /// A connection closed by the client is only noticed when the server reads from it, which is
+    /// after the reply to the pending request, if any, has been written.
+    pub fn closed_connections(&self) -> Vec<usize> {
+        self.shared.closed.lock().unwrap().clone()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // Wakes up the accepting thread
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+    for (connection, stream) in listener.incoming().enumerate() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let shared = shared.clone();
+        let _ = thread::Builder::new()
+            .name("nyquest-test-server-conn".into())
+            .spawn(move || {
+                if let Err(e) = serve_connection(stream, connection, &shared) {
+                    log::debug!("error serving connection {connection}: {e}");
+                }
+                shared.closed.lock().unwrap().push(connection);
+            });
+    }
+}
+
+fn serve_connection(stream: TcpStream, connection: usize, shared: &Shared) -> io::Result<()> {
+    // Replies are written in pieces, which must not wait for delayed acknowledgements
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    while let Some(req) = request::read_request(&mut reader, &mut writer, connection)? {
+        let handler = {
+            let routes = shared.routes.lock().unwrap();
+            routes
+                .iter()
+                .rev()
+                .find(|route| {
+                    (route.method == "*" || route.method.eq_ignore_ascii_case(&req.method))
+                        && route.path == req.path()
+                })
+                .map(|route| route.handler.clone())
+        };
+        shared.requests.lock().unwrap().push(req.clone());
+        let reply = match handler {
+            Some(handler) => handler(&req),
+            None => Reply::new(404).body("Not Found"),
+        };
+        let keep_alive = !req
+            .header("connection")
+            .is_some_and(|value| value.eq_ignore_ascii_case("close"));
+        if !reply.write(&mut writer, &req.method)? || !keep_alive {
+            break;
+        }
+    }
+    Ok(())
+}
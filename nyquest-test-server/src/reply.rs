@@ -0,0 +1,154 @@
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+/// A fault to inject into a reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// Resets the connection instead of responding.
+    Reset,
+    /// Closes the connection after sending the headers and the given number of bytes of the
+    /// body, whose full length is still declared in `content-length`.
+    Truncate(usize),
+    /// Sends the given bytes as-is instead of the response, then closes the connection.
+    Malformed(Vec<u8>),
+    /// Waits for the given duration between sending the headers and the body.
+    StallBody(Duration),
+}
+
+/// The reply of a route to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Option<Duration>,
+    fault: Option<Fault>,
+}
+
+impl Reply {
+    /// Creates an empty reply with the given status code.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+            delay: None,
+            fault: None,
+        }
+    }
+
+    /// Creates an empty `200 OK` reply.
+    pub fn ok() -> Self {
+        Self::new(200)
+    }
+
+    /// Adds a header, keeping the existing values of the header.
+    ///
+    /// `content-length` is added automatically unless set explicitly.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Waits for the given duration before responding.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Injects a fault into the reply.
+    pub fn fault(mut self, fault: Fault) -> Self {
+        self.fault = Some(fault);
+        self
+    }
+
+    /// Writes the reply, and returns whether the connection can be kept alive.
+    pub(crate) fn write(&self, stream: &mut TcpStream, method: &str) -> io::Result<bool> {
+        if let Some(delay) = self.delay {
+            thread::sleep(delay);
+        }
+        match &self.fault {
+            Some(Fault::Reset) => {
+                // Closing a socket that lingers for no time sends RST instead of FIN
+                socket2::SockRef::from(&*stream).set_linger(Some(Duration::ZERO))?;
+                return Ok(false);
+            }
+            Some(Fault::Malformed(raw)) => {
+                stream.write_all(raw)?;
+                stream.flush()?;
+                return Ok(false);
+            }
+            _ => {}
+        }
+
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        let has_length = self.headers.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+        });
+        if !has_length {
+            head.push_str(&format!("content-length: {}\r\n", self.body.len()));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes())?;
+
+        let body = if method.eq_ignore_ascii_case("HEAD") {
+            &[][..]
+        } else {
+            &self.body[..]
+        };
+        match &self.fault {
+            Some(Fault::Truncate(len)) => {
+                stream.write_all(&body[..body.len().min(*len)])?;
+                stream.flush()?;
+                Ok(false)
+            }
+            Some(Fault::StallBody(duration)) => {
+                stream.flush()?;
+                thread::sleep(*duration);
+                stream.write_all(body)?;
+                stream.flush()?;
+                Ok(true)
+            }
+            _ => {
+                stream.write_all(body)?;
+                stream.flush()?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
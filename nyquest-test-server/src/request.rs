@@ -0,0 +1,182 @@
+use std::io::{self, BufRead, Write};
+
+/// A request received by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedRequest {
+    /// The method, such as `GET`.
+    pub method: String,
+    /// The request target, including the query string if any.
+    pub uri: String,
+    /// The headers in the order received, with names as sent by the client.
+    pub headers: Vec<(String, String)>,
+    /// The body, with chunked transfer encoding decoded.
+    pub body: Vec<u8>,
+    /// The number of the connection the request is received on, counting from 0 in the order
+    /// accepted, to tell whether requests share a connection.
+    pub connection: usize,
+}
+
+impl CapturedRequest {
+    /// Returns the path of the request target without the query string.
+    pub fn path(&self) -> &str {
+        self.uri
+            .split_once('?')
+            .map_or(&*self.uri, |(path, _)| path)
+    }
+
+    /// Returns the query string of the request target, if any.
+    pub fn query(&self) -> Option<&str> {
+        self.uri.split_once('?').map(|(_, query)| query)
+    }
+
+    /// Returns the first value of a header, matching the name case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| &**v)
+    }
+
+    /// Returns the body as text, replacing invalid UTF-8 sequences.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(Some(line))
+}
+
+/// Reads the next request of the connection, or returns `None` once the client closes it.
+///
+/// Interim `100 Continue` responses are written to the writer when requested by the client.
+pub(crate) fn read_request(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    connection: usize,
+) -> io::Result<Option<CapturedRequest>> {
+    let Some(request_line) = read_line(reader)? else {
+        return Ok(None);
+    };
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(uri), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid_data("malformed request line"));
+    };
+    let mut req = CapturedRequest {
+        method: method.into(),
+        uri: uri.into(),
+        headers: vec![],
+        body: vec![],
+        connection,
+    };
+    loop {
+        let line = read_line(reader)?.ok_or_else(|| invalid_data("incomplete headers"))?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid_data("malformed header"))?;
+        req.headers.push((name.into(), value.trim().into()));
+    }
+
+    if req
+        .header("expect")
+        .is_some_and(|value| value.eq_ignore_ascii_case("100-continue"))
+    {
+        writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+        writer.flush()?;
+    }
+    let chunked = req
+        .header("transfer-encoding")
+        .is_some_and(|value| value.eq_ignore_ascii_case("chunked"));
+    if chunked {
+        req.body = read_chunked(reader)?;
+    } else if let Some(len) = req.header("content-length") {
+        let len: usize = len
+            .parse()
+            .map_err(|_| invalid_data("malformed content-length"))?;
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body)?;
+        req.body = body;
+    }
+    Ok(Some(req))
+}
+
+fn read_chunked(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = vec![];
+    loop {
+        let line = read_line(reader)?.ok_or_else(|| invalid_data("incomplete chunk"))?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| invalid_data("malformed chunk"))?;
+        if size == 0 {
+            // Skips the trailers
+            while !read_line(reader)?
+                .ok_or_else(|| invalid_data("incomplete trailers"))?
+                .is_empty()
+            {}
+            return Ok(body);
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        read_line(reader)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &[u8]) -> (Option<CapturedRequest>, Vec<u8>) {
+        let mut written = vec![];
+        let req = read_request(&mut &raw[..], &mut written, 0).unwrap();
+        (req, written)
+    }
+
+    #[test]
+    fn test_content_length() {
+        let (req, written) =
+            parse(b"POST /path?a=1 HTTP/1.1\r\nHost: x\r\nContent-Length: 5\r\n\r\nhello");
+        let req = req.unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path(), "/path");
+        assert_eq!(req.query(), Some("a=1"));
+        assert_eq!(req.header("host"), Some("x"));
+        assert_eq!(req.body, b"hello");
+        assert!(written.is_empty());
+    }
+
+    #[test]
+    fn test_chunked() {
+        let (req, _) = parse(
+            b"PUT / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\nX-Trailer: 1\r\n\r\n",
+        );
+        assert_eq!(req.unwrap().body, b"hello world");
+    }
+
+    #[test]
+    fn test_expect_continue() {
+        let (req, written) =
+            parse(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 0\r\n\r\n");
+        assert!(req.unwrap().body.is_empty());
+        assert_eq!(written, b"HTTP/1.1 100 Continue\r\n\r\n");
+    }
+
+    #[test]
+    fn test_closed() {
+        let (req, _) = parse(b"");
+        assert!(req.is_none());
+    }
+}
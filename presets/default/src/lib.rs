@@ -13,7 +13,7 @@
 //!
 //! Add the following at your program startup:
 //! ```no_run
-//! nyquest_backend::register();
+//! nyquest_preset::register();
 //! ```
 //! Based on the target platform, a [`nyquest`] backend will be registered as the default. Refer to
 //! the documentation of [`nyquest`] for usages.
@@ -5,7 +5,17 @@ use std::borrow::Cow;
 use nyquest_interface::r#async::BoxedStream;
 
 pub(crate) mod client;
+mod fallback;
+mod group;
+#[cfg(feature = "gzip")]
+mod gzip_upload;
+mod long_poll;
+#[cfg(feature = "outbox")]
+mod outbox;
+mod replay;
 mod response;
+mod sleep;
+mod tee;
 
 /// The Request Body type for async requests.
 pub type Body = crate::body::Body<BoxedStream>;
@@ -17,6 +27,8 @@ pub type Part = crate::body::Part<BoxedStream>;
 /// The multipart form part body type for async requests.
 #[cfg(feature = "multipart")]
 pub type PartBody = crate::body::PartBody<BoxedStream>;
+pub use group::RequestGroup;
+pub use long_poll::LongPoll;
 pub use response::Response;
 
 /// Shortcut method to quickly make a `GET` request.
@@ -32,7 +44,8 @@ pub async fn get(uri: impl Into<Cow<'static, str>>) -> crate::Result<Response> {
         .build_async()
         .await
         .map_err(|e| match e {
-            crate::client::BuildClientError::NoBackend => Err(e).unwrap(),
+            crate::client::BuildClientError::NoBackend
+            | crate::client::BuildClientError::Unsupported(_) => panic!("{e}"),
             crate::client::BuildClientError::BackendError(e) => e,
         })?;
     client.request(Request::get(uri)).await
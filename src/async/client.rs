@@ -1,8 +1,30 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
+use std::mem;
+use std::sync::Arc;
+use std::time::Duration;
 
-use nyquest_interface::{r#async::AnyAsyncClient, register::BACKEND};
+use nyquest_interface::client::{MetricsSink, RequestMap, UrlCredentials};
+use nyquest_interface::r#async::{AnyAsyncClient, AnyAsyncResponse};
+use nyquest_interface::register::BACKEND;
+use nyquest_interface::HeaderName;
 
 use super::response::Response;
+use super::sleep::sleep;
+#[cfg(feature = "cassette")]
+use crate::client::cassette::{Cassette, Playback};
+use crate::client::circuit_breaker::CircuitBreaker;
+use crate::client::connectivity::ConnectivityWait;
+#[cfg(feature = "fault-injection")]
+use crate::client::fault_injection::FaultInjector;
+use crate::client::har::HarRecorder;
+use crate::client::metrics::MetricsEntry;
+#[cfg(feature = "outbox")]
+use crate::client::outbox::Outbox;
+use crate::client::recording::observe_async;
+use crate::data_url::{handle_local_scheme, is_local_scheme};
+use crate::request_id::assign_request_id;
+use crate::url::normalize_request_url;
 use crate::{
     client::{BuildClientError, BuildClientResult},
     ClientBuilder,
@@ -15,39 +37,272 @@ use crate::{
 /// Depending on the backend implementation, it might holds a connection pool, a thread pool or
 /// other kind of resources internally, so it is advised that you create one and reuse it to avoid
 /// unnecessary overhead.
+///
+/// Cloning the client is cheap. Clones share the backend client, along with its session and
+/// connections, and the state of the client such as circuit breakers. The client is `Send` and
+/// `Sync`, so one client can be shared by the whole app.
+#[derive(Clone)]
 pub struct AsyncClient {
-    pub(super) client: Box<dyn AnyAsyncClient>,
+    pub(super) client: Arc<dyn AnyAsyncClient>,
+    allow_local_schemes: bool,
+    max_response_buffer_size: Option<u64>,
+    pub(super) base_url: Option<Arc<str>>,
+    url_credentials: UrlCredentials,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    har: Option<Arc<HarRecorder>>,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    request_id_header: Option<HeaderName>,
+    request_maps: Arc<[RequestMap]>,
+    request_timeout: Option<Duration>,
+    wait_for_connectivity: bool,
+    #[cfg(feature = "cassette")]
+    cassette: Option<Arc<Cassette>>,
+    #[cfg(feature = "outbox")]
+    pub(super) outbox: Option<Arc<Outbox>>,
+    #[cfg(feature = "fault-injection")]
+    fault_injector: Option<Arc<FaultInjector>>,
 }
 
 impl ClientBuilder {
     /// Build a new async client with the given options.
     pub async fn build_async(self) -> BuildClientResult<AsyncClient> {
+        let allow_local_schemes = self.options.allow_local_schemes;
+        let max_response_buffer_size = self.options.max_response_buffer_size;
+        let base_url = self.options.base_url.as_deref().map(Arc::from);
+        let url_credentials = self.options.url_credentials;
+        let circuit_breaker = self
+            .options
+            .circuit_breaker
+            .clone()
+            .map(|config| Arc::new(CircuitBreaker::new(config, self.options.base_url.clone())));
+        let har = match &self.options.har_path {
+            Some(path) => Some(Arc::new(
+                HarRecorder::create(path, &self.options)
+                    .map_err(|e| BuildClientError::BackendError(e.into()))?,
+            )),
+            None => None,
+        };
+        let metrics = self.options.metrics_sink.clone();
+        let request_id_header = self.options.request_id_header.clone();
+        let request_maps = Arc::from(self.options.request_maps.clone());
+        let request_timeout = self.options.request_timeout;
+        let wait_for_connectivity = self.options.wait_for_connectivity;
+        #[cfg(feature = "cassette")]
+        let cassette = match &self.options.cassette {
+            Some(config) => Some(Arc::new(
+                Cassette::open(config.clone(), &self.options)
+                    .map_err(|e| BuildClientError::BackendError(e.into()))?,
+            )),
+            None => None,
+        };
+        #[cfg(feature = "outbox")]
+        let outbox = match &self.options.outbox {
+            Some(config) => Some(Arc::new(
+                Outbox::open(config.clone(), &self.options)
+                    .map_err(|e| BuildClientError::BackendError(e.into()))?,
+            )),
+            None => None,
+        };
+        #[cfg(feature = "fault-injection")]
+        let fault_injector = self
+            .options
+            .fault_injection
+            .clone()
+            .map(|config| Arc::new(FaultInjector::new(config)));
         Ok(AsyncClient {
             client: BACKEND
                 .get()
                 .ok_or(BuildClientError::NoBackend)?
                 .create_async_client(self.options)
-                .await?,
+                .await?
+                .into(),
+            allow_local_schemes,
+            max_response_buffer_size,
+            base_url,
+            url_credentials,
+            circuit_breaker,
+            har,
+            metrics,
+            request_id_header,
+            request_maps,
+            request_timeout,
+            wait_for_connectivity,
+            #[cfg(feature = "cassette")]
+            cassette,
+            #[cfg(feature = "outbox")]
+            outbox,
+            #[cfg(feature = "fault-injection")]
+            fault_injector,
         })
     }
 }
 
 impl AsyncClient {
     /// Sends a request to the server and returns the response.
-    pub async fn request(&self, req: super::Request) -> crate::Result<Response> {
-        let res = self.client.request(req.inner).await?;
-        Ok(res.into())
+    ///
+    /// The extensions of the request are moved to the response, along with the
+    /// [`crate::RequestId`] if enabled.
+    ///
+    /// The request is cancelled if the future is dropped before it resolves, such as on a timeout
+    /// of the runtime, and so is the transfer of the body if the response is dropped before it is
+    /// read entirely.
+    pub async fn request(&self, mut req: super::Request) -> crate::Result<Response> {
+        let mut extensions = mem::take(&mut req.extensions);
+        if let Some(header) = &self.request_id_header {
+            assign_request_id(header, &mut req.inner, &mut extensions);
+        }
+        let send = async {
+            let res = match req.fallback.urls.is_empty() {
+                true => self.dispatch(req).await,
+                false => self.dispatch_with_fallbacks(req).await,
+            };
+            #[cfg(feature = "tracing")]
+            crate::request_id::trace_failure(&res);
+            res
+        };
+        #[cfg(feature = "tracing")]
+        let send =
+            tracing::Instrument::instrument(send, crate::request_id::request_span(&extensions));
+        let mut res = send.await?;
+        res.extensions = extensions;
+        Ok(res)
+    }
+
+    pub(super) async fn dispatch(&self, mut req: super::Request) -> crate::Result<Response> {
+        req.apply_maps(&self.request_maps);
+        req.allow_early_data();
+        if let Some(res) = handle_local_scheme(
+            &req.inner.relative_uri,
+            self.allow_local_schemes,
+            self.max_response_buffer_size,
+        )? {
+            return Ok(Response::from(Box::new(res) as Box<dyn AnyAsyncResponse>));
+        }
+        normalize_request_url(
+            &mut req.inner,
+            self.base_url.as_deref(),
+            self.url_credentials,
+        )?;
+        if req.remaining_budget() == Some(Duration::ZERO) {
+            return Err(crate::Error::RequestTimeout);
+        }
+        // Backends with native support hold the request back themselves
+        if self.wait_for_connectivity && !self.client.waits_for_connectivity() {
+            let mut connectivity = ConnectivityWait::new(&req.inner, self.request_timeout);
+            while let Some(wait) = connectivity.next(self.client.network_status())? {
+                sleep(wait).await;
+            }
+            connectivity.finish(&mut req.inner);
+        }
+        #[cfg(feature = "cassette")]
+        let recording = match &self.cassette {
+            Some(cassette) => match cassette.play(&req.inner)? {
+                Playback::Replay(res) => {
+                    return Ok(Response::from(Box::new(res) as Box<dyn AnyAsyncResponse>))
+                }
+                Playback::Record(entry) => Some(entry),
+            },
+            None => None,
+        };
+        let permit = match &self.circuit_breaker {
+            Some(circuit_breaker) => circuit_breaker.acquire(&req.inner.relative_uri)?,
+            None => None,
+        };
+        let entry = self.har.as_ref().map(|har| har.start(&req.inner));
+        let measurement = self
+            .metrics
+            .as_ref()
+            .map(|sink| MetricsEntry::start(sink, &req.inner));
+        let res = self.send(req.inner).await;
+        if let Some(permit) = permit {
+            permit.finish(&res);
+        }
+        let res = match entry {
+            Some(entry) => observe_async(entry, res),
+            None => res,
+        };
+        let res = match measurement {
+            Some(measurement) => observe_async(measurement, res),
+            None => res,
+        };
+        #[cfg(feature = "cassette")]
+        let res = match recording {
+            Some(entry) => observe_async(entry, res),
+            None => res,
+        };
+        Ok(res?.into())
     }
-}
 
-impl Clone for AsyncClient {
-    fn clone(&self) -> Self {
-        Self {
-            client: self.client.clone_boxed(),
+    /// Sends the request with the backend, injecting the planned faults if any.
+    async fn send(
+        &self,
+        req: nyquest_interface::r#async::Request,
+    ) -> nyquest_interface::Result<Box<dyn AnyAsyncResponse>> {
+        #[cfg(feature = "fault-injection")]
+        if let Some(fault_injector) = &self.fault_injector {
+            let faults = fault_injector.plan();
+            if let Some(latency) = faults.latency {
+                sleep(latency).await;
+            }
+            if let Some(e) = faults.error() {
+                return Err(e);
+            }
+            return faults.truncate_async(self.client.request(req).await);
         }
+        self.client.request(req).await
+    }
+
+    /// Connects to the host of the URI ahead of the first request to it, to cut the latency of
+    /// that request.
+    ///
+    /// The URI is resolved against the base URL like that of a request. `data:` and `file:` URLs
+    /// are ignored.
+    ///
+    /// # Note
+    ///
+    /// All backends send a `HEAD` request to the URI and discard the response, leaving the
+    /// connection in their pools for the next request, so the server sees a real request.
+    pub async fn preconnect(&self, uri: impl Into<Cow<'static, str>>) -> crate::Result<()> {
+        let uri = uri.into();
+        if is_local_scheme(&uri) {
+            return Ok(());
+        }
+        Ok(self.client.preconnect(uri).await?)
+    }
+
+    /// Discards the host name lookups cached by the client, so that later requests look the
+    /// hosts up again, e.g. to follow a DNS failover right away.
+    ///
+    /// Clones of the client share the cache.
+    ///
+    /// # Note
+    ///
+    /// Only `curl` backend supports flushing the DNS cache. Other backends, which rely on the
+    /// system DNS cache, fail with [`crate::Error::Unsupported`].
+    pub fn flush_dns_cache(&self) -> crate::Result<()> {
+        Ok(self.client.flush_dns_cache()?)
+    }
+
+    /// Returns whether the network is available, e.g. to pause a sync loop in airplane mode
+    /// instead of failing requests.
+    ///
+    /// # Note
+    ///
+    /// `nsurlsession` backend watches the network path, `winrt` backend asks for the internet
+    /// connectivity level of the connection profile, and `curl` backend looks for a default route
+    /// over an interface that is up on Linux. Other backends and platforms report
+    /// [`crate::NetworkStatus::Unknown`].
+    pub fn network_status(&self) -> crate::NetworkStatus {
+        self.client.network_status()
     }
 }
 
+// Sharing a client across tasks and threads is part of the API
+const _: fn() = || {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<AsyncClient>();
+};
+
 impl Debug for AsyncClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.client.describe(f)
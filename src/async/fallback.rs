@@ -0,0 +1,74 @@
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::Poll;
+
+use super::client::AsyncClient;
+use super::sleep::sleep;
+use super::{Request, Response};
+use crate::client::fallback::{is_failover, Candidates};
+
+type ResponseFuture<'a> = Pin<Box<dyn Future<Output = crate::Result<Response>> + Send + 'a>>;
+
+impl AsyncClient {
+    /// Sends the request to each of its URLs in turn until one responds, racing them if a stagger
+    /// delay is set.
+    pub(super) async fn dispatch_with_fallbacks(&self, req: Request) -> crate::Result<Response> {
+        let stagger = req.fallback.stagger;
+        let mut candidates = Candidates::new(req);
+        let Some(stagger) = stagger else {
+            loop {
+                let req = candidates
+                    .next()
+                    .expect("the URL of the request is always a candidate");
+                match self.dispatch(req).await {
+                    Err(e) if is_failover(&e) && candidates.has_next() => continue,
+                    res => return res,
+                }
+            }
+        };
+
+        let mut in_flight: Vec<ResponseFuture<'_>> = vec![];
+        let mut timer: Option<Pin<Box<dyn Future<Output = ()> + Send>>> = None;
+        let mut last_error = None;
+        let mut failed = false;
+        poll_fn(|cx| loop {
+            let timer_fired = timer
+                .as_mut()
+                .is_some_and(|timer| timer.as_mut().poll(cx).is_ready());
+            if failed || timer_fired || in_flight.is_empty() {
+                failed = false;
+                timer = None;
+                match candidates.next() {
+                    Some(req) => {
+                        in_flight.push(Box::pin(self.dispatch(req)));
+                        if candidates.has_next() {
+                            timer = Some(Box::pin(sleep(stagger)));
+                        }
+                    }
+                    None if in_flight.is_empty() => {
+                        let e = last_error.take();
+                        return Poll::Ready(Err(e.expect("all the candidates have failed")));
+                    }
+                    None => {}
+                }
+            }
+            let mut i = 0;
+            while i < in_flight.len() {
+                match in_flight[i].as_mut().poll(cx) {
+                    Poll::Ready(Err(e)) if is_failover(&e) => {
+                        drop(in_flight.swap_remove(i));
+                        last_error = Some(e);
+                        failed = true;
+                    }
+                    Poll::Ready(res) => return Poll::Ready(res),
+                    Poll::Pending => i += 1,
+                }
+            }
+            // Send the request to the next URL in place of the failed one
+            if !failed {
+                return Poll::Pending;
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,177 @@
+use std::future::{poll_fn, Future};
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use super::client::AsyncClient;
+use super::{Request, Response};
+
+type ResponseFuture<'a> = Pin<Box<dyn Future<Output = crate::Result<Response>> + Send + 'a>>;
+
+/// A group of requests sent concurrently with the same client.
+///
+/// Created by [`AsyncClient::request_group`].
+#[must_use = "a request group does nothing until sent"]
+pub struct RequestGroup<'a> {
+    client: &'a AsyncClient,
+    requests: Vec<Request>,
+    max_concurrency: Option<NonZeroUsize>,
+    timeout: Option<Duration>,
+}
+
+impl AsyncClient {
+    /// Creates a group of requests to be sent concurrently.
+    pub fn request_group(&self, requests: impl IntoIterator<Item = Request>) -> RequestGroup<'_> {
+        RequestGroup {
+            client: self,
+            requests: requests.into_iter().collect(),
+            max_concurrency: None,
+            timeout: None,
+        }
+    }
+
+    /// Sends the requests concurrently and returns the responses in the same order.
+    ///
+    /// This is a shortcut for [`AsyncClient::request_group`] without limits. See
+    /// [`RequestGroup::send`] for details.
+    pub async fn join_all(
+        &self,
+        requests: impl IntoIterator<Item = Request>,
+    ) -> crate::Result<Vec<Response>> {
+        self.request_group(requests).send().await
+    }
+}
+
+impl RequestGroup<'_> {
+    /// Sets the maximum number of requests in flight at the same time. All requests are sent at
+    /// once by default.
+    pub fn max_concurrency(mut self, limit: NonZeroUsize) -> Self {
+        self.max_concurrency = Some(limit);
+        self
+    }
+
+    /// Sets the timeout for all the responses of the group to be received.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends the requests and returns the responses in the same order as the requests.
+    ///
+    /// Fails with the first error of any request, in which case the requests still in flight are
+    /// cancelled and the remaining ones are not sent. [`crate::Error::RequestTimeout`] is
+    /// returned if the timeout elapses before all the responses are received.
+    ///
+    /// # Note
+    ///
+    /// Only the responses are awaited, not their bodies, which are read afterwards as usual. A
+    /// timeout spawns a thread to keep track of the deadline, so that the group does not depend
+    /// on any async runtime.
+    pub async fn send(self) -> crate::Result<Vec<Response>> {
+        let RequestGroup {
+            client,
+            requests,
+            max_concurrency,
+            timeout,
+        } = self;
+        let mut deadline = timeout.map(Deadline::after).transpose()?;
+        let max_concurrency = max_concurrency.map_or(usize::MAX, NonZeroUsize::get);
+        let mut responses: Vec<Option<Response>> = (0..requests.len()).map(|_| None).collect();
+        let mut pending = requests.into_iter().enumerate();
+        let mut in_flight: Vec<(usize, ResponseFuture<'_>)> = vec![];
+        poll_fn(|cx| {
+            if let Some(deadline) = &mut deadline {
+                if deadline.poll_expired(cx).is_ready() {
+                    return Poll::Ready(Err(crate::Error::RequestTimeout));
+                }
+            }
+            loop {
+                while in_flight.len() < max_concurrency {
+                    let Some((index, req)) = pending.next() else {
+                        break;
+                    };
+                    in_flight.push((index, Box::pin(client.request(req))));
+                }
+                if in_flight.is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                let mut completed = false;
+                let mut i = 0;
+                while i < in_flight.len() {
+                    match in_flight[i].1.as_mut().poll(cx) {
+                        Poll::Ready(res) => {
+                            let (index, _) = in_flight.swap_remove(i);
+                            responses[index] = Some(res?);
+                            completed = true;
+                        }
+                        Poll::Pending => i += 1,
+                    }
+                }
+                // Send more requests in place of the completed ones
+                if !completed {
+                    return Poll::Pending;
+                }
+            }
+        })
+        .await?;
+        Ok(responses.into_iter().flatten().collect())
+    }
+}
+
+#[derive(Default)]
+struct DeadlineState {
+    expired: bool,
+    cancelled: bool,
+    waker: Option<Waker>,
+}
+
+/// Wakes the group once the timeout elapses, with a thread waiting on a condition variable.
+struct Deadline {
+    shared: Arc<(Mutex<DeadlineState>, Condvar)>,
+}
+
+impl Deadline {
+    fn after(timeout: Duration) -> std::io::Result<Self> {
+        let deadline = Instant::now() + timeout;
+        let shared = Arc::new((Mutex::new(DeadlineState::default()), Condvar::new()));
+        let timer_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("nyquest-group-deadline".into())
+            .spawn(move || {
+                let (state, cvar) = &*timer_shared;
+                let mut state = state.lock().unwrap();
+                while !state.cancelled {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        state.expired = true;
+                        if let Some(waker) = state.waker.take() {
+                            waker.wake();
+                        }
+                        break;
+                    }
+                    state = cvar.wait_timeout(state, deadline - now).unwrap().0;
+                }
+            })?;
+        Ok(Self { shared })
+    }
+
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.shared.0.lock().unwrap();
+        if state.expired {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        // Ends the timer thread early
+        let (state, cvar) = &*self.shared;
+        state.lock().unwrap().cancelled = true;
+        cvar.notify_one();
+    }
+}
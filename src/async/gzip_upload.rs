@@ -0,0 +1,46 @@
+use super::client::AsyncClient;
+use super::{Request, Response};
+use crate::client::gzip_upload::{accepts_gzip, probe_request, request_host, Plan};
+use crate::client::GzipUpload;
+
+impl AsyncClient {
+    /// Sends the request with its body compressed with gzip if the server accepts it, as found
+    /// out and remembered by the uploader.
+    ///
+    /// If the server rejects the compressed body with `415 Unsupported Media Type`, the request
+    /// is sent again with the body uncompressed.
+    pub async fn request_gzip(
+        &self,
+        mut req: Request,
+        upload: &GzipUpload,
+    ) -> crate::Result<Response> {
+        let host = request_host(self.base_url.as_deref(), &req);
+        let host = host.as_deref();
+        match upload.plan(&req, host) {
+            Plan::Identity => return self.request(req).await,
+            Plan::Probe => {
+                let supported = match self.request(probe_request(&req)).await {
+                    Ok(res) => accepts_gzip(&res.get_header("accept-encoding")?),
+                    Err(_) => false,
+                };
+                upload.record(host, supported);
+                if !supported {
+                    return self.request(req).await;
+                }
+            }
+            Plan::Compress => {}
+        }
+        let fallback = req.try_clone();
+        upload.compress(&mut req)?;
+        let res = self.request(req).await?;
+        if res.status() != 415 {
+            upload.record(host, true);
+            return Ok(res);
+        }
+        upload.record(host, false);
+        match fallback {
+            Some(req) => self.request(req).await,
+            None => Ok(res),
+        }
+    }
+}
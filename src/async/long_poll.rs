@@ -0,0 +1,58 @@
+use super::client::AsyncClient;
+use super::sleep::sleep;
+use super::{BoxedStream, Request, Response};
+use crate::client::long_poll::{Outcome, PollState};
+use crate::client::LongPollConfig;
+use crate::Validators;
+
+/// Responses of a request re-issued each time it completes, for APIs using long polling.
+///
+/// Created by [`AsyncClient::long_poll`].
+pub struct LongPoll<'a> {
+    client: &'a AsyncClient,
+    state: PollState<BoxedStream>,
+}
+
+impl AsyncClient {
+    /// Polls the server with the request, re-issuing it each time it completes.
+    ///
+    /// Each poll carries `if-none-match` and `if-modified-since` headers from the last successful
+    /// response, if present. `304 Not Modified` responses and polls timed out by
+    /// [`LongPollConfig::poll_timeout`] are not yielded but followed by another poll right away.
+    /// Errors are yielded and followed by another poll after an exponential backoff. Once the
+    /// deadline of the request expires, [`crate::Error::RequestTimeout`] is yielded instead.
+    ///
+    /// Fails with [`crate::Error::Unsupported`] if the request has a streamed or multipart body,
+    /// which cannot be sent more than once. Streamed bodies made replayable with
+    /// `Body::buffered_replayable` are accepted, and polls after the first one fail the same way
+    /// if the body exceeded the limit.
+    pub fn long_poll(&self, req: Request, config: LongPollConfig) -> crate::Result<LongPoll<'_>> {
+        Ok(LongPoll {
+            client: self,
+            state: PollState::new(req, config)?,
+        })
+    }
+}
+
+impl LongPoll<'_> {
+    /// Waits for the next response or error. Polling resumes whenever this method is called
+    /// again.
+    pub async fn next(&mut self) -> crate::Result<Response> {
+        loop {
+            let delay = self.state.delay();
+            if !delay.is_zero() {
+                sleep(delay).await;
+            }
+            let req = self.state.next_request()?;
+            let res = self.client.request(req).await;
+            match self.state.handle(res, validators) {
+                Outcome::Yield(res) => return res,
+                Outcome::Repoll => continue,
+            }
+        }
+    }
+}
+
+fn validators(res: &Response) -> (u16, Validators) {
+    (res.status(), res.validators().unwrap_or_default())
+}
@@ -0,0 +1,49 @@
+use super::client::AsyncClient;
+use super::Request;
+use crate::client::outbox::{self, Outbox};
+
+impl AsyncClient {
+    /// Queues the request in the outbox configured by [`crate::ClientBuilder::outbox`], then
+    /// delivers the queued requests like [`AsyncClient::flush_outbox`]. Returns the number of
+    /// requests still queued.
+    ///
+    /// The request is kept for a later flush if it cannot be delivered now, so failures to
+    /// deliver are not returned. Fails with [`crate::Error::Unsupported`] if the client has no
+    /// outbox or the request has a streamed or multipart body, with
+    /// [`crate::Error::RequestBodyTooLarge`] if the body exceeds
+    /// [`crate::client::OutboxConfig::max_body_size`], and with [`crate::Error::Io`] if the file
+    /// cannot be written.
+    pub async fn enqueue(&self, req: Request) -> crate::Result<usize> {
+        let outbox = outbox::configured(&self.outbox)?;
+        outbox.push(req)?;
+        Ok(self.flush_outbox().await.unwrap_or_else(|_| outbox.len()))
+    }
+
+    /// Delivers the requests queued in the outbox in order while the network is available, and
+    /// returns the number of requests still queued. Call it once the network comes back, e.g.
+    /// when [`AsyncClient::network_status`] no longer reports [`crate::NetworkStatus::Offline`].
+    ///
+    /// Requests answered with `408`, `429` or a server error stay queued and stop the delivery,
+    /// as do those answered with `409 Conflict` or `412 Precondition Failed` that the conflict
+    /// hook keeps. Other responses remove the request. If a request fails, it stays queued and
+    /// the error is returned.
+    pub async fn flush_outbox(&self) -> crate::Result<usize> {
+        let outbox = outbox::configured(&self.outbox)?;
+        let Some(flush) = outbox.start_flush() else {
+            // The requests are being delivered by another call
+            return Ok(outbox.len());
+        };
+        while let Some(req) = outbox.front(&flush, self.network_status()) {
+            let res = self.request(req).await?;
+            let status = res.status();
+            let body = match Outbox::needs_body(status) {
+                true => Some(res.bytes().await?),
+                false => None,
+            };
+            if !outbox.settle(&flush, status, body)? {
+                break;
+            }
+        }
+        Ok(outbox.len())
+    }
+}
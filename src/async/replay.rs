@@ -0,0 +1,100 @@
+use std::io::{self, Read as _, Seek as _, SeekFrom};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncRead, AsyncSeek};
+use nyquest_interface::r#async::BoxedStream;
+use nyquest_interface::{Body as BodyImpl, Bytes, StreamReader};
+
+use crate::replay::ReplayBuffer;
+
+/// A stream copying what the backend reads into a replay buffer.
+struct ReplayTee {
+    inner: Pin<BoxedStream>,
+    buffer: Arc<ReplayBuffer<BoxedStream>>,
+}
+
+impl AsyncRead for ReplayTee {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let len = std::task::ready!(this.inner.as_mut().poll_read(cx, buf))?;
+        if len > 0 || !buf.is_empty() {
+            this.buffer.record(&buf[..len]);
+        }
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncSeek for ReplayTee {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        let pos = std::task::ready!(this.inner.as_mut().poll_seek(cx, pos))?;
+        this.buffer.seek(pos);
+        Poll::Ready(Ok(pos))
+    }
+}
+
+/// A stream over a buffered body.
+struct MemoryStream(io::Cursor<Bytes>);
+
+impl AsyncRead for MemoryStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.read(buf))
+    }
+}
+
+impl AsyncSeek for MemoryStream {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        Poll::Ready(self.get_mut().0.seek(pos))
+    }
+}
+
+fn memory_stream(content: Bytes) -> BoxedStream {
+    Box::new(MemoryStream(io::Cursor::new(content)))
+}
+
+impl crate::body::Body<BoxedStream> {
+    /// Keeps a copy of a streaming body in memory as it is sent, up to `limit` bytes, so that
+    /// the request can be sent again, e.g. by [`AsyncClient::long_poll`].
+    ///
+    /// A body larger than the limit is sent as usual, but cannot be replayed. Other bodies are
+    /// replayable already and returned unchanged.
+    ///
+    /// [`AsyncClient::long_poll`]: crate::AsyncClient::long_poll
+    pub fn buffered_replayable(mut self, limit: u64) -> Self {
+        let BodyImpl::Stream(StreamReader {
+            stream,
+            content_length,
+        }) = self.inner
+        else {
+            return self;
+        };
+        let buffer = Arc::new(ReplayBuffer::new(limit, content_length, memory_stream));
+        self.inner = BodyImpl::Stream(StreamReader {
+            stream: Box::new(ReplayTee {
+                inner: Box::into_pin(stream),
+                buffer: buffer.clone(),
+            }),
+            content_length,
+        });
+        self.replay = Some(buffer);
+        self
+    }
+}
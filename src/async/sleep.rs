@@ -0,0 +1,52 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Waits for the duration on a separate thread, which keeps the client independent of any async
+/// runtime.
+pub(crate) fn sleep(duration: Duration) -> impl Future<Output = ()> {
+    #[derive(Default)]
+    struct State {
+        done: bool,
+        waker: Option<Waker>,
+    }
+
+    struct Sleep {
+        duration: Duration,
+        state: Option<Arc<Mutex<State>>>,
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let duration = self.duration;
+            let state = self.state.get_or_insert_with(|| {
+                let state = Arc::new(Mutex::new(State::default()));
+                let timer_state = state.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(duration);
+                    let mut state = timer_state.lock().unwrap_or_else(PoisonError::into_inner);
+                    state.done = true;
+                    if let Some(waker) = state.waker.take() {
+                        waker.wake();
+                    }
+                });
+                state
+            });
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.done {
+                return Poll::Ready(());
+            }
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    Sleep {
+        duration,
+        state: None,
+    }
+}
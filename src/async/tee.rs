@@ -0,0 +1,83 @@
+use std::fmt;
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+
+use futures_io::AsyncWrite;
+use nyquest_interface::r#async::{AnyAsyncResponse, AsyncResponse};
+use nyquest_interface::Bytes;
+
+use crate::media_type::decode_text;
+
+/// A response whose body is copied to a writer as it is read.
+pub(super) struct TeeResponse<W> {
+    inner: Box<dyn AnyAsyncResponse>,
+    // Only accessed with `&mut self`, which makes the response `Sync` for any `Send` writer
+    writer: Mutex<W>,
+}
+
+impl<W: AsyncWrite + Unpin> TeeResponse<W> {
+    pub(super) fn new(inner: Box<dyn AnyAsyncResponse>, writer: W) -> Self {
+        Self {
+            inner,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Writes the whole body to the writer and flushes it.
+    async fn copy(&mut self, mut body: &[u8]) -> io::Result<()> {
+        let writer = self
+            .writer
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        while !body.is_empty() {
+            let len = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, body)).await?;
+            if len == 0 {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            body = &body[len..];
+        }
+        poll_fn(|cx| Pin::new(&mut *writer).poll_flush(cx)).await
+    }
+}
+
+impl<W: AsyncWrite + Unpin + Send + 'static> AsyncResponse for TeeResponse<W> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TeeResponse(")?;
+        self.inner.describe(f)?;
+        write!(f, ")")
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    async fn text(&mut self) -> nyquest_interface::Result<String> {
+        let bytes = AsyncResponse::bytes(self).await?;
+        let content_type = self.inner.get_header("content-type")?;
+        Ok(decode_text(&bytes, content_type.first().map(|s| &**s)))
+    }
+
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        let bytes = self.inner.bytes().await?;
+        self.copy(&bytes).await?;
+        Ok(bytes)
+    }
+}
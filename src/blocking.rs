@@ -8,7 +8,15 @@ use std::borrow::Cow;
 use nyquest_interface::blocking::BoxedStream;
 
 pub(crate) mod client;
+mod fallback;
+#[cfg(feature = "gzip")]
+mod gzip_upload;
+mod long_poll;
+#[cfg(feature = "outbox")]
+mod outbox;
+mod replay;
 mod response;
+mod tee;
 
 /// The Request Body type for blocking requests.
 pub type Body = crate::body::Body<BoxedStream>;
@@ -20,6 +28,7 @@ pub type Part = crate::body::Part<BoxedStream>;
 /// The multipart form part body type for blocking requests.
 #[cfg(feature = "multipart")]
 pub type PartBody = crate::body::PartBody<BoxedStream>;
+pub use long_poll::LongPoll;
 pub use response::Response;
 
 /// Shortcut method to quickly make a `GET` request.
@@ -34,7 +43,8 @@ pub fn get(uri: impl Into<Cow<'static, str>>) -> crate::Result<Response> {
     let client = crate::client::ClientBuilder::default()
         .build_blocking()
         .map_err(|e| match e {
-            crate::client::BuildClientError::NoBackend => Err(e).unwrap(),
+            crate::client::BuildClientError::NoBackend
+            | crate::client::BuildClientError::Unsupported(_) => panic!("{e}"),
             crate::client::BuildClientError::BackendError(e) => e,
         })?;
     client.request(Request::get(uri))
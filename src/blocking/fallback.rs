@@ -0,0 +1,19 @@
+use super::client::BlockingClient;
+use super::{Request, Response};
+use crate::client::fallback::{is_failover, Candidates};
+
+impl BlockingClient {
+    /// Sends the request to each of its URLs in turn until one responds.
+    pub(super) fn dispatch_with_fallbacks(&self, req: Request) -> crate::Result<Response> {
+        let mut candidates = Candidates::new(req);
+        loop {
+            let req = candidates
+                .next()
+                .expect("the URL of the request is always a candidate");
+            match self.dispatch(req) {
+                Err(e) if is_failover(&e) && candidates.has_next() => continue,
+                res => return res,
+            }
+        }
+    }
+}
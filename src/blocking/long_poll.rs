@@ -0,0 +1,59 @@
+use std::thread;
+
+use super::client::BlockingClient;
+use super::{BoxedStream, Request, Response};
+use crate::client::long_poll::{Outcome, PollState};
+use crate::client::LongPollConfig;
+use crate::Validators;
+
+/// Responses of a request re-issued each time it completes, for APIs using long polling.
+///
+/// Created by [`BlockingClient::long_poll`]. The iterator never ends on its own.
+pub struct LongPoll<'a> {
+    client: &'a BlockingClient,
+    state: PollState<BoxedStream>,
+}
+
+impl BlockingClient {
+    /// Polls the server with the request, re-issuing it each time it completes.
+    ///
+    /// Each poll carries `if-none-match` and `if-modified-since` headers from the last successful
+    /// response, if present. `304 Not Modified` responses and polls timed out by
+    /// [`LongPollConfig::poll_timeout`] are not yielded but followed by another poll right away.
+    /// Errors are yielded and followed by another poll after an exponential backoff. Once the
+    /// deadline of the request expires, [`crate::Error::RequestTimeout`] is yielded instead.
+    ///
+    /// Fails with [`crate::Error::Unsupported`] if the request has a streamed or multipart body,
+    /// which cannot be sent more than once. Streamed bodies made replayable with
+    /// `Body::buffered_replayable` are accepted, and polls after the first one fail the same way
+    /// if the body exceeded the limit.
+    pub fn long_poll(&self, req: Request, config: LongPollConfig) -> crate::Result<LongPoll<'_>> {
+        Ok(LongPoll {
+            client: self,
+            state: PollState::new(req, config)?,
+        })
+    }
+}
+
+impl Iterator for LongPoll<'_> {
+    type Item = crate::Result<Response>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            thread::sleep(self.state.delay());
+            let req = match self.state.next_request() {
+                Ok(req) => req,
+                Err(e) => return Some(Err(e)),
+            };
+            let res = self.client.request(req);
+            match self.state.handle(res, validators) {
+                Outcome::Yield(res) => return Some(res),
+                Outcome::Repoll => continue,
+            }
+        }
+    }
+}
+
+fn validators(res: &Response) -> (u16, Validators) {
+    (res.status(), res.validators().unwrap_or_default())
+}
@@ -0,0 +1,64 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use nyquest_interface::blocking::BoxedStream;
+use nyquest_interface::{Body as BodyImpl, Bytes, StreamReader};
+
+use crate::replay::ReplayBuffer;
+
+/// A stream copying what the backend reads into a replay buffer.
+struct ReplayTee {
+    inner: BoxedStream,
+    buffer: Arc<ReplayBuffer<BoxedStream>>,
+}
+
+impl Read for ReplayTee {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if len > 0 || !buf.is_empty() {
+            self.buffer.record(&buf[..len]);
+        }
+        Ok(len)
+    }
+}
+
+impl Seek for ReplayTee {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        self.buffer.seek(pos);
+        Ok(pos)
+    }
+}
+
+fn memory_stream(content: Bytes) -> BoxedStream {
+    Box::new(io::Cursor::new(content))
+}
+
+impl crate::body::Body<BoxedStream> {
+    /// Keeps a copy of a streaming body in memory as it is sent, up to `limit` bytes, so that
+    /// the request can be sent again, e.g. by [`BlockingClient::long_poll`].
+    ///
+    /// A body larger than the limit is sent as usual, but cannot be replayed. Other bodies are
+    /// replayable already and returned unchanged.
+    ///
+    /// [`BlockingClient::long_poll`]: crate::BlockingClient::long_poll
+    pub fn buffered_replayable(mut self, limit: u64) -> Self {
+        let BodyImpl::Stream(StreamReader {
+            stream,
+            content_length,
+        }) = self.inner
+        else {
+            return self;
+        };
+        let buffer = Arc::new(ReplayBuffer::new(limit, content_length, memory_stream));
+        self.inner = BodyImpl::Stream(StreamReader {
+            stream: Box::new(ReplayTee {
+                inner: stream,
+                buffer: buffer.clone(),
+            }),
+            content_length,
+        });
+        self.replay = Some(buffer);
+        self
+    }
+}
@@ -1,10 +1,15 @@
 use std::{fmt::Debug, io};
 
 use nyquest_interface::blocking::AnyBlockingResponse;
+use nyquest_interface::Bytes;
+
+use super::tee::TeeResponse;
+use crate::{Extensions, MediaType, RequestId, TlsInfo, Validators};
 
 /// A blocking HTTP response.
 pub struct Response {
     inner: Box<dyn AnyBlockingResponse>,
+    pub(crate) extensions: Extensions,
 }
 
 impl Response {
@@ -13,11 +18,48 @@ impl Response {
         self.inner.status()
     }
 
+    /// Returns whether the status is `304 Not Modified`, which answers a conditional request
+    /// whose validators still match.
+    pub fn is_not_modified(&self) -> bool {
+        self.status() == 304
+    }
+
     /// Get the `content-length` of this response, if known by the backend.
     pub fn content_length(&self) -> Option<u64> {
         self.inner.content_length()
     }
 
+    /// Get the extensions attached to the request of this response.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Get the extensions attached to the request of this response mutably.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Get the ID sent with the request of this response, if enabled with
+    /// [`crate::ClientBuilder::request_id_header`] or attached to the request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.extensions.get::<RequestId>().map(RequestId::as_str)
+    }
+
+    /// Get the TLS parameters of the connection this response is received on, such as the
+    /// protocol version and the cipher suite, to be logged for compliance.
+    ///
+    /// Returns `None` if the connection is not encrypted or the backend is not able to tell.
+    ///
+    /// # Note
+    ///
+    /// Support is subject to the backend. `curl` backend reports all parameters with OpenSSL and
+    /// only the ALPN protocol with other TLS libraries. `nsurlsession` backend reports all but
+    /// session resumption, and only once the response body is fully received. `winrt` backend
+    /// does not report TLS parameters.
+    pub fn tls_info(&self) -> Option<TlsInfo> {
+        self.inner.tls_info()
+    }
+
     /// Get the response values of the specified header.
     ///
     /// Multiple values may be returned if the header is present multiple times, depending on the
@@ -26,6 +68,25 @@ impl Response {
         Ok(self.inner.get_header(header)?)
     }
 
+    /// Get the media type of this response from the `content-type` header, to find out which of
+    /// the representations requested with [`crate::Request::accept`] the server chose.
+    ///
+    /// Returns `None` if the header is missing or malformed.
+    pub fn negotiated_content_type(&self) -> crate::Result<Option<MediaType>> {
+        let value = self.get_header("content-type")?.into_iter().next();
+        Ok(value.as_deref().and_then(MediaType::parse))
+    }
+
+    /// Get the `etag` and `last-modified` headers of this response, to be sent back in
+    /// conditional requests.
+    pub fn validators(&self) -> crate::Result<Validators> {
+        let first = |name| Ok::<_, crate::Error>(self.get_header(name)?.into_iter().next());
+        Ok(Validators {
+            etag: first("etag")?,
+            last_modified: first("last-modified")?,
+        })
+    }
+
     /// Block the current thread until getting the full response text.
     ///
     /// Encoding conversion is handled by the backend if possible. Some backends needs extra
@@ -45,10 +106,40 @@ impl Response {
     /// [`crate::ClientBuilder::max_response_buffer_size`] option. If the backend is not able to
     /// receive the response body within the limit, [`crate::Error::ResponseTooLarge`] will be
     /// returned.
-    pub fn bytes(mut self) -> crate::Result<Vec<u8>> {
+    pub fn bytes(mut self) -> crate::Result<Bytes> {
         Ok(AnyBlockingResponse::bytes(&mut *self.inner)?)
     }
 
+    /// Block the current thread until getting the full response body, and split it into gRPC-web
+    /// messages and trailers.
+    ///
+    /// The headers carry the status of the call instead, if the body has no trailers.
+    ///
+    /// The maximum size of the response is limited by the
+    /// [`crate::ClientBuilder::max_response_buffer_size`] option.
+    #[cfg(feature = "grpc-web")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "grpc-web")))]
+    pub fn grpc_web(self) -> crate::Result<crate::grpc_web::GrpcWebBody> {
+        let headers = crate::grpc_web::status_headers(|name| self.get_header(name))?;
+        crate::grpc_web::decode_response(&self.bytes()?, headers)
+    }
+
+    /// Read the parts of a `multipart/*` response body, such as `multipart/mixed`, as they are
+    /// received.
+    ///
+    /// Fails with an [`io::ErrorKind::InvalidData`] error if the response is not multipart or
+    /// has no boundary.
+    #[cfg(feature = "multipart")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+    pub fn multipart(
+        self,
+    ) -> crate::Result<crate::multipart_reader::MultipartReader<impl io::Read>> {
+        let boundary = crate::multipart_reader::boundary(&self.get_header("content-type")?)?;
+        Ok(crate::multipart_reader::MultipartReader::new(
+            self.inner, &boundary,
+        ))
+    }
+
     /// Block the current thread until getting the full response bytes, and deserialize into the
     /// given type.
     ///
@@ -56,10 +147,86 @@ impl Response {
     /// [`crate::ClientBuilder::max_response_buffer_size`] option. If the backend is not able to
     /// receive the response body within the limit, [`crate::Error::ResponseTooLarge`] will be
     /// returned.
+    ///
+    /// With the `simd-json` feature, large bodies are parsed with `simd-json`.
     #[cfg(feature = "json")]
     #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
     pub fn json<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
-        Ok(serde_json::from_slice(&self.bytes()?)?)
+        crate::json::from_bytes(self.bytes()?)
+    }
+
+    /// Block the current thread until getting the full response bytes, and deserialize CBOR into
+    /// the given type.
+    ///
+    /// The maximum size of the response is limited by the
+    /// [`crate::ClientBuilder::max_response_buffer_size`] option.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub fn cbor<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        Ok(ciborium::from_reader(&*self.bytes()?)?)
+    }
+
+    /// Block the current thread until getting the full response bytes, and deserialize
+    /// MessagePack into the given type.
+    ///
+    /// The maximum size of the response is limited by the
+    /// [`crate::ClientBuilder::max_response_buffer_size`] option.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub fn msgpack<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        Ok(rmp_serde::from_slice(&self.bytes()?)?)
+    }
+
+    /// Block the current thread until getting the full response text, and deserialize XML into
+    /// the given type.
+    ///
+    /// The text is decoded with the charset of the `content-type` header as in [`Self::text`],
+    /// while the encoding in the XML declaration is ignored.
+    ///
+    /// The maximum size of the response is limited by the
+    /// [`crate::ClientBuilder::max_response_buffer_size`] option.
+    #[cfg(feature = "xml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "xml")))]
+    pub fn xml<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        Ok(quick_xml::de::from_str(&self.text()?)?)
+    }
+
+    /// Block the current thread until getting the full response bytes, and decode a protobuf
+    /// message.
+    ///
+    /// The maximum size of the response is limited by the
+    /// [`crate::ClientBuilder::max_response_buffer_size`] option.
+    #[cfg(feature = "prost")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+    pub fn protobuf<M: prost::Message + Default>(self) -> crate::Result<M> {
+        Ok(M::decode(self.bytes()?)?)
+    }
+
+    /// Decode the response body as a sequence of length-delimited protobuf messages as they are
+    /// received.
+    #[cfg(feature = "prost")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+    pub fn protobuf_stream<M: prost::Message + Default>(
+        self,
+    ) -> crate::protobuf::DelimitedReader<impl io::Read, M> {
+        crate::protobuf::DelimitedReader::new(self.inner)
+    }
+
+    /// Copies the response body to the writer as it is read by the other methods, e.g. to keep
+    /// a copy of it in a cache file while parsing it.
+    ///
+    /// The writer is flushed once the whole body is read. Errors writing to it fail the read.
+    ///
+    /// # Note
+    ///
+    /// The writer receives the body as received. [`Response::text`] on the returned response
+    /// decodes it with the charset of the `content-type` header only if it is ISO-8859-1 or
+    /// US-ASCII, and as UTF-8 otherwise, instead of the conversion the backend would perform.
+    pub fn tee(self, writer: impl io::Write + Send + 'static) -> Self {
+        Self {
+            inner: Box::new(TeeResponse::new(self.inner, writer)),
+            extensions: self.extensions,
+        }
     }
 
     #[doc(hidden)]
@@ -70,7 +237,10 @@ impl Response {
 
 impl From<Box<dyn AnyBlockingResponse>> for Response {
     fn from(inner: Box<dyn AnyBlockingResponse>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            extensions: Extensions::new(),
+        }
     }
 }
 
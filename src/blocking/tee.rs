@@ -0,0 +1,85 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Mutex, PoisonError};
+
+use nyquest_interface::blocking::{AnyBlockingResponse, BlockingResponse};
+use nyquest_interface::Bytes;
+
+use crate::media_type::decode_text;
+
+/// A response whose body is copied to a writer as it is read.
+pub(super) struct TeeResponse<W> {
+    inner: Box<dyn AnyBlockingResponse>,
+    // Only accessed with `&mut self`, which makes the response `Sync` for any `Send` writer
+    writer: Mutex<W>,
+}
+
+impl<W> TeeResponse<W> {
+    pub(super) fn new(inner: Box<dyn AnyBlockingResponse>, writer: W) -> Self {
+        Self {
+            inner,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    fn writer(&mut self) -> &mut W {
+        self.writer
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<W: Write + Send + 'static> io::Read for TeeResponse<W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        let writer = self.writer();
+        if len == 0 && !buf.is_empty() {
+            writer.flush()?;
+        } else {
+            writer.write_all(&buf[..len])?;
+        }
+        Ok(len)
+    }
+}
+
+impl<W: Write + Send + 'static> BlockingResponse for TeeResponse<W> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TeeResponse(")?;
+        self.inner.describe(f)?;
+        write!(f, ")")
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    fn text(&mut self) -> nyquest_interface::Result<String> {
+        let bytes = BlockingResponse::bytes(self)?;
+        let content_type = self.inner.get_header("content-type")?;
+        Ok(decode_text(&bytes, content_type.first().map(|s| &**s)))
+    }
+
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        let bytes = AnyBlockingResponse::bytes(&mut *self.inner)?;
+        let writer = self.writer();
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+        Ok(bytes)
+    }
+}
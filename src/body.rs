@@ -1,12 +1,21 @@
 use std::borrow::Cow;
+#[cfg(feature = "multipart")]
+use std::path::PathBuf;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use std::sync::Arc;
 
-use nyquest_interface::Body as BodyImpl;
+use nyquest_interface::{Body as BodyImpl, Bytes, StreamReader};
 #[cfg(feature = "multipart")]
-use nyquest_interface::{Part as PartImpl, PartBody as PartBodyImpl, StreamReader};
+use nyquest_interface::{Part as PartImpl, PartBody as PartBodyImpl};
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::replay::ReplayBuffer;
 
 /// A request body generic over async or blocking stream.
 pub struct Body<S> {
     pub(crate) inner: BodyImpl<S>,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) replay: Option<Arc<ReplayBuffer<S>>>,
 }
 
 /// A field in a multipart form.
@@ -21,7 +30,83 @@ pub struct PartBody<S> {
     inner: PartBodyImpl<S>,
 }
 
+/// Conversion into the content of a body, without copying static or owned bytes.
+///
+/// Implemented for [`Bytes`], [`Vec<u8>`], [`String`], static slices, strings and byte arrays,
+/// and [`Cow<'static, [u8]>`], so that byte string literals such as `b"ID3"` can be passed
+/// directly.
+pub trait IntoBodyBytes {
+    /// Converts the value into [`Bytes`].
+    fn into_body_bytes(self) -> Bytes;
+}
+
+impl IntoBodyBytes for Bytes {
+    fn into_body_bytes(self) -> Bytes {
+        self
+    }
+}
+
+impl IntoBodyBytes for Vec<u8> {
+    fn into_body_bytes(self) -> Bytes {
+        self.into()
+    }
+}
+
+impl IntoBodyBytes for Box<[u8]> {
+    fn into_body_bytes(self) -> Bytes {
+        self.into()
+    }
+}
+
+impl IntoBodyBytes for String {
+    fn into_body_bytes(self) -> Bytes {
+        self.into()
+    }
+}
+
+impl IntoBodyBytes for &'static [u8] {
+    fn into_body_bytes(self) -> Bytes {
+        Bytes::from_static(self)
+    }
+}
+
+impl<const N: usize> IntoBodyBytes for &'static [u8; N] {
+    fn into_body_bytes(self) -> Bytes {
+        Bytes::from_static(self)
+    }
+}
+
+impl IntoBodyBytes for &'static str {
+    fn into_body_bytes(self) -> Bytes {
+        Bytes::from_static(self.as_bytes())
+    }
+}
+
+impl IntoBodyBytes for Cow<'static, [u8]> {
+    fn into_body_bytes(self) -> Bytes {
+        match self {
+            Cow::Borrowed(bytes) => Bytes::from_static(bytes),
+            Cow::Owned(bytes) => Bytes::from(bytes),
+        }
+    }
+}
+
+fn text_to_bytes(text: Cow<'static, str>) -> Bytes {
+    match text {
+        Cow::Borrowed(s) => Bytes::from_static(s.as_bytes()),
+        Cow::Owned(s) => Bytes::from(s),
+    }
+}
+
 impl<S> Body<S> {
+    fn from_inner(inner: BodyImpl<S>) -> Self {
+        Self {
+            inner,
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            replay: None,
+        }
+    }
+
     /// Constructs a body from a string of content type `text/plain`.
     pub fn plain_text(text: impl Into<Cow<'static, str>>) -> Self {
         Self::text(text, "text/plain")
@@ -31,36 +116,26 @@ impl<S> Body<S> {
         text: impl Into<Cow<'static, str>>,
         content_type: impl Into<Cow<'static, str>>,
     ) -> Self {
-        Self {
-            inner: BodyImpl::Bytes {
-                content: match text.into() {
-                    Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
-                    Cow::Owned(s) => Cow::Owned(s.into_bytes()),
-                },
-                content_type: content_type.into(),
-            },
-        }
+        Self::from_inner(BodyImpl::Bytes {
+            content: text_to_bytes(text.into()),
+            content_type: content_type.into(),
+        })
     }
 
     /// Constructs a body from a byte array of content type `application/octet-stream`.
-    pub fn binary_bytes(bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+    pub fn binary_bytes(bytes: impl IntoBodyBytes) -> Self {
         Self::bytes(bytes, "application/octet-stream")
     }
     /// Constructs a body from a byte array of the given content type.
-    pub fn bytes(
-        bytes: impl Into<Cow<'static, [u8]>>,
-        content_type: impl Into<Cow<'static, str>>,
-    ) -> Self {
-        Self {
-            inner: BodyImpl::Bytes {
-                content: bytes.into(),
-                content_type: content_type.into(),
-            },
-        }
+    pub fn bytes(bytes: impl IntoBodyBytes, content_type: impl Into<Cow<'static, str>>) -> Self {
+        Self::from_inner(BodyImpl::Bytes {
+            content: bytes.into_body_bytes(),
+            content_type: content_type.into(),
+        })
     }
 
     /// Constructs a body from a byte array of content type `application/json`.
-    pub fn json_bytes(bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+    pub fn json_bytes(bytes: impl IntoBodyBytes) -> Self {
         Self::bytes(bytes, "application/json")
     }
     /// Constructs a body by serializing the given value into JSON.
@@ -70,23 +145,60 @@ impl<S> Body<S> {
         Ok(Self::json_bytes(bytes))
     }
 
+    /// Constructs a body from a byte array of content type `application/cbor`.
+    pub fn cbor_bytes(bytes: impl IntoBodyBytes) -> Self {
+        Self::bytes(bytes, "application/cbor")
+    }
+    /// Constructs a body by serializing the given value into CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn cbor<T: serde::Serialize>(
+        value: &T,
+    ) -> Result<Self, ciborium::ser::Error<std::io::Error>> {
+        let mut bytes = vec![];
+        ciborium::into_writer(value, &mut bytes)?;
+        Ok(Self::cbor_bytes(bytes))
+    }
+
+    /// Constructs a body from a byte array of content type `application/msgpack`.
+    pub fn msgpack_bytes(bytes: impl IntoBodyBytes) -> Self {
+        Self::bytes(bytes, "application/msgpack")
+    }
+    /// Constructs a body by serializing the given value into MessagePack, with structs encoded as
+    /// maps keyed by field names.
+    #[cfg(feature = "msgpack")]
+    pub fn msgpack<T: serde::Serialize>(value: &T) -> Result<Self, rmp_serde::encode::Error> {
+        let bytes = rmp_serde::to_vec_named(value)?;
+        Ok(Self::msgpack_bytes(bytes))
+    }
+
+    /// Constructs a body by encoding the given protobuf message, of content type
+    /// `application/x-protobuf`.
+    #[cfg(feature = "prost")]
+    pub fn protobuf<M: prost::Message>(message: &M) -> Self {
+        Self::bytes(message.encode_to_vec(), crate::protobuf::CONTENT_TYPE)
+    }
+
     /// Constructs a url-encoded form body from given key-value string pairs.
     pub fn form(fields: impl IntoIterator<Item = (Cow<'static, str>, Cow<'static, str>)>) -> Self {
-        Self {
-            inner: BodyImpl::Form {
-                fields: fields.into_iter().collect(),
-            },
-        }
+        Self::from_inner(BodyImpl::Form {
+            fields: fields.into_iter().collect(),
+        })
     }
 
     /// Constructs a multipart form body from the given parts.
     #[cfg(feature = "multipart")]
     pub fn multipart(parts: impl IntoIterator<Item = Part<S>>) -> Self {
-        Self {
-            inner: BodyImpl::Multipart {
-                parts: parts.into_iter().map(|part| part.inner).collect(),
-            },
-        }
+        Self::from_inner(BodyImpl::Multipart {
+            parts: parts.into_iter().map(|part| part.inner).collect(),
+        })
+    }
+
+    #[doc(hidden)]
+    pub fn stream(stream: S, content_length: Option<u64>) -> Self {
+        Self::from_inner(BodyImpl::Stream(StreamReader {
+            stream,
+            content_length,
+        }))
     }
 }
 
@@ -166,23 +278,28 @@ impl<S> PartBody<S> {
     pub fn text(text: impl Into<Cow<'static, str>>) -> Self {
         Self {
             inner: PartBodyImpl::Bytes {
-                content: match text.into() {
-                    Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
-                    Cow::Owned(s) => Cow::Owned(s.into_bytes()),
-                },
+                content: text_to_bytes(text.into()),
             },
         }
     }
 
     /// Constructs a part body from a byte array.
-    pub fn bytes(bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+    pub fn bytes(bytes: impl IntoBodyBytes) -> Self {
         Self {
             inner: PartBodyImpl::Bytes {
-                content: bytes.into(),
+                content: bytes.into_body_bytes(),
             },
         }
     }
 
+    /// Constructs a part body from the file at the given path, which is read when the request is
+    /// sent. The file is streamed from disk rather than loaded into memory.
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner: PartBodyImpl::File { path: path.into() },
+        }
+    }
+
     #[doc(hidden)]
     pub fn stream(stream: S, content_length: Option<u64>) -> Self {
         Self {
@@ -1,8 +1,61 @@
 //! Contains types for building a client.
 //!
 
+mod auth;
 mod builder;
+#[cfg(all(feature = "cassette", any(feature = "async", feature = "blocking")))]
+pub(crate) mod cassette;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod circuit_breaker;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod connectivity;
 mod error;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod fallback;
+#[cfg(all(
+    feature = "fault-injection",
+    any(feature = "async", feature = "blocking")
+))]
+pub(crate) mod fault_injection;
+#[cfg(all(feature = "gzip", any(feature = "async", feature = "blocking")))]
+pub(crate) mod gzip_upload;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod har;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod long_poll;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod metrics;
+#[cfg(all(feature = "outbox", any(feature = "async", feature = "blocking")))]
+pub(crate) mod outbox;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) mod recording;
 
+pub use auth::Auth;
 pub use builder::ClientBuilder;
 pub use error::{BuildClientError, BuildClientResult};
+#[cfg(all(feature = "gzip", any(feature = "async", feature = "blocking")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+pub use gzip_upload::{GzipProbe, GzipUpload};
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub use long_poll::LongPollConfig;
+#[cfg(feature = "fault-injection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fault-injection")))]
+pub use nyquest_interface::client::FaultInjectionConfig;
+#[cfg(feature = "cassette")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cassette")))]
+pub use nyquest_interface::client::{CassetteConfig, CassetteMode};
+pub use nyquest_interface::client::{
+    CircuitBreakerConfig, DecompressionLimits, Http2KeepAlive, HttpVersion, MetricsSink,
+    ProxyTlsConfig, PushPolicy, PushPromise, QualityOfService, RedactionPolicy, RequestDurations,
+    RequestLabels, RequestParts, Resolve, RevocationPolicy, TransferSizes, UrlCredentials,
+};
+#[cfg(feature = "outbox")]
+#[cfg_attr(docsrs, doc(cfg(feature = "outbox")))]
+pub use nyquest_interface::client::{
+    ConflictHook, ConflictResolution, OutboxConfig, OutboxConflict,
+};
+#[cfg(feature = "prometheus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+pub use prometheus::PrometheusMetrics;
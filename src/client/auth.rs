@@ -0,0 +1,40 @@
+use nyquest_interface::client::Auth as AuthImpl;
+
+/// Authentication performed by the backend when challenged by servers or proxies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Auth {
+    pub(crate) inner: AuthImpl,
+}
+
+impl Auth {
+    /// Answers Negotiate (Kerberos or NTLM) challenges with the credentials of the logged-in user.
+    ///
+    /// The credentials are sent to any host that asks for them, unless restricted with
+    /// [`Auth::with_host`].
+    ///
+    /// # Note
+    ///
+    /// Support for single sign-on is subject to the backend. For example, building a client on
+    /// `curl` backend fails with [`BuildClientError::Unsupported`] unless libcurl is built with
+    /// GSS-API or SSPI, while `winrt` backend follows the system policy for automatic logon
+    /// regardless of the allowed hosts. Without this, `nsurlsession` backend declines Negotiate
+    /// and NTLM challenges instead of answering them with the system credentials.
+    ///
+    /// [`BuildClientError::Unsupported`]: super::BuildClientError::Unsupported
+    pub fn native_sso() -> Self {
+        Self {
+            inner: AuthImpl::NativeSso {
+                allowed_hosts: vec![],
+            },
+        }
+    }
+
+    /// Restricts the credentials to be sent only to the given host. Can be called multiple times
+    /// to allow more hosts.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        match &mut self.inner {
+            AuthImpl::NativeSso { allowed_hosts } => allowed_hosts.push(host.into()),
+        }
+        self
+    }
+}
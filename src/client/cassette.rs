@@ -0,0 +1,372 @@
+use std::fmt;
+use std::fs;
+#[cfg(feature = "blocking")]
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "blocking")]
+use nyquest_interface::blocking::BlockingResponse;
+use nyquest_interface::client::{CassetteConfig, CassetteMode, ClientOptions};
+#[cfg(feature = "async")]
+use nyquest_interface::r#async::AsyncResponse;
+use nyquest_interface::{Bytes, Request};
+use serde_json::{json, Value};
+
+use super::recording::{
+    decode_base64, default_headers, method_name, push_base64, request_headers, ResponseObserver,
+};
+use crate::url::resolve_url;
+use crate::Error;
+
+/// Written in place of the values of redacted headers.
+const REDACTED: &str = "[REDACTED]";
+
+struct Interaction {
+    method: String,
+    url: String,
+    request_headers: Vec<(String, String)>,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    replayed: Vec<bool>,
+}
+
+/// Records the responses of a client into a file, or replays them from it.
+pub(crate) struct Cassette {
+    config: CassetteConfig,
+    base_url: Option<String>,
+    default_headers: Vec<(String, String)>,
+    replaying: bool,
+    state: Mutex<CassetteState>,
+}
+
+/// What to do with a request.
+pub(crate) enum Playback {
+    /// The request is not sent, and the recorded response is returned instead.
+    Replay(CassetteResponse),
+    /// The request is sent, and its response is to be recorded.
+    Record(CassetteEntry),
+}
+
+/// A response whose interaction is recorded once its body is read entirely.
+pub(crate) struct CassetteEntry {
+    cassette: Arc<Cassette>,
+    interaction: Interaction,
+    body: Vec<u8>,
+}
+
+/// A response replayed from a cassette.
+pub(crate) struct CassetteResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+    #[cfg(feature = "blocking")]
+    position: usize,
+}
+
+impl Cassette {
+    /// Loads the interactions to replay, or creates an empty cassette to record into.
+    pub(crate) fn open(config: CassetteConfig, options: &ClientOptions) -> std::io::Result<Self> {
+        let replaying = match config.mode {
+            CassetteMode::Auto => config.path.exists(),
+            CassetteMode::Record => false,
+            CassetteMode::Replay => true,
+        };
+        let interactions = if replaying {
+            let data = fs::read(&config.path)?;
+            parse_interactions(&data).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed cassette")
+            })?
+        } else {
+            let interactions = vec![];
+            fs::write(&config.path, serialize_interactions(&interactions))?;
+            interactions
+        };
+        Ok(Self {
+            config,
+            base_url: options.base_url.clone(),
+            default_headers: default_headers(options),
+            replaying,
+            state: Mutex::new(CassetteState {
+                replayed: vec![false; interactions.len()],
+                interactions,
+            }),
+        })
+    }
+
+    /// Replays the response recorded for the request, or starts recording it.
+    pub(crate) fn play<S>(self: &Arc<Self>, req: &Request<S>) -> crate::Result<Playback> {
+        let request_headers = request_headers(&self.default_headers, req)
+            .map(|(name, value)| (name.to_owned(), self.redact(name, value).to_owned()))
+            .collect();
+        let interaction = Interaction {
+            method: method_name(&req.method).to_owned(),
+            url: resolve_url(self.base_url.as_deref(), &req.relative_uri),
+            request_headers,
+            status: 0,
+            headers: vec![],
+            body: Bytes::new(),
+        };
+        if !self.replaying {
+            return Ok(Playback::Record(CassetteEntry {
+                cassette: self.clone(),
+                interaction,
+                body: vec![],
+            }));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let CassetteState {
+            interactions,
+            replayed,
+        } = &mut *state;
+        let mut matches = interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, recorded)| self.matches(recorded, &interaction))
+            .map(|(i, _)| i);
+        // Responses are replayed in the order recorded, and the last one is replayed repeatedly
+        let mut last = None;
+        let index = matches
+            .find(|i| {
+                last = Some(*i);
+                !replayed[*i]
+            })
+            .or(last)
+            .ok_or(Error::UnrecordedRequest)?;
+        replayed[index] = true;
+        let recorded = &interactions[index];
+        Ok(Playback::Replay(CassetteResponse {
+            status: recorded.status,
+            headers: recorded.headers.clone(),
+            body: recorded.body.clone(),
+            #[cfg(feature = "blocking")]
+            position: 0,
+        }))
+    }
+
+    fn matches(&self, recorded: &Interaction, interaction: &Interaction) -> bool {
+        let header_values = |headers: &[(String, String)], name: &str| {
+            headers
+                .iter()
+                .filter(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+                .collect::<Vec<_>>()
+        };
+        recorded.method.eq_ignore_ascii_case(&interaction.method)
+            && recorded.url == interaction.url
+            && self.config.match_headers.iter().all(|name| {
+                header_values(&recorded.request_headers, name)
+                    == header_values(&interaction.request_headers, name)
+            })
+    }
+
+    fn redact<'a>(&self, name: &str, value: &'a str) -> &'a str {
+        if self.config.redact_headers.iter().any(|n| *n == *name) {
+            REDACTED
+        } else {
+            value
+        }
+    }
+
+    fn record(&self, interaction: Interaction) {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(interaction);
+        // Recording is best effort and never fails the request
+        let _ = fs::write(
+            &self.config.path,
+            serialize_interactions(&state.interactions),
+        );
+    }
+}
+
+impl ResponseObserver for CassetteEntry {
+    fn respond(&mut self, status: u16, headers: Vec<(String, String)>) {
+        self.interaction.status = status;
+        self.interaction.headers = headers
+            .into_iter()
+            .map(|(name, value)| {
+                let value = self.cassette.redact(&name, &value).to_owned();
+                (name, value)
+            })
+            .collect();
+    }
+
+    fn receive(&mut self, chunk: &[u8]) {
+        self.body.extend_from_slice(chunk);
+    }
+
+    fn finish(mut self, error: Option<&dyn fmt::Display>) {
+        // Failed requests are not recorded, so that they are sent again in the next run
+        if error.is_none() {
+            self.interaction.body = self.body.into();
+            self.cassette.record(self.interaction);
+        }
+    }
+
+    fn abandon(self) {
+        // Responses whose body is not read entirely are not recorded
+    }
+}
+
+impl CassetteResponse {
+    fn get_header(&self, header: &str) -> Vec<String> {
+        self.headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(header))
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncResponse for CassetteResponse {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CassetteResponse")
+    }
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        Some(self.body.len() as u64)
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        Ok(self.get_header(header))
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers.clone())
+    }
+
+    async fn text(&mut self) -> nyquest_interface::Result<String> {
+        Ok(CassetteResponse::text(self))
+    }
+
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        Ok(self.body.clone())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl io::Read for CassetteResponse {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = (&self.body[self.position..]).read(buf)?;
+        self.position += len;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingResponse for CassetteResponse {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CassetteResponse")
+    }
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        Some(self.body.len() as u64)
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        Ok(self.get_header(header))
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers.clone())
+    }
+
+    fn text(&mut self) -> nyquest_interface::Result<String> {
+        Ok(CassetteResponse::text(self))
+    }
+
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        Ok(self.body.clone())
+    }
+}
+
+fn serialize_interactions(interactions: &[Interaction]) -> Vec<u8> {
+    let headers_to_json = |headers: &[(String, String)]| {
+        headers
+            .iter()
+            .map(|(name, value)| json!([name, value]))
+            .collect::<Vec<_>>()
+    };
+    let interactions = interactions
+        .iter()
+        .map(|interaction| {
+            let mut response = json!({
+                "status": interaction.status,
+                "headers": headers_to_json(&interaction.headers),
+            });
+            match std::str::from_utf8(&interaction.body) {
+                Ok(body) => response["body"] = body.into(),
+                Err(_) => {
+                    let mut body = String::new();
+                    push_base64(&mut body, &interaction.body);
+                    response["body_base64"] = body.into();
+                }
+            }
+            json!({
+                "request": {
+                    "method": interaction.method,
+                    "url": interaction.url,
+                    "headers": headers_to_json(&interaction.request_headers),
+                },
+                "response": response,
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut data = serde_json::to_vec_pretty(&json!({ "interactions": interactions }))
+        .expect("cassette is serializable");
+    data.push(b'\n');
+    data
+}
+
+fn parse_interactions(data: &[u8]) -> Option<Vec<Interaction>> {
+    let headers_from_json = |headers: &Value| {
+        headers
+            .as_array()?
+            .iter()
+            .map(|header| match header.as_array()?.as_slice() {
+                [name, value] => Some((name.as_str()?.to_owned(), value.as_str()?.to_owned())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+    let root: Value = serde_json::from_slice(data).ok()?;
+    root.get("interactions")?
+        .as_array()?
+        .iter()
+        .map(|interaction| {
+            let request = interaction.get("request")?;
+            let response = interaction.get("response")?;
+            let body = match (response.get("body"), response.get("body_base64")) {
+                (Some(body), _) => Bytes::from(body.as_str()?.to_owned()),
+                (None, Some(body)) => Bytes::from(decode_base64(body.as_str()?)?),
+                (None, None) => Bytes::new(),
+            };
+            Some(Interaction {
+                method: request.get("method")?.as_str()?.to_owned(),
+                url: request.get("url")?.as_str()?.to_owned(),
+                request_headers: headers_from_json(request.get("headers")?)?,
+                status: response.get("status")?.as_u64()?.try_into().ok()?,
+                headers: headers_from_json(response.get("headers")?)?,
+                body,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use nyquest_interface::client::CircuitBreakerConfig;
+use nyquest_interface::Error as ErrorImpl;
+
+use crate::Error;
+
+#[derive(Debug)]
+enum HostState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { probes: u32 },
+}
+
+/// Tracks the failures of the hosts a client sends requests to.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    base_url: Option<String>,
+    hosts: Mutex<HashMap<String, HostState>>,
+}
+
+/// Permission to send a request to a host, to be settled with the result of the request.
+pub(crate) struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    host: String,
+    probe: bool,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig, base_url: Option<String>) -> Self {
+        Self {
+            config,
+            base_url,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fails fast if the breaker of the host of the URI is open. URIs without a host are always
+    /// permitted.
+    pub(crate) fn acquire(&self, uri: &str) -> crate::Result<Option<Permit<'_>>> {
+        let Some(host) = host_of(self.base_url.as_deref(), uri) else {
+            return Ok(None);
+        };
+        let host = host.to_ascii_lowercase();
+        let mut hosts = self.hosts.lock().unwrap();
+        // Hosts without failures are not tracked
+        let probe = match hosts.get_mut(&host) {
+            None | Some(HostState::Closed { .. }) => false,
+            Some(HostState::Open { until }) if Instant::now() < *until => {
+                return Err(Error::CircuitOpen)
+            }
+            Some(state @ HostState::Open { .. }) => {
+                *state = HostState::HalfOpen { probes: 1 };
+                true
+            }
+            Some(HostState::HalfOpen { probes }) if *probes < self.config.max_probes.get() => {
+                *probes += 1;
+                true
+            }
+            Some(HostState::HalfOpen { .. }) => return Err(Error::CircuitOpen),
+        };
+        Ok(Some(Permit {
+            breaker: self,
+            host,
+            probe,
+        }))
+    }
+}
+
+impl Permit<'_> {
+    /// Settles the permit with the result of the request.
+    pub(crate) fn finish<T>(mut self, res: &Result<T, ErrorImpl>) {
+        let failed = matches!(res, Err(ErrorImpl::Io(_) | ErrorImpl::RequestTimeout));
+        let config = &self.breaker.config;
+        let mut hosts = self.breaker.hosts.lock().unwrap();
+        if !failed {
+            hosts.remove(&self.host);
+        } else {
+            let state = hosts
+                .entry(self.host.clone())
+                .or_insert(HostState::Closed { failures: 0 });
+            match state {
+                HostState::Closed { failures } => {
+                    *failures += 1;
+                    if *failures >= config.failure_threshold.get() {
+                        *state = HostState::Open {
+                            until: Instant::now() + config.cooldown,
+                        };
+                    }
+                }
+                HostState::HalfOpen { .. } if self.probe => {
+                    *state = HostState::Open {
+                        until: Instant::now() + config.cooldown,
+                    };
+                }
+                // Requests started before the breaker tripped
+                HostState::HalfOpen { .. } | HostState::Open { .. } => {}
+            }
+        }
+        // The probe is settled
+        self.probe = false;
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        // Releases the probe of a cancelled request
+        if !self.probe {
+            return;
+        }
+        let mut hosts = self.breaker.hosts.lock().unwrap();
+        if let Some(HostState::HalfOpen { probes }) = hosts.get_mut(&self.host) {
+            *probes = probes.saturating_sub(1);
+        }
+    }
+}
+
+/// Extracts the authority of an absolute URI, or of the base URL for relative ones.
+pub(crate) fn host_of<'a>(base_url: Option<&'a str>, uri: &'a str) -> Option<&'a str> {
+    let url = if uri.contains("://") { uri } else { base_url? };
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    (!host.is_empty()).then_some(host)
+}
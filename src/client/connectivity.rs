@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use nyquest_interface::{NetworkStatus, Request as RequestImpl};
+
+/// How often the network status is checked while waiting for connectivity.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Holds a request back while the network is offline, until the deadline of the request or the
+/// request timeout of the client.
+pub(crate) struct ConnectivityWait {
+    deadline: Option<Instant>,
+    waited: bool,
+}
+
+impl ConnectivityWait {
+    pub(crate) fn new<S>(req: &RequestImpl<S>, request_timeout: Option<Duration>) -> Self {
+        let timeout = request_timeout.map(|timeout| Instant::now() + timeout);
+        let deadline = match (req.deadline, timeout) {
+            (Some(deadline), Some(timeout)) => Some(deadline.min(timeout)),
+            (deadline, timeout) => deadline.or(timeout),
+        };
+        Self {
+            deadline,
+            waited: false,
+        }
+    }
+
+    /// Returns how long to wait before checking the network again, or `None` once the request
+    /// may be sent.
+    pub(crate) fn next(&mut self, status: NetworkStatus) -> crate::Result<Option<Duration>> {
+        if status != NetworkStatus::Offline {
+            return Ok(None);
+        }
+        let wait = match self.deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => remaining.min(POLL_INTERVAL),
+                _ => return Err(crate::Error::RequestTimeout),
+            },
+            None => POLL_INTERVAL,
+        };
+        self.waited = true;
+        Ok(Some(wait))
+    }
+
+    /// Carries the deadline over to the request, so that the time spent waiting counts towards
+    /// the request timeout.
+    pub(crate) fn finish<S>(self, req: &mut RequestImpl<S>) {
+        if self.waited {
+            req.deadline = self.deadline;
+        }
+    }
+}
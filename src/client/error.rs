@@ -14,6 +14,10 @@ pub enum BuildClientError {
     /// The backend has returned an error while creating the client.
     #[error("Error creating client: {0}")]
     BackendError(#[from] BackendError),
+    /// The backend does not support an option set on the [`ClientBuilder`](super::ClientBuilder).
+    /// The name of the builder method is included.
+    #[error("The backend does not support `{0}`")]
+    Unsupported(&'static str),
 }
 
 /// A `Result` alias where the `Err` case is [`BuildClientError`].
@@ -24,6 +28,7 @@ impl From<BuildClientErrorImpl> for BuildClientError {
         match e {
             BuildClientErrorImpl::BackendError(e) => Self::BackendError(e.into()),
             BuildClientErrorImpl::NoBackend => Self::NoBackend,
+            BuildClientErrorImpl::Unsupported(option) => Self::Unsupported(option),
         }
     }
 }
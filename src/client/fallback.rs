@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::{Error, Request};
+
+/// The fallback URLs of a request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Fallback {
+    pub(crate) urls: Vec<Cow<'static, str>>,
+    pub(crate) stagger: Option<Duration>,
+}
+
+/// Returns whether the request may be sent to the next URL after the error, which happens before
+/// any response is received.
+pub(crate) fn is_failover(e: &Error) -> bool {
+    matches!(e, Error::Io(_) | Error::CircuitOpen)
+}
+
+/// The request to send to each of its URLs in turn.
+pub(crate) struct Candidates<S> {
+    req: Option<Request<S>>,
+    uris: VecDeque<Cow<'static, str>>,
+}
+
+impl<S> Candidates<S> {
+    pub(crate) fn new(mut req: Request<S>) -> Self {
+        let urls = std::mem::take(&mut req.fallback.urls);
+        let mut uris = VecDeque::from(urls);
+        uris.push_front(req.inner.relative_uri.clone());
+        Self {
+            req: Some(req),
+            uris,
+        }
+    }
+
+    pub(crate) fn has_next(&self) -> bool {
+        !self.uris.is_empty()
+    }
+
+    /// Returns the request to the next URL. Requests whose bodies cannot be sent twice are sent
+    /// to the first URL only.
+    pub(crate) fn next(&mut self) -> Option<Request<S>> {
+        let uri = self.uris.pop_front()?;
+        let mut req = match self.uris.is_empty() {
+            true => self.req.take()?,
+            false => match self.req.as_ref()?.try_clone() {
+                Some(req) => req,
+                None => {
+                    self.uris.clear();
+                    self.req.take()?
+                }
+            },
+        };
+        req.inner.relative_uri = uri;
+        Some(req)
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "blocking")]
+use nyquest_interface::blocking::{AnyBlockingResponse, BlockingResponse};
+use nyquest_interface::client::FaultInjectionConfig;
+#[cfg(feature = "async")]
+use nyquest_interface::r#async::{AnyAsyncResponse, AsyncResponse};
+use nyquest_interface::{Bytes, Error as ErrorImpl};
+
+/// Decides the faults to inject into each request.
+pub(crate) struct FaultInjector {
+    config: FaultInjectionConfig,
+    state: Mutex<u64>,
+}
+
+/// The faults injected into a request.
+pub(crate) struct Faults {
+    /// Latency to wait for before sending the request.
+    pub(crate) latency: Option<Duration>,
+    /// Whether the request fails instead of being sent.
+    error: bool,
+    /// Random number to pick where the response body is cut short, if it is.
+    truncate: Option<u64>,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(config: FaultInjectionConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64
+        });
+        Self {
+            config,
+            state: Mutex::new(seed),
+        }
+    }
+
+    /// Returns the next random number with SplitMix64.
+    fn next(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `[0, 1)`.
+    fn next_f64(&self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Decides the faults to inject into the next request.
+    pub(crate) fn plan(&self) -> Faults {
+        let config = &self.config;
+        let latency = (self.next_f64() < config.latency_probability).then(|| {
+            let range = config.latency.end.saturating_sub(config.latency.start);
+            config.latency.start + range.mul_f64(self.next_f64())
+        });
+        Faults {
+            latency,
+            error: self.next_f64() < config.error_probability,
+            truncate: (self.next_f64() < config.truncate_probability).then(|| self.next()),
+        }
+    }
+}
+
+fn injected_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionReset, message.to_owned())
+}
+
+impl Faults {
+    /// The error to fail the request with instead of sending it.
+    pub(crate) fn error(&self) -> Option<ErrorImpl> {
+        self.error
+            .then(|| ErrorImpl::Io(injected_error("injected fault: request failed")))
+    }
+
+    /// Cuts the body of the response short if planned.
+    #[cfg(feature = "async")]
+    pub(crate) fn truncate_async(
+        &self,
+        res: Result<Box<dyn AnyAsyncResponse>, ErrorImpl>,
+    ) -> Result<Box<dyn AnyAsyncResponse>, ErrorImpl> {
+        match res {
+            Ok(inner) if self.truncate.is_some() => Ok(Box::new(TruncatedResponse {
+                inner,
+                remaining: 0,
+            })),
+            res => res,
+        }
+    }
+
+    /// Cuts the body of the response short if planned.
+    #[cfg(feature = "blocking")]
+    pub(crate) fn truncate_blocking(
+        &self,
+        res: Result<Box<dyn AnyBlockingResponse>, ErrorImpl>,
+    ) -> Result<Box<dyn AnyBlockingResponse>, ErrorImpl> {
+        match (res, self.truncate) {
+            (Ok(inner), Some(random)) => {
+                let remaining = match inner.content_length() {
+                    Some(len) if len > 0 => random % len,
+                    // Cuts bodies of unknown length after the first read
+                    _ => 1,
+                };
+                Ok(Box::new(TruncatedResponse { inner, remaining }))
+            }
+            (res, _) => res,
+        }
+    }
+}
+
+/// A response whose body fails with an I/O error before it completes.
+struct TruncatedResponse<R: ?Sized> {
+    inner: Box<R>,
+    /// Bytes of the body to read successfully before failing
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    remaining: u64,
+}
+
+fn truncated() -> io::Error {
+    injected_error("injected fault: body truncated")
+}
+
+#[cfg(feature = "async")]
+impl AsyncResponse for TruncatedResponse<dyn AnyAsyncResponse> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.describe(f)
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    async fn text(&mut self) -> nyquest_interface::Result<String> {
+        self.inner.text().await?;
+        Err(truncated().into())
+    }
+
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        self.inner.bytes().await?;
+        Err(truncated().into())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl io::Read for TruncatedResponse<dyn AnyBlockingResponse> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Err(truncated());
+        }
+        let len = buf
+            .len()
+            .min(self.remaining.try_into().unwrap_or(usize::MAX));
+        let len = self.inner.read(&mut buf[..len])?;
+        if len == 0 {
+            return Err(truncated());
+        }
+        self.remaining -= len as u64;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingResponse for TruncatedResponse<dyn AnyBlockingResponse> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.describe(f)
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    fn text(&mut self) -> nyquest_interface::Result<String> {
+        AnyBlockingResponse::text(&mut *self.inner)?;
+        Err(truncated().into())
+    }
+
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        AnyBlockingResponse::bytes(&mut *self.inner)?;
+        Err(truncated().into())
+    }
+}
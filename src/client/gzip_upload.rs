@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Mutex, PoisonError};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use nyquest_interface::{header, Body as BodyImpl};
+
+use crate::client::circuit_breaker::host_of;
+use crate::{Method, Request};
+
+/// How [`GzipUpload`] finds out whether a server accepts gzip-compressed request bodies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GzipProbe {
+    /// Compresses bodies until the server rejects one with `415 Unsupported Media Type`.
+    #[default]
+    Optimistic,
+    /// Sends an `OPTIONS` request to the server first, and compresses bodies only if the
+    /// `accept-encoding` header of its response lists `gzip`.
+    Options,
+}
+
+/// Uploads request bodies compressed with gzip to servers accepting them, and remembers per host
+/// whether they do.
+///
+/// A request whose compressed body is rejected with `415 Unsupported Media Type` is sent again
+/// uncompressed, and later requests to the same host are not compressed any more. Known hosts
+/// may be configured with [`GzipUpload::set_supported`] to skip probing.
+///
+/// Only bodies of bytes are compressed. Other bodies are sent as usual.
+#[derive(Debug)]
+pub struct GzipUpload {
+    level: u32,
+    probe: GzipProbe,
+    hosts: Mutex<HashMap<String, bool>>,
+}
+
+/// What to do with a request before sending it.
+pub(crate) enum Plan {
+    Compress,
+    Identity,
+    /// Sends the `OPTIONS` request from [`probe_request`] first.
+    Probe,
+}
+
+impl Default for GzipUpload {
+    fn default() -> Self {
+        Self {
+            level: Compression::default().level(),
+            probe: GzipProbe::default(),
+            hosts: Mutex::default(),
+        }
+    }
+}
+
+impl GzipUpload {
+    /// Creates an uploader with the default compression level, probing optimistically.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the compression level, from `0` for no compression to `9` for the best compression.
+    pub fn with_level(mut self, level: u32) -> Self {
+        self.level = level.min(9);
+        self
+    }
+
+    /// Sets how to find out whether a server accepts compressed bodies.
+    pub fn with_probe(mut self, probe: GzipProbe) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Records whether the host, such as `example.com:8080`, accepts compressed bodies.
+    pub fn set_supported(&self, host: impl Into<String>, supported: bool) {
+        self.hosts().insert(host.into(), supported);
+    }
+
+    /// Returns whether the host is known to accept compressed bodies.
+    pub fn is_supported(&self, host: &str) -> Option<bool> {
+        self.hosts().get(host).copied()
+    }
+
+    fn hosts(&self) -> std::sync::MutexGuard<'_, HashMap<String, bool>> {
+        self.hosts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Decides whether to compress the body of the request to the host.
+    pub(crate) fn plan<S>(&self, req: &Request<S>, host: Option<&str>) -> Plan {
+        if !matches!(req.inner.body, Some(BodyImpl::Bytes { .. })) {
+            return Plan::Identity;
+        }
+        match (host.and_then(|host| self.is_supported(host)), self.probe) {
+            (Some(true), _) | (None, GzipProbe::Optimistic) => Plan::Compress,
+            (Some(false), _) => Plan::Identity,
+            (None, GzipProbe::Options) => Plan::Probe,
+        }
+    }
+
+    /// Records the outcome of a probe or a compressed upload to the host.
+    pub(crate) fn record(&self, host: Option<&str>, supported: bool) {
+        if let Some(host) = host {
+            self.set_supported(host, supported);
+        }
+    }
+
+    /// Compresses the bytes body of the request, keeping its content type.
+    pub(crate) fn compress<S>(&self, req: &mut Request<S>) -> crate::Result<()> {
+        let Some(BodyImpl::Bytes { content, .. }) = &mut req.inner.body else {
+            return Ok(());
+        };
+        let mut encoder = GzEncoder::new(vec![], Compression::new(self.level));
+        encoder.write_all(content)?;
+        *content = encoder.finish()?.into();
+        req.inner
+            .additional_headers
+            .insert(header::CONTENT_ENCODING, "gzip");
+        Ok(())
+    }
+}
+
+/// Returns the host of the request, used to remember whether it accepts compressed bodies.
+pub(crate) fn request_host<S>(base_url: Option<&str>, req: &Request<S>) -> Option<String> {
+    host_of(base_url, &req.inner.relative_uri).map(str::to_owned)
+}
+
+/// Constructs the `OPTIONS` request probing the server of the request.
+pub(crate) fn probe_request<S>(req: &Request<S>) -> Request<S> {
+    Request::new(Method::custom("OPTIONS"), req.inner.relative_uri.clone())
+}
+
+/// Returns whether the values of an `accept-encoding` header list `gzip` as acceptable.
+pub(crate) fn accepts_gzip(values: &[String]) -> bool {
+    values
+        .iter()
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut params = coding.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let rejected = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    == Some(0.0)
+            });
+            (name.eq_ignore_ascii_case("gzip") || name == "*") && !rejected
+        })
+}
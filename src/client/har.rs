@@ -0,0 +1,336 @@
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use nyquest_interface::client::{ClientOptions, RedactionPolicy};
+use nyquest_interface::{Body, Request};
+
+use super::recording::{
+    default_headers, method_name, push_base64, request_headers, ResponseObserver,
+};
+use crate::http_date::civil_from_days;
+use crate::url::resolve_url;
+
+/// Closes the `entries` array and the document, and is overwritten by each new entry.
+const TRAILER: &[u8] = b"]}}\n";
+
+struct HarFile {
+    file: File,
+    empty: bool,
+}
+
+/// Records the requests and responses of a client into a HAR 1.2 file.
+pub(crate) struct HarRecorder {
+    file: Mutex<HarFile>,
+    base_url: Option<String>,
+    default_headers: Vec<(String, String)>,
+    max_body_size: Option<u64>,
+    redaction: RedactionPolicy,
+}
+
+/// An entry whose request is sent, to be written once the response is complete.
+pub(crate) struct HarEntry {
+    recorder: Arc<HarRecorder>,
+    started: SystemTime,
+    start: Instant,
+    request: String,
+    response: Option<HarResponseInfo>,
+}
+
+struct HarResponseInfo {
+    status: u16,
+    headers: Vec<(String, String)>,
+    wait: Duration,
+    body: Vec<u8>,
+    body_size: u64,
+}
+
+impl HarRecorder {
+    /// Creates the file, replacing any existing one, with an empty log.
+    pub(crate) fn create(path: &Path, options: &ClientOptions) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        write!(
+            file,
+            r#"{{"log":{{"version":"1.2","creator":{{"name":"nyquest","version":"{}"}},"entries":["#,
+            env!("CARGO_PKG_VERSION")
+        )?;
+        file.write_all(TRAILER)?;
+        file.flush()?;
+        Ok(Self {
+            file: Mutex::new(HarFile { file, empty: true }),
+            base_url: options.base_url.clone(),
+            default_headers: default_headers(options),
+            max_body_size: options.har_max_body_size,
+            redaction: options.redaction.clone(),
+        })
+    }
+
+    /// Starts an entry for the request about to be sent.
+    pub(crate) fn start<S>(self: &Arc<Self>, req: &Request<S>) -> HarEntry {
+        HarEntry {
+            recorder: self.clone(),
+            started: SystemTime::now(),
+            start: Instant::now(),
+            request: self.format_request(req),
+            response: None,
+        }
+    }
+
+    fn format_request<S>(&self, req: &Request<S>) -> String {
+        let url = resolve_url(self.base_url.as_deref(), &req.relative_uri);
+        let url = self.redaction.redact_url(&url);
+        let method = method_name(&req.method);
+        let headers = request_headers(&self.default_headers, req)
+            .map(|(name, value)| (name, self.redact_header(name, value)));
+        let query = url
+            .split_once('?')
+            .map(|(_, query)| query.split('#').next().unwrap_or_default())
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")));
+
+        let mut out = String::new();
+        out.push_str(r#"{"method":"#);
+        push_str(&mut out, method);
+        out.push_str(r#","url":"#);
+        push_str(&mut out, &url);
+        out.push_str(r#","httpVersion":"","cookies":[],"headers":"#);
+        push_pairs(&mut out, headers);
+        out.push_str(r#","queryString":"#);
+        push_pairs(&mut out, query);
+        let body_size = match &req.body {
+            Some(body) => {
+                out.push_str(r#","postData":"#);
+                self.push_post_data(&mut out, body)
+            }
+            None => Some(0),
+        };
+        let _ = write!(
+            out,
+            r#","headersSize":-1,"bodySize":{}}}"#,
+            body_size.map_or(-1, |size| size as i64)
+        );
+        out
+    }
+
+    /// Returns the size of the body if known.
+    fn push_post_data<S>(&self, out: &mut String, body: &Body<S>) -> Option<u64> {
+        out.push_str(r#"{"mimeType":"#);
+        let size = match body {
+            Body::Bytes {
+                content,
+                content_type,
+            } => {
+                push_str(out, content_type);
+                self.push_content(out, "text", content);
+                Some(content.len() as u64)
+            }
+            Body::Form { fields } => {
+                push_str(out, "application/x-www-form-urlencoded");
+                out.push_str(r#","text":"""#);
+                if self.max_body_size.is_some() {
+                    out.push_str(r#","params":"#);
+                    push_pairs(out, fields.iter().map(|(k, v)| (&**k, &**v)));
+                }
+                None
+            }
+            #[cfg(feature = "multipart")]
+            Body::Multipart { .. } => {
+                push_str(out, "multipart/form-data");
+                out.push_str(r#","text":"""#);
+                None
+            }
+            Body::Stream(stream) => {
+                push_str(out, "application/octet-stream");
+                out.push_str(r#","text":"""#);
+                stream.content_length
+            }
+        };
+        out.push('}');
+        size
+    }
+
+    /// Writes a body truncated to the size limit, or an empty one if bodies are not recorded.
+    fn push_content(&self, out: &mut String, key: &str, body: &[u8]) {
+        let _ = write!(out, r#","{key}":"#);
+        let Some(max_body_size) = self.max_body_size else {
+            out.push_str(r#""""#);
+            return;
+        };
+        let truncated = body.len() as u64 > max_body_size;
+        let body = &body[..body.len().min(max_body_size as usize)];
+        match std::str::from_utf8(body) {
+            Ok(text) => push_str(out, text),
+            Err(_) => {
+                out.push('"');
+                push_base64(out, body);
+                out.push_str(r#"","encoding":"base64""#);
+            }
+        }
+        if truncated {
+            out.push_str(r#","comment":"truncated""#);
+        }
+    }
+
+    fn redact_header<'a>(&self, name: &str, value: &'a str) -> &'a str {
+        match self.redaction.redacts_header(name) {
+            true => RedactionPolicy::REDACTED,
+            false => value,
+        }
+    }
+
+    fn write_entry(&self, entry: &str) {
+        let mut file = self.file.lock().unwrap();
+        let HarFile { file, empty } = &mut *file;
+        // Recording is best effort and never fails the request
+        let _ = (|| -> io::Result<()> {
+            file.seek(SeekFrom::End(-(TRAILER.len() as i64)))?;
+            if !*empty {
+                file.write_all(b",")?;
+            }
+            file.write_all(entry.as_bytes())?;
+            file.write_all(TRAILER)?;
+            file.flush()
+        })();
+        *empty = false;
+    }
+}
+
+impl ResponseObserver for HarEntry {
+    fn respond(&mut self, status: u16, headers: Vec<(String, String)>) {
+        self.response = Some(HarResponseInfo {
+            status,
+            headers,
+            wait: self.start.elapsed(),
+            body: vec![],
+            body_size: 0,
+        });
+    }
+
+    /// Keeps the response body only up to the size limit.
+    fn receive(&mut self, chunk: &[u8]) {
+        let Some(response) = &mut self.response else {
+            return;
+        };
+        response.body_size += chunk.len() as u64;
+        if let Some(max_body_size) = self.recorder.max_body_size {
+            // Keeps one byte more to tell whether the body is truncated
+            let room = (max_body_size + 1).saturating_sub(response.body.len() as u64) as usize;
+            response
+                .body
+                .extend_from_slice(&chunk[..chunk.len().min(room)]);
+        }
+    }
+
+    fn finish(self, error: Option<&dyn fmt::Display>) {
+        let recorder = &self.recorder;
+        let total = self.start.elapsed();
+        let mut out = String::new();
+        out.push_str(r#"{"startedDateTime":"#);
+        push_str(&mut out, &format_date_time(self.started));
+        let _ = write!(out, r#","time":{}"#, millis(total));
+        out.push_str(r#","request":"#);
+        out.push_str(&self.request);
+        let (status, headers, wait, body, body_size) = match &self.response {
+            Some(res) => (
+                res.status,
+                &*res.headers,
+                res.wait,
+                &*res.body,
+                res.body_size as i64,
+            ),
+            None => (0, &[][..], total, &[][..], -1),
+        };
+        let _ = write!(
+            out,
+            r#","response":{{"status":{status},"statusText":"","httpVersion":"","cookies":[],"headers":"#
+        );
+        push_pairs(
+            &mut out,
+            headers
+                .iter()
+                .map(|(name, value)| (&**name, recorder.redact_header(name, value))),
+        );
+        let mime_type = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map_or("", |(_, value)| value);
+        let _ = write!(
+            out,
+            r#","content":{{"size":{},"mimeType":"#,
+            body_size.max(0)
+        );
+        push_str(&mut out, mime_type);
+        recorder.push_content(&mut out, "text", body);
+        let _ = write!(
+            out,
+            r#"}},"redirectURL":"","headersSize":-1,"bodySize":{body_size}"#
+        );
+        if let Some(error) = error {
+            out.push_str(r#","_error":"#);
+            push_str(&mut out, &error.to_string());
+        }
+        let _ = write!(
+            out,
+            r#"}},"cache":{{}},"timings":{{"send":0,"wait":{},"receive":{}}}}}"#,
+            millis(wait),
+            millis(total.saturating_sub(wait))
+        );
+        recorder.write_entry(&out);
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
+
+fn push_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str(r#"\""#),
+            '\\' => out.push_str(r"\\"),
+            '\n' => out.push_str(r"\n"),
+            '\r' => out.push_str(r"\r"),
+            '\t' => out.push_str(r"\t"),
+            c if c < ' ' => {
+                let _ = write!(out, r"\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn push_pairs<'a>(out: &mut String, pairs: impl Iterator<Item = (&'a str, &'a str)>) {
+    out.push('[');
+    for (i, (name, value)) in pairs.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(r#"{"name":"#);
+        push_str(out, name);
+        out.push_str(r#","value":"#);
+        push_str(out, value);
+        out.push('}');
+    }
+    out.push(']');
+}
+
+/// Formats the time in ISO 8601 with milliseconds in UTC.
+fn format_date_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days(secs / 86400);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
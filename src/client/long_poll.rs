@@ -0,0 +1,148 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::{Error, Request, Validators};
+
+/// Configures long polling, which re-issues a request each time it completes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongPollConfig {
+    /// Delay before re-issuing the request once a response is received.
+    pub delay: Duration,
+    /// Upper bound of a random delay added to every delay, so that clients do not reconnect all
+    /// at once.
+    pub jitter: Duration,
+    /// Optional timeout of each poll, after which the request is re-issued silently. Should be
+    /// longer than the server holds requests.
+    pub poll_timeout: Option<Duration>,
+    /// Delay after a failed poll, doubled after each consecutive failure.
+    pub retry_delay: Duration,
+    /// Upper bound of the delay after consecutive failed polls.
+    pub max_retry_delay: Duration,
+}
+
+impl Default for LongPollConfig {
+    fn default() -> Self {
+        Self {
+            delay: Duration::ZERO,
+            jitter: Duration::from_millis(500),
+            poll_timeout: None,
+            retry_delay: Duration::from_secs(1),
+            max_retry_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// What to do with the outcome of a poll.
+pub(crate) enum Outcome<T> {
+    /// Hands the response or the error over to the caller.
+    Yield(crate::Result<T>),
+    /// Polls again without bothering the caller.
+    Repoll,
+}
+
+/// Tracks the conditional headers and failures across polls.
+pub(crate) struct PollState<S> {
+    config: LongPollConfig,
+    request: Request<S>,
+    first: Option<Request<S>>,
+    validators: Validators,
+    /// Deadline of the request set by the caller, which ends polling.
+    deadline: Option<Instant>,
+    polls: u32,
+    failures: u32,
+}
+
+impl<S> PollState<S> {
+    /// Fails with [`Error::Unsupported`] for requests with streamed bodies, which cannot be
+    /// re-issued unless made replayable.
+    pub(crate) fn new(mut request: Request<S>, config: LongPollConfig) -> crate::Result<Self> {
+        let first = request.take_replayable();
+        if first.is_none() && request.try_clone().is_none() {
+            return Err(Error::Unsupported("long polling with a streamed body"));
+        }
+        Ok(Self {
+            config,
+            deadline: request.deadline(),
+            request,
+            first,
+            validators: Validators::default(),
+            polls: 0,
+            failures: 0,
+        })
+    }
+
+    /// Returns the delay before the next poll, which is zero for the first one.
+    pub(crate) fn delay(&self) -> Duration {
+        if self.polls == 0 {
+            return Duration::ZERO;
+        }
+        let delay = match self.failures {
+            0 => self.config.delay,
+            failures => self
+                .config
+                .retry_delay
+                .saturating_mul(1 << (failures - 1).min(16))
+                .min(self.config.max_retry_delay),
+        };
+        let jitter = self
+            .config
+            .jitter
+            .mul_f64(RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64);
+        delay + jitter
+    }
+
+    /// Returns the request for the next poll, with conditional headers from the last response.
+    ///
+    /// Fails with [`Error::Unsupported`] if a replayable body was not buffered entirely.
+    pub(crate) fn next_request(&mut self) -> crate::Result<Request<S>> {
+        self.polls += 1;
+        let mut req = match self.first.take() {
+            Some(req) => req,
+            None => self
+                .request
+                .try_clone()
+                .ok_or(Error::Unsupported("replaying a body over the buffer limit"))?,
+        };
+        self.validators.apply_to(&mut req.inner.additional_headers);
+        if let Some(timeout) = self.config.poll_timeout {
+            let deadline = Instant::now() + timeout;
+            req.inner.deadline = Some(req.inner.deadline.map_or(deadline, |d| d.min(deadline)));
+        }
+        Ok(req)
+    }
+
+    /// Decides what to do with the outcome of a poll. Responses are reduced to their status and
+    /// validators by the caller.
+    pub(crate) fn handle<T>(
+        &mut self,
+        res: crate::Result<T>,
+        validators: impl FnOnce(&T) -> (u16, Validators),
+    ) -> Outcome<T> {
+        match res {
+            Ok(res) => {
+                self.failures = 0;
+                let (status, validators) = validators(&res);
+                if status == 304 {
+                    return Outcome::Repoll;
+                }
+                if (200..300).contains(&status) {
+                    self.validators = validators;
+                }
+                Outcome::Yield(Ok(res))
+            }
+            // Only timeouts of single polls are silent, not the caller's deadline
+            Err(Error::RequestTimeout)
+                if self.config.poll_timeout.is_some()
+                    && self.deadline.map_or(true, |d| Instant::now() < d) =>
+            {
+                self.failures = 0;
+                Outcome::Repoll
+            }
+            Err(e) => {
+                self.failures = self.failures.saturating_add(1);
+                Outcome::Yield(Err(e))
+            }
+        }
+    }
+}
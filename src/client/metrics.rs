@@ -0,0 +1,73 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nyquest_interface::client::{MetricsSink, RequestDurations, RequestLabels, TransferSizes};
+use nyquest_interface::{Body, Request};
+
+use super::recording::{method_name, ResponseObserver};
+use crate::Url;
+
+/// Measures a request sent with a normalized URL, to be reported once it completes.
+pub(crate) struct MetricsEntry {
+    sink: Arc<dyn MetricsSink>,
+    labels: RequestLabels,
+    start: Instant,
+    headers: Option<Duration>,
+    request_body: Option<u64>,
+    response_body: u64,
+    status: Option<u16>,
+}
+
+impl MetricsEntry {
+    pub(crate) fn start<S>(sink: &Arc<dyn MetricsSink>, req: &Request<S>) -> Self {
+        let host = Url::parse(&req.relative_uri)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned));
+        let request_body = match &req.body {
+            None => Some(0),
+            Some(Body::Bytes { content, .. }) => Some(content.len() as u64),
+            Some(Body::Stream(stream)) => stream.content_length,
+            Some(_) => None,
+        };
+        Self {
+            sink: sink.clone(),
+            labels: RequestLabels {
+                method: method_name(&req.method).to_ascii_uppercase(),
+                host: host.unwrap_or_default(),
+            },
+            start: Instant::now(),
+            headers: None,
+            request_body,
+            response_body: 0,
+            status: None,
+        }
+    }
+}
+
+impl ResponseObserver for MetricsEntry {
+    fn respond(&mut self, status: u16, _headers: Vec<(String, String)>) {
+        self.headers = Some(self.start.elapsed());
+        self.status = Some(status);
+    }
+
+    fn receive(&mut self, chunk: &[u8]) {
+        self.response_body += chunk.len() as u64;
+    }
+
+    fn finish(self, _error: Option<&dyn fmt::Display>) {
+        let total = self.start.elapsed();
+        self.sink.on_request_complete(
+            &self.labels,
+            &RequestDurations {
+                headers: self.headers.unwrap_or(total),
+                total,
+            },
+            &TransferSizes {
+                request_body: self.request_body,
+                response_body: self.response_body,
+            },
+            self.status,
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nyquest_interface::client::{ClientOptions, ConflictResolution, OutboxConfig, OutboxConflict};
+use nyquest_interface::{Body as BodyImpl, Bytes, HeaderName, NetworkStatus};
+use serde_json::{json, Value};
+
+use super::recording::{decode_base64, method_name, push_base64};
+use crate::url::resolve_url;
+use crate::{Body, Error, Method, Request};
+
+/// A request kept in the outbox.
+struct Entry {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<EntryBody>,
+}
+
+enum EntryBody {
+    Bytes {
+        content: Bytes,
+        content_type: String,
+    },
+    Form {
+        fields: Vec<(String, String)>,
+    },
+}
+
+/// What the response to a queued request means for the outbox.
+enum Delivery {
+    /// The request is done with, and the next one may be sent.
+    Delivered,
+    /// The request conflicts with the state on the server, and the hook decides what to do.
+    Conflict,
+    /// The request is to be sent again later, such as after a server error.
+    Retry,
+}
+
+impl Delivery {
+    fn of(status: u16) -> Self {
+        match status {
+            409 | 412 => Self::Conflict,
+            408 | 429 | 500.. => Self::Retry,
+            _ => Self::Delivered,
+        }
+    }
+}
+
+/// Requests queued by a client, kept in a file until they are delivered in order.
+pub(crate) struct Outbox {
+    config: OutboxConfig,
+    base_url: Option<String>,
+    entries: Mutex<VecDeque<Entry>>,
+    flushing: AtomicBool,
+}
+
+/// Marks the outbox as being flushed, so that its requests are only sent once.
+pub(crate) struct FlushGuard<'a>(&'a Outbox);
+
+impl Drop for FlushGuard<'_> {
+    fn drop(&mut self) {
+        self.0.flushing.store(false, Ordering::Release);
+    }
+}
+
+/// Returns the outbox of a client, or fails with [`Error::Unsupported`] if it has none.
+pub(crate) fn configured(outbox: &Option<Arc<Outbox>>) -> crate::Result<&Outbox> {
+    outbox
+        .as_deref()
+        .ok_or(Error::Unsupported("enqueuing without an outbox"))
+}
+
+impl Outbox {
+    /// Loads the requests left in the file, if it exists.
+    pub(crate) fn open(config: OutboxConfig, options: &ClientOptions) -> io::Result<Self> {
+        let entries = match fs::read(&config.path) {
+            Ok(data) => parse_entries(&data)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed outbox"))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            config,
+            base_url: options.base_url.clone(),
+            entries: Mutex::new(entries),
+            flushing: AtomicBool::new(false),
+        })
+    }
+
+    /// Returns the number of requests not delivered yet.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Appends the request to the outbox and writes it to the file.
+    ///
+    /// Fails with [`Error::Unsupported`] for streamed and multipart bodies, and with
+    /// [`Error::RequestBodyTooLarge`] for bodies over the limit.
+    pub(crate) fn push<S>(&self, req: Request<S>) -> crate::Result<()> {
+        let req = req.inner;
+        let body = match req.body {
+            None => None,
+            Some(BodyImpl::Bytes {
+                content,
+                content_type,
+            }) => Some(EntryBody::Bytes {
+                content,
+                content_type: content_type.into_owned(),
+            }),
+            Some(BodyImpl::Form { fields }) => Some(EntryBody::Form {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                    .collect(),
+            }),
+            Some(_) => return Err(Error::Unsupported("queuing a streamed or multipart body")),
+        };
+        let body_size = match &body {
+            None => 0,
+            Some(EntryBody::Bytes { content, .. }) => content.len(),
+            Some(EntryBody::Form { fields }) => fields
+                .iter()
+                .map(|(name, value)| name.len() + value.len() + 2)
+                .sum(),
+        };
+        if body_size as u64 > self.config.max_body_size {
+            return Err(Error::RequestBodyTooLarge);
+        }
+        let entry = Entry {
+            method: method_name(&req.method).to_owned(),
+            url: resolve_url(self.base_url.as_deref(), &req.relative_uri),
+            headers: req
+                .additional_headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body,
+        };
+        let mut entries = self.entries.lock().unwrap();
+        entries.push_back(entry);
+        if let Err(e) = self.save(&entries) {
+            entries.pop_back();
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Starts flushing the outbox, unless it is already being flushed.
+    pub(crate) fn start_flush(&self) -> Option<FlushGuard<'_>> {
+        (!self.flushing.swap(true, Ordering::Acquire)).then_some(FlushGuard(self))
+    }
+
+    /// Returns a copy of the first request not delivered yet, unless the network is offline.
+    pub(crate) fn front<S>(
+        &self,
+        _flush: &FlushGuard<'_>,
+        network_status: NetworkStatus,
+    ) -> Option<Request<S>> {
+        if network_status == NetworkStatus::Offline {
+            return None;
+        }
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.front()?;
+        let method = match &*entry.method {
+            "GET" => Method::get(),
+            "POST" => Method::post(),
+            "PUT" => Method::put(),
+            "DELETE" => Method::delete(),
+            "PATCH" => Method::patch(),
+            method => Method::custom(method.to_owned()),
+        };
+        let mut req = Request::new(method, entry.url.clone());
+        for (name, value) in &entry.headers {
+            req = req.with_header(name.clone(), value.clone());
+        }
+        let body = match &entry.body {
+            None => return Some(req),
+            Some(EntryBody::Bytes {
+                content,
+                content_type,
+            }) => Body::bytes(content.clone(), content_type.clone()),
+            Some(EntryBody::Form { fields }) => Body::form(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone().into(), value.clone().into())),
+            ),
+        };
+        Some(req.with_body(body))
+    }
+
+    /// Returns whether [`Outbox::settle`] needs the body of the response with the status.
+    pub(crate) fn needs_body(status: u16) -> bool {
+        matches!(Delivery::of(status), Delivery::Conflict)
+    }
+
+    /// Removes the first request if the response with the status delivers it, or if the conflict
+    /// hook discards it. Returns whether the next request may be sent.
+    pub(crate) fn settle(
+        &self,
+        flush: &FlushGuard<'_>,
+        status: u16,
+        body: Option<Bytes>,
+    ) -> crate::Result<bool> {
+        match Delivery::of(status) {
+            Delivery::Delivered => {}
+            Delivery::Conflict => {
+                let body = body.unwrap_or_default();
+                if self.resolve_conflict(flush, status, body) == ConflictResolution::Keep {
+                    return Ok(false);
+                }
+            }
+            Delivery::Retry => return Ok(false),
+        }
+        self.pop_front(flush)?;
+        Ok(true)
+    }
+
+    /// Decides what to do with the first request, which conflicts with the state on the server.
+    fn resolve_conflict(
+        &self,
+        _flush: &FlushGuard<'_>,
+        status: u16,
+        body: Bytes,
+    ) -> ConflictResolution {
+        let Some(hook) = &self.config.on_conflict else {
+            return ConflictResolution::Discard;
+        };
+        let conflict = {
+            let entries = self.entries.lock().unwrap();
+            let Some(entry) = entries.front() else {
+                return ConflictResolution::Discard;
+            };
+            OutboxConflict {
+                method: entry.method.clone(),
+                url: entry.url.clone(),
+                status,
+                body,
+            }
+        };
+        (hook.0)(&conflict)
+    }
+
+    /// Removes the first request once it is done with.
+    fn pop_front(&self, _flush: &FlushGuard<'_>) -> crate::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.pop_front();
+        Ok(self.save(&entries)?)
+    }
+
+    fn save(&self, entries: &VecDeque<Entry>) -> io::Result<()> {
+        // Replacing the file keeps the previous requests if the app stops while it is written
+        let mut tmp_path = self.config.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(
+            &tmp_path,
+            serialize_entries(entries, &self.config.redact_headers),
+        )?;
+        fs::rename(&tmp_path, &self.config.path)
+    }
+}
+
+fn serialize_entries(entries: &VecDeque<Entry>, redact_headers: &[HeaderName]) -> Vec<u8> {
+    let pairs_to_json = |pairs: &[(String, String)]| {
+        pairs
+            .iter()
+            .map(|(name, value)| json!([name, value]))
+            .collect::<Vec<_>>()
+    };
+    // Redacted headers are only kept in memory, not to leave credentials on disk
+    let headers_to_json = |headers: &[(String, String)]| {
+        headers
+            .iter()
+            .filter(|(name, _)| !redact_headers.iter().any(|n| *n == **name))
+            .map(|(name, value)| json!([name, value]))
+            .collect::<Vec<_>>()
+    };
+    let requests = entries
+        .iter()
+        .map(|entry| {
+            let mut request = json!({
+                "method": entry.method,
+                "url": entry.url,
+                "headers": headers_to_json(&entry.headers),
+            });
+            match &entry.body {
+                None => {}
+                Some(EntryBody::Bytes {
+                    content,
+                    content_type,
+                }) => {
+                    request["content_type"] = content_type.as_str().into();
+                    match std::str::from_utf8(content) {
+                        Ok(body) => request["body"] = body.into(),
+                        Err(_) => {
+                            let mut body = String::new();
+                            push_base64(&mut body, content);
+                            request["body_base64"] = body.into();
+                        }
+                    }
+                }
+                Some(EntryBody::Form { fields }) => request["form"] = pairs_to_json(fields).into(),
+            }
+            request
+        })
+        .collect::<Vec<_>>();
+    let mut data = serde_json::to_vec_pretty(&json!({ "requests": requests }))
+        .expect("outbox is serializable");
+    data.push(b'\n');
+    data
+}
+
+fn parse_entries(data: &[u8]) -> Option<VecDeque<Entry>> {
+    let pairs_from_json = |pairs: &Value| {
+        pairs
+            .as_array()?
+            .iter()
+            .map(|pair| match pair.as_array()?.as_slice() {
+                [name, value] => Some((name.as_str()?.to_owned(), value.as_str()?.to_owned())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+    let root: Value = serde_json::from_slice(data).ok()?;
+    root.get("requests")?
+        .as_array()?
+        .iter()
+        .map(|request| {
+            let content = match (request.get("body"), request.get("body_base64")) {
+                (Some(body), _) => Some(Bytes::from(body.as_str()?.to_owned())),
+                (None, Some(body)) => Some(Bytes::from(decode_base64(body.as_str()?)?)),
+                (None, None) => None,
+            };
+            let body = match (content, request.get("form")) {
+                (Some(content), _) => Some(EntryBody::Bytes {
+                    content,
+                    content_type: request.get("content_type")?.as_str()?.to_owned(),
+                }),
+                (None, Some(form)) => Some(EntryBody::Form {
+                    fields: pairs_from_json(form)?,
+                }),
+                (None, None) => None,
+            };
+            Some(Entry {
+                method: request.get("method")?.as_str()?.to_owned(),
+                url: request.get("url")?.as_str()?.to_owned(),
+                headers: pairs_from_json(request.get("headers")?)?,
+                body,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,100 @@
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use prometheus_client::registry::Registry;
+
+use super::{MetricsSink, RequestDurations, RequestLabels, TransferSizes};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct Labels {
+    method: String,
+    host: String,
+    status: String,
+}
+
+type HistogramFamily = Family<Labels, Histogram, fn() -> Histogram>;
+
+/// A [`MetricsSink`] feeding request metrics into a [`prometheus_client`] registry.
+///
+/// The following metrics are registered, labelled by `method`, `host` and `status`, where
+/// `status` is `error` for requests failing before a response is received:
+///
+/// - `http_client_requests_total`: the number of requests.
+/// - `http_client_request_duration_seconds`: a histogram of the durations of requests until
+///   their response bodies are read.
+/// - `http_client_response_body_size_bytes`: a histogram of the sizes of the response bodies
+///   read.
+///
+/// ```
+/// use nyquest::client::PrometheusMetrics;
+/// use prometheus_client::registry::Registry;
+///
+/// let mut registry = Registry::with_prefix("app");
+/// let builder = nyquest::ClientBuilder::default()
+///     .metrics_sink(PrometheusMetrics::register(&mut registry));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrometheusMetrics {
+    requests: Family<Labels, Counter>,
+    durations: HistogramFamily,
+    response_sizes: HistogramFamily,
+}
+
+impl PrometheusMetrics {
+    /// Registers the metrics into the registry, returning the sink to install with
+    /// [`crate::ClientBuilder::metrics_sink`].
+    ///
+    /// Clients sharing a registry should share the returned sink, as registering metrics of the
+    /// same name more than once duplicates them.
+    pub fn register(registry: &mut Registry) -> Self {
+        let metrics = Self {
+            requests: Family::default(),
+            durations: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.005, 2., 12))
+            }),
+            response_sizes: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(64., 4., 10))
+            }),
+        };
+        registry.register(
+            "http_client_requests",
+            "Number of HTTP requests",
+            metrics.requests.clone(),
+        );
+        registry.register(
+            "http_client_request_duration_seconds",
+            "Durations of HTTP requests until their response bodies are read",
+            metrics.durations.clone(),
+        );
+        registry.register(
+            "http_client_response_body_size_bytes",
+            "Sizes of the HTTP response bodies read",
+            metrics.response_sizes.clone(),
+        );
+        metrics
+    }
+}
+
+impl MetricsSink for PrometheusMetrics {
+    fn on_request_complete(
+        &self,
+        labels: &RequestLabels,
+        durations: &RequestDurations,
+        sizes: &TransferSizes,
+        status: Option<u16>,
+    ) {
+        let labels = Labels {
+            method: labels.method.clone(),
+            host: labels.host.clone(),
+            status: status.map_or_else(|| "error".to_owned(), |status| status.to_string()),
+        };
+        self.requests.get_or_create(&labels).inc();
+        self.durations
+            .get_or_create(&labels)
+            .observe(durations.total.as_secs_f64());
+        self.response_sizes
+            .get_or_create(&labels)
+            .observe(sizes.response_body as f64);
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt;
+#[cfg(feature = "blocking")]
+use std::io;
+
+#[cfg(feature = "blocking")]
+use nyquest_interface::blocking::{AnyBlockingResponse, BlockingResponse};
+use nyquest_interface::client::ClientOptions;
+#[cfg(feature = "async")]
+use nyquest_interface::r#async::{AnyAsyncResponse, AsyncResponse};
+use nyquest_interface::{Bytes, Error as ErrorImpl, Method, Request};
+
+/// Observes a response from the backend as its body is read.
+pub(crate) trait ResponseObserver: Send + Sync + 'static {
+    /// Called once the status and headers are received.
+    fn respond(&mut self, status: u16, headers: Vec<(String, String)>);
+
+    /// Called with each chunk of the body as it is read.
+    fn receive(&mut self, chunk: &[u8]);
+
+    /// Called once the body is read entirely, or when the request or the body fails.
+    fn finish(self, error: Option<&dyn fmt::Display>);
+
+    /// Called when the response is dropped before its body is read entirely.
+    fn abandon(self)
+    where
+        Self: Sized,
+    {
+        self.finish(None);
+    }
+}
+
+/// A response whose body is passed to an observer as it is read.
+struct ObservedResponse<R: ?Sized, O: ResponseObserver> {
+    inner: Box<R>,
+    observer: Option<O>,
+}
+
+impl<R: ?Sized, O: ResponseObserver> ObservedResponse<R, O> {
+    fn receive<T: AsRef<[u8]>>(&mut self, res: Result<T, ErrorImpl>) -> Result<T, ErrorImpl> {
+        if let Some(mut observer) = self.observer.take() {
+            match &res {
+                Ok(body) => {
+                    observer.receive(body.as_ref());
+                    observer.finish(None);
+                }
+                Err(e) => observer.finish(Some(e)),
+            }
+        }
+        res
+    }
+}
+
+impl<R: ?Sized, O: ResponseObserver> Drop for ObservedResponse<R, O> {
+    fn drop(&mut self) {
+        if let Some(observer) = self.observer.take() {
+            observer.abandon();
+        }
+    }
+}
+
+/// Passes the failure of a request to the observer, or wraps the response to observe its body.
+#[cfg(feature = "async")]
+pub(crate) fn observe_async<O: ResponseObserver>(
+    mut observer: O,
+    res: Result<Box<dyn AnyAsyncResponse>, ErrorImpl>,
+) -> Result<Box<dyn AnyAsyncResponse>, ErrorImpl> {
+    match res {
+        Ok(inner) => {
+            observer.respond(inner.status(), inner.headers().unwrap_or_default());
+            Ok(Box::new(ObservedResponse {
+                inner,
+                observer: Some(observer),
+            }))
+        }
+        Err(e) => {
+            observer.finish(Some(&e));
+            Err(e)
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<O: ResponseObserver> AsyncResponse for ObservedResponse<dyn AnyAsyncResponse, O> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.describe(f)
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    async fn text(&mut self) -> nyquest_interface::Result<String> {
+        let res = self.inner.text().await;
+        self.receive(res)
+    }
+
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        let res = self.inner.bytes().await;
+        self.receive(res)
+    }
+}
+
+/// Passes the failure of a request to the observer, or wraps the response to observe its body.
+#[cfg(feature = "blocking")]
+pub(crate) fn observe_blocking<O: ResponseObserver>(
+    mut observer: O,
+    res: Result<Box<dyn AnyBlockingResponse>, ErrorImpl>,
+) -> Result<Box<dyn AnyBlockingResponse>, ErrorImpl> {
+    match res {
+        Ok(inner) => {
+            observer.respond(inner.status(), inner.headers().unwrap_or_default());
+            Ok(Box::new(ObservedResponse {
+                inner,
+                observer: Some(observer),
+            }))
+        }
+        Err(e) => {
+            observer.finish(Some(&e));
+            Err(e)
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<O: ResponseObserver> io::Read for ObservedResponse<dyn AnyBlockingResponse, O> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res = self.inner.read(buf);
+        match &res {
+            Ok(0) if !buf.is_empty() => {
+                if let Some(observer) = self.observer.take() {
+                    observer.finish(None);
+                }
+            }
+            Ok(len) => {
+                if let Some(observer) = &mut self.observer {
+                    observer.receive(&buf[..*len]);
+                }
+            }
+            Err(e) => {
+                if let Some(observer) = self.observer.take() {
+                    observer.finish(Some(e));
+                }
+            }
+        }
+        res
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<O: ResponseObserver> BlockingResponse for ObservedResponse<dyn AnyBlockingResponse, O> {
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.describe(f)
+    }
+
+    fn status(&self) -> u16 {
+        self.inner.status()
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        self.inner.get_header(header)
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        self.inner.headers()
+    }
+
+    fn tls_info(&self) -> Option<nyquest_interface::TlsInfo> {
+        self.inner.tls_info()
+    }
+
+    fn text(&mut self) -> nyquest_interface::Result<String> {
+        let res = self.inner.text();
+        self.receive(res)
+    }
+
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        let res = AnyBlockingResponse::bytes(&mut *self.inner);
+        self.receive(res)
+    }
+}
+
+/// Collects the user agent and the default headers of the client.
+pub(crate) fn default_headers(options: &ClientOptions) -> Vec<(String, String)> {
+    let user_agent = options
+        .user_agent
+        .iter()
+        .map(|user_agent| ("user-agent".to_owned(), user_agent.clone()));
+    let default_headers = options
+        .default_headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+    user_agent.chain(default_headers).collect()
+}
+
+/// Iterates over the headers sent with the request, where those of the request override the
+/// default ones.
+pub(crate) fn request_headers<'a, S>(
+    default_headers: &'a [(String, String)],
+    req: &'a Request<S>,
+) -> impl Iterator<Item = (&'a str, &'a str)> {
+    default_headers
+        .iter()
+        .filter(|(name, _)| !req.additional_headers.contains(name))
+        .map(|(name, value)| (&**name, &**value))
+        .chain(
+            req.additional_headers
+                .iter()
+                .map(|(name, value)| (&**name, &**value)),
+        )
+}
+
+pub(crate) fn method_name(method: &Method) -> &str {
+    match method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+        Method::Put => "PUT",
+        Method::Delete => "DELETE",
+        Method::Patch => "PATCH",
+        Method::Other(method) => method,
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn push_base64(out: &mut String, data: &[u8]) {
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+/// Decodes padded base64, or returns `None` if it is malformed.
+#[cfg(any(feature = "cassette", feature = "outbox"))]
+pub(crate) fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let data = data.as_bytes();
+    if data.len() % 4 != 0 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+    for chunk in data.chunks(4) {
+        let padding = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 {
+            return None;
+        }
+        let mut n = 0u32;
+        for (i, b) in chunk[..4 - padding].iter().enumerate() {
+            let digit = BASE64_ALPHABET.iter().position(|a| a == b)? as u32;
+            n |= digit << (18 - 6 * i);
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+    Some(out)
+}
@@ -0,0 +1,209 @@
+//! Emulation of `data:` URLs, which are answered by the frontend without involving the backend.
+
+#[cfg(feature = "blocking")]
+use std::io;
+
+use nyquest_interface::Bytes;
+
+use crate::{Error, Result};
+
+const DEFAULT_CONTENT_TYPE: &str = "text/plain;charset=US-ASCII";
+
+/// A response decoded from a `data:` URL.
+pub(crate) struct DataUrlResponse {
+    content_type: String,
+    data: Vec<u8>,
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    read_offset: usize,
+}
+
+/// Returns whether the URI is of a `data:` or `file:` URL, which involves no connection.
+pub(crate) fn is_local_scheme(uri: &str) -> bool {
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.eq_ignore_ascii_case("data") || scheme.eq_ignore_ascii_case("file")
+    })
+}
+
+/// Answers `data:` URLs if local schemes are allowed, and rejects `data:` and `file:` URLs
+/// otherwise. Returns `None` for other URLs, which are left to the backend.
+pub(crate) fn handle_local_scheme(
+    uri: &str,
+    allow_local_schemes: bool,
+    max_response_buffer_size: Option<u64>,
+) -> Result<Option<DataUrlResponse>> {
+    let scheme = uri.split_once(':').map(|(scheme, _)| scheme);
+    let is_data = scheme.is_some_and(|s| s.eq_ignore_ascii_case("data"));
+    let is_file = scheme.is_some_and(|s| s.eq_ignore_ascii_case("file"));
+    if !is_data && !is_file {
+        return Ok(None);
+    }
+    if !allow_local_schemes {
+        return Err(Error::InvalidUrl);
+    }
+    if is_file {
+        return Ok(None);
+    }
+    let res = DataUrlResponse::parse(&uri[5..]).ok_or(Error::InvalidUrl)?;
+    if max_response_buffer_size.is_some_and(|limit| res.data.len() as u64 > limit) {
+        return Err(Error::ResponseTooLarge);
+    }
+    Ok(Some(res))
+}
+
+impl DataUrlResponse {
+    fn parse(url: &str) -> Option<Self> {
+        let (meta, data) = url.split_once(',')?;
+        let (media_type, is_base64) = match meta.strip_suffix(";base64") {
+            Some(media_type) => (media_type, true),
+            None => (meta, false),
+        };
+        let content_type = match media_type.trim() {
+            "" => DEFAULT_CONTENT_TYPE.to_string(),
+            t if t.starts_with(';') => format!("text/plain{t}"),
+            t => t.to_string(),
+        };
+        let data = percent_decode(data.as_bytes());
+        let data = if is_base64 {
+            base64_decode(&data)?
+        } else {
+            data
+        };
+        Some(Self {
+            content_type,
+            data,
+            read_offset: 0,
+        })
+    }
+
+    fn get_header(&self, header: &str) -> Vec<String> {
+        if header.eq_ignore_ascii_case("content-type") {
+            vec![self.content_type.clone()]
+        } else if header.eq_ignore_ascii_case("content-length") {
+            vec![self.data.len().to_string()]
+        } else {
+            vec![]
+        }
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        vec![
+            ("content-type".into(), self.content_type.clone()),
+            ("content-length".into(), self.data.len().to_string()),
+        ]
+    }
+}
+
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let decoded = match input[i..] {
+            [b'%', h, l, ..] => (hex_value(h).zip(hex_value(l))).map(|(h, l)| h << 4 | l),
+            _ => None,
+        };
+        match decoded {
+            Some(byte) => {
+                output.push(byte);
+                i += 3;
+            }
+            None => {
+                output.push(input[i]);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+fn base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for &c in input {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        acc = acc << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((acc >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+#[cfg(feature = "async")]
+impl nyquest_interface::r#async::AsyncResponse for DataUrlResponse {
+    fn status(&self) -> u16 {
+        200
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        Some(self.data.len() as u64)
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        Ok(self.get_header(header))
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers())
+    }
+
+    async fn text(&mut self) -> nyquest_interface::Result<String> {
+        Ok(String::from_utf8_lossy(&self.data).into_owned())
+    }
+
+    async fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        Ok(std::mem::take(&mut self.data).into())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl nyquest_interface::blocking::BlockingResponse for DataUrlResponse {
+    fn status(&self) -> u16 {
+        200
+    }
+
+    fn content_length(&self) -> Option<u64> {
+        Some(self.data.len() as u64)
+    }
+
+    fn get_header(&self, header: &str) -> nyquest_interface::Result<Vec<String>> {
+        Ok(self.get_header(header))
+    }
+
+    fn headers(&self) -> nyquest_interface::Result<Vec<(String, String)>> {
+        Ok(self.headers())
+    }
+
+    fn text(&mut self) -> nyquest_interface::Result<String> {
+        Ok(String::from_utf8_lossy(&self.data).into_owned())
+    }
+
+    fn bytes(&mut self) -> nyquest_interface::Result<Bytes> {
+        Ok(std::mem::take(&mut self.data).into())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl io::Read for DataUrlResponse {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut remaining = &self.data[self.read_offset..];
+        let len = io::Read::read(&mut remaining, buf)?;
+        self.read_offset += len;
+        Ok(len)
+    }
+}
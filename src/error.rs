@@ -8,6 +8,13 @@ pub enum Error {
     /// The backend does not recognize the input as a valid URL.
     #[error("Invalid URL")]
     InvalidUrl,
+    /// The input is rejected by [`crate::Url::parse`].
+    #[error("Malformed URL: {0}")]
+    MalformedUrl(#[from] crate::ParseUrlError),
+    /// The URL of the request contains credentials, which are rejected as configured in
+    /// [`crate::ClientBuilder::url_credentials`].
+    #[error("URL contains credentials")]
+    CredentialsInUrl,
     /// A generic backend error.
     #[error("IO Error")]
     Io(#[from] std::io::Error),
@@ -16,14 +23,59 @@ pub enum Error {
     #[error("JSON ser/de Error")]
     #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
     Json(#[from] serde_json::Error),
+    /// Error occurred while deserializing CBOR.
+    #[cfg(feature = "cbor")]
+    #[error("CBOR de Error")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    Cbor(#[from] ciborium::de::Error<std::io::Error>),
+    /// Error occurred while deserializing MessagePack.
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack de Error")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    MessagePack(#[from] rmp_serde::decode::Error),
+    /// Error occurred while deserializing XML.
+    #[cfg(feature = "xml")]
+    #[error("XML de Error")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "xml")))]
+    Xml(#[from] quick_xml::DeError),
+    /// Error occurred while decoding a protobuf message.
+    #[cfg(feature = "prost")]
+    #[error("Protobuf decode Error")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+    Protobuf(#[from] prost::DecodeError),
     /// The backend has received a response body that exceeds the maximum size limit specified in
-    /// [`crate::ClientBuilder::max_response_buffer_size`].
+    /// [`crate::ClientBuilder::max_response_buffer_size`] or
+    /// [`crate::ClientBuilder::decompression_limits`].
     #[error("Response body size exceeds max limit")]
     ResponseTooLarge,
+    /// The backend has received response headers that exceed the limits specified in
+    /// [`crate::ClientBuilder::max_response_headers`].
+    #[error("Response headers exceed max limit")]
+    ResponseHeadersTooLarge,
     /// The backend is not able to finish transferring the request within the timeout specified in
     /// [`crate::ClientBuilder::request_timeout`].
     #[error("Request is not finished within timeout")]
     RequestTimeout,
+    /// The request is not sent because the circuit breaker of the host is open, as configured in
+    /// [`crate::ClientBuilder::circuit_breaker`].
+    #[error("Circuit breaker is open for the host")]
+    CircuitOpen,
+    /// The backend or the client does not support a feature used by the request, such as a body
+    /// on `GET` requests or long polling with a streamed body.
+    #[error("Unsupported by the backend: {0}")]
+    Unsupported(&'static str),
+    /// No response is recorded for the request in the cassette being replayed, as configured in
+    /// [`crate::ClientBuilder::cassette`].
+    #[cfg(feature = "cassette")]
+    #[error("No response is recorded for the request in the cassette")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cassette")))]
+    UnrecordedRequest,
+    /// The body of the request exceeds the size limit of the outbox, as configured in
+    /// [`crate::client::OutboxConfig::max_body_size`].
+    #[cfg(feature = "outbox")]
+    #[error("Request body size exceeds the outbox limit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "outbox")))]
+    RequestBodyTooLarge,
 }
 
 /// A `Result` alias where the `Err` case is [`crate::Error`].
@@ -35,7 +87,9 @@ impl From<ErrorImpl> for Error {
             ErrorImpl::InvalidUrl => Self::InvalidUrl,
             ErrorImpl::Io(e) => Self::Io(e),
             ErrorImpl::ResponseTooLarge => Self::ResponseTooLarge,
+            ErrorImpl::ResponseHeadersTooLarge => Self::ResponseHeadersTooLarge,
             ErrorImpl::RequestTimeout => Self::RequestTimeout,
+            ErrorImpl::Unsupported(feature) => Self::Unsupported(feature),
         }
     }
 }
@@ -0,0 +1,122 @@
+//! A type map for attaching metadata to requests and responses.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+trait AnyClone: Any + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AnyClone>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyClone for T {
+    fn clone_box(&self) -> Box<dyn AnyClone> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A map holding at most one value of each type, to attach metadata such as correlation IDs or
+/// retry counts to a request.
+///
+/// The extensions of a request are never sent to the server. They are moved to the response of
+/// the request instead, so that code handling the response can read them.
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn AnyClone>>,
+}
+
+impl Extensions {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value, returning the previous value of the same type if any.
+    pub fn insert<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|prev| prev.into_any().downcast().ok())
+            .map(|prev| *prev)
+    }
+
+    /// Returns a reference to the value of the type, if any.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|value| (**value).as_any().downcast_ref())
+    }
+
+    /// Returns a mutable reference to the value of the type, if any.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| (**value).as_any_mut().downcast_mut())
+    }
+
+    /// Removes and returns the value of the type, if any.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.into_any().downcast().ok())
+            .map(|value| *value)
+    }
+
+    /// Returns whether the map holds a value of the type.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Moves all values of the other map into this one, replacing values of the same types.
+    pub fn extend(&mut self, other: Extensions) {
+        self.map.extend(other.map);
+    }
+}
+
+impl Clone for Extensions {
+    fn clone(&self) -> Self {
+        Self {
+            map: self
+                .map
+                .iter()
+                .map(|(id, value)| (*id, (**value).clone_box()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
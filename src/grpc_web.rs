@@ -0,0 +1,230 @@
+//! Building blocks for [gRPC-web] clients.
+//!
+//! gRPC-web carries gRPC calls over plain HTTP/1.1 or HTTP/2 requests, which the native backends
+//! are able to send. Messages in both directions are prefixed with a flag byte and their length,
+//! and the status of the call is sent at the end of the response body as a trailer frame,
+//! because the backends do not expose HTTP trailers.
+//!
+//! Message encoding, e.g. with `prost`, is left to the caller.
+//!
+//! ```no_run
+//! # async fn call(client: nyquest::AsyncClient, message: Vec<u8>) -> nyquest::Result<()> {
+//! use nyquest::grpc_web;
+//!
+//! let req = grpc_web::request("/helloworld.Greeter/SayHello", &message);
+//! let body = client.request(req).await?.grpc_web().await?;
+//! let status = body.status();
+//! for message in body.messages {
+//!     // Decode the message
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [gRPC-web]: https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-WEB.md
+
+use std::io;
+
+use nyquest_interface::Bytes;
+
+use crate::url::percent_decode;
+use crate::{Body, Request};
+
+/// The content type of gRPC-web requests and responses with binary protobuf messages.
+pub const CONTENT_TYPE: &str = "application/grpc-web+proto";
+
+/// Flag of frames carrying trailers instead of a message.
+const TRAILERS_FLAG: u8 = 0x80;
+/// Flag of frames carrying compressed messages.
+const COMPRESSED_FLAG: u8 = 0x01;
+
+/// Prefixes the message with the flag byte and its length.
+pub fn encode_frame(message: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(5 + message.len());
+    frame.push(0);
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message);
+    frame
+}
+
+/// Constructs a `POST` request calling the method at the path, such as
+/// `/helloworld.Greeter/SayHello`, with a single message.
+pub fn request<S>(path: impl Into<std::borrow::Cow<'static, str>>, message: &[u8]) -> Request<S> {
+    Request::post(path)
+        .with_body(Body::bytes(encode_frame(message), CONTENT_TYPE))
+        .with_header("accept", CONTENT_TYPE)
+        .with_header("x-grpc-web", "1")
+}
+
+/// A frame of a gRPC-web response body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// A message, to be decoded by the caller.
+    Message(Bytes),
+    /// The trailers ending the call, with lowercased names.
+    Trailers(Vec<(String, String)>),
+}
+
+fn invalid_data(message: &str) -> crate::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned()).into()
+}
+
+/// Splits a gRPC-web response body into frames as it is received, chunk by chunk.
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    buf: Vec<u8>,
+}
+
+impl FrameDecoder {
+    /// Creates a decoder without buffered data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a chunk of the body.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// Returns the next complete frame, or `None` if more data is needed.
+    ///
+    /// Compressed messages fail with an [`io::ErrorKind::InvalidData`] error, as no message
+    /// encoding is advertised in requests.
+    pub fn next_frame(&mut self) -> crate::Result<Option<Frame>> {
+        let Some(header) = self.buf.get(..5) else {
+            return Ok(None);
+        };
+        let flag = header[0];
+        let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        if self.buf.len() < 5 + len {
+            return Ok(None);
+        }
+        let payload: Vec<u8> = self.buf.drain(..5 + len).skip(5).collect();
+        if flag & TRAILERS_FLAG != 0 {
+            return parse_trailers(&payload).map(|trailers| Some(Frame::Trailers(trailers)));
+        }
+        if flag & COMPRESSED_FLAG != 0 {
+            return Err(invalid_data("compressed gRPC-web message"));
+        }
+        Ok(Some(Frame::Message(payload.into())))
+    }
+
+    /// Checks that the body ended on a frame boundary.
+    pub fn finish(&self) -> crate::Result<()> {
+        match self.buf.is_empty() {
+            true => Ok(()),
+            false => Err(invalid_data("incomplete gRPC-web frame")),
+        }
+    }
+}
+
+fn parse_trailers(payload: &[u8]) -> crate::Result<Vec<(String, String)>> {
+    let text = std::str::from_utf8(payload).map_err(|_| invalid_data("non-UTF-8 trailers"))?;
+    text.split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid_data("malformed trailer"))?;
+            Ok((name.trim().to_ascii_lowercase(), value.trim().to_owned()))
+        })
+        .collect()
+}
+
+/// The status of a gRPC call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// The status code, where `0` means OK.
+    pub code: u32,
+    /// The percent-decoded status message, which may be empty.
+    pub message: String,
+}
+
+impl Status {
+    /// Reads the status from the `grpc-status` and `grpc-message` trailers or headers, if present.
+    pub fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<Self> {
+        let mut code = None;
+        let mut message = String::new();
+        for (name, value) in headers {
+            if name.eq_ignore_ascii_case("grpc-status") {
+                code = value.trim().parse().ok();
+            } else if name.eq_ignore_ascii_case("grpc-message") {
+                message = percent_decode(value);
+            }
+        }
+        Some(Self {
+            code: code?,
+            message,
+        })
+    }
+
+    /// Returns whether the call succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.code == 0
+    }
+}
+
+/// A fully received gRPC-web response body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcWebBody {
+    /// The messages in the order received.
+    pub messages: Vec<Bytes>,
+    /// The trailers, or the headers of a trailers-only response.
+    pub trailers: Vec<(String, String)>,
+}
+
+impl GrpcWebBody {
+    /// Splits a whole response body into messages and trailers.
+    pub fn decode(body: &[u8]) -> crate::Result<Self> {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(body);
+        let mut res = Self {
+            messages: vec![],
+            trailers: vec![],
+        };
+        while let Some(frame) = decoder.next_frame()? {
+            match frame {
+                Frame::Message(message) => res.messages.push(message),
+                Frame::Trailers(trailers) => res.trailers.extend(trailers),
+            }
+        }
+        decoder.finish()?;
+        Ok(res)
+    }
+
+    /// Returns the status of the call, if sent by the server.
+    pub fn status(&self) -> Option<Status> {
+        Status::from_headers(
+            self.trailers
+                .iter()
+                .map(|(name, value)| (&**name, &**value)),
+        )
+    }
+}
+
+/// Collects the status headers of a response, which carry the status of trailers-only responses.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn status_headers(
+    get_header: impl Fn(&str) -> crate::Result<Vec<String>>,
+) -> crate::Result<Vec<(String, String)>> {
+    let mut headers = vec![];
+    for name in ["grpc-status", "grpc-message"] {
+        for value in get_header(name)? {
+            headers.push((name.to_owned(), value));
+        }
+    }
+    Ok(headers)
+}
+
+/// Decodes the body, taking the status from the headers if the body has no trailers.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn decode_response(
+    body: &[u8],
+    headers: Vec<(String, String)>,
+) -> crate::Result<GrpcWebBody> {
+    let mut res = GrpcWebBody::decode(body)?;
+    if res.trailers.is_empty() {
+        res.trailers = headers;
+    }
+    Ok(res)
+}
@@ -0,0 +1,20 @@
+use serde::de::DeserializeOwned;
+
+use crate::Bytes;
+
+/// Response bodies below the size are parsed with `serde_json`, which is as fast on small
+/// payloads without copying them.
+#[cfg(feature = "simd-json")]
+const SIMD_JSON_THRESHOLD: usize = 64 * 1024;
+
+/// Deserializes a response body, with simd-json for large bodies if enabled.
+pub(crate) fn from_bytes<T: DeserializeOwned>(bytes: Bytes) -> crate::Result<T> {
+    #[cfg(feature = "simd-json")]
+    if bytes.len() >= SIMD_JSON_THRESHOLD {
+        // simd-json parses in place, which takes the buffer without copying it if not shared
+        let mut bytes = Vec::from(bytes);
+        return simd_json::serde::from_slice(&mut bytes)
+            .map_err(|e| <serde_json::Error as serde::de::Error>::custom(e).into());
+    }
+    Ok(serde_json::from_slice(&bytes)?)
+}
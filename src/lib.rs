@@ -60,6 +60,18 @@
 //! to reuse the one in the current thread if it is available, which may require certain features
 //! of the runtime to be enabled.
 //!
+//! Backends only make progress on "async" requests through the [`Waker`] of the polling
+//! [`Context`], so the `Future`s can be driven by any executor, such as `tokio`, `smol`,
+//! `async-std` or `futures::executor::block_on`, without extra features or adapters.
+//!
+//! A "blocking" request waits on the calling thread, which drives the transfer itself or parks
+//! until the HTTP stack notifies it, so no thread is spawned for each request. Issuing many
+//! requests in parallel costs no more threads than the callers already use, except for streaming
+//! uploads with the NSURLSession backend, which pump the body on a thread of their own.
+//!
+//! [`Waker`]: std::task::Waker
+//! [`Context`]: std::task::Context
+//!
 //! ## Usage
 //!
 //! Assume a backend with `async` feature has been registered. For a simple GET request, you can
@@ -98,12 +110,25 @@
 //! ## Features
 //!
 //! - `async`: Enable async support. The registered backend must implement the async interface
-//! to compile.
+//!   to compile.
 //! - `blocking`: Enable blocking support. The registered backend must implement the blocking
-//! interface to compile.
+//!   interface to compile.
 //! - `multipart`: Enable multipart form support. The registered backend must implement the
-//! multipart interface to compile.
+//!   multipart interface to compile.
 //! - `json`: Enable JSON request/response shorthand methods.
+//! - `simd-json`: Enable parsing large JSON responses with `simd-json`. Implies `json`.
+//! - `cbor`: Enable CBOR request/response shorthand methods.
+//! - `msgpack`: Enable MessagePack request/response shorthand methods.
+//! - `xml`: Enable deserializing XML responses.
+//! - `prost`: Enable protobuf request/response shorthand methods with `prost`.
+//! - `cassette`: Enable recording responses into a file to replay them in tests.
+//! - `outbox`: Enable queuing requests in a file to deliver them once the network is available.
+//! - `grpc-web`: Enable building blocks for gRPC-web clients.
+//! - `gzip`: Enable uploading request bodies compressed with gzip.
+//! - `prometheus`: Enable reporting request metrics into a `prometheus-client` registry.
+//! - `tracing`: Enable running requests in `tracing` spans that carry their request IDs.
+//! - `fault-injection`: Enable injecting faults into requests for resilience testing. Not meant
+//!   for production builds.
 //!
 //! [^1]: Subject to the backend's capability.
 //!
@@ -115,8 +140,23 @@
 #![forbid(missing_docs)]
 
 mod body;
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod data_url;
 mod error;
+mod extensions;
+mod http_date;
+#[cfg(all(feature = "json", any(feature = "async", feature = "blocking")))]
+mod json;
+mod media_type;
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod replay;
 mod request;
+mod request_id;
+mod revalidate;
+mod url;
+mod user_agent;
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod wire_debug;
 
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
@@ -125,18 +165,37 @@ pub mod r#async;
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 pub mod blocking;
 pub mod client;
+#[cfg(feature = "grpc-web")]
+#[cfg_attr(docsrs, doc(cfg(feature = "grpc-web")))]
+pub mod grpc_web;
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+pub mod multipart_reader;
+#[cfg(feature = "prost")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+pub mod protobuf;
 
 #[cfg(feature = "blocking")]
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 pub use blocking::client::BlockingClient;
-pub use body::Body;
+pub use body::{Body, IntoBodyBytes};
 #[cfg(feature = "multipart")]
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 pub use body::{Part, PartBody};
 #[doc(inline)]
 pub use client::ClientBuilder;
 pub use error::{Error, Result};
+pub use extensions::Extensions;
+#[doc(no_inline)]
+pub use header::{HeaderMap, HeaderName};
+pub use media_type::{MediaType, MediaTypeQ};
+pub use nyquest_interface::header;
+pub use nyquest_interface::{Bytes, NetworkStatus, TlsInfo};
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use r#async::client::AsyncClient;
-pub use request::{Method, Request};
+pub use request::{CachePolicy, Method, Progress, Proxy, Request, Timings};
+pub use request_id::RequestId;
+pub use revalidate::{Revalidator, Validators};
+pub use url::{ParseUrlError, Url};
+pub use user_agent::UserAgent;
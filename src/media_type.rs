@@ -0,0 +1,227 @@
+//! Media types for content negotiation.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// A media type in an `accept` header, weighted with a quality value.
+///
+/// The media type may be a range such as `*/*` or `text/*`, and may carry parameters such as
+/// `charset=utf-8`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MediaTypeQ {
+    media_type: Cow<'static, str>,
+    /// The quality value in thousandths, as it has at most three decimal places.
+    q: u16,
+}
+
+impl MediaTypeQ {
+    /// Creates a media type with the highest quality value of `1`.
+    pub fn new(media_type: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            media_type: media_type.into(),
+            q: 1000,
+        }
+    }
+
+    /// Sets the quality value, which is clamped to between `0` and `1` and rounded to three
+    /// decimal places. A quality value of `0` marks the media type as not acceptable.
+    pub fn with_q(mut self, q: f32) -> Self {
+        self.q = (q.clamp(0., 1.) * 1000.).round() as u16;
+        self
+    }
+
+    /// Returns the media type or range without the quality value.
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// Returns the quality value.
+    pub fn q(&self) -> f32 {
+        f32::from(self.q) / 1000.
+    }
+
+    /// Returns whether the media type falls within this media range, ignoring parameters.
+    pub fn matches(&self, media_type: &MediaType) -> bool {
+        let range = self.media_type.split(';').next().unwrap_or_default().trim();
+        let Some((main_type, subtype)) = range.split_once('/') else {
+            return false;
+        };
+        (main_type == "*" || main_type.eq_ignore_ascii_case(media_type.main_type()))
+            && (subtype == "*" || subtype.eq_ignore_ascii_case(media_type.subtype()))
+    }
+}
+
+impl From<&'static str> for MediaTypeQ {
+    fn from(media_type: &'static str) -> Self {
+        Self::new(media_type)
+    }
+}
+
+impl fmt::Display for MediaTypeQ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.media_type)?;
+        match self.q {
+            1000 => Ok(()),
+            0 => f.write_str(";q=0"),
+            q => {
+                let decimals = format!("{q:03}");
+                write!(f, ";q=0.{}", decimals.trim_end_matches('0'))
+            }
+        }
+    }
+}
+
+/// Formats the media types as the value of an `accept` header.
+pub(crate) fn accept_header(media_types: &[MediaTypeQ]) -> String {
+    let mut value = String::new();
+    for (i, media_type) in media_types.iter().enumerate() {
+        if i > 0 {
+            value.push_str(", ");
+        }
+        value.push_str(&media_type.to_string());
+    }
+    value
+}
+
+/// A media type parsed from a `content-type` header, such as `text/html; charset=utf-8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType {
+    /// The lowercased type and subtype, such as `text/html`.
+    essence: String,
+    slash: usize,
+    /// The parameters with lowercased names and unquoted values.
+    params: Vec<(String, String)>,
+}
+
+impl MediaType {
+    /// Parses a media type, returning `None` if it is malformed.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (essence, mut rest) = s.split_once(';').unwrap_or((s, ""));
+        let essence = essence.trim().to_ascii_lowercase();
+        let slash = essence.find('/')?;
+        let (main_type, subtype) = (&essence[..slash], &essence[slash + 1..]);
+        if !is_token(main_type) || !is_token(subtype) {
+            return None;
+        }
+        let mut params = vec![];
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ';' || c.is_ascii_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            let (name, after_name) = rest.split_once('=')?;
+            let name = name.trim();
+            if !is_token(name) {
+                return None;
+            }
+            let value;
+            (value, rest) = match after_name.strip_prefix('"') {
+                Some(quoted) => parse_quoted(quoted)?,
+                None => {
+                    let (value, rest) = after_name.split_once(';').unwrap_or((after_name, ""));
+                    (value.trim().to_owned(), rest)
+                }
+            };
+            params.push((name.to_ascii_lowercase(), value));
+        }
+        Some(Self {
+            essence,
+            slash,
+            params,
+        })
+    }
+
+    /// Returns the lowercased type and subtype without parameters, such as `text/html`.
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// Returns the lowercased type, such as `text`.
+    pub fn main_type(&self) -> &str {
+        &self.essence[..self.slash]
+    }
+
+    /// Returns the lowercased subtype, such as `html`.
+    pub fn subtype(&self) -> &str {
+        &self.essence[self.slash + 1..]
+    }
+
+    /// Returns the value of the parameter, matching the name case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| &**value)
+    }
+
+    /// Returns the parameters with lowercased names and unquoted values.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(name, value)| (&**name, &**value))
+    }
+
+    /// Returns the value of the `charset` parameter, if any.
+    pub fn charset(&self) -> Option<&str> {
+        self.param("charset")
+    }
+}
+
+/// Decodes a body in the charset of its `content-type` header, where the backend cannot decode
+/// it as the raw bytes are needed as well.
+///
+/// ISO-8859-1 and US-ASCII bodies are decoded as such, and the others as UTF-8, replacing invalid
+/// sequences.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn decode_text(bytes: &[u8], content_type: Option<&str>) -> String {
+    let media_type = content_type.and_then(MediaType::parse);
+    match media_type.as_ref().and_then(MediaType::charset) {
+        Some(charset)
+            if ["iso-8859-1", "latin1", "us-ascii"]
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(charset)) =>
+        {
+            bytes.iter().map(|&b| char::from(b)).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.essence)?;
+        for (name, value) in &self.params {
+            if is_token(value) {
+                write!(f, ";{name}={value}")?;
+            } else {
+                write!(f, ";{name}=\"")?;
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+                f.write_str("\"")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Parses a quoted string after the opening quote, returning the unescaped value and the rest.
+fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &s[i + 1..])),
+            '\\' => value.push(chars.next()?.1),
+            c => value.push(c),
+        }
+    }
+    None
+}
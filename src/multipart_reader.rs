@@ -0,0 +1,273 @@
+//! Reading `multipart/*` response bodies, such as `multipart/mixed` responses of batch APIs.
+//!
+//! Parts are read one after another from the body as it is received. Each part exposes its
+//! headers and implements [`io::Read`] over its body, which ends at the next boundary.
+//!
+//! ```no_run
+//! # fn read(client: nyquest::BlockingClient) -> nyquest::Result<()> {
+//! use std::io::Read;
+//!
+//! let res = client.request(nyquest::blocking::Request::get("/batch"))?;
+//! let mut parts = res.multipart()?;
+//! while let Some(mut part) = parts.next_part()? {
+//!     let content_type = part.header("content-type").map(str::to_owned);
+//!     let mut body = vec![];
+//!     part.read_to_end(&mut body)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read};
+
+use crate::MediaType;
+
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+/// Upper bound of the headers of a single part.
+const MAX_HEADERS_SIZE: usize = 64 * 1024;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+/// Extracts the boundary from the `content-type` header of a multipart response.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn boundary(content_type: &[String]) -> crate::Result<String> {
+    content_type
+        .first()
+        .and_then(|value| MediaType::parse(value))
+        .filter(|media_type| media_type.main_type() == "multipart")
+        .and_then(|media_type| media_type.param("boundary").map(str::to_owned))
+        .filter(|boundary| !boundary.is_empty())
+        .ok_or_else(|| invalid_data("not a multipart response").into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the first boundary.
+    Preamble,
+    /// Inside the body of a part.
+    Body,
+    /// At a boundary ending the body of a part.
+    Boundary,
+    /// After the final boundary.
+    Done,
+}
+
+/// Reads the parts of a multipart body one after another.
+#[derive(Debug)]
+pub struct MultipartReader<R> {
+    reader: R,
+    /// The delimiter before each boundary line, including the leading line break.
+    delimiter: Vec<u8>,
+    buf: Vec<u8>,
+    eof: bool,
+    state: State,
+}
+
+impl<R: Read> MultipartReader<R> {
+    /// Creates a reader over the body with the boundary from the `content-type` header.
+    pub fn new(reader: R, boundary: &str) -> Self {
+        Self {
+            reader,
+            delimiter: format!("\r\n--{boundary}").into_bytes(),
+            // The first boundary may be at the very start of the body without a line break
+            buf: b"\r\n".to_vec(),
+            eof: false,
+            state: State::Preamble,
+        }
+    }
+
+    /// Returns the next part, skipping what is left of the previous one, or `None` after the
+    /// last part.
+    pub fn next_part(&mut self) -> crate::Result<Option<MultipartPart<'_, R>>> {
+        if self.state == State::Body {
+            io::copy(&mut PartBodyReader(self), &mut io::sink())?;
+        }
+        if self.state == State::Preamble {
+            loop {
+                if let Some(pos) = find(&self.buf, &self.delimiter) {
+                    self.buf.drain(..pos);
+                    break;
+                }
+                // Keep what may be the start of the delimiter
+                let keep = self.delimiter.len() - 1;
+                if self.buf.len() > keep {
+                    self.buf.drain(..self.buf.len() - keep);
+                }
+                if self.fill()? == 0 {
+                    return Err(invalid_data("missing multipart boundary").into());
+                }
+            }
+            self.state = State::Boundary;
+        }
+        if self.state == State::Done {
+            return Ok(None);
+        }
+        self.consume_boundary()?;
+        if self.state == State::Done {
+            return Ok(None);
+        }
+        let headers = self.read_headers()?;
+        self.state = State::Body;
+        Ok(Some(MultipartPart {
+            reader: self,
+            headers,
+        }))
+    }
+
+    /// Reads more data into the buffer, returning its size, or `0` at the end of the body.
+    fn fill(&mut self) -> io::Result<usize> {
+        if self.eof {
+            return Ok(0);
+        }
+        let len = self.buf.len();
+        self.buf.resize(len + READ_CHUNK_SIZE, 0);
+        let res = self.reader.read(&mut self.buf[len..]);
+        self.buf.truncate(len + *res.as_ref().unwrap_or(&0));
+        let read = res?;
+        self.eof = read == 0;
+        Ok(read)
+    }
+
+    /// Fills the buffer until it holds at least `len` bytes, returning whether it does.
+    fn fill_to(&mut self, len: usize) -> io::Result<bool> {
+        while self.buf.len() < len {
+            if self.fill()? == 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Consumes the line of the boundary at the start of the buffer.
+    fn consume_boundary(&mut self) -> io::Result<()> {
+        let len = self.delimiter.len();
+        if !self.fill_to(len + 2)? {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if self.buf[len..len + 2] == *b"--" {
+            self.state = State::Done;
+            return Ok(());
+        }
+        self.buf.drain(..len);
+        let line = self.read_line()?;
+        if line.iter().any(|&b| b != b' ' && b != b'\t') {
+            return Err(invalid_data("malformed multipart boundary"));
+        }
+        Ok(())
+    }
+
+    /// Reads a line without its line break.
+    fn read_line(&mut self) -> io::Result<Vec<u8>> {
+        let mut searched = 0;
+        loop {
+            if let Some(pos) = find(&self.buf[searched..], b"\r\n") {
+                let end = searched + pos;
+                let line = self.buf[..end].to_vec();
+                self.buf.drain(..end + 2);
+                return Ok(line);
+            }
+            if self.buf.len() > MAX_HEADERS_SIZE {
+                return Err(invalid_data("multipart headers too large"));
+            }
+            searched = self.buf.len().saturating_sub(1);
+            if self.fill()? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+    }
+
+    fn read_headers(&mut self) -> io::Result<Vec<(String, String)>> {
+        let mut headers = vec![];
+        let mut size = 0;
+        loop {
+            let line = self.read_line()?;
+            if line.is_empty() {
+                return Ok(headers);
+            }
+            size += line.len();
+            if size > MAX_HEADERS_SIZE {
+                return Err(invalid_data("multipart headers too large"));
+            }
+            let line = String::from_utf8_lossy(&line);
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid_data("malformed multipart header"))?;
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+
+    fn read_body(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.state != State::Body || out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let available = match find(&self.buf, &self.delimiter) {
+                Some(0) => {
+                    self.state = State::Boundary;
+                    return Ok(0);
+                }
+                Some(pos) => pos,
+                // The end of the buffer may be the start of the delimiter
+                None => self.buf.len().saturating_sub(self.delimiter.len() - 1),
+            };
+            if available > 0 {
+                let len = available.min(out.len());
+                out[..len].copy_from_slice(&self.buf[..len]);
+                self.buf.drain(..len);
+                return Ok(len);
+            }
+            if self.fill()? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+    }
+}
+
+struct PartBodyReader<'a, R>(&'a mut MultipartReader<R>);
+
+impl<R: Read> Read for PartBodyReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read_body(buf)
+    }
+}
+
+/// A part of a multipart body, whose body is read with [`io::Read`].
+#[derive(Debug)]
+pub struct MultipartPart<'a, R> {
+    reader: &'a mut MultipartReader<R>,
+    headers: Vec<(String, String)>,
+}
+
+impl<R> MultipartPart<'_, R> {
+    /// Returns the headers of the part in the order received, with lowercased names.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns the first value of a header, matching the name case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| &**v)
+    }
+
+    /// Returns the media type of the part from its `content-type` header, if present and valid.
+    pub fn content_type(&self) -> Option<MediaType> {
+        self.header("content-type").and_then(MediaType::parse)
+    }
+}
+
+impl<R: Read> Read for MultipartPart<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read_body(buf)
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
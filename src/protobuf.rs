@@ -0,0 +1,99 @@
+//! Reading sequences of length-delimited protobuf messages from response bodies.
+//!
+//! Each message is prefixed with its length as a varint, as written by
+//! `prost::Message::encode_length_delimited`. Messages are decoded one after another as the body
+//! is read.
+//!
+//! ```no_run
+//! # fn read<Event: prost::Message + Default>(client: nyquest::BlockingClient) -> nyquest::Result<()> {
+//! let res = client.request(nyquest::blocking::Request::get("/events"))?;
+//! for event in res.protobuf_stream::<Event>() {
+//!     let event = event?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::io::{self, Read};
+use std::marker::PhantomData;
+
+use prost::Message;
+
+/// The content type of protobuf bodies.
+pub(crate) const CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Decodes length-delimited messages of type `M` from a reader.
+pub struct DelimitedReader<R, M> {
+    reader: R,
+    done: bool,
+    _message: PhantomData<fn() -> M>,
+}
+
+impl<R, M> fmt::Debug for DelimitedReader<R, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelimitedReader")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: Read, M: Message + Default> DelimitedReader<R, M> {
+    /// Creates a reader decoding the messages in the body.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            done: false,
+            _message: PhantomData,
+        }
+    }
+
+    /// Reads the length prefix of the next message, or `None` at the end of the body.
+    fn read_len(&mut self) -> io::Result<Option<u64>> {
+        let mut len = 0;
+        for i in 0..10 {
+            let mut byte = [0];
+            if self.reader.read(&mut byte)? == 0 {
+                return match i {
+                    0 => Ok(None),
+                    _ => Err(io::ErrorKind::UnexpectedEof.into()),
+                };
+            }
+            len |= u64::from(byte[0] & 0x7f) << (i * 7);
+            if byte[0] & 0x80 == 0 {
+                return Ok(Some(len));
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid protobuf message length",
+        ))
+    }
+
+    fn read_message(&mut self) -> crate::Result<Option<M>> {
+        let Some(len) = self.read_len()? else {
+            return Ok(None);
+        };
+        // Reads without allocating the claimed length upfront
+        let mut buf = vec![];
+        (&mut self.reader).take(len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(Some(M::decode(&*buf)?))
+    }
+}
+
+impl<R: Read, M: Message + Default> Iterator for DelimitedReader<R, M> {
+    type Item = crate::Result<M>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = self.read_message().transpose();
+        // Stops after the end of the body or an error, which leaves the reader mid-message
+        self.done = !matches!(res, Some(Ok(_)));
+        res
+    }
+}
@@ -0,0 +1,102 @@
+//! Buffers of streaming request bodies, so that the bodies can be sent again.
+
+use std::sync::{Mutex, PoisonError};
+
+use nyquest_interface::{Body as BodyImpl, Bytes, StreamReader};
+
+#[derive(Default)]
+struct State {
+    data: Vec<u8>,
+    /// Position of the stream, which the backend may rewind.
+    pos: u64,
+    complete: Option<Bytes>,
+    overflowed: bool,
+}
+
+impl State {
+    fn finish(&mut self) {
+        let data = std::mem::take(&mut self.data);
+        self.complete = Some(data.into());
+    }
+}
+
+/// Keeps a copy of a streaming body as the backend reads it, up to a size limit.
+pub(crate) struct ReplayBuffer<S> {
+    state: Mutex<State>,
+    limit: u64,
+    content_length: Option<u64>,
+    make_stream: fn(Bytes) -> S,
+}
+
+impl<S> ReplayBuffer<S> {
+    pub(crate) fn new(
+        limit: u64,
+        content_length: Option<u64>,
+        make_stream: fn(Bytes) -> S,
+    ) -> Self {
+        let state = State {
+            overflowed: content_length.is_some_and(|len| len > limit),
+            ..Default::default()
+        };
+        Self {
+            state: Mutex::new(state),
+            limit,
+            content_length,
+            make_stream,
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records a chunk read at the current position of the stream, or its end if empty.
+    pub(crate) fn record(&self, chunk: &[u8]) {
+        let mut state = self.state();
+        if state.overflowed || state.complete.is_some() {
+            return;
+        }
+        let buffered = state.data.len() as u64;
+        if state.pos > buffered {
+            // Part of the body was skipped, so it can never be buffered entirely
+            state.overflowed = true;
+            state.data = vec![];
+            return;
+        }
+        if chunk.is_empty() {
+            if state.pos == buffered {
+                state.finish();
+            }
+            return;
+        }
+        let end = state.pos + chunk.len() as u64;
+        if end > self.limit {
+            state.overflowed = true;
+            state.data = vec![];
+            return;
+        }
+        if end > buffered {
+            let new = &chunk[(buffered - state.pos) as usize..];
+            state.data.extend_from_slice(new);
+        }
+        state.pos = end;
+        // Backends may stop reading once the content length is reached
+        if Some(state.data.len() as u64) == self.content_length {
+            state.finish();
+        }
+    }
+
+    /// Records that the stream was moved to the position.
+    pub(crate) fn seek(&self, pos: u64) {
+        self.state().pos = pos;
+    }
+
+    /// Returns a body with the buffered content, if it was read entirely within the limit.
+    pub(crate) fn body(&self) -> Option<BodyImpl<S>> {
+        let content = self.state().complete.clone()?;
+        Some(BodyImpl::Stream(StreamReader {
+            content_length: Some(content.len() as u64),
+            stream: (self.make_stream)(content),
+        }))
+    }
+}
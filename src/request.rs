@@ -1,8 +1,23 @@
-use std::{borrow::Cow, fmt::Debug};
+use std::time::{Duration, Instant, SystemTime};
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
 
-use nyquest_interface::{Method as MethodImpl, Request as RequestImpl};
+#[cfg(any(feature = "async", feature = "blocking"))]
+use nyquest_interface::client::{RequestMap, RequestParts};
+use nyquest_interface::{
+    header, HeaderMap, HeaderName, Method as MethodImpl, ProgressCallback, Request as RequestImpl,
+    TimingsCallback,
+};
+
+pub use nyquest_interface::{CachePolicy, Progress, Proxy, Timings};
 
 use crate::body::Body;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::client::fallback::Fallback;
+use crate::extensions::Extensions;
+use crate::http_date::format_http_date;
+use crate::media_type::{accept_header, MediaTypeQ};
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::replay::ReplayBuffer;
 
 /// The Request Method (VERB)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -13,6 +28,11 @@ pub struct Method {
 /// A request generic over async or blocking stream.
 pub struct Request<S> {
     pub(crate) inner: RequestImpl<S>,
+    pub(crate) extensions: Extensions,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) replay: Option<Arc<ReplayBuffer<S>>>,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fallback: Fallback,
 }
 
 impl Method {
@@ -64,14 +84,28 @@ impl<S> Request<S> {
     ///
     /// If the URI is relative, it will be resolved against the [`crate::ClientBuilder::base_url`]
     /// option that was used to create the client, if specified.
+    ///
+    /// A [`crate::Url`] can be given instead of a string to have the URL validated up front.
+    /// Strings are validated and normalized the same way once the request is sent.
     pub fn new(method: Method, relative_uri: impl Into<Cow<'static, str>>) -> Self {
         Self {
             inner: RequestImpl {
                 method: method.inner,
                 relative_uri: relative_uri.into(),
-                additional_headers: vec![],
+                additional_headers: HeaderMap::new(),
                 body: None,
+                progress: None,
+                cache_policy: None,
+                timings: None,
+                deadline: None,
+                proxy: None,
+                early_data: false,
             },
+            extensions: Extensions::new(),
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            replay: None,
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            fallback: Fallback::default(),
         }
     }
 
@@ -113,22 +147,346 @@ impl<S> Request<S> {
     /// Attach a request header to the request.
     pub fn with_header(
         mut self,
-        name: impl Into<Cow<'static, str>>,
+        name: impl Into<HeaderName>,
         value: impl Into<Cow<'static, str>>,
     ) -> Self {
+        self.inner.additional_headers.append(name, value);
+        self
+    }
+
+    /// Sets the `user-agent` header of the request, overriding the one of the client set by
+    /// [`crate::ClientBuilder::user_agent`]. A [`crate::UserAgent`] can be given to build a
+    /// well-formed value.
+    ///
+    /// # Note
+    ///
+    /// Without a `user-agent` header, `nsurlsession` backend sends a default one of the system,
+    /// while `curl` backend sends none.
+    pub fn user_agent(mut self, user_agent: impl Into<Cow<'static, str>>) -> Self {
+        self.inner
+            .additional_headers
+            .insert(header::USER_AGENT, user_agent);
+        self
+    }
+
+    /// Sets the `host` header of the request, such as `vhost.example:8080`, independently of the
+    /// URL the request is sent to. This reaches a virtual host directly by its IP address, or with
+    /// [`crate::ClientBuilder::resolve`], at another address than its own.
+    ///
+    /// The server name sent for TLS (SNI) and the certificate validation still follow the host of
+    /// the URL, so an HTTPS virtual host is reached with its own URL and a resolve override rather
+    /// than with this header.
+    ///
+    /// # Note
+    ///
+    /// `nsurlsession` backend fails such requests with [`crate::Error::Unsupported`], as the
+    /// system reserves the header.
+    pub fn host_header(mut self, host: impl Into<Cow<'static, str>>) -> Self {
+        self.inner.additional_headers.insert(header::HOST, host);
+        self
+    }
+
+    /// Sets the `accept` header to the media types in order of preference, with their quality
+    /// values, replacing any previous value. An empty list removes the header.
+    ///
+    /// ```
+    /// use nyquest::{MediaTypeQ, Request};
+    ///
+    /// let req: Request<()> = Request::get("/articles/1").accept(&[
+    ///     MediaTypeQ::new("application/vnd.api+json"),
+    ///     MediaTypeQ::new("application/json").with_q(0.8),
+    /// ]);
+    /// ```
+    pub fn accept(mut self, media_types: &[MediaTypeQ]) -> Self {
+        let headers = &mut self.inner.additional_headers;
+        if media_types.is_empty() {
+            headers.remove(header::ACCEPT.as_str());
+        } else {
+            headers.insert(header::ACCEPT, accept_header(media_types));
+        }
+        self
+    }
+
+    /// Makes the request conditional on the resource not matching the entity tag, such as
+    /// `"v1"` from the `etag` header of a previous response. The server answers `304 Not
+    /// Modified` if it still matches.
+    pub fn if_none_match(mut self, etag: impl Into<Cow<'static, str>>) -> Self {
         self.inner
             .additional_headers
-            .push((name.into(), value.into()));
+            .insert(header::IF_NONE_MATCH, etag);
+        self
+    }
+
+    /// Makes the request conditional on the resource being modified after the time. The server
+    /// answers `304 Not Modified` otherwise.
+    pub fn if_modified_since(mut self, time: SystemTime) -> Self {
+        self.inner
+            .additional_headers
+            .insert(header::IF_MODIFIED_SINCE, format_http_date(time));
         self
     }
 
     /// Set the request body of the request.
     ///
     /// When called multiple times, the last call will override any previous body.
+    ///
+    /// A body can be attached to requests of any method, including `GET` and `DELETE`.
+    ///
+    /// # Note
+    ///
+    /// `nsurlsession` backend fails `GET` and `HEAD` requests with a body with
+    /// [`crate::Error::Unsupported`]. Other backends send the body as is.
     pub fn with_body(mut self, body: Body<S>) -> Self {
         self.inner.body = Some(body.inner);
+        #[cfg(any(feature = "async", feature = "blocking"))]
+        {
+            self.replay = body.replay;
+        }
+        self
+    }
+
+    /// Reports the progress of uploading the request body and downloading the response body to
+    /// the callback, at most once per `min_interval` except for the final report.
+    ///
+    /// # Note
+    ///
+    /// Only `curl` backend reports progress. The callback is never called on other backends.
+    pub fn with_progress(
+        mut self,
+        min_interval: Duration,
+        callback: impl Fn(Progress) + Send + Sync + 'static,
+    ) -> Self {
+        self.inner.progress = Some(ProgressCallback {
+            callback: Arc::new(callback),
+            min_interval,
+        });
         self
     }
+
+    /// Reports the timings of the transfer to the callback once it completes, which is after the
+    /// response body is fully received.
+    ///
+    /// # Note
+    ///
+    /// Only `nsurlsession` backend reports timings. The callback is never called on other
+    /// backends.
+    pub fn with_timings(mut self, callback: impl Fn(Timings) + Send + Sync + 'static) -> Self {
+        self.inner.timings = Some(TimingsCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Sets how the request uses cached responses, overriding the caching behavior of the client.
+    ///
+    /// # Note
+    ///
+    /// Only `nsurlsession` backend supports this option. Sending the request fails with
+    /// [`crate::Error::Unsupported`] on other backends, where `curl` backend does not cache
+    /// responses at all.
+    pub fn with_cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.inner.cache_policy = Some(policy);
+        self
+    }
+
+    /// Sets the point in time by which the whole transfer must complete, including redirects and
+    /// receiving the response body.
+    ///
+    /// Unlike [`crate::ClientBuilder::request_timeout`], the deadline is absolute, so that a
+    /// budget can be propagated through layers of code. The earlier of the two applies. Sending
+    /// the request fails with [`crate::Error::RequestTimeout`] right away if the deadline has
+    /// passed.
+    ///
+    /// # Note
+    ///
+    /// The precision of the deadline is implementation defined, like that of the timeout.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.inner.deadline = Some(deadline);
+        self
+    }
+
+    /// Sends the request through the given proxy instead of the one of the client set by
+    /// [`crate::ClientBuilder::proxy`], or directly to the server with `None`, such as for
+    /// metadata endpoints that must not be reached through a proxy.
+    ///
+    /// # Note
+    ///
+    /// Only `curl` backend supports this option. Other backends set proxies for the whole client
+    /// and fail such requests with [`crate::Error::Unsupported`].
+    pub fn proxy(mut self, proxy: Option<Proxy>) -> Self {
+        self.inner.proxy = Some(proxy);
+        self
+    }
+
+    /// Adds URLs to try in order when sending the request fails before any response is received,
+    /// such as mirrors of an artifact or endpoints in other regions.
+    ///
+    /// Failures are [`crate::Error::Io`] errors and [`crate::Error::CircuitOpen`]. The request is
+    /// not sent to the next URL after a response of any status, or a timeout. Relative URLs are
+    /// resolved against the base URL as usual.
+    ///
+    /// # Note
+    ///
+    /// Requests with streamed or multipart bodies are sent to their own URL only, as the bodies
+    /// cannot be sent twice.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub fn with_fallback_urls(
+        mut self,
+        urls: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.fallback.urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Races the fallback URLs set by [`Request::with_fallback_urls`] instead of trying them one
+    /// after another: the request is also sent to the next URL whenever no response is received
+    /// within the delay, and the first response wins. The other requests are cancelled.
+    ///
+    /// # Note
+    ///
+    /// Only async clients race the URLs. Blocking clients try them one after another.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub fn with_fallback_stagger(mut self, delay: Duration) -> Self {
+        self.fallback.stagger = Some(delay);
+        self
+    }
+
+    /// Returns the deadline of the request, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.inner.deadline
+    }
+
+    /// Formats an approximation of the request as sent over HTTP/1.1, with the request line, the
+    /// headers and a summary of the body, to be pasted into bug reports.
+    ///
+    /// Credentials are redacted as with the default [`crate::client::RedactionPolicy`]. The
+    /// headers added by the client, such as its default headers and `user-agent`, and those added
+    /// by the backend, such as `accept-encoding`, are not included. Relative URIs are kept as-is,
+    /// without the base URL of the client.
+    ///
+    /// ```
+    /// use nyquest::{Body, Request};
+    ///
+    /// let req: Request<()> = Request::post("https://example.com/items?page=1")
+    ///     .with_header("authorization", "Bearer secret")
+    ///     .with_body(Body::text("hello", "text/plain"));
+    /// assert_eq!(
+    ///     req.to_wire_debug(),
+    ///     "POST /items?page=1 HTTP/1.1\n\
+    ///      host: example.com\n\
+    ///      authorization: [REDACTED]\n\
+    ///      content-type: text/plain\n\
+    ///      content-length: 5\n\
+    ///      \n\
+    ///      hello",
+    /// );
+    /// ```
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "async", feature = "blocking"))))]
+    pub fn to_wire_debug(&self) -> String {
+        crate::wire_debug::format_request(&self.inner)
+    }
+
+    /// Returns the time remaining until the deadline of the request, if any.
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.inner
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Attaches a value to the extensions of the request, replacing the value of the same type if
+    /// any.
+    ///
+    /// See [`Extensions`] for how the values are passed on.
+    pub fn with_extension<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    /// Returns the extensions of the request.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns the extensions of the request mutably.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Clones the request unless its body is streamed or multipart, which cannot be sent twice.
+    ///
+    /// Streaming bodies made replayable with `Body::buffered_replayable` are cloned from their
+    /// buffers, once sent entirely within the limit.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        use nyquest_interface::Body as BodyImpl;
+
+        let body = match &self.inner.body {
+            _ if self.replay.is_some() => Some(self.replay.as_ref()?.body()?),
+            None => None,
+            Some(BodyImpl::Bytes {
+                content,
+                content_type,
+            }) => Some(BodyImpl::Bytes {
+                content: content.clone(),
+                content_type: content_type.clone(),
+            }),
+            Some(BodyImpl::Form { fields }) => Some(BodyImpl::Form {
+                fields: fields.clone(),
+            }),
+            Some(_) => return None,
+        };
+        Some(self.clone_with_body(body))
+    }
+
+    /// Takes the request for its first send if its body is replayable, leaving a copy without the
+    /// body to be cloned with [`Request::try_clone`] afterwards.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn take_replayable(&mut self) -> Option<Self> {
+        self.replay.as_ref()?;
+        let rest = self.clone_with_body(None);
+        Some(std::mem::replace(self, rest))
+    }
+
+    /// Rewrites the request with the request maps of the client, in order.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn apply_maps(&mut self, maps: &[RequestMap]) {
+        let inner = &mut self.inner;
+        for map in maps {
+            (map.0)(&mut RequestParts {
+                method: crate::client::recording::method_name(&inner.method),
+                uri: &mut inner.relative_uri,
+                headers: &mut inner.additional_headers,
+            });
+        }
+    }
+
+    /// Allows the request in TLS 1.3 early data if its method is safe, as early data can be
+    /// replayed by an attacker.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn allow_early_data(&mut self) {
+        let method = crate::client::recording::method_name(&self.inner.method);
+        self.inner.early_data = matches!(method, "GET" | "HEAD" | "OPTIONS" | "TRACE");
+    }
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    fn clone_with_body(&self, body: Option<nyquest_interface::Body<S>>) -> Self {
+        Self {
+            inner: RequestImpl {
+                method: self.inner.method.clone(),
+                relative_uri: self.inner.relative_uri.clone(),
+                additional_headers: self.inner.additional_headers.clone(),
+                body,
+                progress: self.inner.progress.clone(),
+                cache_policy: self.inner.cache_policy,
+                timings: self.inner.timings.clone(),
+                deadline: self.inner.deadline,
+                proxy: self.inner.proxy.clone(),
+                early_data: self.inner.early_data,
+            },
+            extensions: self.extensions.clone(),
+            replay: self.replay.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
 }
 
 impl<S> Debug for Request<S>
@@ -147,6 +505,11 @@ where
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            extensions: self.extensions.clone(),
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            replay: self.replay.clone(),
+            #[cfg(any(feature = "async", feature = "blocking"))]
+            fallback: self.fallback.clone(),
         }
     }
 }
@@ -0,0 +1,120 @@
+//! Request IDs for correlating requests with logs.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use nyquest_interface::{HeaderName, Request as RequestImpl};
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::Extensions;
+
+/// The ID of a request, sent in the header set by [`crate::ClientBuilder::request_id_header`].
+///
+/// The client generates a UUIDv7 for each request, unless the request already carries an ID in
+/// its extensions or in the header. The ID ends up in the extensions of the response, where
+/// `request_id` of the async or blocking `Response` reads it.
+///
+/// With the `tracing` feature, each request runs in a `nyquest_request` span with the ID in its
+/// `request_id` field, and a failed request emits a debug event with the error in that span.
+/// Errors returned to the caller do not carry the ID. To log it along with them, generate it up
+/// front and attach it to the request:
+///
+/// ```
+/// use nyquest::{Request, RequestId};
+///
+/// let request_id = RequestId::new();
+/// let req: Request<()> = Request::get("/orders").with_extension(request_id.clone());
+/// // Log `request_id` along with the error if sending the request fails
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Generates a new UUIDv7, which sorts by the time it is generated.
+    pub fn new() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let random = |salt| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(salt);
+            hasher.finish()
+        };
+        let (rand_a, rand_b) = (random(millis), random(!millis));
+        let high = (millis & 0xffff_ffff_ffff) << 16 | 0x7000 | (rand_a & 0x0fff);
+        let low = rand_b & 0x3fff_ffff_ffff_ffff | 0x8000_0000_0000_0000;
+        Self(format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            high >> 16 & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff,
+        ))
+    }
+
+    /// Returns the ID as sent in the header.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<String> for RequestId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Sends the ID of the request in the header, generating one unless the request already carries
+/// one.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn assign_request_id<S>(
+    header: &HeaderName,
+    req: &mut RequestImpl<S>,
+    extensions: &mut Extensions,
+) {
+    if let Some(id) = extensions.get::<RequestId>() {
+        req.additional_headers.insert(header.clone(), id.0.clone());
+        return;
+    }
+    let id = match req.additional_headers.get(header.as_str()) {
+        Some(value) => RequestId(value.to_string()),
+        None => {
+            let id = RequestId::new();
+            req.additional_headers.insert(header.clone(), id.0.clone());
+            id
+        }
+    };
+    extensions.insert(id);
+}
+
+/// Creates the span that a request runs in, which carries its ID if assigned.
+#[cfg(all(feature = "tracing", any(feature = "async", feature = "blocking")))]
+pub(crate) fn request_span(extensions: &Extensions) -> tracing::Span {
+    let request_id = extensions.get::<RequestId>().map(RequestId::as_str);
+    tracing::debug_span!("nyquest_request", request_id)
+}
+
+/// Emits an event in the span of the request if it failed.
+#[cfg(all(feature = "tracing", any(feature = "async", feature = "blocking")))]
+pub(crate) fn trace_failure<T>(res: &crate::Result<T>) {
+    if let Err(e) = res {
+        tracing::debug!(error = %e, "request failed");
+    }
+}
@@ -0,0 +1,111 @@
+//! Validators for conditional requests.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use nyquest_interface::{header, HeaderMap};
+
+use crate::Request;
+
+/// The validators of a response, sent back in conditional requests to revalidate the response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// The `etag` header of the response, sent back as `if-none-match`.
+    pub etag: Option<String>,
+    /// The `last-modified` header of the response, sent back as `if-modified-since`.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Returns whether neither validator is present.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Sets the conditional headers from the validators, replacing existing ones.
+    pub(crate) fn apply_to(&self, headers: &mut HeaderMap) {
+        if let Some(etag) = &self.etag {
+            headers.insert(header::IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.insert(header::IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+}
+
+/// Stores the validators of responses per URI, so that later requests to the same URI are sent
+/// as conditional requests.
+///
+/// This is meant for apps revalidating a few resources they keep themselves, without a full HTTP
+/// cache. URIs are matched exactly as given to [`Request::new`], without resolving or
+/// normalizing them.
+///
+/// ```no_run
+/// # async fn refresh(client: nyquest::AsyncClient) -> nyquest::Result<()> {
+/// use nyquest::r#async::Request;
+///
+/// let revalidator = nyquest::Revalidator::new();
+///
+/// let req = revalidator.apply(Request::get("https://example.com/feed.xml"));
+/// let res = client.request(req).await?;
+/// if !res.is_not_modified() {
+///     revalidator.update("https://example.com/feed.xml", res.validators()?);
+///     let feed = res.text().await?;
+///     // Keep the feed
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Revalidator {
+    validators: Mutex<HashMap<String, Validators>>,
+}
+
+impl Revalidator {
+    /// Creates a revalidator without any validators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds conditional headers to the request from the validators stored for its URI, if any.
+    pub fn apply<S>(&self, mut req: Request<S>) -> Request<S> {
+        let validators = self.lock();
+        if let Some(validators) = validators.get(&*req.inner.relative_uri) {
+            validators.apply_to(&mut req.inner.additional_headers);
+        }
+        drop(validators);
+        req
+    }
+
+    /// Stores the validators of a response for the URI, or forgets the URI if there are none.
+    pub fn update(&self, uri: impl Into<String>, validators: Validators) {
+        let uri = uri.into();
+        let mut map = self.lock();
+        if validators.is_empty() {
+            map.remove(&uri);
+        } else {
+            map.insert(uri, validators);
+        }
+    }
+
+    /// Returns the validators stored for the URI, if any.
+    pub fn get(&self, uri: &str) -> Option<Validators> {
+        self.lock().get(uri).cloned()
+    }
+
+    /// Forgets the validators stored for the URI.
+    pub fn remove(&self, uri: &str) {
+        self.lock().remove(uri);
+    }
+
+    /// Forgets all stored validators.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Validators>> {
+        self.validators
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,568 @@
+//! Absolute URLs validated on construction.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use nyquest_interface::client::UrlCredentials;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use nyquest_interface::header;
+use thiserror::Error;
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::client::recording::push_base64;
+
+/// The reasons for a string to be rejected by [`Url::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ParseUrlError {
+    /// The URL has no scheme, so it is relative.
+    #[error("Relative URL without a scheme")]
+    RelativeUrl,
+    /// The scheme contains characters other than ASCII letters, digits, `+`, `-` and `.`.
+    #[error("Invalid scheme")]
+    InvalidScheme,
+    /// The URL contains ASCII control characters.
+    #[error("Invalid character in URL")]
+    InvalidCharacter,
+    /// The host is empty for a scheme that requires one.
+    #[error("Empty host")]
+    EmptyHost,
+    /// The host contains characters not allowed in host names.
+    #[error("Invalid character in host")]
+    InvalidHost,
+    /// The host is enclosed in brackets but is not a valid IPv6 address.
+    #[error("Invalid IPv6 address")]
+    InvalidIpv6Address,
+    /// A label of the internationalized host name is too long once encoded in punycode.
+    #[error("Invalid internationalized domain name")]
+    InvalidIdn,
+    /// The port is not a number between 0 and 65535.
+    #[error("Invalid port number")]
+    InvalidPort,
+}
+
+/// An absolute URL, validated when parsed.
+///
+/// Building a [`crate::Request`] with a `Url` instead of a string rejects malformed URLs up front
+/// with a precise [`ParseUrlError`], instead of an opaque [`crate::Error::InvalidUrl`] from the
+/// backend once the request is sent.
+///
+/// URLs are normalized so that every backend receives the same URL:
+///
+/// - The scheme and the host are lowercased.
+/// - Non-ASCII labels of the host are lowercased and encoded in punycode, as IDNA 2008 requires.
+///   Labels are not normalized otherwise, so they should be given in Unicode NFC.
+/// - Non-ASCII characters and characters not allowed in the user info, the path, the query or
+///   the fragment are percent-encoded, while existing escapes are kept.
+/// - The port is removed if it is the default one of the scheme.
+///
+/// Requests given a string are normalized the same way before being sent, so that an invalid
+/// URL fails with [`crate::Error::MalformedUrl`].
+///
+/// ```
+/// use nyquest::Url;
+///
+/// let url = Url::parse("HTTPS://Bücher.example:443/suche?q=Straße").unwrap();
+/// assert_eq!(url.as_str(), "https://xn--bcher-kva.example/suche?q=Stra%C3%9Fe");
+/// assert_eq!(url.host_str(), Some("xn--bcher-kva.example"));
+/// assert_eq!(url.port(), None);
+/// assert!(Url::parse("/relative").is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Url {
+    serialization: String,
+    /// Position of the `:` after the scheme
+    scheme_end: usize,
+    /// Range of `user:password` before the `@`
+    userinfo: Option<(usize, usize)>,
+    /// Range of the host, including the brackets of IPv6 addresses
+    host: Option<(usize, usize)>,
+    port: Option<u16>,
+    path_start: usize,
+    /// Position of the `?`
+    query_start: Option<usize>,
+    /// Position of the `#`
+    fragment_start: Option<usize>,
+}
+
+impl Url {
+    /// Parses an absolute URL.
+    pub fn parse(input: &str) -> Result<Self, ParseUrlError> {
+        let input = input.trim_matches(|c: char| c == ' ' || c.is_ascii_control());
+        if input.chars().any(|c| c.is_ascii_control()) {
+            return Err(ParseUrlError::InvalidCharacter);
+        }
+        let (scheme, rest) = input.split_once(':').ok_or(ParseUrlError::RelativeUrl)?;
+        if scheme.is_empty() || scheme.contains(['/', '?', '#']) {
+            return Err(ParseUrlError::RelativeUrl);
+        }
+        if !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return Err(ParseUrlError::InvalidScheme);
+        }
+        let mut url = Self {
+            serialization: scheme.to_ascii_lowercase(),
+            scheme_end: scheme.len(),
+            userinfo: None,
+            host: None,
+            port: None,
+            path_start: 0,
+            query_start: None,
+            fragment_start: None,
+        };
+        url.serialization.push(':');
+        let requires_host = matches!(url.scheme(), "http" | "https" | "ws" | "wss" | "ftp");
+
+        let rest = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+                let (authority, rest) = rest.split_at(authority_end);
+                url.serialization.push_str("//");
+                url.push_authority(authority, requires_host)?;
+                if rest.is_empty() && requires_host {
+                    "/"
+                } else {
+                    rest
+                }
+            }
+            None if requires_host => return Err(ParseUrlError::EmptyHost),
+            None => rest,
+        };
+        let hierarchical = url.host.is_some();
+
+        url.path_start = url.serialization.len();
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (rest, None),
+        };
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+        let path_set: fn(u8) -> bool = match hierarchical {
+            true => |b| matches!(b, b' ' | b'"' | b'<' | b'>' | b'`' | b'{' | b'}'),
+            false => |_| false,
+        };
+        push_encoded(&mut url.serialization, path, path_set);
+        if let Some(query) = query {
+            url.query_start = Some(url.serialization.len());
+            url.serialization.push('?');
+            let query_set: fn(u8) -> bool = match requires_host {
+                true => |b| matches!(b, b' ' | b'"' | b'<' | b'>' | b'\''),
+                false => |b| matches!(b, b' ' | b'"' | b'<' | b'>'),
+            };
+            push_encoded(&mut url.serialization, query, query_set);
+        }
+        if let Some(fragment) = fragment {
+            url.fragment_start = Some(url.serialization.len());
+            url.serialization.push('#');
+            push_encoded(&mut url.serialization, fragment, |b| {
+                matches!(b, b' ' | b'"' | b'<' | b'>' | b'`')
+            });
+        }
+        Ok(url)
+    }
+
+    fn push_authority(
+        &mut self,
+        authority: &str,
+        requires_host: bool,
+    ) -> Result<(), ParseUrlError> {
+        let host_port = match authority.rsplit_once('@') {
+            Some((userinfo, host_port)) => {
+                let start = self.serialization.len();
+                push_encoded(&mut self.serialization, userinfo, |b| {
+                    matches!(
+                        b,
+                        b' ' | b'"'
+                            | b'<'
+                            | b'>'
+                            | b'`'
+                            | b'{'
+                            | b'}'
+                            | b'/'
+                            | b';'
+                            | b'='
+                            | b'@'
+                            | b'['
+                            | b'\\'
+                            | b']'
+                            | b'^'
+                            | b'|'
+                    )
+                });
+                self.userinfo = Some((start, self.serialization.len()));
+                self.serialization.push('@');
+                host_port
+            }
+            None => authority,
+        };
+        let (host, port) = if host_port.starts_with('[') {
+            let (host, rest) = host_port
+                .split_once(']')
+                .ok_or(ParseUrlError::InvalidIpv6Address)?;
+            let addr: Ipv6Addr = host[1..]
+                .parse()
+                .map_err(|_| ParseUrlError::InvalidIpv6Address)?;
+            let port = match rest {
+                "" => None,
+                rest => Some(rest.strip_prefix(':').ok_or(ParseUrlError::InvalidPort)?),
+            };
+            (Cow::Owned(format!("[{addr}]")), port)
+        } else {
+            let (host, port) = match host_port.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_port, None),
+            };
+            (domain_to_ascii(host)?, port)
+        };
+        if host.is_empty() && (requires_host || self.userinfo.is_some() || port.is_some()) {
+            return Err(ParseUrlError::EmptyHost);
+        }
+
+        let start = self.serialization.len();
+        self.serialization.push_str(&host);
+        self.host = Some((start, self.serialization.len()));
+        match port {
+            None | Some("") => {}
+            Some(port) => {
+                if !port.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseUrlError::InvalidPort);
+                }
+                let port: u16 = port.parse().map_err(|_| ParseUrlError::InvalidPort)?;
+                if Some(port) == default_port(self.scheme()) {
+                    return Ok(());
+                }
+                self.port = Some(port);
+                self.serialization.push(':');
+                self.serialization.push_str(&port.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the URL as a string.
+    pub fn as_str(&self) -> &str {
+        &self.serialization
+    }
+
+    /// Returns the lowercased scheme, such as `https`.
+    pub fn scheme(&self) -> &str {
+        &self.serialization[..self.scheme_end]
+    }
+
+    /// Returns the user name, which is empty if absent.
+    pub fn username(&self) -> &str {
+        self.userinfo.map_or("", |(start, end)| {
+            let userinfo = &self.serialization[start..end];
+            userinfo.split_once(':').map_or(userinfo, |(user, _)| user)
+        })
+    }
+
+    /// Returns the password, if any.
+    pub fn password(&self) -> Option<&str> {
+        let (start, end) = self.userinfo?;
+        self.serialization[start..end]
+            .split_once(':')
+            .map(|(_, password)| password)
+    }
+
+    /// Removes the user info from the URL, and returns the percent-decoded user name and password
+    /// unless both are empty.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn take_credentials(&mut self) -> Option<(String, String)> {
+        let username = percent_decode(self.username());
+        let password = percent_decode(self.password().unwrap_or_default());
+        let (start, end) = self.userinfo.take()?;
+        // Removes the `@` as well
+        let len = end + 1 - start;
+        self.serialization.replace_range(start..end + 1, "");
+        self.host = self.host.map(|(start, end)| (start - len, end - len));
+        self.path_start -= len;
+        self.query_start = self.query_start.map(|start| start - len);
+        self.fragment_start = self.fragment_start.map(|start| start - len);
+        (!username.is_empty() || !password.is_empty()).then_some((username, password))
+    }
+
+    /// Returns the host in ASCII, with IPv6 addresses enclosed in brackets, if any.
+    pub fn host_str(&self) -> Option<&str> {
+        self.host
+            .map(|(start, end)| &self.serialization[start..end])
+    }
+
+    /// Returns the port, unless it is absent or the default one of the scheme.
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns the path, which is never empty for schemes that require a host.
+    pub fn path(&self) -> &str {
+        let end = self
+            .query_start
+            .or(self.fragment_start)
+            .unwrap_or(self.serialization.len());
+        &self.serialization[self.path_start..end]
+    }
+
+    /// Returns the query string without the leading `?`, if any.
+    pub fn query(&self) -> Option<&str> {
+        let start = self.query_start? + 1;
+        let end = self.fragment_start.unwrap_or(self.serialization.len());
+        Some(&self.serialization[start..end])
+    }
+
+    /// Returns the fragment without the leading `#`, if any.
+    pub fn fragment(&self) -> Option<&str> {
+        Some(&self.serialization[self.fragment_start? + 1..])
+    }
+}
+
+/// Resolves the URI against the base URL, the same way `curl` backend does.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn resolve_url(base_url: Option<&str>, uri: &str) -> String {
+    let Some(base_url) = base_url.filter(|_| !uri.contains("://")) else {
+        return uri.into();
+    };
+    let (scheme, rest) = base_url.split_once("//").unwrap_or(("", base_url));
+    let host_path = rest.split(['?', '#']).next().unwrap_or_default();
+    if let Some(uri) = uri.strip_prefix("//") {
+        format!("{scheme}//{uri}")
+    } else if uri.starts_with('/') {
+        let host = host_path.split('/').next().unwrap_or_default();
+        format!("{scheme}//{host}{uri}")
+    } else {
+        let dir = host_path.rsplit_once('/').map_or(host_path, |(dir, _)| dir);
+        format!("{scheme}//{dir}/{uri}")
+    }
+}
+
+/// Resolves the URI of a request against the base URL and normalizes it like [`Url::parse`], so
+/// that every backend receives the same URL.
+///
+/// Credentials in the URL are handled in the frontend as well, instead of by the backend.
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn normalize_request_url<S>(
+    req: &mut nyquest_interface::Request<S>,
+    base_url: Option<&str>,
+    url_credentials: UrlCredentials,
+) -> crate::Result<()> {
+    let mut url = Url::parse(&resolve_url(base_url, &req.relative_uri))?;
+    if let Some((username, password)) = url.take_credentials() {
+        match url_credentials {
+            UrlCredentials::Reject => return Err(crate::Error::CredentialsInUrl),
+            UrlCredentials::BasicAuth
+                if !req.additional_headers.contains(&header::AUTHORIZATION) =>
+            {
+                let mut value = String::from("Basic ");
+                push_base64(&mut value, format!("{username}:{password}").as_bytes());
+                req.additional_headers.insert(header::AUTHORIZATION, value);
+            }
+            UrlCredentials::BasicAuth => {}
+        }
+    }
+    req.relative_uri = url.into();
+    Ok(())
+}
+
+#[cfg(any(feature = "async", feature = "blocking", feature = "grpc-web"))]
+pub(crate) fn percent_decode(s: &str) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) if b == b'%' => {
+                bytes.push(byte);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
+/// Appends the string, percent-encoding non-ASCII bytes and ASCII bytes in the set.
+fn push_encoded(out: &mut String, s: &str, set: fn(u8) -> bool) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    for b in s.bytes() {
+        if b.is_ascii() && !set(b) {
+            out.push(b as char);
+        } else {
+            out.push('%');
+            out.push(HEX[(b >> 4) as usize] as char);
+            out.push(HEX[(b & 0xf) as usize] as char);
+        }
+    }
+}
+
+/// Lowercases the host name and converts non-ASCII labels to punycode.
+fn domain_to_ascii(host: &str) -> Result<Cow<'_, str>, ParseUrlError> {
+    const FORBIDDEN: &[char] = &[
+        ' ', '#', '%', '/', ':', '<', '>', '?', '@', '[', '\\', ']', '^', '|',
+    ];
+    if host.contains(FORBIDDEN) {
+        return Err(ParseUrlError::InvalidHost);
+    }
+    if host.is_ascii() {
+        return Ok(match host.bytes().any(|b| b.is_ascii_uppercase()) {
+            true => Cow::Owned(host.to_ascii_lowercase()),
+            false => Cow::Borrowed(host),
+        });
+    }
+    let mut ascii = String::with_capacity(host.len() * 2);
+    // Ideographic full stops separate labels as well
+    for (i, label) in host
+        .split(['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'])
+        .enumerate()
+    {
+        if i > 0 {
+            ascii.push('.');
+        }
+        if label.is_ascii() {
+            ascii.push_str(&label.to_ascii_lowercase());
+            continue;
+        }
+        let label: Vec<char> = label.chars().flat_map(char::to_lowercase).collect();
+        let encoded = punycode_encode(&label).ok_or(ParseUrlError::InvalidIdn)?;
+        if encoded.len() + 4 > 63 {
+            return Err(ParseUrlError::InvalidIdn);
+        }
+        ascii.push_str("xn--");
+        ascii.push_str(&encoded);
+    }
+    Ok(Cow::Owned(ascii))
+}
+
+/// Encodes the label with punycode as specified in RFC 3492.
+fn punycode_encode(input: &[char]) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+
+    fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+        let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+        delta += delta / num_points;
+        let mut k = 0;
+        while delta > (BASE - T_MIN) * T_MAX / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+    }
+
+    fn digit(d: u32) -> char {
+        (if d < 26 {
+            b'a' + d as u8
+        } else {
+            b'0' + (d - 26) as u8
+        }) as char
+    }
+
+    let mut output: String = input.iter().filter(|c| c.is_ascii()).collect();
+    let basic_len = output.len() as u32;
+    if basic_len > 0 {
+        output.push('-');
+    }
+    let mut n = 0x80;
+    let mut delta = 0u32;
+    let mut bias = 72;
+    let mut handled = basic_len;
+    while (handled as usize) < input.len() {
+        let m = input.iter().map(|&c| c as u32).filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in input {
+            let c = c as u32;
+            if c < n {
+                delta = delta.checked_add(1)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = k.saturating_sub(bias).clamp(T_MIN, T_MAX);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+    Some(output)
+}
+
+impl FromStr for Url {
+    type Err = ParseUrlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Url {
+    type Error = ParseUrlError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl AsRef<str> for Url {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Url").field(&self.as_str()).finish()
+    }
+}
+
+impl From<Url> for String {
+    fn from(url: Url) -> Self {
+        url.serialization
+    }
+}
+
+impl From<Url> for Cow<'static, str> {
+    fn from(url: Url) -> Self {
+        Cow::Owned(url.serialization)
+    }
+}
@@ -0,0 +1,101 @@
+//! A builder of `user-agent` header values.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// A `user-agent` header value built from products and comments, such as
+/// `my-app/1.2 (+https://example.com) nyquest/0.3`.
+///
+/// Characters not allowed in product names and versions are replaced with `-`, and parentheses
+/// and backslashes in comments are escaped, so that the value is always well-formed.
+///
+/// ```
+/// use nyquest::UserAgent;
+///
+/// let user_agent = UserAgent::product("my-app", "1.2")
+///     .comment("+https://example.com")
+///     .and_product("nyquest", env!("CARGO_PKG_VERSION"));
+/// assert!(user_agent.as_str().starts_with("my-app/1.2 (+https://example.com) nyquest/"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UserAgent {
+    value: String,
+}
+
+impl UserAgent {
+    /// Starts with a product, omitting the version if empty.
+    pub fn product(name: &str, version: &str) -> Self {
+        let mut user_agent = Self {
+            value: String::new(),
+        };
+        user_agent.push_product(name, version);
+        user_agent
+    }
+
+    /// Appends another product, omitting the version if empty.
+    pub fn and_product(mut self, name: &str, version: &str) -> Self {
+        self.value.push(' ');
+        self.push_product(name, version);
+        self
+    }
+
+    /// Appends a comment, which usually describes the preceding product.
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.value.push_str(" (");
+        for c in comment.chars().filter(|c| !c.is_control()) {
+            if matches!(c, '(' | ')' | '\\') {
+                self.value.push('\\');
+            }
+            self.value.push(c);
+        }
+        self.value.push(')');
+        self
+    }
+
+    /// Returns the header value.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    fn push_product(&mut self, name: &str, version: &str) {
+        push_token(&mut self.value, name);
+        if !version.is_empty() {
+            self.value.push('/');
+            push_token(&mut self.value, version);
+        }
+    }
+}
+
+fn push_token(out: &mut String, token: &str) {
+    if token.is_empty() {
+        out.push('-');
+    }
+    out.extend(token.chars().map(|c| match c {
+        c if c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c) => c,
+        _ => '-',
+    }));
+}
+
+impl fmt::Display for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+impl AsRef<str> for UserAgent {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl From<UserAgent> for String {
+    fn from(user_agent: UserAgent) -> Self {
+        user_agent.value
+    }
+}
+
+impl From<UserAgent> for Cow<'static, str> {
+    fn from(user_agent: UserAgent) -> Self {
+        Cow::Owned(user_agent.value)
+    }
+}
@@ -0,0 +1,121 @@
+//! Approximate serialization of requests for bug reports.
+
+use std::fmt::Write;
+
+use nyquest_interface::client::RedactionPolicy;
+use nyquest_interface::{header, Body, Request as RequestImpl};
+
+use crate::client::recording::method_name;
+use crate::url::Url;
+
+/// Bodies up to this size are included as text if they are valid UTF-8.
+const MAX_TEXT_BODY: usize = 4096;
+
+pub(crate) fn format_request<S>(req: &RequestImpl<S>) -> String {
+    let redaction = RedactionPolicy::default();
+    let uri = redaction.redact_url(&req.relative_uri);
+    let url = Url::parse(&uri).ok();
+    let target = match &url {
+        Some(url) => match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_owned(),
+        },
+        None => uri.split('#').next().unwrap_or_default().to_owned(),
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{} {target} HTTP/1.1", method_name(&req.method));
+    let host = url.as_ref().and_then(|url| {
+        let host = url.host_str()?;
+        Some(match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_owned(),
+        })
+    });
+    if let Some(host) = host.filter(|_| !req.additional_headers.contains(header::HOST.as_str())) {
+        let _ = writeln!(out, "host: {host}");
+    }
+    for (name, value) in &req.additional_headers {
+        let value = match redaction.redacts_header(name) {
+            true => RedactionPolicy::REDACTED,
+            false => value,
+        };
+        let _ = writeln!(out, "{name}: {value}");
+    }
+    let (content_type, content_length, content) = match &req.body {
+        None => (None, None, String::new()),
+        Some(Body::Bytes {
+            content,
+            content_type,
+        }) => (
+            Some(content_type.to_string()),
+            Some(content.len() as u64),
+            match std::str::from_utf8(content) {
+                Ok(text) if text.len() <= MAX_TEXT_BODY => text.to_owned(),
+                _ => format!("<{} bytes>", content.len()),
+            },
+        ),
+        Some(Body::Form { fields }) => {
+            let mut encoded = String::new();
+            for (i, (name, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    encoded.push('&');
+                }
+                push_form_encoded(&mut encoded, name);
+                encoded.push('=');
+                push_form_encoded(&mut encoded, value);
+            }
+            (
+                Some("application/x-www-form-urlencoded".to_owned()),
+                Some(encoded.len() as u64),
+                encoded,
+            )
+        }
+        #[cfg(feature = "multipart")]
+        Some(Body::Multipart { parts }) => {
+            let names: Vec<_> = parts.iter().map(|part| &*part.name).collect();
+            (
+                Some("multipart/form-data; boundary=...".to_owned()),
+                None,
+                format!("<multipart body with parts: {}>", names.join(", ")),
+            )
+        }
+        Some(Body::Stream(stream)) => (
+            None,
+            stream.content_length,
+            match stream.content_length {
+                Some(len) => format!("<streamed body of {len} bytes>"),
+                None => "<streamed body of unknown size>".to_owned(),
+            },
+        ),
+    };
+    let has_header = |name: &str| req.additional_headers.contains(name);
+    if let Some(content_type) = content_type.filter(|_| !has_header("content-type")) {
+        let _ = writeln!(out, "content-type: {content_type}");
+    }
+    if req.body.is_some() && !has_header("content-length") {
+        match content_length {
+            Some(len) => {
+                let _ = writeln!(out, "content-length: {len}");
+            }
+            None => out.push_str("transfer-encoding: chunked\n"),
+        }
+    }
+    out.push('\n');
+    out.push_str(&content);
+    out
+}
+
+/// Appends the string as encoded in `application/x-www-form-urlencoded` bodies.
+fn push_form_encoded(out: &mut String, s: &str) {
+    for b in s.bytes() {
+        match b {
+            b' ' => out.push('+'),
+            b'*' | b'-' | b'.' | b'_' => out.push(b as char),
+            _ if b.is_ascii_alphanumeric() => out.push(b as char),
+            _ => {
+                let _ = write!(out, "%{b:02X}");
+            }
+        }
+    }
+}